@@ -0,0 +1,4 @@
+// Rebuild when migrations change, since they are embedded into the binaries.
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+}
@@ -1,3 +1,3 @@
 -- Write your down sql migration here
 DROP TABLE IF EXISTS tasks;
-DROP IF EXISTS EXTENSION "uuid-ossp";
\ No newline at end of file
+DROP EXTENSION IF EXISTS "uuid-ossp";
\ No newline at end of file
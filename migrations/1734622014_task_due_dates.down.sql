@@ -0,0 +1,4 @@
+-- Write your down sql migration here
+ALTER TABLE tasks
+    DROP COLUMN IF EXISTS reminder_offsets,
+    DROP COLUMN IF EXISTS due_at;
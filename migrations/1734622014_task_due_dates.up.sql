@@ -0,0 +1,5 @@
+-- Write your up sql migration here
+ALTER TABLE tasks
+    ADD COLUMN due_at TIMESTAMP WITH TIME ZONE,
+    -- Minutes before due_at at which a reminder fires
+    ADD COLUMN reminder_offsets INTEGER[] NOT NULL DEFAULT '{}';
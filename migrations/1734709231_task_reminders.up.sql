@@ -0,0 +1,18 @@
+-- Write your up sql migration here
+CREATE TABLE task_reminders (
+    task_id uuid NOT NULL REFERENCES tasks (id) ON DELETE CASCADE,
+    remind_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- Set when a scheduler claims the reminder, so that it is delivered at most once
+    fired_at TIMESTAMP WITH TIME ZONE,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    PRIMARY KEY (task_id, remind_at)
+);
+
+CREATE INDEX task_reminders_pending_idx ON task_reminders (remind_at) WHERE fired_at IS NULL;
+
+INSERT INTO task_reminders (task_id, remind_at)
+SELECT id, due_at - make_interval(mins => offset_minutes)
+FROM tasks, unnest(reminder_offsets) AS offset_minutes
+WHERE due_at IS NOT NULL
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,3 @@
+-- Write your up sql migration here
+-- An RFC 5545 RRULE value, such as FREQ=WEEKLY;INTERVAL=2;BYDAY=MO
+ALTER TABLE tasks ADD COLUMN recurrence TEXT;
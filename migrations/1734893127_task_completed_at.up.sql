@@ -0,0 +1,5 @@
+-- Write your up sql migration here
+-- When the task was last completed, used to purge tasks that have been done for a while
+ALTER TABLE tasks ADD COLUMN completed_at TIMESTAMP WITH TIME ZONE;
+
+UPDATE tasks SET completed_at = updated_at WHERE completed;
@@ -0,0 +1,2 @@
+-- Write your down sql migration here
+DROP INDEX IF EXISTS tasks_incomplete_title_idx;
@@ -0,0 +1,14 @@
+-- Write your up sql migration here
+-- Titles are unique among incomplete tasks, ignoring case. Existing duplicates are renamed by
+-- numbering all but the oldest, so that the index can be created.
+WITH duplicates AS (
+    SELECT id, row_number() OVER (PARTITION BY lower(title) ORDER BY created_at, id) AS n
+    FROM tasks
+    WHERE NOT completed
+)
+UPDATE tasks
+SET title = tasks.title || ' (' || duplicates.n || ')'
+FROM duplicates
+WHERE tasks.id = duplicates.id AND duplicates.n > 1;
+
+CREATE UNIQUE INDEX tasks_incomplete_title_idx ON tasks (lower(title)) WHERE NOT completed;
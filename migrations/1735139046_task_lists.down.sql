@@ -0,0 +1,6 @@
+-- Write your down sql migration here
+DROP INDEX IF EXISTS tasks_list_id_idx;
+
+ALTER TABLE tasks DROP COLUMN IF EXISTS list_id;
+
+DROP TABLE IF EXISTS task_lists;
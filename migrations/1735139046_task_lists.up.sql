@@ -0,0 +1,19 @@
+-- Write your up sql migration here
+CREATE TABLE task_lists (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    title TEXT NOT NULL,
+    colour TEXT,
+    position INTEGER NOT NULL DEFAULT 0,
+    archived BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (CURRENT_TIMESTAMP AT TIME ZONE 'UTC'),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (CURRENT_TIMESTAMP AT TIME ZONE 'UTC')
+);
+
+-- Titles are unique among unarchived lists, ignoring case
+CREATE UNIQUE INDEX task_lists_unarchived_title_idx ON task_lists (lower(title)) WHERE NOT archived;
+
+-- Tasks without a list are in the inbox. Deleting a list that still has tasks is refused, so the
+-- application decides whether they are deleted or moved to the inbox.
+ALTER TABLE tasks ADD COLUMN list_id uuid REFERENCES task_lists (id);
+
+CREATE INDEX tasks_list_id_idx ON tasks (list_id);
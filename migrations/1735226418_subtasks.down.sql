@@ -0,0 +1,6 @@
+-- Write your down sql migration here
+DROP INDEX IF EXISTS tasks_parent_id_idx;
+
+ALTER TABLE tasks DROP COLUMN IF EXISTS position;
+
+ALTER TABLE tasks DROP COLUMN IF EXISTS parent_id;
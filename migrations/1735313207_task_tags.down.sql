@@ -0,0 +1,2 @@
+-- Write your down sql migration here
+DROP TABLE IF EXISTS task_tags;
@@ -0,0 +1,9 @@
+-- Write your up sql migration here
+-- Tags are normalised to lowercase by the application and exist only while a task carries them
+CREATE TABLE task_tags (
+    task_id uuid NOT NULL REFERENCES tasks (id) ON DELETE CASCADE,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (task_id, tag)
+);
+
+CREATE INDEX task_tags_tag_idx ON task_tags (tag);
@@ -0,0 +1,4 @@
+-- Write your down sql migration here
+ALTER TABLE tasks DROP COLUMN IF EXISTS notes;
+
+ALTER TABLE tasks DROP COLUMN IF EXISTS priority;
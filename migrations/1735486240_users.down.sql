@@ -0,0 +1,27 @@
+-- Write your down sql migration here
+ALTER TABLE tasks DROP CONSTRAINT IF EXISTS tasks_parent_id_fkey;
+ALTER TABLE tasks ADD CONSTRAINT tasks_parent_id_fkey
+    FOREIGN KEY (parent_id) REFERENCES tasks (id) ON DELETE CASCADE;
+
+ALTER TABLE tasks DROP CONSTRAINT IF EXISTS tasks_list_id_fkey;
+ALTER TABLE tasks ADD CONSTRAINT tasks_list_id_fkey FOREIGN KEY (list_id) REFERENCES task_lists (id);
+
+ALTER TABLE tasks DROP CONSTRAINT IF EXISTS tasks_id_owner_id_key;
+ALTER TABLE task_lists DROP CONSTRAINT IF EXISTS task_lists_id_owner_id_key;
+
+DROP INDEX IF EXISTS task_lists_unarchived_title_idx;
+DROP INDEX IF EXISTS tasks_incomplete_title_idx;
+
+DROP INDEX IF EXISTS tasks_owner_id_idx;
+DROP INDEX IF EXISTS task_lists_owner_id_idx;
+
+ALTER TABLE tasks DROP COLUMN IF EXISTS owner_id;
+ALTER TABLE task_lists DROP COLUMN IF EXISTS owner_id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS tasks_incomplete_title_idx ON tasks (lower(title))
+WHERE NOT completed;
+CREATE UNIQUE INDEX IF NOT EXISTS task_lists_unarchived_title_idx ON task_lists (lower(title))
+WHERE NOT archived;
+
+DROP TABLE IF EXISTS user_sessions;
+DROP TABLE IF EXISTS users;
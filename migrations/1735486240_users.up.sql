@@ -0,0 +1,55 @@
+-- Write your up sql migration here
+CREATE TABLE users (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    email TEXT NOT NULL UNIQUE,
+    password_hash TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (CURRENT_TIMESTAMP AT TIME ZONE 'UTC'),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (CURRENT_TIMESTAMP AT TIME ZONE 'UTC')
+);
+
+-- Sessions are stored under the SHA-256 digest of their token, never the token itself
+CREATE TABLE user_sessions (
+    token_digest TEXT PRIMARY KEY,
+    user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (CURRENT_TIMESTAMP AT TIME ZONE 'UTC'),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX user_sessions_user_id_idx ON user_sessions (user_id);
+
+-- Existing tasks and lists are given to a user who cannot sign in until an administrator sets
+-- their password, so that no data is lost or exposed
+INSERT INTO users (email, password_hash)
+SELECT 'admin@localhost', '!'
+WHERE EXISTS (SELECT 1 FROM tasks) OR EXISTS (SELECT 1 FROM task_lists);
+
+ALTER TABLE task_lists ADD COLUMN owner_id uuid REFERENCES users (id) ON DELETE CASCADE;
+UPDATE task_lists SET owner_id = (SELECT id FROM users WHERE email = 'admin@localhost');
+ALTER TABLE task_lists ALTER COLUMN owner_id SET NOT NULL;
+
+ALTER TABLE tasks ADD COLUMN owner_id uuid REFERENCES users (id) ON DELETE CASCADE;
+UPDATE tasks SET owner_id = (SELECT id FROM users WHERE email = 'admin@localhost');
+ALTER TABLE tasks ALTER COLUMN owner_id SET NOT NULL;
+
+CREATE INDEX task_lists_owner_id_idx ON task_lists (owner_id);
+CREATE INDEX tasks_owner_id_idx ON tasks (owner_id);
+
+-- Titles are unique per owner
+DROP INDEX tasks_incomplete_title_idx;
+CREATE UNIQUE INDEX tasks_incomplete_title_idx ON tasks (owner_id, lower(title)) WHERE NOT completed;
+
+DROP INDEX task_lists_unarchived_title_idx;
+CREATE UNIQUE INDEX task_lists_unarchived_title_idx ON task_lists (owner_id, lower(title))
+WHERE NOT archived;
+
+-- A task can only be in a list, or nested below a task, of its own owner
+ALTER TABLE task_lists ADD CONSTRAINT task_lists_id_owner_id_key UNIQUE (id, owner_id);
+ALTER TABLE tasks ADD CONSTRAINT tasks_id_owner_id_key UNIQUE (id, owner_id);
+
+ALTER TABLE tasks DROP CONSTRAINT tasks_list_id_fkey;
+ALTER TABLE tasks ADD CONSTRAINT tasks_list_id_fkey
+    FOREIGN KEY (list_id, owner_id) REFERENCES task_lists (id, owner_id);
+
+ALTER TABLE tasks DROP CONSTRAINT tasks_parent_id_fkey;
+ALTER TABLE tasks ADD CONSTRAINT tasks_parent_id_fkey
+    FOREIGN KEY (parent_id, owner_id) REFERENCES tasks (id, owner_id) ON DELETE CASCADE;
@@ -0,0 +1,17 @@
+-- Write your up sql migration here
+-- API tokens are stored under the SHA-256 digest of their secret, never the secret itself
+CREATE TABLE api_tokens (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    token_digest TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    access TEXT NOT NULL DEFAULT 'read_write' CHECK (access IN ('read_only', 'read_write')),
+    list_id uuid,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (CURRENT_TIMESTAMP AT TIME ZONE 'UTC'),
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    -- A token can only be restricted to a list of its own user, and is revoked with the list
+    CONSTRAINT api_tokens_list_id_fkey FOREIGN KEY (list_id, user_id)
+        REFERENCES task_lists (id, owner_id) ON DELETE CASCADE
+);
+
+CREATE INDEX api_tokens_user_id_idx ON api_tokens (user_id);
@@ -0,0 +1,5 @@
+-- Write your down sql migration here
+ALTER TABLE tasks DROP COLUMN IF EXISTS completed_by;
+ALTER TABLE tasks DROP COLUMN IF EXISTS updated_by;
+
+DROP TABLE IF EXISTS task_list_members;
@@ -0,0 +1,15 @@
+-- Write your up sql migration here
+-- A list can be shared with other users. Its creator always owns it and is not listed here
+CREATE TABLE task_list_members (
+    list_id uuid NOT NULL REFERENCES task_lists (id) ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    role TEXT NOT NULL CHECK (role IN ('viewer', 'editor', 'owner')),
+    added_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (CURRENT_TIMESTAMP AT TIME ZONE 'UTC'),
+    PRIMARY KEY (list_id, user_id)
+);
+
+CREATE INDEX task_list_members_user_id_idx ON task_list_members (user_id);
+
+-- Tasks in a shared list still belong to the owner of the list, so record who worked on them
+ALTER TABLE tasks ADD COLUMN updated_by uuid REFERENCES users (id) ON DELETE SET NULL;
+ALTER TABLE tasks ADD COLUMN completed_by uuid REFERENCES users (id) ON DELETE SET NULL;
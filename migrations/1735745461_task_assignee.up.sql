@@ -0,0 +1,5 @@
+-- Write your up sql migration here
+-- A task in a shared list can be assigned to one of its members, who then receives its reminders
+ALTER TABLE tasks ADD COLUMN assignee_id uuid REFERENCES users (id) ON DELETE SET NULL;
+
+CREATE INDEX tasks_assignee_id_idx ON tasks (assignee_id);
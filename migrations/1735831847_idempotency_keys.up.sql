@@ -0,0 +1,16 @@
+-- Write your up sql migration here
+-- Requests made with an Idempotency-Key header, and their responses once handled, replayed to
+-- retries until the key expires. Keys are scoped to the digest of the credentials that sent them
+CREATE TABLE idempotency_keys (
+    owner_digest TEXT NOT NULL,
+    key TEXT NOT NULL,
+    fingerprint TEXT NOT NULL,
+    response_status SMALLINT,
+    response_content_type TEXT,
+    response_body BYTEA,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (CURRENT_TIMESTAMP AT TIME ZONE 'UTC'),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (owner_digest, key)
+);
+
+CREATE INDEX idempotency_keys_expires_at_idx ON idempotency_keys (expires_at);
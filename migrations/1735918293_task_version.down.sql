@@ -0,0 +1,2 @@
+-- Write your down sql migration here
+ALTER TABLE tasks DROP COLUMN IF EXISTS version;
@@ -0,0 +1,4 @@
+-- Write your up sql migration here
+-- Incremented on every change to a task, so that clients can tell whether the task they edit
+-- was changed by someone else since they read it
+ALTER TABLE tasks ADD COLUMN version bigint NOT NULL DEFAULT 1;
@@ -0,0 +1,17 @@
+-- Write your down sql migration here
+DELETE FROM tasks WHERE deleted_at IS NOT NULL;
+DELETE FROM tasks WHERE list_id IN (SELECT id FROM task_lists WHERE deleted_at IS NOT NULL);
+DELETE FROM task_lists WHERE deleted_at IS NOT NULL;
+
+DROP INDEX IF EXISTS tasks_incomplete_title_idx;
+DROP INDEX IF EXISTS task_lists_unarchived_title_idx;
+DROP INDEX IF EXISTS tasks_deleted_at_idx;
+DROP INDEX IF EXISTS task_lists_deleted_at_idx;
+
+ALTER TABLE tasks DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE task_lists DROP COLUMN IF EXISTS deleted_at;
+
+CREATE UNIQUE INDEX IF NOT EXISTS tasks_incomplete_title_idx ON tasks (owner_id, lower(title))
+WHERE NOT completed;
+CREATE UNIQUE INDEX IF NOT EXISTS task_lists_unarchived_title_idx ON task_lists (owner_id, lower(title))
+WHERE NOT archived;
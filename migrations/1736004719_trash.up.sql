@@ -0,0 +1,17 @@
+-- Write your up sql migration here
+-- Deleted tasks and lists stay in the trash until they are restored, or purged once the
+-- retention period has passed
+ALTER TABLE tasks ADD COLUMN deleted_at timestamptz;
+ALTER TABLE task_lists ADD COLUMN deleted_at timestamptz;
+
+CREATE INDEX tasks_deleted_at_idx ON tasks (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX task_lists_deleted_at_idx ON task_lists (deleted_at) WHERE deleted_at IS NOT NULL;
+
+-- Titles in the trash may be reused
+DROP INDEX tasks_incomplete_title_idx;
+CREATE UNIQUE INDEX tasks_incomplete_title_idx ON tasks (owner_id, lower(title))
+WHERE NOT completed AND deleted_at IS NULL;
+
+DROP INDEX task_lists_unarchived_title_idx;
+CREATE UNIQUE INDEX task_lists_unarchived_title_idx ON task_lists (owner_id, lower(title))
+WHERE NOT archived AND deleted_at IS NULL;
@@ -0,0 +1,2 @@
+-- Write your down sql migration here
+ALTER TABLE idempotency_keys DROP COLUMN IF EXISTS response_headers;
@@ -0,0 +1,4 @@
+-- Write your up sql migration here
+-- The headers of stored responses other than Content-Type, such as ETag and Location, as
+-- "name: value" lines in the order they were sent
+ALTER TABLE idempotency_keys ADD COLUMN response_headers TEXT[] NOT NULL DEFAULT '{}';
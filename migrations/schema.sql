@@ -19,18 +19,214 @@ CREATE TABLE tasks (
  id uuid  NOT NULL,
  completed boolean  NOT NULL,
  created_at timestamp with time zone  NOT NULL,
+ updated_at timestamp with time zone  NOT NULL,
+ due_at timestamp with time zone,
+ reminder_offsets integer[]  NOT NULL,
+ recurrence text,
+ completed_at timestamp with time zone,
+ list_id uuid,
+ parent_id uuid,
+ position integer  NOT NULL,
+ priority text  NOT NULL,
+ notes text,
+ owner_id uuid  NOT NULL,
+ updated_by uuid,
+ completed_by uuid,
+ assignee_id uuid,
+ version bigint  NOT NULL,
+ deleted_at timestamp with time zone
+);
+
+CREATE TABLE task_reminders (
+ task_id uuid  NOT NULL,
+ remind_at timestamp with time zone  NOT NULL,
+ fired_at timestamp with time zone,
+ attempts integer  NOT NULL,
+ last_error text
+);
+
+CREATE TABLE task_lists (
+ id uuid  NOT NULL,
+ title text  NOT NULL,
+ colour text,
+ position integer  NOT NULL,
+ archived boolean  NOT NULL,
+ created_at timestamp with time zone  NOT NULL,
+ updated_at timestamp with time zone  NOT NULL,
+ owner_id uuid  NOT NULL,
+ deleted_at timestamp with time zone
+);
+
+CREATE TABLE task_tags (
+ task_id uuid  NOT NULL,
+ tag text  NOT NULL
+);
+
+CREATE TABLE users (
+ id uuid  NOT NULL,
+ email text  NOT NULL,
+ password_hash text  NOT NULL,
+ created_at timestamp with time zone  NOT NULL,
  updated_at timestamp with time zone  NOT NULL
 );
 
+CREATE TABLE user_sessions (
+ token_digest text  NOT NULL,
+ user_id uuid  NOT NULL,
+ created_at timestamp with time zone  NOT NULL,
+ expires_at timestamp with time zone  NOT NULL
+);
+
+CREATE TABLE api_tokens (
+ id uuid  NOT NULL,
+ user_id uuid  NOT NULL,
+ token_digest text  NOT NULL,
+ name text  NOT NULL,
+ access text  NOT NULL,
+ list_id uuid ,
+ created_at timestamp with time zone  NOT NULL,
+ last_used_at timestamp with time zone 
+);
+
+CREATE TABLE task_list_members (
+ list_id uuid  NOT NULL,
+ user_id uuid  NOT NULL,
+ role text  NOT NULL,
+ added_at timestamp with time zone  NOT NULL
+);
+
+CREATE TABLE idempotency_keys (
+ owner_digest text  NOT NULL,
+ key text  NOT NULL,
+ fingerprint text  NOT NULL,
+ response_status smallint ,
+ response_content_type text ,
+ response_body bytea ,
+ response_headers text[]  NOT NULL,
+ created_at timestamp with time zone  NOT NULL,
+ expires_at timestamp with time zone  NOT NULL
+);
+
 -- CONSTRAINTS 
 
 ALTER TABLE schema_migrations ADD CONSTRAINT schema_migrations_pkey PRIMARY KEY (id);
 
 ALTER TABLE tasks ADD CONSTRAINT tasks_pkey PRIMARY KEY (id);
 
+ALTER TABLE task_reminders ADD CONSTRAINT task_reminders_pkey PRIMARY KEY (task_id, remind_at);
+
+ALTER TABLE task_reminders ADD CONSTRAINT task_reminders_task_id_fkey FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE;
+
+ALTER TABLE task_lists ADD CONSTRAINT task_lists_pkey PRIMARY KEY (id);
+
+ALTER TABLE tasks ADD CONSTRAINT tasks_list_id_fkey FOREIGN KEY (list_id, owner_id) REFERENCES task_lists(id, owner_id);
+
+ALTER TABLE tasks ADD CONSTRAINT tasks_parent_id_fkey FOREIGN KEY (parent_id, owner_id) REFERENCES tasks(id, owner_id) ON DELETE CASCADE;
+
+ALTER TABLE tasks ADD CONSTRAINT tasks_priority_check CHECK (priority IN ('none', 'low', 'medium', 'high', 'urgent'));
+
+ALTER TABLE task_tags ADD CONSTRAINT task_tags_pkey PRIMARY KEY (task_id, tag);
+
+ALTER TABLE task_tags ADD CONSTRAINT task_tags_task_id_fkey FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE;
+
+ALTER TABLE users ADD CONSTRAINT users_pkey PRIMARY KEY (id);
+
+ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE (email);
+
+ALTER TABLE user_sessions ADD CONSTRAINT user_sessions_pkey PRIMARY KEY (token_digest);
+
+ALTER TABLE user_sessions ADD CONSTRAINT user_sessions_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE;
+
+ALTER TABLE task_lists ADD CONSTRAINT task_lists_owner_id_fkey FOREIGN KEY (owner_id) REFERENCES users(id) ON DELETE CASCADE;
+
+ALTER TABLE task_lists ADD CONSTRAINT task_lists_id_owner_id_key UNIQUE (id, owner_id);
+
+ALTER TABLE tasks ADD CONSTRAINT tasks_owner_id_fkey FOREIGN KEY (owner_id) REFERENCES users(id) ON DELETE CASCADE;
+
+ALTER TABLE tasks ADD CONSTRAINT tasks_id_owner_id_key UNIQUE (id, owner_id);
+
+ALTER TABLE api_tokens ADD CONSTRAINT api_tokens_access_check CHECK (access IN ('read_only', 'read_write'));
+
+ALTER TABLE api_tokens ADD CONSTRAINT api_tokens_pkey PRIMARY KEY (id);
+
+ALTER TABLE api_tokens ADD CONSTRAINT api_tokens_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE;
+
+ALTER TABLE api_tokens ADD CONSTRAINT api_tokens_token_digest_key UNIQUE (token_digest);
+
+ALTER TABLE api_tokens ADD CONSTRAINT api_tokens_list_id_fkey FOREIGN KEY (list_id, user_id) REFERENCES task_lists(id, owner_id) ON DELETE CASCADE;
+
+ALTER TABLE task_list_members ADD CONSTRAINT task_list_members_role_check CHECK (role IN ('viewer', 'editor', 'owner'));
+
+ALTER TABLE task_list_members ADD CONSTRAINT task_list_members_pkey PRIMARY KEY (list_id, user_id);
+
+ALTER TABLE task_list_members ADD CONSTRAINT task_list_members_list_id_fkey FOREIGN KEY (list_id) REFERENCES task_lists(id) ON DELETE CASCADE;
+
+ALTER TABLE task_list_members ADD CONSTRAINT task_list_members_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE;
+
+ALTER TABLE tasks ADD CONSTRAINT tasks_updated_by_fkey FOREIGN KEY (updated_by) REFERENCES users(id) ON DELETE SET NULL;
+
+ALTER TABLE tasks ADD CONSTRAINT tasks_completed_by_fkey FOREIGN KEY (completed_by) REFERENCES users(id) ON DELETE SET NULL;
+
+ALTER TABLE tasks ADD CONSTRAINT tasks_assignee_id_fkey FOREIGN KEY (assignee_id) REFERENCES users(id) ON DELETE SET NULL;
+
+ALTER TABLE idempotency_keys ADD CONSTRAINT idempotency_keys_pkey PRIMARY KEY (owner_digest, key);
+
 -- INDEXES 
 
 CREATE UNIQUE INDEX schema_migrations_pkey ON public.schema_migrations USING btree (id)
 
 CREATE UNIQUE INDEX tasks_pkey ON public.tasks USING btree (id)
 
+CREATE UNIQUE INDEX task_reminders_pkey ON public.task_reminders USING btree (task_id, remind_at)
+
+CREATE INDEX task_reminders_pending_idx ON public.task_reminders USING btree (remind_at) WHERE (fired_at IS NULL)
+
+CREATE UNIQUE INDEX tasks_incomplete_title_idx ON public.tasks USING btree (owner_id, lower(title)) WHERE ((NOT completed) AND (deleted_at IS NULL))
+
+CREATE UNIQUE INDEX task_lists_pkey ON public.task_lists USING btree (id)
+
+CREATE UNIQUE INDEX task_lists_unarchived_title_idx ON public.task_lists USING btree (owner_id, lower(title)) WHERE ((NOT archived) AND (deleted_at IS NULL))
+
+CREATE INDEX tasks_list_id_idx ON public.tasks USING btree (list_id)
+
+CREATE INDEX tasks_parent_id_idx ON public.tasks USING btree (parent_id)
+
+CREATE UNIQUE INDEX task_tags_pkey ON public.task_tags USING btree (task_id, tag)
+
+CREATE INDEX task_tags_tag_idx ON public.task_tags USING btree (tag)
+
+CREATE UNIQUE INDEX users_pkey ON public.users USING btree (id)
+
+CREATE UNIQUE INDEX users_email_key ON public.users USING btree (email)
+
+CREATE UNIQUE INDEX user_sessions_pkey ON public.user_sessions USING btree (token_digest)
+
+CREATE INDEX user_sessions_user_id_idx ON public.user_sessions USING btree (user_id)
+
+CREATE INDEX task_lists_owner_id_idx ON public.task_lists USING btree (owner_id)
+
+CREATE INDEX tasks_owner_id_idx ON public.tasks USING btree (owner_id)
+
+CREATE UNIQUE INDEX task_lists_id_owner_id_key ON public.task_lists USING btree (id, owner_id)
+
+CREATE UNIQUE INDEX tasks_id_owner_id_key ON public.tasks USING btree (id, owner_id)
+
+CREATE UNIQUE INDEX api_tokens_pkey ON public.api_tokens USING btree (id)
+
+CREATE UNIQUE INDEX api_tokens_token_digest_key ON public.api_tokens USING btree (token_digest)
+
+CREATE INDEX api_tokens_user_id_idx ON public.api_tokens USING btree (user_id)
+
+CREATE UNIQUE INDEX task_list_members_pkey ON public.task_list_members USING btree (list_id, user_id)
+
+CREATE INDEX task_list_members_user_id_idx ON public.task_list_members USING btree (user_id)
+
+CREATE INDEX tasks_assignee_id_idx ON public.tasks USING btree (assignee_id)
+
+CREATE UNIQUE INDEX idempotency_keys_pkey ON public.idempotency_keys USING btree (owner_digest, key)
+
+CREATE INDEX idempotency_keys_expires_at_idx ON public.idempotency_keys USING btree (expires_at)
+
+CREATE INDEX tasks_deleted_at_idx ON public.tasks USING btree (deleted_at) WHERE (deleted_at IS NOT NULL)
+
+CREATE INDEX task_lists_deleted_at_idx ON public.task_lists USING btree (deleted_at) WHERE (deleted_at IS NOT NULL)
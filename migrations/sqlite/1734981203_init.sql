@@ -0,0 +1,29 @@
+-- The SQLite schema mirrors the PostgreSQL migrations in the parent directory
+
+CREATE TABLE tasks (
+    id BLOB PRIMARY KEY NOT NULL,
+    title TEXT NOT NULL,
+    completed BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    due_at TEXT,
+    -- A JSON array of minutes before due_at, such as [60, 15]
+    reminder_offsets TEXT NOT NULL DEFAULT '[]',
+    -- An RFC 5545 RRULE value, such as FREQ=WEEKLY;INTERVAL=2;BYDAY=MO
+    recurrence TEXT,
+    completed_at TEXT
+);
+
+-- Titles are unique among incomplete tasks, ignoring case
+CREATE UNIQUE INDEX tasks_incomplete_title_idx ON tasks (lower(title)) WHERE NOT completed;
+
+CREATE TABLE task_reminders (
+    task_id BLOB NOT NULL REFERENCES tasks (id) ON DELETE CASCADE,
+    remind_at TEXT NOT NULL,
+    fired_at TEXT,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    PRIMARY KEY (task_id, remind_at)
+);
+
+CREATE INDEX task_reminders_pending_idx ON task_reminders (remind_at) WHERE fired_at IS NULL;
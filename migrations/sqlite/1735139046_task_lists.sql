@@ -0,0 +1,18 @@
+CREATE TABLE task_lists (
+    id BLOB PRIMARY KEY NOT NULL,
+    title TEXT NOT NULL,
+    colour TEXT,
+    position INTEGER NOT NULL DEFAULT 0,
+    archived BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- Titles are unique among unarchived lists, ignoring case
+CREATE UNIQUE INDEX task_lists_unarchived_title_idx ON task_lists (lower(title)) WHERE NOT archived;
+
+-- Tasks without a list are in the inbox. Deleting a list that still has tasks is refused, so the
+-- application decides whether they are deleted or moved to the inbox.
+ALTER TABLE tasks ADD COLUMN list_id BLOB REFERENCES task_lists (id);
+
+CREATE INDEX tasks_list_id_idx ON tasks (list_id);
@@ -0,0 +1,7 @@
+-- Subtasks are deleted along with the task they are nested below
+ALTER TABLE tasks ADD COLUMN parent_id BLOB REFERENCES tasks (id) ON DELETE CASCADE;
+
+-- The order of a task among its siblings, which share its parent or are all top-level
+ALTER TABLE tasks ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX tasks_parent_id_idx ON tasks (parent_id);
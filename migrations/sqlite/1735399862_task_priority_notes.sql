@@ -0,0 +1,6 @@
+-- Priorities are stored by name; the application bounds the length of notes and titles
+ALTER TABLE tasks ADD COLUMN priority TEXT NOT NULL DEFAULT 'none'
+    CHECK (priority IN ('none', 'low', 'medium', 'high', 'urgent'));
+
+-- Free-form Markdown notes
+ALTER TABLE tasks ADD COLUMN notes TEXT;
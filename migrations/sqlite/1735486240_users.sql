@@ -0,0 +1,44 @@
+CREATE TABLE users (
+    id BLOB PRIMARY KEY NOT NULL,
+    email TEXT NOT NULL UNIQUE,
+    password_hash TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- Sessions are stored under the SHA-256 digest of their token, never the token itself
+CREATE TABLE user_sessions (
+    token_digest TEXT PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
+
+CREATE INDEX user_sessions_user_id_idx ON user_sessions (user_id);
+
+-- Existing tasks and lists are given to a user who cannot sign in until an administrator sets
+-- their password, so that no data is lost or exposed
+INSERT INTO users (id, email, password_hash, created_at, updated_at)
+SELECT
+    randomblob(16), 'admin@localhost', '!',
+    strftime('%Y-%m-%dT%H:%M:%fZ', 'now'), strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE EXISTS (SELECT 1 FROM tasks) OR EXISTS (SELECT 1 FROM task_lists);
+
+-- SQLite cannot add NOT NULL columns or change foreign keys in place, so the application keeps
+-- tasks in lists, and below tasks, of their own owner
+ALTER TABLE task_lists ADD COLUMN owner_id BLOB REFERENCES users (id) ON DELETE CASCADE;
+UPDATE task_lists SET owner_id = (SELECT id FROM users WHERE email = 'admin@localhost');
+
+ALTER TABLE tasks ADD COLUMN owner_id BLOB REFERENCES users (id) ON DELETE CASCADE;
+UPDATE tasks SET owner_id = (SELECT id FROM users WHERE email = 'admin@localhost');
+
+CREATE INDEX task_lists_owner_id_idx ON task_lists (owner_id);
+CREATE INDEX tasks_owner_id_idx ON tasks (owner_id);
+
+-- Titles are unique per owner
+DROP INDEX tasks_incomplete_title_idx;
+CREATE UNIQUE INDEX tasks_incomplete_title_idx ON tasks (owner_id, lower(title)) WHERE NOT completed;
+
+DROP INDEX task_lists_unarchived_title_idx;
+CREATE UNIQUE INDEX task_lists_unarchived_title_idx ON task_lists (owner_id, lower(title))
+WHERE NOT archived;
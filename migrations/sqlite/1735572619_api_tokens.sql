@@ -0,0 +1,14 @@
+-- API tokens are stored under the SHA-256 digest of their secret, never the secret itself
+CREATE TABLE api_tokens (
+    id BLOB PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    token_digest TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    access TEXT NOT NULL DEFAULT 'read_write' CHECK (access IN ('read_only', 'read_write')),
+    -- The application checks that the list belongs to the user of the token
+    list_id BLOB REFERENCES task_lists (id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL,
+    last_used_at TEXT
+);
+
+CREATE INDEX api_tokens_user_id_idx ON api_tokens (user_id);
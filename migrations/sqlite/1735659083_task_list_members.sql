@@ -0,0 +1,14 @@
+-- A list can be shared with other users. Its creator always owns it and is not listed here
+CREATE TABLE task_list_members (
+    list_id BLOB NOT NULL REFERENCES task_lists (id) ON DELETE CASCADE,
+    user_id BLOB NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    role TEXT NOT NULL CHECK (role IN ('viewer', 'editor', 'owner')),
+    added_at TEXT NOT NULL,
+    PRIMARY KEY (list_id, user_id)
+);
+
+CREATE INDEX task_list_members_user_id_idx ON task_list_members (user_id);
+
+-- Tasks in a shared list still belong to the owner of the list, so record who worked on them
+ALTER TABLE tasks ADD COLUMN updated_by BLOB REFERENCES users (id) ON DELETE SET NULL;
+ALTER TABLE tasks ADD COLUMN completed_by BLOB REFERENCES users (id) ON DELETE SET NULL;
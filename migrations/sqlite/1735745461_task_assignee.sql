@@ -0,0 +1,4 @@
+-- A task in a shared list can be assigned to one of its members, who then receives its reminders
+ALTER TABLE tasks ADD COLUMN assignee_id BLOB REFERENCES users (id) ON DELETE SET NULL;
+
+CREATE INDEX tasks_assignee_id_idx ON tasks (assignee_id);
@@ -0,0 +1,15 @@
+-- Requests made with an Idempotency-Key header, and their responses once handled, replayed to
+-- retries until the key expires. Keys are scoped to the digest of the credentials that sent them
+CREATE TABLE idempotency_keys (
+    owner_digest TEXT NOT NULL,
+    key TEXT NOT NULL,
+    fingerprint TEXT NOT NULL,
+    response_status INTEGER,
+    response_content_type TEXT,
+    response_body BLOB,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    PRIMARY KEY (owner_digest, key)
+);
+
+CREATE INDEX idempotency_keys_expires_at_idx ON idempotency_keys (expires_at);
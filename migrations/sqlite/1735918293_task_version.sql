@@ -0,0 +1,3 @@
+-- Incremented on every change to a task, so that clients can tell whether the task they edit
+-- was changed by someone else since they read it
+ALTER TABLE tasks ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
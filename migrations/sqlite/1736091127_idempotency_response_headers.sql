@@ -0,0 +1,3 @@
+-- The headers of stored responses other than Content-Type, such as ETag and Location, as a JSON
+-- array of "name: value" lines in the order they were sent
+ALTER TABLE idempotency_keys ADD COLUMN response_headers TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,207 @@
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use dotenvy::dotenv;
+use modus::config::{Config, DatabaseBackend};
+use modus::domain::readiness::ports::ReadinessRepository;
+use modus::outbound::sql::admin::Export;
+use modus::outbound::sql::Sql;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+
+/// Operate the modus database.
+///
+/// Connects to the database at DATABASE_URL, read from the environment or a .env file.
+#[derive(Debug, Parser)]
+#[command(name = "modus_admin", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Check that the database can be reached and is fully migrated
+    Check,
+    /// Apply, roll back or inspect migrations
+    Migrate {
+        #[command(subcommand)]
+        command: MigrateCommand,
+    },
+    /// Delete tasks that were completed a while ago
+    Purge {
+        /// Only delete tasks completed more than this many days ago
+        #[arg(long, value_name = "DAYS")]
+        older_than_days: u32,
+        /// Count the tasks that would be deleted without deleting them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Rebuild the indexes of all tables
+    Reindex {
+        /// Rebuild without blocking writes, which takes longer
+        #[arg(long)]
+        concurrently: bool,
+    },
+    /// Write all data as JSON
+    Export {
+        /// The file to write to instead of standard output
+        #[arg(long, short)]
+        file: Option<PathBuf>,
+    },
+    /// Load data written by `export` into a database at the same migration
+    Import {
+        /// The file to read from instead of standard input
+        #[arg(long, short)]
+        file: Option<PathBuf>,
+        /// Delete all existing data first instead of requiring an empty database
+        #[arg(long)]
+        replace: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum MigrateCommand {
+    /// Apply all pending migrations
+    Up,
+    /// Roll back the most recently applied migrations
+    Down {
+        /// How many migrations to roll back
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+    },
+    /// List migrations and whether they have been applied
+    Status,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenv().ok();
+    let cli = Cli::parse();
+    let config = Config::from_env()?;
+    if config.database_backend != DatabaseBackend::Postgres {
+        anyhow::bail!("modus_admin only supports PostgreSQL databases");
+    }
+    let sql = Sql::new(&config.database_url).await?;
+
+    match cli.command {
+        Command::Check => {
+            sql.is_ready().await?;
+            let version = sql.server_version().await?;
+            println!("Connected to PostgreSQL {}", version);
+            let pending = sql
+                .migration_status()
+                .await?
+                .into_iter()
+                .filter(|m| !m.applied)
+                .count();
+            if pending > 0 {
+                anyhow::bail!(
+                    "{} pending migrations, run `modus_admin migrate up`",
+                    pending
+                );
+            }
+            println!("All migrations applied");
+        }
+        Command::Migrate {
+            command: MigrateCommand::Up,
+        } => {
+            let migrated = sql.migrate_up().await?;
+            for migration in &migrated {
+                println!("Applied {} {}", migration.version, migration.description);
+            }
+            if migrated.is_empty() {
+                println!("No pending migrations");
+            }
+        }
+        Command::Migrate {
+            command: MigrateCommand::Down { steps },
+        } => {
+            let rolled_back = sql.migrate_down(steps).await?;
+            for migration in &rolled_back {
+                println!(
+                    "Rolled back {} {}",
+                    migration.version, migration.description
+                );
+            }
+            if rolled_back.is_empty() {
+                println!("No applied migrations");
+            }
+        }
+        Command::Migrate {
+            command: MigrateCommand::Status,
+        } => {
+            for migration in sql.migration_status().await? {
+                println!(
+                    "{:<8} {} {}",
+                    if migration.applied {
+                        "applied"
+                    } else {
+                        "pending"
+                    },
+                    migration.version,
+                    migration
+                        .description
+                        .as_deref()
+                        .unwrap_or("(unknown to this build)")
+                );
+            }
+        }
+        Command::Purge {
+            older_than_days,
+            dry_run,
+        } => {
+            let count = sql.purge_completed_tasks(older_than_days, dry_run).await?;
+            if dry_run {
+                println!("Would delete {} completed tasks", count);
+            } else {
+                println!("Deleted {} completed tasks", count);
+            }
+        }
+        Command::Reindex { concurrently } => {
+            for table in sql.reindex(concurrently).await? {
+                println!("Reindexed {}", table);
+            }
+        }
+        Command::Export { file } => {
+            let export = sql.export().await?;
+            let writer: Box<dyn Write> = match &file {
+                Some(path) => Box::new(
+                    File::create(path)
+                        .with_context(|| format!("failed to create {}", path.display()))?,
+                ),
+                None => Box::new(io::stdout().lock()),
+            };
+            let mut writer = BufWriter::new(writer);
+            serde_json::to_writer_pretty(&mut writer, &export)?;
+            writeln!(writer)?;
+            writer.flush()?;
+        }
+        Command::Import { file, replace } => {
+            let reader: Box<dyn Read> = match &file {
+                Some(path) => Box::new(
+                    File::open(path)
+                        .with_context(|| format!("failed to open {}", path.display()))?,
+                ),
+                None => Box::new(io::stdin().lock()),
+            };
+            let export: Export = serde_json::from_reader(BufReader::new(reader))
+                .context("failed to parse export")?;
+            for (table, count) in sql.import(&export, replace).await? {
+                println!("Imported {} rows into {}", count, table);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+}
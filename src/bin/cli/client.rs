@@ -0,0 +1,522 @@
+use reqwest::header::{HeaderMap, HeaderName, ETAG, IF_MATCH};
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// The envelope wrapping every successful response from the server.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResponseBody<T> {
+    pub data: T,
+}
+
+/// The body of an error response, an RFC 9457 problem details document.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Problem {
+    pub status: u16,
+    pub detail: String,
+}
+
+/// A task as returned by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Task {
+    pub id: String,
+    pub title: String,
+    pub completed: bool,
+    #[serde(default)]
+    pub priority: String,
+    pub notes: Option<String>,
+    pub due_at: Option<String>,
+    #[serde(default)]
+    pub remind_at: Vec<String>,
+    pub recurrence: Option<String>,
+    pub list_id: Option<String>,
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// The user the task is assigned to, if any.
+    pub assignee_id: Option<String>,
+    /// Only included when the task is fetched on its own.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtasks: Vec<Task>,
+}
+
+/// A task list as returned by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskList {
+    pub id: String,
+    pub title: String,
+    pub colour: Option<String>,
+    pub position: i32,
+    pub archived: bool,
+    /// The role of the signed in user in the list.
+    #[serde(default)]
+    pub role: String,
+}
+
+/// A deleted task or task list, as returned by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trashed<T> {
+    #[serde(flatten)]
+    pub item: T,
+    pub deleted_at: String,
+}
+
+/// The deleted tasks and task lists that can still be restored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trash {
+    pub lists: Vec<Trashed<TaskList>>,
+    pub tasks: Vec<Trashed<Task>>,
+}
+
+/// A user who may access a task list, as returned by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Member {
+    pub user_id: String,
+    pub email: String,
+    pub role: String,
+    pub added_at: String,
+}
+
+/// A tag as returned by the server, with the number of tasks that carry it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagUsage {
+    pub tag: String,
+    pub tasks: u64,
+}
+
+/// A page of tasks as returned by the server.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TaskPage {
+    pub tasks: Vec<Task>,
+    pub next_cursor: Option<String>,
+}
+
+/// A user account as returned by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub id: String,
+    pub email: String,
+    pub created_at: String,
+}
+
+/// A session as returned by the server when logging in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub token: String,
+    pub expires_at: String,
+    pub user: User,
+}
+
+/// An API token as returned by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    pub access: String,
+    pub list_id: Option<String>,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    /// Only included when the token is created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+/// The response data of a successful task creation.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreatedTask {
+    pub id: String,
+}
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("{message}")]
+    Api { status_code: u16, message: String },
+    #[error("failed to reach server at {url}")]
+    Connection {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("task {id} was changed since it was read, check it with `modus show` and try again")]
+    Conflict { id: String },
+    #[error("unexpected response from server: {0}")]
+    InvalidResponse(String),
+}
+
+impl ClientError {
+    /// The process exit code that reports this error to scripts, derived from the `status`
+    /// of the problem document. See [EXIT_CODES](crate::EXIT_CODES).
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            ClientError::Api { status_code, .. } => match StatusCode::from_u16(*status_code) {
+                Ok(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) => ExitCode::from(7),
+                Ok(StatusCode::NOT_FOUND) => ExitCode::from(3),
+                Ok(
+                    StatusCode::BAD_REQUEST
+                    | StatusCode::CONFLICT
+                    | StatusCode::UNPROCESSABLE_ENTITY,
+                ) => ExitCode::from(4),
+                Ok(status) if status.is_server_error() => ExitCode::from(5),
+                _ => ExitCode::from(1),
+            },
+            ClientError::Conflict { .. } => ExitCode::from(4),
+            ClientError::Connection { .. } => ExitCode::from(6),
+            ClientError::InvalidResponse(_) => ExitCode::from(1),
+        }
+    }
+}
+
+/// A client for the modus HTTP API.
+pub struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl Client {
+    /// Create a client that authenticates with the bearer `token` of a session, if given.
+    pub fn new(server_url: &str, token: Option<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: format!("{}/api", server_url.trim_end_matches('/')),
+            token,
+        }
+    }
+
+    pub async fn register_user(&self, email: &str, password: &str) -> Result<User, ClientError> {
+        let body = serde_json::json!({ "email": email, "password": password });
+        self.send(Method::POST, "/users", &[], Some(&body)).await
+    }
+
+    pub async fn get_current_user(&self) -> Result<User, ClientError> {
+        self.send(Method::GET, "/users/me", &[], None).await
+    }
+
+    pub async fn log_in(&self, email: &str, password: &str) -> Result<Session, ClientError> {
+        let body = serde_json::json!({ "email": email, "password": password });
+        self.send(Method::POST, "/sessions", &[], Some(&body)).await
+    }
+
+    pub async fn log_out(&self) -> Result<(), ClientError> {
+        self.send_raw(Method::DELETE, "/sessions/current", &[], None)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn create_api_token(&self, body: &Value) -> Result<ApiToken, ClientError> {
+        self.send(Method::POST, "/tokens", &[], Some(body)).await
+    }
+
+    pub async fn list_api_tokens(&self) -> Result<Vec<ApiToken>, ClientError> {
+        self.send(Method::GET, "/tokens", &[], None).await
+    }
+
+    pub async fn revoke_api_token(&self, id: &str) -> Result<(), ClientError> {
+        self.send_raw(Method::DELETE, &format!("/tokens/{}", id), &[], None)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn create_task(&self, body: &Value) -> Result<CreatedTask, ClientError> {
+        self.send(Method::POST, "/tasks", &[], Some(body)).await
+    }
+
+    /// List every task matching the query parameters `params`, following the cursor of each
+    /// page to the next.
+    pub async fn list_tasks(&self, params: &[(&str, String)]) -> Result<Vec<Task>, ClientError> {
+        let mut tasks = Vec::new();
+        let mut params = params.to_vec();
+        params.push(("limit", "100".to_string()));
+        loop {
+            let page: TaskPage = self.send(Method::GET, "/tasks", &params, None).await?;
+            tasks.extend(page.tasks);
+            match page.next_cursor {
+                Some(cursor) => {
+                    params.retain(|(name, _)| *name != "cursor");
+                    params.push(("cursor", cursor));
+                }
+                None => return Ok(tasks),
+            }
+        }
+    }
+
+    pub async fn get_task(&self, id: &str) -> Result<Task, ClientError> {
+        self.send(Method::GET, &format!("/tasks/{}", id), &[], None)
+            .await
+    }
+
+    /// The `ETag` of the current version of a task, to send back in `If-Match` when changing it.
+    pub async fn get_task_etag(&self, id: &str) -> Result<String, ClientError> {
+        let path = format!("/tasks/{}", id);
+        let (headers, _) = self
+            .send_raw_with(Method::GET, &path, &[], None, &[])
+            .await?;
+        headers
+            .get(ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(str::to_string)
+            .ok_or_else(|| ClientError::InvalidResponse("task has no ETag".to_string()))
+    }
+
+    /// Change a task, provided it is still at the version of `etag`.
+    pub async fn update_task(
+        &self,
+        id: &str,
+        body: &Value,
+        etag: &str,
+    ) -> Result<Task, ClientError> {
+        let path = format!("/tasks/{}", id);
+        let (_, bytes) = self
+            .send_raw_with(Method::PATCH, &path, &[], Some(body), &[(IF_MATCH, etag)])
+            .await
+            .map_err(|e| changed(e, id))?;
+        decode(&bytes)
+    }
+
+    /// Delete a task, provided it is still at the version of `etag`.
+    pub async fn delete_task(&self, id: &str, etag: &str) -> Result<(), ClientError> {
+        let path = format!("/tasks/{}", id);
+        self.send_raw_with(Method::DELETE, &path, &[], None, &[(IF_MATCH, etag)])
+            .await
+            .map(|_| ())
+            .map_err(|e| changed(e, id))
+    }
+
+    pub async fn restore_task(&self, id: &str) -> Result<Task, ClientError> {
+        self.send(Method::POST, &format!("/tasks/{}/restore", id), &[], None)
+            .await
+    }
+
+    pub async fn create_list(&self, body: &Value) -> Result<TaskList, ClientError> {
+        self.send(Method::POST, "/lists", &[], Some(body)).await
+    }
+
+    pub async fn list_lists(&self, archived: bool) -> Result<Vec<TaskList>, ClientError> {
+        let params = [("archived", archived.to_string())];
+        self.send(Method::GET, "/lists", &params, None).await
+    }
+
+    pub async fn update_list(&self, id: &str, body: &Value) -> Result<TaskList, ClientError> {
+        self.send(Method::PATCH, &format!("/lists/{}", id), &[], Some(body))
+            .await
+    }
+
+    /// Delete a list, doing with its tasks what `tasks` says: `delete` or `move_to_inbox`.
+    pub async fn delete_list(&self, id: &str, tasks: Option<&str>) -> Result<(), ClientError> {
+        let params = tasks
+            .map(|tasks| vec![("tasks", tasks.to_string())])
+            .unwrap_or_default();
+        self.send_raw(Method::DELETE, &format!("/lists/{}", id), &params, None)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn restore_list(&self, id: &str) -> Result<TaskList, ClientError> {
+        self.send(Method::POST, &format!("/lists/{}/restore", id), &[], None)
+            .await
+    }
+
+    pub async fn list_trash(&self) -> Result<Trash, ClientError> {
+        self.send(Method::GET, "/trash", &[], None).await
+    }
+
+    pub async fn empty_trash(&self) -> Result<(), ClientError> {
+        self.send_raw(Method::DELETE, "/trash", &[], None)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn list_members(&self, id: &str) -> Result<Vec<Member>, ClientError> {
+        self.send(Method::GET, &format!("/lists/{}/members", id), &[], None)
+            .await
+    }
+
+    /// Share a list with the user registered under `email`, or change their role in it.
+    pub async fn share_list(
+        &self,
+        id: &str,
+        email: &str,
+        role: &str,
+    ) -> Result<Member, ClientError> {
+        let body = serde_json::json!({ "email": email, "role": role });
+        self.send(
+            Method::POST,
+            &format!("/lists/{}/members", id),
+            &[],
+            Some(&body),
+        )
+        .await
+    }
+
+    pub async fn unshare_list(&self, id: &str, user_id: &str) -> Result<(), ClientError> {
+        let path = format!("/lists/{}/members/{}", id, user_id);
+        self.send_raw(Method::DELETE, &path, &[], None)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn tag_task(&self, id: &str, tags: &[String]) -> Result<Task, ClientError> {
+        let body = serde_json::json!({ "tags": tags });
+        self.send(
+            Method::POST,
+            &format!("/tasks/{}/tags", id),
+            &[],
+            Some(&body),
+        )
+        .await
+    }
+
+    pub async fn untag_task(&self, id: &str, tag: &str) -> Result<Task, ClientError> {
+        let path = format!("/tasks/{}/tags/{}", id, path_segment(tag));
+        self.send(Method::DELETE, &path, &[], None).await
+    }
+
+    pub async fn list_tags(&self) -> Result<Vec<TagUsage>, ClientError> {
+        self.send(Method::GET, "/tags", &[], None).await
+    }
+
+    pub async fn rename_tag(&self, tag: &str, to: &str) -> Result<TagUsage, ClientError> {
+        let body = serde_json::json!({ "tag": to });
+        self.send(
+            Method::PATCH,
+            &format!("/tags/{}", path_segment(tag)),
+            &[],
+            Some(&body),
+        )
+        .await
+    }
+
+    /// Merge `tags` into `into`, which may be a new tag.
+    pub async fn merge_tags(&self, tags: &[String], into: &str) -> Result<TagUsage, ClientError> {
+        let body = serde_json::json!({ "tags": tags });
+        let path = format!("/tags/{}/merge", path_segment(into));
+        self.send(Method::POST, &path, &[], Some(&body)).await
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<&Value>,
+    ) -> Result<T, ClientError> {
+        let bytes = self.send_raw(method, path, query, body).await?;
+        decode(&bytes)
+    }
+
+    async fn send_raw(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<&Value>,
+    ) -> Result<Vec<u8>, ClientError> {
+        self.send_raw_with(method, path, query, body, &[])
+            .await
+            .map(|(_, bytes)| bytes)
+    }
+
+    async fn send_raw_with(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<&Value>,
+        headers: &[(HeaderName, &str)],
+    ) -> Result<(HeaderMap, Vec<u8>), ClientError> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut request = self.http.request(method, &url).query(query);
+        for (name, value) in headers {
+            request = request.header(name, *value);
+        }
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let connection_error = |source| ClientError::Connection {
+            url: url.clone(),
+            source,
+        };
+        let response = request.send().await.map_err(connection_error)?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = response.bytes().await.map_err(connection_error)?;
+        if status.is_success() {
+            return Ok((headers, bytes.to_vec()));
+        }
+
+        // Responses that do not come from the API, such as from a proxy, may not be problems
+        let error = match serde_json::from_slice::<Problem>(&bytes) {
+            Ok(problem) => ClientError::Api {
+                status_code: problem.status,
+                message: problem.detail,
+            },
+            Err(_) => ClientError::Api {
+                status_code: status.as_u16(),
+                message: String::from_utf8_lossy(&bytes).trim().to_string(),
+            },
+        };
+        Err(error)
+    }
+}
+
+/// Report a change to the task with `id` that the server refused because the task changed
+/// since its `ETag` was read. The change is not retried, as it was based on an older version.
+fn changed(e: ClientError, id: &str) -> ClientError {
+    match e {
+        ClientError::Api { status_code, .. }
+            if status_code == StatusCode::PRECONDITION_FAILED.as_u16() =>
+        {
+            ClientError::Conflict { id: id.to_string() }
+        }
+        e => e,
+    }
+}
+
+fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, ClientError> {
+    serde_json::from_slice::<ResponseBody<T>>(bytes)
+        .map(|body| body.data)
+        .map_err(|e| ClientError::InvalidResponse(e.to_string()))
+}
+
+/// Percent-encode `raw` for use as a single segment of a URL path, as tags may contain spaces.
+fn path_segment(raw: &str) -> String {
+    let mut url = reqwest::Url::parse("http://localhost/").expect("the base URL is valid");
+    url.path_segments_mut()
+        .expect("the base URL can have path segments")
+        .push(raw);
+    url.path().trim_start_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refused_changes_are_reported_as_conflicts() {
+        let refused = ClientError::Api {
+            status_code: 412,
+            message: "task was changed".to_string(),
+        };
+        let conflict = changed(refused, "42");
+        assert!(matches!(&conflict, ClientError::Conflict { id } if id == "42"));
+        assert_eq!(conflict.exit_code(), ExitCode::from(4));
+
+        let missing = ClientError::Api {
+            status_code: 404,
+            message: "task not found".to_string(),
+        };
+        assert!(matches!(
+            changed(missing, "42"),
+            ClientError::Api {
+                status_code: 404,
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,793 @@
+use clap::{Parser, Subcommand};
+use client::{Client, ClientError};
+use output::OutputFormat;
+use serde_json::{json, Map, Value};
+use std::io::{self, BufRead};
+use std::process::ExitCode;
+
+mod client;
+mod output;
+
+/// Documents how failures are reported to scripts.
+const EXIT_CODES: &str = "\
+Exit codes:
+  0  success
+  1  unexpected error
+  3  the task, list or tag does not exist
+  4  the request was rejected as invalid
+  5  the server failed to handle the request
+  6  the server could not be reached
+  7  the request was not authenticated or not allowed";
+
+/// Manage reminders from the terminal.
+#[derive(Debug, Parser)]
+#[command(name = "modus", version, after_help = EXIT_CODES)]
+struct Cli {
+    /// The URL of the modus server
+    #[arg(
+        long,
+        global = true,
+        env = "MODUS_SERVER_URL",
+        default_value = "http://localhost:8080"
+    )]
+    server: String,
+
+    /// How to format results
+    #[arg(
+        long,
+        short,
+        global = true,
+        env = "MODUS_OUTPUT",
+        value_enum,
+        default_value_t = OutputFormat::Table
+    )]
+    output: OutputFormat,
+
+    /// The session token or API token secret to authenticate with, as printed by `modus login`
+    /// or `modus tokens create`
+    #[arg(long, global = true, env = "MODUS_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Create a user account
+    Register {
+        /// The email address to log in with
+        #[arg(long)]
+        email: String,
+        /// The password, read from the first line of standard input when not given
+        #[arg(long, env = "MODUS_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+    },
+    /// Log in and print a session token to pass as --token or MODUS_TOKEN
+    Login {
+        /// The email address of the account
+        #[arg(long)]
+        email: String,
+        /// The password, read from the first line of standard input when not given
+        #[arg(long, env = "MODUS_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+    },
+    /// End the session of the token
+    Logout,
+    /// Show the account the token belongs to
+    Whoami,
+    /// Add a new task
+    Add {
+        /// The title of the task
+        title: String,
+        /// How important the task is: none, low, medium, high or urgent
+        #[arg(long)]
+        priority: Option<String>,
+        /// Notes on the task, in Markdown
+        #[arg(long)]
+        notes: Option<String>,
+        /// When the task is due, as an RFC 3339 timestamp such as 2025-01-31T09:00:00+01:00
+        #[arg(long)]
+        due: Option<String>,
+        /// Remind this long before the task is due, such as 15m, 2h, 1d or 1w (repeatable)
+        #[arg(long = "remind", value_name = "OFFSET")]
+        remind_at: Vec<String>,
+        /// Repeat the task according to an RFC 5545 RRULE, such as FREQ=WEEKLY;BYDAY=MO
+        #[arg(long = "repeat", value_name = "RRULE")]
+        recurrence: Option<String>,
+        /// Add the task to the list with this id instead of the inbox
+        #[arg(long = "list", value_name = "LIST_ID")]
+        list_id: Option<String>,
+        /// Add the task as a subtask of the task with this id
+        #[arg(long = "parent", value_name = "TASK_ID")]
+        parent_id: Option<String>,
+        /// Tag the task (repeatable)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+        /// Assign the task to the member of its list with this user id
+        #[arg(long = "assign", value_name = "USER_ID")]
+        assignee_id: Option<String>,
+    },
+    /// List tasks
+    #[command(alias = "ls")]
+    List {
+        /// Only list tasks whose title contains this text, ignoring case
+        #[arg(long)]
+        search: Option<String>,
+        /// Only list completed tasks
+        #[arg(long, conflicts_with = "pending")]
+        completed: bool,
+        /// Only list tasks that are not completed
+        #[arg(long)]
+        pending: bool,
+        /// Only list tasks due before this RFC 3339 timestamp
+        #[arg(long, value_name = "TIMESTAMP")]
+        due_before: Option<String>,
+        /// Only list tasks due after this RFC 3339 timestamp
+        #[arg(long, value_name = "TIMESTAMP")]
+        due_after: Option<String>,
+        /// Order by created, due or priority, prefixed with - for descending order
+        #[arg(long, allow_hyphen_values = true)]
+        sort: Option<String>,
+        /// Only list tasks in the list with this id, or in the inbox with `inbox`
+        #[arg(long, value_name = "LIST_ID")]
+        list: Option<String>,
+        /// Only list tasks with this tag (repeatable), all of them unless --any-tag is given
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+        /// Only list tasks with any of the tags rather than all of them
+        #[arg(long, requires = "tags")]
+        any_tag: bool,
+        /// Only list tasks assigned to you
+        #[arg(long)]
+        mine: bool,
+    },
+    /// Show the details of a task and its subtasks
+    Show {
+        /// The id of the task
+        id: String,
+    },
+    /// Mark a task as completed
+    Done {
+        /// The id of the task
+        id: String,
+        /// Mark the task as not completed instead
+        #[arg(long)]
+        undo: bool,
+    },
+    /// Change a task
+    Edit {
+        /// The id of the task
+        id: String,
+        /// The new title of the task
+        #[arg(long)]
+        title: Option<String>,
+        /// How important the task is: none, low, medium, high or urgent
+        #[arg(long)]
+        priority: Option<String>,
+        /// Replace the notes on the task, in Markdown
+        #[arg(long, conflicts_with = "clear_notes")]
+        notes: Option<String>,
+        /// Remove the notes
+        #[arg(long)]
+        clear_notes: bool,
+        /// When the task is due, as an RFC 3339 timestamp
+        #[arg(long, conflicts_with = "clear_due")]
+        due: Option<String>,
+        /// Remove the due date
+        #[arg(long)]
+        clear_due: bool,
+        /// Replace the reminders with these offsets before the due date (repeatable)
+        #[arg(
+            long = "remind",
+            value_name = "OFFSET",
+            conflicts_with = "clear_remind"
+        )]
+        remind_at: Vec<String>,
+        /// Remove all reminders
+        #[arg(long)]
+        clear_remind: bool,
+        /// Repeat the task according to an RFC 5545 RRULE
+        #[arg(long = "repeat", value_name = "RRULE", conflicts_with = "clear_repeat")]
+        recurrence: Option<String>,
+        /// Stop repeating the task
+        #[arg(long)]
+        clear_repeat: bool,
+        /// Move the task to the list with this id
+        #[arg(long = "list", value_name = "LIST_ID", conflicts_with = "inbox")]
+        list_id: Option<String>,
+        /// Move the task out of its list into the inbox
+        #[arg(long)]
+        inbox: bool,
+        /// Move the task, with its subtasks, below the task with this id
+        #[arg(long = "parent", value_name = "TASK_ID", conflicts_with = "top_level")]
+        parent_id: Option<String>,
+        /// Make a subtask a top-level task
+        #[arg(long)]
+        top_level: bool,
+        /// Move the task to this position among its siblings
+        #[arg(long, allow_hyphen_values = true)]
+        position: Option<i32>,
+        /// Replace the tags of the task with these (repeatable)
+        #[arg(long = "tag", value_name = "TAG", conflicts_with = "clear_tags")]
+        tags: Vec<String>,
+        /// Remove all tags
+        #[arg(long)]
+        clear_tags: bool,
+        /// Assign the task to the member of its list with this user id
+        #[arg(long = "assign", value_name = "USER_ID", conflicts_with = "unassign")]
+        assignee_id: Option<String>,
+        /// Unassign the task
+        #[arg(long)]
+        unassign: bool,
+    },
+    /// Add tags to a task
+    Tag {
+        /// The id of the task
+        id: String,
+        /// The tags to add
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// Remove a tag from a task
+    Untag {
+        /// The id of the task
+        id: String,
+        /// The tag to remove
+        tag: String,
+    },
+    /// Move a task and its subtasks to the trash
+    #[command(alias = "delete")]
+    Rm {
+        /// The id of the task
+        id: String,
+    },
+    /// Restore a task and its subtasks from the trash
+    Restore {
+        /// The id of the task
+        id: String,
+    },
+    /// Manage deleted tasks and lists
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommand,
+    },
+    /// Manage task lists
+    Lists {
+        #[command(subcommand)]
+        command: ListsCommand,
+    },
+    /// Manage tags
+    Tags {
+        #[command(subcommand)]
+        command: TagsCommand,
+    },
+    /// Manage API tokens for scripts and integrations
+    Tokens {
+        #[command(subcommand)]
+        command: TokensCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ListsCommand {
+    /// Add a new task list
+    Add {
+        /// The title of the list
+        title: String,
+        /// The colour of the list, as a hex code such as #1e90ff
+        #[arg(long)]
+        colour: Option<String>,
+    },
+    /// List task lists
+    #[command(alias = "list")]
+    Ls {
+        /// Include archived lists
+        #[arg(long)]
+        archived: bool,
+    },
+    /// Rename a task list
+    Rename {
+        /// The id of the list
+        id: String,
+        /// The new title of the list
+        title: String,
+    },
+    /// Archive a task list, hiding it from listings
+    Archive {
+        /// The id of the list
+        id: String,
+        /// Unarchive the list instead
+        #[arg(long)]
+        undo: bool,
+    },
+    /// Move a task list to the trash, which must be empty unless --cascade or --move-to-inbox
+    /// is given
+    #[command(alias = "delete")]
+    Rm {
+        /// The id of the list
+        id: String,
+        /// Move the tasks in the list to the trash too
+        #[arg(long, conflicts_with = "move_to_inbox")]
+        cascade: bool,
+        /// Move the tasks in the list to the inbox
+        #[arg(long)]
+        move_to_inbox: bool,
+    },
+    /// Restore a task list and its tasks from the trash
+    Restore {
+        /// The id of the list
+        id: String,
+    },
+    /// List the users who may access a task list
+    Members {
+        /// The id of the list
+        id: String,
+    },
+    /// Share a task list with another user, or change their role in it
+    Share {
+        /// The id of the list
+        id: String,
+        /// The email address the user registered with
+        email: String,
+        /// What the user may do: viewer, editor or owner
+        #[arg(long, default_value = "viewer")]
+        role: String,
+    },
+    /// Stop sharing a task list with a user, or leave a list shared with you
+    Unshare {
+        /// The id of the list
+        id: String,
+        /// The id of the user
+        user_id: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum TrashCommand {
+    /// List deleted tasks and lists, most recently deleted first
+    #[command(alias = "list")]
+    Ls,
+    /// Permanently delete the tasks and lists in the trash
+    Empty,
+}
+
+#[derive(Debug, Subcommand)]
+enum TagsCommand {
+    /// List tags with the number of tasks that carry them
+    #[command(alias = "list")]
+    Ls,
+    /// Rename a tag on every task that carries it
+    Rename {
+        /// The current name of the tag
+        tag: String,
+        /// The new name of the tag
+        to: String,
+    },
+    /// Merge tags into another tag on every task that carries them
+    Merge {
+        /// The tags to merge
+        #[arg(required = true)]
+        tags: Vec<String>,
+        /// The tag to merge them into, which may be new
+        #[arg(long)]
+        into: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum TokensCommand {
+    /// Create an API token and print its secret, which is only shown once
+    Create {
+        /// A name to tell the token apart by
+        #[arg(long)]
+        name: String,
+        /// Only allow the token to read data
+        #[arg(long)]
+        read_only: bool,
+        /// Restrict the token to the list with this id
+        #[arg(long = "list", value_name = "LIST_ID")]
+        list_id: Option<String>,
+    },
+    /// List API tokens with when they were last used
+    #[command(alias = "list")]
+    Ls,
+    /// Revoke an API token
+    #[command(alias = "rm")]
+    Revoke {
+        /// The id of the token
+        id: String,
+    },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let client = Client::new(&cli.server, cli.token);
+    match run(&client, cli.output, cli.command).await {
+        Ok(()) => ExitCode::SUCCESS,
+        // The reader of our output went away, as with `modus list | head`
+        Err(e) if is_broken_pipe(&e) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            match e.downcast_ref::<ClientError>() {
+                Some(e) => e.exit_code(),
+                None => ExitCode::FAILURE,
+            }
+        }
+    }
+}
+
+fn is_broken_pipe(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<io::Error>()
+        .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe)
+}
+
+/// The password given on the command line, or else the first line of standard input.
+fn read_password(password: Option<String>) -> anyhow::Result<String> {
+    if let Some(password) = password {
+        return Ok(password);
+    }
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+async fn run(client: &Client, format: OutputFormat, command: Command) -> anyhow::Result<()> {
+    match command {
+        Command::Register { email, password } => {
+            let password = read_password(password)?;
+            let user = client.register_user(&email, &password).await?;
+            Ok(output::print_user(format, &user)?)
+        }
+        Command::Login { email, password } => {
+            let password = read_password(password)?;
+            let session = client.log_in(&email, &password).await?;
+            Ok(output::print_session(format, &session)?)
+        }
+        Command::Logout => {
+            client.log_out().await?;
+            Ok(output::print_message(
+                format,
+                "Logged out",
+                &json!({ "logged_out": true }),
+            )?)
+        }
+        Command::Whoami => {
+            let user = client.get_current_user().await?;
+            Ok(output::print_user(format, &user)?)
+        }
+        Command::Add {
+            title,
+            priority,
+            notes,
+            due,
+            remind_at,
+            recurrence,
+            list_id,
+            parent_id,
+            tags,
+            assignee_id,
+        } => {
+            let mut body = Map::new();
+            body.insert("title".to_string(), json!(title));
+            body.insert("remind_at".to_string(), json!(remind_at));
+            body.insert("tags".to_string(), json!(tags));
+            if let Some(priority) = priority {
+                body.insert("priority".to_string(), json!(priority));
+            }
+            if let Some(notes) = notes {
+                body.insert("notes".to_string(), json!(notes));
+            }
+            if let Some(due) = due {
+                body.insert("due_at".to_string(), json!(due));
+            }
+            if let Some(recurrence) = recurrence {
+                body.insert("recurrence".to_string(), json!(recurrence));
+            }
+            if let Some(list_id) = list_id {
+                body.insert("list_id".to_string(), json!(list_id));
+            }
+            if let Some(parent_id) = parent_id {
+                body.insert("parent_id".to_string(), json!(parent_id));
+            }
+            if let Some(assignee_id) = assignee_id {
+                body.insert("assignee_id".to_string(), json!(assignee_id));
+            }
+            let created = client.create_task(&Value::Object(body)).await?;
+            let task = client.get_task(&created.id).await?;
+            Ok(output::print_task(format, &task)?)
+        }
+        Command::List {
+            search,
+            completed,
+            pending,
+            due_before,
+            due_after,
+            sort,
+            list,
+            tags,
+            any_tag,
+            mine,
+        } => {
+            let mut params = Vec::new();
+            if completed || pending {
+                params.push(("completed", completed.to_string()));
+            }
+            if !tags.is_empty() {
+                params.push(("tag", tags.join(",")));
+            }
+            if any_tag {
+                params.push(("tag_match", "any".to_string()));
+            }
+            if mine {
+                params.push(("assignee", "me".to_string()));
+            }
+            let optional = [
+                ("q", search),
+                ("due_before", due_before),
+                ("due_after", due_after),
+                ("sort", sort),
+                ("list", list),
+            ];
+            for (name, value) in optional {
+                if let Some(value) = value {
+                    params.push((name, value));
+                }
+            }
+            let tasks = client.list_tasks(&params).await?;
+            Ok(output::print_tasks(format, &tasks)?)
+        }
+        Command::Show { id } => {
+            let task = client.get_task(&id).await?;
+            Ok(output::print_task(format, &task)?)
+        }
+        Command::Done { id, undo } => {
+            let etag = client.get_task_etag(&id).await?;
+            let task = client
+                .update_task(&id, &json!({ "completed": !undo }), &etag)
+                .await?;
+            Ok(output::print_task(format, &task)?)
+        }
+        Command::Edit {
+            id,
+            title,
+            priority,
+            notes,
+            clear_notes,
+            due,
+            clear_due,
+            remind_at,
+            clear_remind,
+            recurrence,
+            clear_repeat,
+            list_id,
+            inbox,
+            parent_id,
+            top_level,
+            position,
+            tags,
+            clear_tags,
+            assignee_id,
+            unassign,
+        } => {
+            let mut body = Map::new();
+            if let Some(title) = title {
+                body.insert("title".to_string(), json!(title));
+            }
+            if let Some(priority) = priority {
+                body.insert("priority".to_string(), json!(priority));
+            }
+            if notes.is_some() || clear_notes {
+                body.insert("notes".to_string(), json!(notes));
+            }
+            if due.is_some() || clear_due {
+                body.insert("due_at".to_string(), json!(due));
+            }
+            if !remind_at.is_empty() || clear_remind {
+                body.insert("remind_at".to_string(), json!(remind_at));
+            }
+            if recurrence.is_some() || clear_repeat {
+                body.insert("recurrence".to_string(), json!(recurrence));
+            }
+            if list_id.is_some() || inbox {
+                body.insert("list_id".to_string(), json!(list_id));
+            }
+            if parent_id.is_some() || top_level {
+                body.insert("parent_id".to_string(), json!(parent_id));
+            }
+            if let Some(position) = position {
+                body.insert("position".to_string(), json!(position));
+            }
+            if !tags.is_empty() || clear_tags {
+                body.insert("tags".to_string(), json!(tags));
+            }
+            if assignee_id.is_some() || unassign {
+                body.insert("assignee_id".to_string(), json!(assignee_id));
+            }
+            if body.is_empty() {
+                anyhow::bail!("nothing to change, see `modus edit --help`");
+            }
+            let etag = client.get_task_etag(&id).await?;
+            let task = client.update_task(&id, &Value::Object(body), &etag).await?;
+            Ok(output::print_task(format, &task)?)
+        }
+        Command::Rm { id } => {
+            let etag = client.get_task_etag(&id).await?;
+            client.delete_task(&id, &etag).await?;
+            Ok(output::print_message(
+                format,
+                &format!("Moved task {} to the trash", id),
+                &json!({ "id": id, "deleted": true }),
+            )?)
+        }
+        Command::Restore { id } => {
+            let task = client.restore_task(&id).await?;
+            Ok(output::print_task(format, &task)?)
+        }
+        Command::Trash { command } => run_trash(client, format, command).await,
+        Command::Tag { id, tags } => {
+            let task = client.tag_task(&id, &tags).await?;
+            Ok(output::print_task(format, &task)?)
+        }
+        Command::Untag { id, tag } => {
+            let task = client.untag_task(&id, &tag).await?;
+            Ok(output::print_task(format, &task)?)
+        }
+        Command::Lists { command } => run_lists(client, format, command).await,
+        Command::Tags { command } => run_tags(client, format, command).await,
+        Command::Tokens { command } => run_tokens(client, format, command).await,
+    }
+}
+
+async fn run_lists(
+    client: &Client,
+    format: OutputFormat,
+    command: ListsCommand,
+) -> anyhow::Result<()> {
+    match command {
+        ListsCommand::Add { title, colour } => {
+            let list = client
+                .create_list(&json!({ "title": title, "colour": colour }))
+                .await?;
+            Ok(output::print_lists(format, &[list])?)
+        }
+        ListsCommand::Ls { archived } => {
+            let lists = client.list_lists(archived).await?;
+            Ok(output::print_lists(format, &lists)?)
+        }
+        ListsCommand::Rename { id, title } => {
+            let list = client.update_list(&id, &json!({ "title": title })).await?;
+            Ok(output::print_lists(format, &[list])?)
+        }
+        ListsCommand::Archive { id, undo } => {
+            let list = client
+                .update_list(&id, &json!({ "archived": !undo }))
+                .await?;
+            Ok(output::print_lists(format, &[list])?)
+        }
+        ListsCommand::Rm {
+            id,
+            cascade,
+            move_to_inbox,
+        } => {
+            let tasks = if cascade {
+                Some("delete")
+            } else if move_to_inbox {
+                Some("move_to_inbox")
+            } else {
+                None
+            };
+            client.delete_list(&id, tasks).await?;
+            Ok(output::print_message(
+                format,
+                &format!("Moved list {} to the trash", id),
+                &json!({ "id": id, "deleted": true }),
+            )?)
+        }
+        ListsCommand::Restore { id } => {
+            let list = client.restore_list(&id).await?;
+            Ok(output::print_lists(format, &[list])?)
+        }
+        ListsCommand::Members { id } => {
+            let members = client.list_members(&id).await?;
+            Ok(output::print_members(format, &members)?)
+        }
+        ListsCommand::Share { id, email, role } => {
+            let member = client.share_list(&id, &email, &role).await?;
+            Ok(output::print_members(format, &[member])?)
+        }
+        ListsCommand::Unshare { id, user_id } => {
+            client.unshare_list(&id, &user_id).await?;
+            Ok(output::print_message(
+                format,
+                &format!("Stopped sharing list {} with user {}", id, user_id),
+                &json!({ "id": id, "user_id": user_id, "unshared": true }),
+            )?)
+        }
+    }
+}
+
+async fn run_trash(
+    client: &Client,
+    format: OutputFormat,
+    command: TrashCommand,
+) -> anyhow::Result<()> {
+    match command {
+        TrashCommand::Ls => {
+            let trash = client.list_trash().await?;
+            Ok(output::print_trash(format, &trash)?)
+        }
+        TrashCommand::Empty => {
+            client.empty_trash().await?;
+            Ok(output::print_message(
+                format,
+                "Emptied the trash",
+                &json!({ "emptied": true }),
+            )?)
+        }
+    }
+}
+
+async fn run_tags(
+    client: &Client,
+    format: OutputFormat,
+    command: TagsCommand,
+) -> anyhow::Result<()> {
+    match command {
+        TagsCommand::Ls => {
+            let tags = client.list_tags().await?;
+            Ok(output::print_tags(format, &tags)?)
+        }
+        TagsCommand::Rename { tag, to } => {
+            let tag = client.rename_tag(&tag, &to).await?;
+            Ok(output::print_tags(format, &[tag])?)
+        }
+        TagsCommand::Merge { tags, into } => {
+            let tag = client.merge_tags(&tags, &into).await?;
+            Ok(output::print_tags(format, &[tag])?)
+        }
+    }
+}
+
+async fn run_tokens(
+    client: &Client,
+    format: OutputFormat,
+    command: TokensCommand,
+) -> anyhow::Result<()> {
+    match command {
+        TokensCommand::Create {
+            name,
+            read_only,
+            list_id,
+        } => {
+            let access = if read_only { "read_only" } else { "read_write" };
+            let body = json!({ "name": name, "access": access, "list_id": list_id });
+            let token = client.create_api_token(&body).await?;
+            Ok(output::print_new_api_token(format, &token)?)
+        }
+        TokensCommand::Ls => {
+            let tokens = client.list_api_tokens().await?;
+            Ok(output::print_api_tokens(format, &tokens)?)
+        }
+        TokensCommand::Revoke { id } => {
+            client.revoke_api_token(&id).await?;
+            Ok(output::print_message(
+                format,
+                &format!("Revoked token {}", id),
+                &json!({ "id": id, "revoked": true }),
+            )?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+}
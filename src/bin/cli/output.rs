@@ -0,0 +1,282 @@
+use crate::client::{ApiToken, Member, Session, TagUsage, Task, TaskList, Trash, User};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::{self, Write};
+
+/// How results are written to standard output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable tables.
+    Table,
+    /// The response data as JSON, for scripts.
+    Json,
+}
+
+/// Print a list of tasks.
+pub fn print_tasks(format: OutputFormat, tasks: &[Task]) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => print_json(&tasks),
+        OutputFormat::Table => {
+            let rows = tasks
+                .iter()
+                .map(|task| {
+                    [
+                        task.id.clone(),
+                        if task.completed { "x" } else { " " }.to_string(),
+                        task.due_at.clone().unwrap_or_default(),
+                        task.title.clone(),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            print_table(["ID", "DONE", "DUE", "TITLE"], &rows)
+        }
+    }
+}
+
+/// Print the details of a single task.
+pub fn print_task(format: OutputFormat, task: &Task) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => print_json(task),
+        OutputFormat::Table => {
+            let rows = [
+                ["ID".to_string(), task.id.clone()],
+                ["TITLE".to_string(), task.title.clone()],
+                ["DONE".to_string(), yes_no(task.completed).to_string()],
+                ["PRIORITY".to_string(), task.priority.clone()],
+                ["DUE".to_string(), task.due_at.clone().unwrap_or_default()],
+                ["REMIND".to_string(), task.remind_at.join(", ")],
+                [
+                    "REPEAT".to_string(),
+                    task.recurrence.clone().unwrap_or_default(),
+                ],
+                ["LIST".to_string(), task.list_id.clone().unwrap_or_default()],
+                [
+                    "PARENT".to_string(),
+                    task.parent_id.clone().unwrap_or_default(),
+                ],
+                ["TAGS".to_string(), task.tags.join(", ")],
+                [
+                    "ASSIGNEE".to_string(),
+                    task.assignee_id.clone().unwrap_or_default(),
+                ],
+            ];
+            let mut out = io::stdout().lock();
+            for [field, value] in rows {
+                writeln!(out, "{:<9} {}", field, value)?;
+            }
+            if let Some(notes) = &task.notes {
+                writeln!(out, "NOTES")?;
+                for line in notes.lines() {
+                    writeln!(out, "  {}", line)?;
+                }
+            }
+            if !task.subtasks.is_empty() {
+                writeln!(out, "SUBTASKS")?;
+                write_subtasks(&mut out, &task.subtasks, 1)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Write `subtasks` as a checklist, indented by `depth`.
+fn write_subtasks(out: &mut impl Write, subtasks: &[Task], depth: usize) -> io::Result<()> {
+    for subtask in subtasks {
+        let done = if subtask.completed { "x" } else { " " };
+        let indent = "  ".repeat(depth);
+        writeln!(
+            out,
+            "{}[{}] {}  {}",
+            indent, done, subtask.title, subtask.id
+        )?;
+        write_subtasks(out, &subtask.subtasks, depth + 1)?;
+    }
+    Ok(())
+}
+
+/// Print a list of task lists.
+pub fn print_lists(format: OutputFormat, lists: &[TaskList]) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => print_json(&lists),
+        OutputFormat::Table => {
+            let rows = lists
+                .iter()
+                .map(|list| {
+                    [
+                        list.id.clone(),
+                        list.colour.clone().unwrap_or_default(),
+                        if list.archived { "x" } else { " " }.to_string(),
+                        list.role.clone(),
+                        list.title.clone(),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            print_table(["ID", "COLOUR", "ARCHIVED", "ROLE", "TITLE"], &rows)
+        }
+    }
+}
+
+/// Print the members of a task list.
+pub fn print_members(format: OutputFormat, members: &[Member]) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => print_json(&members),
+        OutputFormat::Table => {
+            let rows = members
+                .iter()
+                .map(|member| {
+                    [
+                        member.user_id.clone(),
+                        member.role.clone(),
+                        member.email.clone(),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            print_table(["USER ID", "ROLE", "EMAIL"], &rows)
+        }
+    }
+}
+
+/// Print tags with the number of tasks that carry them.
+pub fn print_tags(format: OutputFormat, tags: &[TagUsage]) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => print_json(&tags),
+        OutputFormat::Table => {
+            let rows = tags
+                .iter()
+                .map(|usage| [usage.tag.clone(), usage.tasks.to_string()])
+                .collect::<Vec<_>>();
+            print_table(["TAG", "TASKS"], &rows)
+        }
+    }
+}
+
+/// Print the deleted lists and tasks, most recently deleted first.
+pub fn print_trash(format: OutputFormat, trash: &Trash) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => print_json(trash),
+        OutputFormat::Table => {
+            let lists = trash.lists.iter().map(|trashed| {
+                [
+                    trashed.item.id.clone(),
+                    "list".to_string(),
+                    trashed.deleted_at.clone(),
+                    trashed.item.title.clone(),
+                ]
+            });
+            let tasks = trash.tasks.iter().map(|trashed| {
+                [
+                    trashed.item.id.clone(),
+                    "task".to_string(),
+                    trashed.deleted_at.clone(),
+                    trashed.item.title.clone(),
+                ]
+            });
+            let rows = lists.chain(tasks).collect::<Vec<_>>();
+            print_table(["ID", "KIND", "DELETED", "TITLE"], &rows)
+        }
+    }
+}
+
+/// Print a user account.
+pub fn print_user(format: OutputFormat, user: &User) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => print_json(user),
+        OutputFormat::Table => print_table(
+            ["ID", "EMAIL", "CREATED"],
+            &[[user.id.clone(), user.email.clone(), user.created_at.clone()]],
+        ),
+    }
+}
+
+/// Print a new session. Table mode prints only the token, so that it can be captured with
+/// `export MODUS_TOKEN=$(modus login ...)`.
+pub fn print_session(format: OutputFormat, session: &Session) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => print_json(session),
+        OutputFormat::Table => writeln!(io::stdout().lock(), "{}", session.token),
+    }
+}
+
+/// Print API tokens.
+pub fn print_api_tokens(format: OutputFormat, tokens: &[ApiToken]) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => print_json(&tokens),
+        OutputFormat::Table => {
+            let rows = tokens
+                .iter()
+                .map(|token| {
+                    [
+                        token.id.clone(),
+                        token.access.clone(),
+                        token.list_id.clone().unwrap_or_default(),
+                        token.last_used_at.clone().unwrap_or_default(),
+                        token.name.clone(),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            print_table(["ID", "ACCESS", "LIST", "LAST USED", "NAME"], &rows)
+        }
+    }
+}
+
+/// Print a new API token. Table mode prints only the secret, which the server never shows
+/// again.
+pub fn print_new_api_token(format: OutputFormat, token: &ApiToken) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => print_json(token),
+        OutputFormat::Table => writeln!(
+            io::stdout().lock(),
+            "{}",
+            token.secret.as_deref().unwrap_or_default()
+        ),
+    }
+}
+
+/// Print a message in table mode, or `value` as JSON.
+pub fn print_message<T: Serialize>(
+    format: OutputFormat,
+    message: &str,
+    value: &T,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => print_json(value),
+        OutputFormat::Table => writeln!(io::stdout().lock(), "{}", message),
+    }
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut out, value)?;
+    writeln!(out)
+}
+
+fn print_table<const N: usize>(headers: [&str; N], rows: &[[String; N]]) -> io::Result<()> {
+    let mut widths = headers.map(|header| header.chars().count());
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut out = io::stdout().lock();
+    let mut line = |cells: [&str; N]| {
+        let padded = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>();
+        writeln!(out, "{}", padded.join("  ").trim_end())
+    };
+    line(headers)?;
+    for row in rows {
+        line(row.each_ref().map(String::as_str))?;
+    }
+    Ok(())
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
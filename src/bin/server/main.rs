@@ -1,23 +1,133 @@
 use dotenvy::dotenv;
-use modus::config::Config;
+use modus::config::{Config, DatabaseBackend, LogFormat};
+use modus::domain::idempotency::ports::IdempotencyRepository;
+use modus::domain::idempotency::service::Service as IdempotencyService;
+use modus::domain::metrics::ports::Metrics;
+use modus::domain::readiness::ports::ReadinessRepository;
 use modus::domain::readiness::service::Service as ReadinessService;
+use modus::domain::reminders::ports::ReminderRepository;
 use modus::domain::reminders::service::Service as ReminderService;
+use modus::domain::users::ports::UserRepository;
+use modus::domain::users::service::Service as UserService;
 use modus::inbound::http::{HttpServer, HttpServerConfig};
+use modus::inbound::scheduler::{Scheduler, SchedulerConfig};
+use modus::outbound::memory::Memory;
+use modus::outbound::prometheus::Prometheus;
 use modus::outbound::sql::Sql;
+#[cfg(feature = "sqlite")]
+use modus::outbound::sqlite::Sqlite;
+use modus::outbound::stdout::Stdout;
+use tokio::signal;
+use tokio::sync::watch;
+use tracing_subscriber::EnvFilter;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenv().ok();
     let config = Config::from_env()?;
-    // A minimal tracing middleware for request logging
-    // tracing_subscriber::fmt::init();
-    let sql = Sql::new(&config.database_url).await?;
-    let reminder_service = ReminderService::new(sql.clone());
-    let readiness_service = ReadinessService::new(sql.clone());
+    init_tracing(config.log_format);
+    match config.database_backend {
+        DatabaseBackend::Postgres => {
+            let sql = Sql::new(&config.database_url).await?;
+            let metrics = Prometheus::new()?.with_pool(sql.pool().clone());
+            serve(&config, sql, metrics).await
+        }
+        #[cfg(feature = "sqlite")]
+        DatabaseBackend::Sqlite => {
+            let sqlite = Sqlite::new(&config.database_url).await?;
+            serve(&config, sqlite, Prometheus::new()?).await
+        }
+        DatabaseBackend::Memory => {
+            tracing::warn!("Storing data in memory, it will be lost when the server stops");
+            serve(&config, Memory::new(), Prometheus::new()?).await
+        }
+    }
+}
+
+/// Write log lines in the given `format` to stdout, at the levels set in `RUST_LOG` or at info
+/// and above if it is unset.
+fn init_tracing(format: LogFormat) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let subscriber = tracing_subscriber::fmt().with_env_filter(filter);
+    match format {
+        LogFormat::Pretty => subscriber.pretty().init(),
+        LogFormat::Json => subscriber.json().flatten_event(true).init(),
+    }
+}
+
+/// Run the HTTP server and reminder scheduler on top of `repo`, recording `metrics`, until
+/// shutdown is requested.
+async fn serve<R, M>(config: &Config, repo: R, metrics: M) -> anyhow::Result<()>
+where
+    R: ReminderRepository + ReadinessRepository + UserRepository + IdempotencyRepository,
+    M: Metrics,
+{
+    let reminder_service = ReminderService::new(repo.clone(), Stdout::new(), metrics.clone());
+    let mut user_service = UserService::new(repo.clone());
+    if !config.registration_open {
+        user_service = user_service.with_registration_closed();
+    }
+    let idempotency_service =
+        IdempotencyService::new(repo.clone()).with_ttl(config.idempotency_ttl);
+    let readiness_service = ReadinessService::new(repo);
     let server_config = HttpServerConfig {
         port: &config.server_port,
     };
-    let http_server = HttpServer::new(reminder_service, readiness_service, server_config).await?;
-    println!("Starting server on port {}", config.server_port);
-    http_server.run().await
+    let scheduler_config = SchedulerConfig {
+        interval: config.scheduler_interval,
+        trash_retention: config.trash_retention,
+    };
+
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        let _ = shutdown_tx.send(true);
+    });
+
+    let scheduler = Scheduler::new(reminder_service.clone(), scheduler_config);
+    let scheduler = tokio::spawn(scheduler.run(shutdown_requested(shutdown_rx.clone())));
+    let http_server = HttpServer::new(
+        reminder_service,
+        readiness_service,
+        user_service,
+        idempotency_service,
+        metrics,
+        server_config,
+    )
+    .await?;
+    tracing::info!(port = %config.server_port, "Starting server");
+    http_server.run(shutdown_requested(shutdown_rx)).await?;
+    scheduler.await??;
+    tracing::info!("Server stopped");
+    Ok(())
+}
+
+/// Completes once shutdown has been requested through the watch channel.
+async fn shutdown_requested(mut rx: watch::Receiver<bool>) {
+    let _ = rx.wait_for(|requested| *requested).await;
+}
+
+/// Completes when the process receives Ctrl+C or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
 }
@@ -1,23 +1,123 @@
-use anyhow::Context;
+use anyhow::{bail, Context};
 use std::env;
+use std::str::FromStr;
+use std::time::Duration;
+use thiserror::Error;
 
 const DATABASE_URL_KEY: &str = "DATABASE_URL";
 const SERVER_PORT_KEY: &str = "SERVER_PORT";
+const SCHEDULER_INTERVAL_SECS_KEY: &str = "SCHEDULER_INTERVAL_SECS";
+const REGISTRATION_OPEN_KEY: &str = "REGISTRATION_OPEN";
+const LOG_FORMAT_KEY: &str = "LOG_FORMAT";
+const IDEMPOTENCY_TTL_SECS_KEY: &str = "IDEMPOTENCY_TTL_SECS";
+const TRASH_RETENTION_DAYS_KEY: &str = "TRASH_RETENTION_DAYS";
+
+const DEFAULT_SCHEDULER_INTERVAL_SECS: u64 = 30;
+const DEFAULT_IDEMPOTENCY_TTL_SECS: u64 = 24 * 60 * 60;
+const DEFAULT_TRASH_RETENTION_DAYS: u64 = 30;
+
+/// The URL that keeps all data in process memory instead of a database.
+pub const MEMORY_DATABASE_URL: &str = "memory:";
+
+/// Where data is stored, selected by the scheme of `DATABASE_URL`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseBackend {
+    /// `postgres://` or `postgresql://`
+    Postgres,
+    /// `sqlite:`, only available when built with the `sqlite` feature
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+    /// `memory:`, where data is lost when the process exits
+    Memory,
+}
+
+impl DatabaseBackend {
+    fn from_url(url: &str) -> anyhow::Result<Self> {
+        match url.split_once(':').map(|(scheme, _)| scheme) {
+            Some("postgres" | "postgresql") => Ok(DatabaseBackend::Postgres),
+            #[cfg(feature = "sqlite")]
+            Some("sqlite") => Ok(DatabaseBackend::Sqlite),
+            #[cfg(not(feature = "sqlite"))]
+            Some("sqlite") => bail!("SQLite support requires building with the `sqlite` feature"),
+            Some("memory") if url == MEMORY_DATABASE_URL => Ok(DatabaseBackend::Memory),
+            _ => bail!(
+                "unsupported {}, expected a postgres:// or sqlite: URL, or {}",
+                DATABASE_URL_KEY,
+                MEMORY_DATABASE_URL
+            ),
+        }
+    }
+}
+
+/// How log lines are written to stdout, selected by `LOG_FORMAT`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// `pretty`, multi-line and colored for reading in a terminal
+    #[default]
+    Pretty,
+    /// `json`, one object per line for log collectors
+    Json,
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("log format {0:?} must be pretty or json")]
+pub struct LogFormatInvalidError(String);
+
+impl FromStr for LogFormat {
+    type Err = LogFormatInvalidError;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw {
+            "pretty" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(LogFormatInvalidError(raw.to_string())),
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Config {
     pub server_port: String,
     pub database_url: String,
+    pub database_backend: DatabaseBackend,
+    pub scheduler_interval: Duration,
+    /// Whether anyone may register a new user through the API.
+    pub registration_open: bool,
+    pub log_format: LogFormat,
+    /// How long the responses to requests sent with an `Idempotency-Key` are replayed.
+    pub idempotency_ttl: Duration,
+    /// How long deleted tasks and lists stay in the trash before they are deleted for good.
+    pub trash_retention: Duration,
 }
 
 impl Config {
     pub fn from_env() -> anyhow::Result<Config> {
         let server_port = load_env(SERVER_PORT_KEY)?;
         let database_url = load_env(DATABASE_URL_KEY)?;
+        let database_backend = DatabaseBackend::from_url(&database_url)?;
+        let scheduler_interval = Duration::from_secs(parse_env_or(
+            SCHEDULER_INTERVAL_SECS_KEY,
+            DEFAULT_SCHEDULER_INTERVAL_SECS,
+        )?);
+        let registration_open = parse_env_or(REGISTRATION_OPEN_KEY, true)?;
+        let log_format = parse_env_or(LOG_FORMAT_KEY, LogFormat::default())?;
+        let idempotency_ttl = Duration::from_secs(parse_env_or(
+            IDEMPOTENCY_TTL_SECS_KEY,
+            DEFAULT_IDEMPOTENCY_TTL_SECS,
+        )?);
+        let trash_retention = Duration::from_secs(
+            parse_env_or(TRASH_RETENTION_DAYS_KEY, DEFAULT_TRASH_RETENTION_DAYS)? * 24 * 60 * 60,
+        );
 
         Ok(Config {
             server_port,
             database_url,
+            database_backend,
+            scheduler_interval,
+            registration_open,
+            log_format,
+            idempotency_ttl,
+            trash_retention,
         })
     }
 }
@@ -25,3 +125,16 @@ impl Config {
 fn load_env(key: &str) -> anyhow::Result<String> {
     env::var(key).with_context(|| format!("failed to load environment variable {}", key))
 }
+
+fn parse_env_or<T>(key: &str, default: T) -> anyhow::Result<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    match env::var(key) {
+        Ok(raw) => raw
+            .parse()
+            .with_context(|| format!("failed to parse environment variable {}", key)),
+        Err(_) => Ok(default),
+    }
+}
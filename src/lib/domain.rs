@@ -1,2 +1,5 @@
+pub mod idempotency;
+pub mod metrics;
 pub mod readiness;
 pub mod reminders;
+pub mod users;
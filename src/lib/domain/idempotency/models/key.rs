@@ -0,0 +1,156 @@
+use thiserror::Error;
+
+/// A key chosen by a client to identify one logical request across its retries, so that
+/// retrying it does not repeat its effects.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IdempotencyKey(String);
+
+#[derive(Clone, Debug, Error)]
+#[error(
+    "idempotency key must be between 1 and {max} visible ASCII characters",
+    max = IdempotencyKey::MAX_LENGTH
+)]
+pub struct IdempotencyKeyInvalidError;
+
+impl IdempotencyKey {
+    pub const MAX_LENGTH: usize = 255;
+
+    pub fn new(raw: &str) -> Result<Self, IdempotencyKeyInvalidError> {
+        let valid = (1..=Self::MAX_LENGTH).contains(&raw.len())
+            && raw.bytes().all(|b| b.is_ascii_graphic());
+        if valid {
+            Ok(Self(raw.to_string()))
+        } else {
+            Err(IdempotencyKeyInvalidError)
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A request made with an [IdempotencyKey]. Keys are scoped to their `owner`, a digest of the
+/// credentials that sent them, and bound to the `fingerprint` of the first request that used
+/// them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdempotentRequest {
+    owner: String,
+    key: IdempotencyKey,
+    fingerprint: String,
+}
+
+impl IdempotentRequest {
+    pub fn new(owner: String, key: IdempotencyKey, fingerprint: String) -> Self {
+        Self {
+            owner,
+            key,
+            fingerprint,
+        }
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    pub fn key(&self) -> &IdempotencyKey {
+        &self.key
+    }
+
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+}
+
+/// The response to an [IdempotentRequest], replayed when the request is retried.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoredResponse {
+    status: u16,
+    content_type: Option<String>,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl StoredResponse {
+    pub fn new(status: u16, content_type: Option<String>, body: Vec<u8>) -> Self {
+        Self {
+            status,
+            content_type,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    /// The headers of the response other than `Content-Type`, such as `ETag` and `Location`, as
+    /// names and values in the order they were sent.
+    pub fn with_headers(mut self, headers: Vec<(String, String)>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+}
+
+/// What is stored under an [IdempotencyKey]: the fingerprint of the request that claimed it,
+/// and its response once the request has completed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdempotencyRecord {
+    pub fingerprint: String,
+    pub response: Option<StoredResponse>,
+}
+
+/// The outcome of claiming an [IdempotencyKey] for a request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Claim {
+    /// The key is new, and the request should be handled.
+    Claimed,
+    /// The request was already handled, and its response should be sent again.
+    Replay(StoredResponse),
+}
+
+#[derive(Debug, Error)]
+pub enum ClaimIdempotencyKeyError {
+    #[error("a request with this idempotency key is still in progress")]
+    InProgress,
+    #[error("this idempotency key was already used for a different request")]
+    Mismatch,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum CompleteIdempotentRequestError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idempotency_key_must_be_visible_ascii() {
+        assert!(IdempotencyKey::new("4f1c2a9e-retry").is_ok());
+        assert!(IdempotencyKey::new("").is_err());
+        assert!(IdempotencyKey::new("with space").is_err());
+        assert!(IdempotencyKey::new("clé").is_err());
+        assert!(IdempotencyKey::new(&"k".repeat(IdempotencyKey::MAX_LENGTH)).is_ok());
+        assert!(IdempotencyKey::new(&"k".repeat(IdempotencyKey::MAX_LENGTH + 1)).is_err());
+    }
+}
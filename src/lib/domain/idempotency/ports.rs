@@ -0,0 +1,64 @@
+use crate::domain::idempotency::models::key::{
+    Claim, ClaimIdempotencyKeyError, CompleteIdempotentRequestError, IdempotencyRecord,
+    IdempotentRequest, StoredResponse,
+};
+use chrono::{DateTime, Utc};
+use std::future::Future;
+
+/// `IdempotencyService` is the public API for the idempotency domain.
+pub trait IdempotencyService: Clone + Send + Sync + 'static {
+    /// Asynchronously claim the idempotency key of `req`, unless an earlier request claimed it.
+    ///
+    /// # Errors
+    ///
+    /// - [ClaimIdempotencyKeyError::InProgress] if an earlier request with the key has not
+    ///   completed.
+    /// - [ClaimIdempotencyKeyError::Mismatch] if an earlier request with the key had a different
+    ///   fingerprint.
+    fn claim(
+        &self,
+        req: &IdempotentRequest,
+    ) -> impl Future<Output = Result<Claim, ClaimIdempotencyKeyError>> + Send;
+
+    /// Asynchronously store the `response` to `req`, to replay it to retries until the
+    /// idempotency key expires.
+    fn complete(
+        &self,
+        req: &IdempotentRequest,
+        response: &StoredResponse,
+    ) -> impl Future<Output = Result<(), CompleteIdempotentRequestError>> + Send;
+
+    /// Asynchronously release the idempotency key of `req` without a response, so that the
+    /// request can be retried.
+    fn abandon(
+        &self,
+        req: &IdempotentRequest,
+    ) -> impl Future<Output = Result<(), CompleteIdempotentRequestError>> + Send;
+}
+
+/// `IdempotencyRepository` represents a store of idempotency keys and the responses to the
+/// requests made with them.
+pub trait IdempotencyRepository: Clone + Send + Sync + 'static {
+    /// Asynchronously delete the keys that expired at `now`, then claim the idempotency key of
+    /// `req` until `expires_at`. Returns `None` if the key was claimed, or the
+    /// [IdempotencyRecord] of the request that claimed it first.
+    fn claim_idempotency_key(
+        &self,
+        req: &IdempotentRequest,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Option<IdempotencyRecord>, ClaimIdempotencyKeyError>> + Send;
+
+    /// Asynchronously store the `response` under the idempotency key of `req`.
+    fn save_idempotent_response(
+        &self,
+        req: &IdempotentRequest,
+        response: &StoredResponse,
+    ) -> impl Future<Output = Result<(), CompleteIdempotentRequestError>> + Send;
+
+    /// Asynchronously delete the idempotency key of `req`.
+    fn delete_idempotency_key(
+        &self,
+        req: &IdempotentRequest,
+    ) -> impl Future<Output = Result<(), CompleteIdempotentRequestError>> + Send;
+}
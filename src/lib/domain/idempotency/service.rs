@@ -0,0 +1,177 @@
+use crate::domain::idempotency::models::key::{
+    Claim, ClaimIdempotencyKeyError, CompleteIdempotentRequestError, IdempotentRequest,
+    StoredResponse,
+};
+use crate::domain::idempotency::ports::{IdempotencyRepository, IdempotencyService};
+use anyhow::Context;
+use chrono::Utc;
+use std::time::Duration;
+use tracing::instrument;
+
+/// Cannonical implementation of the [IdempotencyService] port, through which the idempotency
+/// domain is consumed
+#[derive(Debug, Clone)]
+pub struct Service<R>
+where
+    R: IdempotencyRepository,
+{
+    repo: R,
+    ttl: Duration,
+}
+
+impl<R> Service<R>
+where
+    R: IdempotencyRepository,
+{
+    /// How long keys are kept unless configured otherwise.
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+    /// Create a new instance of the [Service] with the provided [IdempotencyRepository]. Keys
+    /// are kept for [Service::DEFAULT_TTL].
+    pub fn new(repo: R) -> Self {
+        Self {
+            repo,
+            ttl: Self::DEFAULT_TTL,
+        }
+    }
+
+    /// Keep keys, and replay the responses stored under them, for `ttl` after their first use.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+impl<R> IdempotencyService for Service<R>
+where
+    R: IdempotencyRepository,
+{
+    #[instrument(skip(self, req), fields(key = req.key().as_str()))]
+    async fn claim(&self, req: &IdempotentRequest) -> Result<Claim, ClaimIdempotencyKeyError> {
+        let now = Utc::now();
+        let ttl = chrono::Duration::from_std(self.ttl).context("idempotency TTL is too long")?;
+        let record = self.repo.claim_idempotency_key(req, now, now + ttl).await?;
+        match record {
+            None => Ok(Claim::Claimed),
+            Some(record) if record.fingerprint != req.fingerprint() => {
+                Err(ClaimIdempotencyKeyError::Mismatch)
+            }
+            Some(record) => record
+                .response
+                .map(Claim::Replay)
+                .ok_or(ClaimIdempotencyKeyError::InProgress),
+        }
+    }
+
+    #[instrument(skip(self, req, response), fields(key = req.key().as_str()))]
+    async fn complete(
+        &self,
+        req: &IdempotentRequest,
+        response: &StoredResponse,
+    ) -> Result<(), CompleteIdempotentRequestError> {
+        self.repo.save_idempotent_response(req, response).await
+    }
+
+    #[instrument(skip(self, req), fields(key = req.key().as_str()))]
+    async fn abandon(&self, req: &IdempotentRequest) -> Result<(), CompleteIdempotentRequestError> {
+        self.repo.delete_idempotency_key(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::idempotency::models::key::{IdempotencyKey, IdempotencyRecord};
+    use chrono::DateTime;
+    use std::sync::{Arc, Mutex};
+
+    /// An [IdempotencyRepository] that returns a preconfigured record for every claim.
+    #[derive(Clone, Default)]
+    struct MockIdempotencyRepository {
+        record: Option<IdempotencyRecord>,
+        claimed_until: Arc<Mutex<Option<DateTime<Utc>>>>,
+    }
+
+    impl IdempotencyRepository for MockIdempotencyRepository {
+        async fn claim_idempotency_key(
+            &self,
+            _: &IdempotentRequest,
+            _: DateTime<Utc>,
+            expires_at: DateTime<Utc>,
+        ) -> Result<Option<IdempotencyRecord>, ClaimIdempotencyKeyError> {
+            *self.claimed_until.lock().unwrap() = Some(expires_at);
+            Ok(self.record.clone())
+        }
+
+        async fn save_idempotent_response(
+            &self,
+            _: &IdempotentRequest,
+            _: &StoredResponse,
+        ) -> Result<(), CompleteIdempotentRequestError> {
+            Ok(())
+        }
+
+        async fn delete_idempotency_key(
+            &self,
+            _: &IdempotentRequest,
+        ) -> Result<(), CompleteIdempotentRequestError> {
+            Ok(())
+        }
+    }
+
+    fn request(fingerprint: &str) -> IdempotentRequest {
+        IdempotentRequest::new(
+            "owner".to_string(),
+            IdempotencyKey::new("key-1").unwrap(),
+            fingerprint.to_string(),
+        )
+    }
+
+    fn service(record: Option<IdempotencyRecord>) -> Service<MockIdempotencyRepository> {
+        Service::new(MockIdempotencyRepository {
+            record,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_new_key_is_claimed_for_the_ttl() {
+        let service = service(None).with_ttl(Duration::from_secs(60));
+        let before = Utc::now();
+        let claim = service.claim(&request("a")).await.unwrap();
+        assert_eq!(claim, Claim::Claimed);
+        let expires_at = service.repo.claimed_until.lock().unwrap().unwrap();
+        assert!(expires_at >= before + chrono::Duration::seconds(60));
+    }
+
+    #[tokio::test]
+    async fn test_completed_request_is_replayed() {
+        let response = StoredResponse::new(201, Some("application/json".to_string()), vec![1]);
+        let service = service(Some(IdempotencyRecord {
+            fingerprint: "a".to_string(),
+            response: Some(response.clone()),
+        }));
+        let claim = service.claim(&request("a")).await.unwrap();
+        assert_eq!(claim, Claim::Replay(response));
+    }
+
+    #[tokio::test]
+    async fn test_pending_request_is_in_progress() {
+        let service = service(Some(IdempotencyRecord {
+            fingerprint: "a".to_string(),
+            response: None,
+        }));
+        let result = service.claim(&request("a")).await;
+        assert!(matches!(result, Err(ClaimIdempotencyKeyError::InProgress)));
+    }
+
+    #[tokio::test]
+    async fn test_key_reused_for_another_request_is_a_mismatch() {
+        let service = service(Some(IdempotencyRecord {
+            fingerprint: "a".to_string(),
+            response: Some(StoredResponse::new(201, None, vec![])),
+        }));
+        let result = service.claim(&request("b")).await;
+        assert!(matches!(result, Err(ClaimIdempotencyKeyError::Mismatch)));
+    }
+}
@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+/// `Metrics` records how the application is used and how it performs, for operators to watch.
+/// Recording never fails and never blocks, so that it cannot disturb the work being measured.
+pub trait Metrics: Clone + Send + Sync + 'static {
+    /// Record an HTTP request to the `route` it was matched against, such as `/api/tasks/:id`,
+    /// with the `status` of its response and the `latency` with which it was answered.
+    fn record_http_request(&self, method: &str, route: &str, status: u16, latency: Duration);
+
+    /// Record that a task was created.
+    fn record_task_creation_success(&self);
+
+    /// Record that a task could not be created.
+    fn record_task_creation_failure(&self);
+
+    /// Record that a user completed a task.
+    fn record_task_completion(&self);
+
+    /// Record that a user deleted a task, along with its subtasks.
+    fn record_task_deletion(&self);
+
+    /// Record that a reminder was delivered.
+    fn record_reminder_delivery(&self);
+
+    /// Record that delivery of a reminder failed.
+    fn record_reminder_failure(&self);
+
+    /// Record how long after its time a reminder was fired by the scheduler.
+    fn record_scheduler_lag(&self, lag: Duration);
+
+    /// Render the current value of every metric in the Prometheus text exposition format.
+    ///
+    /// # Errors
+    ///
+    /// - [anyhow::Error] if the metrics could not be rendered.
+    fn render(&self) -> anyhow::Result<String>;
+}
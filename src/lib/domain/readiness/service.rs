@@ -26,17 +26,16 @@ impl<R> ReadinessService for Service<R>
 where
     R: ReadinessRepository,
 {
+    #[tracing::instrument(skip(self))]
     async fn is_ready(&self) -> Result<(), ReadinessError> {
         // Attempt to execute a simple query to check database readiness
         match self.repo.is_ready().await {
             Ok(_) => {
-                // Log success if needed
-                println!("Database is ready.");
+                tracing::debug!("Database is ready");
                 Ok(())
             }
             Err(e) => {
-                // Log the error for debugging purposes
-                eprintln!("Database readiness check failed: {:?}", e);
+                tracing::error!(error = ?e, "Database readiness check failed");
                 Err(ReadinessError::DatabaseNotReady)
             }
         }
@@ -1 +1,8 @@
+pub mod list;
+pub mod member;
+pub mod query;
+pub mod recurrence;
+pub mod reminder;
+pub mod tag;
 pub mod task;
+pub mod trash;
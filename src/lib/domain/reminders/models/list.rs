@@ -0,0 +1,313 @@
+use crate::domain::reminders::models::member::ListRole;
+use crate::domain::reminders::models::task::TaskTitle;
+use chrono::{DateTime, Utc};
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// A valid title for a [TaskList].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskListTitle(String);
+
+#[derive(Clone, Debug, Error)]
+#[error("task list title cannot be empty")]
+pub struct TaskListTitleEmptyError;
+
+impl TaskListTitle {
+    pub fn new(raw: &str) -> Result<Self, TaskListTitleEmptyError> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            Err(TaskListTitleEmptyError)
+        } else {
+            Ok(Self(trimmed.to_string()))
+        }
+    }
+}
+
+impl Display for TaskListTitle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The colour a [TaskList] is displayed in, as a `#rrggbb` hex code normalised to lowercase.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskListColour(String);
+
+#[derive(Clone, Debug, Error)]
+#[error("colour {0:?} must be a hex code such as #1e90ff")]
+pub struct TaskListColourInvalidError(String);
+
+impl TaskListColour {
+    pub fn new(raw: &str) -> Result<Self, TaskListColourInvalidError> {
+        let trimmed = raw.trim();
+        match trimmed.strip_prefix('#') {
+            Some(hex) if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+                Ok(Self(trimmed.to_ascii_lowercase()))
+            }
+            _ => Err(TaskListColourInvalidError(raw.to_string())),
+        }
+    }
+}
+
+impl Display for TaskListColour {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A named group of tasks, such as a project. Tasks that belong to no list are in the inbox.
+/// Lists are shown in ascending `position`, then in the order they were created.
+///
+/// A list and its tasks belong to the user who created it, who may share it with other users.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskList {
+    pub id: Uuid,
+    pub title: TaskListTitle,
+    pub colour: Option<TaskListColour>,
+    pub position: i32,
+    pub archived: bool,
+    pub created_at: DateTime<Utc>,
+    /// The user who created the list.
+    pub owner_id: Uuid,
+    /// The role of the user the list was retrieved for.
+    pub role: ListRole,
+}
+
+impl TaskList {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn title(&self) -> &TaskListTitle {
+        &self.title
+    }
+
+    pub fn colour(&self) -> Option<&TaskListColour> {
+        self.colour.as_ref()
+    }
+
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    pub fn archived(&self) -> bool {
+        self.archived
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn owner_id(&self) -> Uuid {
+        self.owner_id
+    }
+
+    pub fn role(&self) -> ListRole {
+        self.role
+    }
+}
+
+/// The fields required by the domain to create a [TaskList]. Without a position, the list is
+/// placed after all existing lists.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CreateTaskListRequest {
+    title: TaskListTitle,
+    colour: Option<TaskListColour>,
+    position: Option<i32>,
+}
+
+impl CreateTaskListRequest {
+    pub fn new(title: TaskListTitle) -> Self {
+        Self {
+            title,
+            colour: None,
+            position: None,
+        }
+    }
+
+    pub fn with_colour(mut self, colour: TaskListColour) -> Self {
+        self.colour = Some(colour);
+        self
+    }
+
+    pub fn with_position(mut self, position: i32) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    pub fn title(&self) -> &TaskListTitle {
+        &self.title
+    }
+
+    pub fn colour(&self) -> Option<&TaskListColour> {
+        self.colour.as_ref()
+    }
+
+    pub fn position(&self) -> Option<i32> {
+        self.position
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CreateTaskListError {
+    #[error("task list with title {title} already exists")]
+    Duplicate { title: TaskListTitle },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum GetTaskListError {
+    #[error("task list with id {id} not found")]
+    NotFound { id: Uuid },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum ListTaskListsError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+/// The fields required by the domain to update a [TaskList], which covers renaming, recolouring,
+/// reordering and archiving it. Fields that are not set are left unchanged; the colour is
+/// cleared by setting it to `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UpdateTaskListRequest {
+    title: Option<TaskListTitle>,
+    colour: Option<Option<TaskListColour>>,
+    position: Option<i32>,
+    archived: Option<bool>,
+}
+
+impl UpdateTaskListRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_title(mut self, title: TaskListTitle) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    pub fn with_colour(mut self, colour: Option<TaskListColour>) -> Self {
+        self.colour = Some(colour);
+        self
+    }
+
+    pub fn with_position(mut self, position: i32) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    pub fn with_archived(mut self, archived: bool) -> Self {
+        self.archived = Some(archived);
+        self
+    }
+
+    pub fn title(&self) -> Option<&TaskListTitle> {
+        self.title.as_ref()
+    }
+
+    pub fn colour(&self) -> Option<Option<&TaskListColour>> {
+        self.colour.as_ref().map(Option::as_ref)
+    }
+
+    pub fn position(&self) -> Option<i32> {
+        self.position
+    }
+
+    pub fn archived(&self) -> Option<bool> {
+        self.archived
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateTaskListError {
+    #[error("task list with id {id} not found")]
+    NotFound { id: Uuid },
+    #[error("task list with title {title} already exists")]
+    Duplicate { title: TaskListTitle },
+    #[error("your role in task list {id}, {role}, does not let you change it")]
+    Forbidden { id: Uuid, role: ListRole },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+impl From<GetTaskListError> for UpdateTaskListError {
+    fn from(e: GetTaskListError) -> Self {
+        match e {
+            GetTaskListError::NotFound { id } => Self::NotFound { id },
+            GetTaskListError::Unknown(e) => Self::Unknown(e),
+        }
+    }
+}
+
+/// What happens to the tasks of a [TaskList] when it is deleted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DeleteTaskListMode {
+    /// Only delete the list if it has no tasks.
+    #[default]
+    OnlyIfEmpty,
+    /// Delete the tasks along with the list, and restore them along with it.
+    Cascade,
+    /// Keep the tasks, moving them to the inbox of the owner and unassigning them from members.
+    MoveToInbox,
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteTaskListError {
+    #[error("task list with id {id} not found")]
+    NotFound { id: Uuid },
+    #[error("task list with id {id} still has {tasks} tasks")]
+    NotEmpty { id: Uuid, tasks: u64 },
+    #[error("your role in task list {id}, {role}, does not let you delete it")]
+    Forbidden { id: Uuid, role: ListRole },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+impl From<GetTaskListError> for DeleteTaskListError {
+    fn from(e: GetTaskListError) -> Self {
+        match e {
+            GetTaskListError::NotFound { id } => Self::NotFound { id },
+            GetTaskListError::Unknown(e) => Self::Unknown(e),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RestoreTaskListError {
+    #[error("task list with id {id} not found in the trash")]
+    NotFound { id: Uuid },
+    #[error("task list with title {title} already exists")]
+    Duplicate { title: TaskListTitle },
+    #[error("task with title {title} already exists")]
+    TaskDuplicate { title: TaskTitle },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colour_is_normalised_hex() {
+        assert_eq!(
+            TaskListColour::new(" #1E90FF ").unwrap().to_string(),
+            "#1e90ff"
+        );
+        assert!(TaskListColour::new("1e90ff").is_err());
+        assert!(TaskListColour::new("#1e90f").is_err());
+        assert!(TaskListColour::new("#1e90fg").is_err());
+    }
+}
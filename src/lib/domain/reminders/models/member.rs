@@ -0,0 +1,179 @@
+use crate::domain::reminders::models::list::GetTaskListError;
+use crate::domain::users::models::user::EmailAddress;
+use chrono::{DateTime, Utc};
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// What a user may do with a [TaskList](crate::domain::reminders::models::list::TaskList) and
+/// its tasks, in ascending order. The user who created a list always owns it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ListRole {
+    /// Read the list and its tasks.
+    #[default]
+    Viewer,
+    /// Also create, change, complete and delete the tasks of the list.
+    Editor,
+    /// Also change, share and delete the list itself.
+    Owner,
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("role {0:?} must be one of viewer, editor or owner")]
+pub struct ListRoleInvalidError(String);
+
+impl ListRole {
+    /// Parse the lowercase name of a role.
+    pub fn parse(raw: &str) -> Result<Self, ListRoleInvalidError> {
+        match raw {
+            "viewer" => Ok(Self::Viewer),
+            "editor" => Ok(Self::Editor),
+            "owner" => Ok(Self::Owner),
+            _ => Err(ListRoleInvalidError(raw.to_string())),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Viewer => "viewer",
+            Self::Editor => "editor",
+            Self::Owner => "owner",
+        }
+    }
+}
+
+impl Display for ListRole {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A user who may access a [TaskList](crate::domain::reminders::models::list::TaskList), either
+/// because they created it or because it was shared with them.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TaskListMember {
+    pub user_id: Uuid,
+    pub email: EmailAddress,
+    pub role: ListRole,
+    /// When the list was shared with the user, or created by them.
+    pub added_at: DateTime<Utc>,
+}
+
+impl TaskListMember {
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    pub fn email(&self) -> &EmailAddress {
+        &self.email
+    }
+
+    pub fn role(&self) -> ListRole {
+        self.role
+    }
+
+    pub fn added_at(&self) -> DateTime<Utc> {
+        self.added_at
+    }
+}
+
+/// The fields required by the domain to share a
+/// [TaskList](crate::domain::reminders::models::list::TaskList) with the user registered under
+/// `email`, or to change the role of a user it is already shared with.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ShareTaskListRequest {
+    email: EmailAddress,
+    role: ListRole,
+}
+
+impl ShareTaskListRequest {
+    pub fn new(email: EmailAddress, role: ListRole) -> Self {
+        Self { email, role }
+    }
+
+    pub fn email(&self) -> &EmailAddress {
+        &self.email
+    }
+
+    pub fn role(&self) -> ListRole {
+        self.role
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ShareTaskListError {
+    #[error("task list with id {id} not found")]
+    NotFound { id: Uuid },
+    #[error("your role in task list {id}, {role}, does not let you share it")]
+    Forbidden { id: Uuid, role: ListRole },
+    #[error("user with email {email} not found")]
+    UserNotFound { email: EmailAddress },
+    #[error("user with email {email} created task list {id} and always owns it")]
+    Creator { id: Uuid, email: EmailAddress },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+impl From<GetTaskListError> for ShareTaskListError {
+    fn from(e: GetTaskListError) -> Self {
+        match e {
+            GetTaskListError::NotFound { id } => Self::NotFound { id },
+            GetTaskListError::Unknown(e) => Self::Unknown(e),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ListTaskListMembersError {
+    #[error("task list with id {id} not found")]
+    NotFound { id: Uuid },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+impl From<GetTaskListError> for ListTaskListMembersError {
+    fn from(e: GetTaskListError) -> Self {
+        match e {
+            GetTaskListError::NotFound { id } => Self::NotFound { id },
+            GetTaskListError::Unknown(e) => Self::Unknown(e),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum UnshareTaskListError {
+    #[error("task list with id {id} not found")]
+    NotFound { id: Uuid },
+    #[error("user with id {user_id} is not a member of task list {id}")]
+    MemberNotFound { id: Uuid, user_id: Uuid },
+    #[error("your role in task list {id}, {role}, does not let you remove other members")]
+    Forbidden { id: Uuid, role: ListRole },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+impl From<GetTaskListError> for UnshareTaskListError {
+    fn from(e: GetTaskListError) -> Self {
+        match e {
+            GetTaskListError::NotFound { id } => Self::NotFound { id },
+            GetTaskListError::Unknown(e) => Self::Unknown(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles_round_trip_and_are_ordered() {
+        for role in [ListRole::Viewer, ListRole::Editor, ListRole::Owner] {
+            assert_eq!(ListRole::parse(role.as_str()).unwrap(), role);
+        }
+        assert!(ListRole::Owner > ListRole::Editor && ListRole::Editor > ListRole::Viewer);
+        assert!(ListRole::parse("admin").is_err());
+    }
+}
@@ -0,0 +1,631 @@
+use crate::domain::reminders::models::tag::Tag;
+use crate::domain::reminders::models::task::{Priority, Task};
+use chrono::{DateTime, Utc};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Which tasks to list. Every condition that is set must hold.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TaskFilter {
+    completed: Option<bool>,
+    due_before: Option<DateTime<Utc>>,
+    due_after: Option<DateTime<Utc>>,
+    search: Option<TaskSearch>,
+    list: Option<TaskListFilter>,
+    tags: Option<TaskTagFilter>,
+    assignee: Option<Uuid>,
+}
+
+/// Which [TaskList](crate::domain::reminders::models::list::TaskList) listed tasks belong to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TaskListFilter {
+    /// Tasks that belong to no list.
+    Inbox,
+    /// Tasks that belong to the list with this id.
+    List(Uuid),
+}
+
+impl TaskListFilter {
+    /// The list id tasks must have to match.
+    pub fn list_id(&self) -> Option<Uuid> {
+        match self {
+            Self::Inbox => None,
+            Self::List(id) => Some(*id),
+        }
+    }
+}
+
+/// Whether a task must carry every tag of a [TaskTagFilter] or at least one of them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TagMatch {
+    #[default]
+    All,
+    Any,
+}
+
+/// The tags listed tasks must carry.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TaskTagFilter {
+    tags: Vec<Tag>,
+    mode: TagMatch,
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("tag filter needs at least one tag")]
+pub struct TaskTagFilterEmptyError;
+
+impl TaskTagFilter {
+    pub fn new(tags: Vec<Tag>, mode: TagMatch) -> Result<Self, TaskTagFilterEmptyError> {
+        let mut tags = tags;
+        tags.sort();
+        tags.dedup();
+        if tags.is_empty() {
+            Err(TaskTagFilterEmptyError)
+        } else {
+            Ok(Self { tags, mode })
+        }
+    }
+
+    /// The distinct tags of the filter, in alphabetical order.
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    pub fn mode(&self) -> TagMatch {
+        self.mode
+    }
+
+    pub fn matches(&self, task: &Task) -> bool {
+        let mut tags = self.tags.iter();
+        match self.mode {
+            TagMatch::All => tags.all(|tag| task.tags().contains(tag)),
+            TagMatch::Any => tags.any(|tag| task.tags().contains(tag)),
+        }
+    }
+}
+
+impl TaskFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_completed(mut self, completed: bool) -> Self {
+        self.completed = Some(completed);
+        self
+    }
+
+    /// Only tasks due strictly before `at`. Tasks without a due date never match.
+    pub fn with_due_before(mut self, at: DateTime<Utc>) -> Self {
+        self.due_before = Some(at);
+        self
+    }
+
+    /// Only tasks due strictly after `at`. Tasks without a due date never match.
+    pub fn with_due_after(mut self, at: DateTime<Utc>) -> Self {
+        self.due_after = Some(at);
+        self
+    }
+
+    pub fn with_search(mut self, search: TaskSearch) -> Self {
+        self.search = Some(search);
+        self
+    }
+
+    pub fn with_list(mut self, list: TaskListFilter) -> Self {
+        self.list = Some(list);
+        self
+    }
+
+    pub fn completed(&self) -> Option<bool> {
+        self.completed
+    }
+
+    pub fn due_before(&self) -> Option<&DateTime<Utc>> {
+        self.due_before.as_ref()
+    }
+
+    pub fn due_after(&self) -> Option<&DateTime<Utc>> {
+        self.due_after.as_ref()
+    }
+
+    pub fn search(&self) -> Option<&TaskSearch> {
+        self.search.as_ref()
+    }
+
+    pub fn with_tags(mut self, tags: TaskTagFilter) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+
+    /// Only tasks assigned to the user with the given id.
+    pub fn with_assignee(mut self, assignee: Uuid) -> Self {
+        self.assignee = Some(assignee);
+        self
+    }
+
+    pub fn list(&self) -> Option<TaskListFilter> {
+        self.list
+    }
+
+    pub fn tags(&self) -> Option<&TaskTagFilter> {
+        self.tags.as_ref()
+    }
+
+    pub fn assignee(&self) -> Option<Uuid> {
+        self.assignee
+    }
+
+    /// Whether `task` satisfies every condition of the filter.
+    pub fn matches(&self, task: &Task) -> bool {
+        let due_at = task.due_at().map(|due_at| *due_at.as_datetime());
+        self.completed
+            .is_none_or(|completed| task.completed() == completed)
+            && self
+                .due_before
+                .is_none_or(|before| due_at.is_some_and(|due_at| due_at < before))
+            && self
+                .due_after
+                .is_none_or(|after| due_at.is_some_and(|due_at| due_at > after))
+            && self
+                .search
+                .as_ref()
+                .is_none_or(|search| search.matches(&task.title().to_string()))
+            && self
+                .list
+                .is_none_or(|list| task.list_id() == list.list_id())
+            && self.tags.as_ref().is_none_or(|tags| tags.matches(task))
+            && self
+                .assignee
+                .is_none_or(|assignee| task.assignee_id() == Some(assignee))
+    }
+}
+
+/// Text to look for in task titles, ignoring case.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TaskSearch(String);
+
+#[derive(Clone, Debug, Error)]
+#[error("search text cannot be empty")]
+pub struct TaskSearchEmptyError;
+
+impl TaskSearch {
+    pub fn new(raw: &str) -> Result<Self, TaskSearchEmptyError> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            Err(TaskSearchEmptyError)
+        } else {
+            Ok(Self(trimmed.to_string()))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// A `LIKE` pattern matching titles that contain the search text, with `\` as the escape
+    /// character.
+    pub fn like_pattern(&self) -> String {
+        let mut pattern = String::with_capacity(self.0.len() + 2);
+        pattern.push('%');
+        for c in self.0.chars() {
+            if matches!(c, '%' | '_' | '\\') {
+                pattern.push('\\');
+            }
+            pattern.push(c);
+        }
+        pattern.push('%');
+        pattern
+    }
+
+    pub fn matches(&self, title: &str) -> bool {
+        title.to_lowercase().contains(&self.0.to_lowercase())
+    }
+}
+
+/// The property tasks are ordered by. Ties are broken by creation time, then by id.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TaskSortField {
+    /// When the task was created.
+    #[default]
+    Created,
+    /// When the task is due. Tasks without a due date come last in either direction.
+    Due,
+    /// How important the task is, from no priority up to urgent.
+    Priority,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// The order in which tasks are listed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TaskSort {
+    field: TaskSortField,
+    direction: SortDirection,
+}
+
+#[derive(Clone, Debug, Error)]
+#[error(
+    "unsupported sort {0:?}, expected one of created, -created, due, -due, priority or -priority"
+)]
+pub struct TaskSortInvalidError(String);
+
+impl TaskSort {
+    pub fn new(field: TaskSortField, direction: SortDirection) -> Self {
+        Self { field, direction }
+    }
+
+    /// Parse a sort such as `due`, or `-due` for descending order.
+    pub fn parse(raw: &str) -> Result<Self, TaskSortInvalidError> {
+        let raw = raw.trim();
+        let (direction, name) = match raw.strip_prefix('-') {
+            Some(name) => (SortDirection::Descending, name),
+            None => (SortDirection::Ascending, raw),
+        };
+        let field = match name {
+            "created" => TaskSortField::Created,
+            "due" => TaskSortField::Due,
+            "priority" => TaskSortField::Priority,
+            _ => return Err(TaskSortInvalidError(raw.to_string())),
+        };
+        Ok(Self { field, direction })
+    }
+
+    pub fn field(&self) -> TaskSortField {
+        self.field
+    }
+
+    pub fn direction(&self) -> SortDirection {
+        self.direction
+    }
+
+    /// The position of `task` relative to `cursor` in this order.
+    fn compare_key(&self, task: &Task, cursor: &TaskCursor) -> Ordering {
+        let due_at = task.due_at().map(|due_at| *due_at.as_datetime());
+        let ordering = self
+            .key(due_at, task.priority(), task.created_at(), task.id())
+            .cmp(&self.key(cursor.due_at, cursor.priority, cursor.created_at, cursor.id));
+        match self.direction {
+            SortDirection::Ascending => ordering,
+            SortDirection::Descending => ordering.reverse(),
+        }
+    }
+
+    /// Compare two tasks in this order.
+    pub fn compare(&self, a: &Task, b: &Task) -> Ordering {
+        self.compare_key(a, &TaskCursor::after(*self, b))
+    }
+
+    /// The key tasks are ordered by in ascending order, where the earliest possible due date
+    /// stands in for a missing one when descending so that such tasks still come last.
+    fn key(
+        &self,
+        due_at: Option<DateTime<Utc>>,
+        priority: Priority,
+        created_at: DateTime<Utc>,
+        id: Uuid,
+    ) -> (DateTime<Utc>, Priority, DateTime<Utc>, Uuid) {
+        let priority = match self.field {
+            TaskSortField::Priority => priority,
+            TaskSortField::Created | TaskSortField::Due => Priority::None,
+        };
+        let due_at = match (self.field, self.direction) {
+            (TaskSortField::Created | TaskSortField::Priority, _) => DateTime::<Utc>::MIN_UTC,
+            (TaskSortField::Due, SortDirection::Ascending) => {
+                due_at.unwrap_or(DateTime::<Utc>::MAX_UTC)
+            }
+            (TaskSortField::Due, SortDirection::Descending) => {
+                due_at.unwrap_or(DateTime::<Utc>::MIN_UTC)
+            }
+        };
+        (due_at, priority, created_at, id)
+    }
+}
+
+impl Display for TaskSort {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.direction == SortDirection::Descending {
+            f.write_str("-")?;
+        }
+        match self.field {
+            TaskSortField::Created => f.write_str("created"),
+            TaskSortField::Due => f.write_str("due"),
+            TaskSortField::Priority => f.write_str("priority"),
+        }
+    }
+}
+
+impl FromStr for TaskSort {
+    type Err = TaskSortInvalidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// The position after the last task of a page, from which the next page continues. A cursor is
+/// only meaningful for the [TaskSort] it was created with.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TaskCursor {
+    sort: TaskSort,
+    due_at: Option<DateTime<Utc>>,
+    priority: Priority,
+    created_at: DateTime<Utc>,
+    id: Uuid,
+}
+
+impl TaskCursor {
+    pub fn new(
+        sort: TaskSort,
+        due_at: Option<DateTime<Utc>>,
+        priority: Priority,
+        created_at: DateTime<Utc>,
+        id: Uuid,
+    ) -> Self {
+        Self {
+            sort,
+            due_at,
+            priority,
+            created_at,
+            id,
+        }
+    }
+
+    /// The cursor that continues after `task`.
+    pub fn after(sort: TaskSort, task: &Task) -> Self {
+        Self::new(
+            sort,
+            task.due_at().map(|due_at| *due_at.as_datetime()),
+            task.priority(),
+            task.created_at(),
+            task.id(),
+        )
+    }
+
+    pub fn sort(&self) -> TaskSort {
+        self.sort
+    }
+
+    pub fn due_at(&self) -> Option<&DateTime<Utc>> {
+        self.due_at.as_ref()
+    }
+
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Whether `task` comes after the cursor and so belongs to a following page.
+    pub fn precedes(&self, task: &Task) -> bool {
+        self.sort.compare_key(task, self) == Ordering::Greater
+    }
+}
+
+/// The maximum number of tasks in a page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PageSize(u32);
+
+#[derive(Clone, Debug, Error)]
+#[error("page size must be between 1 and {max}", max = PageSize::MAX)]
+pub struct PageSizeInvalidError;
+
+impl PageSize {
+    pub const DEFAULT: u32 = 50;
+    pub const MAX: u32 = 100;
+
+    pub fn new(size: u32) -> Result<Self, PageSizeInvalidError> {
+        if (1..=Self::MAX).contains(&size) {
+            Ok(Self(size))
+        } else {
+            Err(PageSizeInvalidError)
+        }
+    }
+
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Default for PageSize {
+    fn default() -> Self {
+        Self(Self::DEFAULT)
+    }
+}
+
+/// The fields required by the domain to list a page of [Task]s.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ListTasksRequest {
+    filter: TaskFilter,
+    sort: TaskSort,
+    size: PageSize,
+    cursor: Option<TaskCursor>,
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("cursor belongs to a listing sorted by {cursor}, not {requested}")]
+pub struct TaskCursorSortMismatchError {
+    pub cursor: TaskSort,
+    pub requested: TaskSort,
+}
+
+impl ListTasksRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_filter(mut self, filter: TaskFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_sort(mut self, sort: TaskSort) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    pub fn with_size(mut self, size: PageSize) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Continue after `cursor`, which must have been created for the same sort.
+    pub fn with_cursor(mut self, cursor: TaskCursor) -> Result<Self, TaskCursorSortMismatchError> {
+        if cursor.sort != self.sort {
+            return Err(TaskCursorSortMismatchError {
+                cursor: cursor.sort,
+                requested: self.sort,
+            });
+        }
+        self.cursor = Some(cursor);
+        Ok(self)
+    }
+
+    pub fn filter(&self) -> &TaskFilter {
+        &self.filter
+    }
+
+    pub fn sort(&self) -> TaskSort {
+        self.sort
+    }
+
+    pub fn size(&self) -> PageSize {
+        self.size
+    }
+
+    pub fn cursor(&self) -> Option<&TaskCursor> {
+        self.cursor.as_ref()
+    }
+
+    /// Build the page from the matching tasks in order, where repositories fetch one task more
+    /// than the page size to learn whether another page follows.
+    pub fn page(&self, mut tasks: Vec<Task>) -> TaskPage {
+        let size = self.size.get() as usize;
+        let next_cursor = if tasks.len() > size {
+            tasks.truncate(size);
+            tasks.last().map(|task| TaskCursor::after(self.sort, task))
+        } else {
+            None
+        };
+        TaskPage { tasks, next_cursor }
+    }
+}
+
+/// A page of [Task]s and the cursor to the following page, if there is one.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TaskPage {
+    pub tasks: Vec<Task>,
+    pub next_cursor: Option<TaskCursor>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::reminders::models::tag::TaskTags;
+    use crate::domain::reminders::models::task::{DueAt, TaskTitle};
+    use chrono::Duration;
+
+    fn task(title: &str, due_in_hours: Option<i64>) -> Task {
+        let mut task = Task::new(Uuid::new_v4(), TaskTitle::new(title).unwrap());
+        task.due_at = due_in_hours.map(|hours| DueAt::new(Utc::now() + Duration::hours(hours)));
+        task
+    }
+
+    #[test]
+    fn test_sort_by_due_puts_tasks_without_due_date_last() {
+        let later = task("Later", Some(2));
+        let sooner = task("Sooner", Some(1));
+        let never = task("Never", None);
+        let mut tasks = vec![never.clone(), later.clone(), sooner.clone()];
+
+        let ascending = TaskSort::parse("due").unwrap();
+        tasks.sort_by(|a, b| ascending.compare(a, b));
+        assert_eq!(tasks, vec![sooner.clone(), later.clone(), never.clone()]);
+
+        let descending = TaskSort::parse("-due").unwrap();
+        tasks.sort_by(|a, b| descending.compare(a, b));
+        assert_eq!(tasks, vec![later.clone(), sooner, never]);
+
+        let cursor = TaskCursor::after(descending, &later);
+        assert!(!cursor.precedes(&later));
+        assert!(tasks[1..].iter().all(|task| cursor.precedes(task)));
+    }
+
+    #[test]
+    fn test_sort_by_priority_breaks_ties_by_creation() {
+        let urgent = Task {
+            priority: Priority::Urgent,
+            ..task("Pay rent", None)
+        };
+        let first = Task {
+            priority: Priority::Low,
+            ..task("Buy milk", None)
+        };
+        let second = Task {
+            priority: Priority::Low,
+            created_at: first.created_at() + Duration::seconds(1),
+            ..task("Walk dog", None)
+        };
+        let mut tasks = vec![second.clone(), urgent.clone(), first.clone()];
+
+        let descending = TaskSort::parse("-priority").unwrap();
+        tasks.sort_by(|a, b| descending.compare(a, b));
+        assert_eq!(tasks, vec![urgent, second.clone(), first.clone()]);
+
+        let cursor = TaskCursor::after(descending, &second);
+        assert!(cursor.precedes(&first));
+        assert!(!cursor.precedes(&tasks[0]));
+        assert_eq!(descending.to_string(), "-priority");
+    }
+
+    #[test]
+    fn test_search_escapes_like_wildcards() {
+        let search = TaskSearch::new(" 100%_done\\ ").unwrap();
+        assert_eq!(search.like_pattern(), "%100\\%\\_done\\\\%");
+        assert!(search.matches("Get to 100%_DONE\\ today"));
+        assert!(TaskSearch::new("  ").is_err());
+    }
+
+    #[test]
+    fn test_tag_filter_matches_all_or_any() {
+        let tag = |name| Tag::new(name).unwrap();
+        let mut errand = task("Buy stamps", None);
+        errand.tags = TaskTags::new(vec![tag("errands"), tag("post")]).unwrap();
+        let untagged = task("Water plants", None);
+
+        let all = TaskTagFilter::new(vec![tag("errands"), tag("home")], TagMatch::All).unwrap();
+        assert!(!all.matches(&errand));
+        let all = TaskTagFilter::new(vec![tag("post"), tag("errands")], TagMatch::All).unwrap();
+        assert!(all.matches(&errand));
+        let any = TaskTagFilter::new(vec![tag("errands"), tag("home")], TagMatch::Any).unwrap();
+        assert!(any.matches(&errand));
+        assert!(!any.matches(&untagged));
+        assert!(TaskTagFilter::new(vec![], TagMatch::Any).is_err());
+    }
+
+    #[test]
+    fn test_cursor_must_match_sort() {
+        let due = TaskSort::parse("due").unwrap();
+        let cursor = TaskCursor::after(due, &task("Water plants", None));
+        let req = ListTasksRequest::new().with_sort(TaskSort::parse("-due").unwrap());
+        assert!(req.with_cursor(cursor.clone()).is_err());
+        let req = ListTasksRequest::new().with_sort(due);
+        assert_eq!(
+            req.with_cursor(cursor.clone()).unwrap().cursor(),
+            Some(&cursor)
+        );
+    }
+}
@@ -0,0 +1,474 @@
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// The upper bound on candidate dates examined when searching for the next occurrence, so that
+/// rules which can never produce another date (such as `FREQ=YEARLY` from February 29th with an
+/// interval of 3) terminate.
+const MAX_CANDIDATES: u32 = 1000;
+
+/// How often a [Recurrence] repeats, before its interval is applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Display for Frequency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        })
+    }
+}
+
+/// When a [Recurrence] stops producing occurrences.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RecurrenceEnd {
+    /// No occurrence falls after this instant.
+    Until(DateTime<Utc>),
+    /// The number of occurrences that remain, including the current one.
+    Count(u32),
+}
+
+/// A valid recurrence rule, parsed from the subset of RFC 5545 `RRULE`s that modus supports:
+/// `FREQ` (`DAILY`, `WEEKLY`, `MONTHLY` or `YEARLY`), `INTERVAL`, `BYDAY` (plain weekdays, with
+/// `DAILY` and `WEEKLY` only) and one of `UNTIL` or `COUNT`. Occurrences are computed in UTC.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Recurrence {
+    frequency: Frequency,
+    interval: u32,
+    by_weekday: Vec<Weekday>,
+    end: Option<RecurrenceEnd>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum RecurrenceError {
+    #[error("recurrence rule cannot be empty")]
+    Empty,
+    #[error("recurrence rule part {0:?} must have the form NAME=VALUE")]
+    MalformedPart(String),
+    #[error("recurrence rule part {0} is specified more than once")]
+    DuplicatePart(String),
+    #[error("recurrence rule part {0} is not supported")]
+    UnsupportedPart(String),
+    #[error("recurrence rule must specify FREQ")]
+    MissingFrequency,
+    #[error("recurrence frequency {0:?} is not supported, expected one of DAILY, WEEKLY, MONTHLY or YEARLY")]
+    UnsupportedFrequency(String),
+    #[error("recurrence interval {0:?} must be a positive whole number")]
+    InvalidInterval(String),
+    #[error("recurrence weekday {0:?} must be one of MO, TU, WE, TH, FR, SA or SU")]
+    InvalidWeekday(String),
+    #[error("recurrence BYDAY is only supported with FREQ=DAILY or FREQ=WEEKLY, not FREQ={0}")]
+    UnsupportedByDay(Frequency),
+    #[error(
+        "recurrence UNTIL {0:?} must be a date (YYYYMMDD) or a UTC date-time (YYYYMMDDTHHMMSSZ)"
+    )]
+    InvalidUntil(String),
+    #[error("recurrence COUNT {0:?} must be a positive whole number")]
+    InvalidCount(String),
+    #[error("recurrence rule cannot specify both UNTIL and COUNT")]
+    UntilAndCount,
+}
+
+impl Recurrence {
+    /// Parse an RFC 5545 `RRULE` value such as `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH`. A leading
+    /// `RRULE:` is accepted.
+    pub fn parse(raw: &str) -> Result<Self, RecurrenceError> {
+        let trimmed = raw.trim();
+        let rule = trimmed
+            .strip_prefix("RRULE:")
+            .unwrap_or(trimmed)
+            .trim_end_matches(';');
+        if rule.is_empty() {
+            return Err(RecurrenceError::Empty);
+        }
+
+        let mut frequency = None;
+        let mut interval = None;
+        let mut by_weekday = None;
+        let mut until = None;
+        let mut count = None;
+        for part in rule.split(';') {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| RecurrenceError::MalformedPart(part.to_string()))?;
+            let name = name.trim().to_ascii_uppercase();
+            let value = value.trim();
+            let duplicate = match name.as_str() {
+                "FREQ" => frequency.replace(parse_frequency(value)?).is_some(),
+                "INTERVAL" => interval.replace(parse_interval(value)?).is_some(),
+                "BYDAY" => by_weekday.replace(parse_weekdays(value)?).is_some(),
+                "UNTIL" => until.replace(parse_until(value)?).is_some(),
+                "COUNT" => count.replace(parse_count(value)?).is_some(),
+                _ => return Err(RecurrenceError::UnsupportedPart(name)),
+            };
+            if duplicate {
+                return Err(RecurrenceError::DuplicatePart(name));
+            }
+        }
+
+        let frequency = frequency.ok_or(RecurrenceError::MissingFrequency)?;
+        let by_weekday = by_weekday.unwrap_or_default();
+        if !by_weekday.is_empty() && !matches!(frequency, Frequency::Daily | Frequency::Weekly) {
+            return Err(RecurrenceError::UnsupportedByDay(frequency));
+        }
+        let end = match (until, count) {
+            (Some(_), Some(_)) => return Err(RecurrenceError::UntilAndCount),
+            (Some(until), None) => Some(RecurrenceEnd::Until(until)),
+            (None, Some(count)) => Some(RecurrenceEnd::Count(count)),
+            (None, None) => None,
+        };
+
+        Ok(Self {
+            frequency,
+            interval: interval.unwrap_or(1),
+            by_weekday,
+            end,
+        })
+    }
+
+    pub fn frequency(&self) -> Frequency {
+        self.frequency
+    }
+
+    pub fn interval(&self) -> u32 {
+        self.interval
+    }
+
+    pub fn by_weekday(&self) -> &[Weekday] {
+        &self.by_weekday
+    }
+
+    pub fn end(&self) -> Option<RecurrenceEnd> {
+        self.end
+    }
+
+    /// The occurrence that follows one at `current`, together with the rule that governs the
+    /// occurrences after it. Returns `None` once the rule is exhausted.
+    pub fn next_occurrence(&self, current: &DateTime<Utc>) -> Option<(DateTime<Utc>, Self)> {
+        let remaining = match self.end {
+            Some(RecurrenceEnd::Count(count)) if count <= 1 => return None,
+            Some(RecurrenceEnd::Count(count)) => Some(RecurrenceEnd::Count(count - 1)),
+            end => end,
+        };
+        let next = match self.frequency {
+            Frequency::Daily => self.next_daily(current),
+            Frequency::Weekly => self.next_weekly(current),
+            Frequency::Monthly => self.next_by_months(current, self.interval),
+            Frequency::Yearly => self.next_by_months(current, self.interval.checked_mul(12)?),
+        }?;
+        if let Some(RecurrenceEnd::Until(until)) = self.end {
+            if next > until {
+                return None;
+            }
+        }
+
+        let mut following = self.clone();
+        following.end = remaining;
+        Some((next, following))
+    }
+
+    fn matches_weekday(&self, at: &DateTime<Utc>) -> bool {
+        self.by_weekday.is_empty() || self.by_weekday.contains(&at.weekday())
+    }
+
+    fn next_daily(&self, current: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut candidate = *current;
+        for _ in 0..MAX_CANDIDATES {
+            candidate = candidate.checked_add_days(Days::new(u64::from(self.interval)))?;
+            if self.matches_weekday(&candidate) {
+                return Some(candidate);
+            }
+        }
+        None
+    }
+
+    fn next_weekly(&self, current: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.by_weekday.is_empty() {
+            return current.checked_add_days(Days::new(7 * u64::from(self.interval)));
+        }
+
+        // Weeks start on Monday, the RFC 5545 default for WKST
+        let days_since_monday = u64::from(current.weekday().num_days_from_monday());
+        let week_start = current.checked_sub_days(Days::new(days_since_monday))?;
+        let later_this_week = (days_since_monday + 1..7)
+            .map(|offset| week_start.checked_add_days(Days::new(offset)))
+            .find(|candidate| candidate.is_some_and(|c| self.matches_weekday(&c)));
+        if let Some(next) = later_this_week {
+            return next;
+        }
+
+        let next_week_start =
+            week_start.checked_add_days(Days::new(7 * u64::from(self.interval)))?;
+        (0..7)
+            .filter_map(|offset| next_week_start.checked_add_days(Days::new(offset)))
+            .find(|candidate| self.matches_weekday(candidate))
+    }
+
+    /// Step forward `months` at a time, keeping the day of the month and skipping months in
+    /// which that day does not exist, as RFC 5545 requires.
+    fn next_by_months(&self, current: &DateTime<Utc>, months: u32) -> Option<DateTime<Utc>> {
+        let day = current.day();
+        let time = current.time();
+        let mut month_start = NaiveDate::from_ymd_opt(current.year(), current.month(), 1)?;
+        for _ in 0..MAX_CANDIDATES {
+            month_start = month_start.checked_add_months(Months::new(months))?;
+            if let Some(date) = month_start.with_day(day) {
+                return Some(Utc.from_utc_datetime(&NaiveDateTime::new(date, time)));
+            }
+        }
+        None
+    }
+}
+
+/// Recurrences are ordered by their canonical form, since [Weekday] has no ordering of its own.
+impl PartialOrd for Recurrence {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Recurrence {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.to_string().cmp(&other.to_string())
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = RecurrenceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Renders the rule in canonical RFC 5545 form, without the `RRULE:` prefix.
+impl Display for Recurrence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FREQ={}", self.frequency)?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_weekday.is_empty() {
+            let days = self
+                .by_weekday
+                .iter()
+                .map(|day| weekday_code(*day))
+                .collect::<Vec<_>>();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        match self.end {
+            Some(RecurrenceEnd::Until(until)) => {
+                write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))
+            }
+            Some(RecurrenceEnd::Count(count)) => write!(f, ";COUNT={}", count),
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse_frequency(value: &str) -> Result<Frequency, RecurrenceError> {
+    match value.to_ascii_uppercase().as_str() {
+        "DAILY" => Ok(Frequency::Daily),
+        "WEEKLY" => Ok(Frequency::Weekly),
+        "MONTHLY" => Ok(Frequency::Monthly),
+        "YEARLY" => Ok(Frequency::Yearly),
+        _ => Err(RecurrenceError::UnsupportedFrequency(value.to_string())),
+    }
+}
+
+fn parse_interval(value: &str) -> Result<u32, RecurrenceError> {
+    match value.parse() {
+        Ok(interval) if interval > 0 => Ok(interval),
+        _ => Err(RecurrenceError::InvalidInterval(value.to_string())),
+    }
+}
+
+fn parse_count(value: &str) -> Result<u32, RecurrenceError> {
+    match value.parse() {
+        Ok(count) if count > 0 => Ok(count),
+        _ => Err(RecurrenceError::InvalidCount(value.to_string())),
+    }
+}
+
+fn parse_weekdays(value: &str) -> Result<Vec<Weekday>, RecurrenceError> {
+    let mut days = value
+        .split(',')
+        .map(|code| match code.trim().to_ascii_uppercase().as_str() {
+            "MO" => Ok(Weekday::Mon),
+            "TU" => Ok(Weekday::Tue),
+            "WE" => Ok(Weekday::Wed),
+            "TH" => Ok(Weekday::Thu),
+            "FR" => Ok(Weekday::Fri),
+            "SA" => Ok(Weekday::Sat),
+            "SU" => Ok(Weekday::Sun),
+            _ => Err(RecurrenceError::InvalidWeekday(code.to_string())),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    days.sort_by_key(Weekday::num_days_from_monday);
+    days.dedup();
+    Ok(days)
+}
+
+fn parse_until(value: &str) -> Result<DateTime<Utc>, RecurrenceError> {
+    let invalid = || RecurrenceError::InvalidUntil(value.to_string());
+    if let Some(date_time) = value.strip_suffix('Z') {
+        let naive =
+            NaiveDateTime::parse_from_str(date_time, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+        return Ok(Utc.from_utc_datetime(&naive));
+    }
+    // A plain date includes every occurrence on that day
+    let date = NaiveDate::parse_from_str(value, "%Y%m%d").map_err(|_| invalid())?;
+    let end_of_day = date.and_hms_opt(23, 59, 59).ok_or_else(invalid)?;
+    Ok(Utc.from_utc_datetime(&end_of_day))
+}
+
+fn weekday_code(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(raw: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(raw)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn occurrences(rule: &str, start: &str, n: usize) -> Vec<String> {
+        let mut recurrence = Recurrence::parse(rule).unwrap();
+        let mut current = at(start);
+        let mut result = vec![];
+        for _ in 0..n {
+            match recurrence.next_occurrence(&current) {
+                Some((next, following)) => {
+                    result.push(next.format("%Y-%m-%d").to_string());
+                    current = next;
+                    recurrence = following;
+                }
+                None => break,
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_parse_round_trips_canonical_form() {
+        let recurrence = Recurrence::parse("RRULE:freq=weekly;BYDAY=TH,MO,MO;INTERVAL=2").unwrap();
+        assert_eq!(recurrence.to_string(), "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH");
+        assert_eq!(
+            Recurrence::parse(&recurrence.to_string()).unwrap(),
+            recurrence
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_rules() {
+        let cases = [
+            ("", RecurrenceError::Empty),
+            ("INTERVAL=2", RecurrenceError::MissingFrequency),
+            (
+                "FREQ=HOURLY",
+                RecurrenceError::UnsupportedFrequency("HOURLY".to_string()),
+            ),
+            (
+                "FREQ=DAILY;INTERVAL=0",
+                RecurrenceError::InvalidInterval("0".to_string()),
+            ),
+            (
+                "FREQ=MONTHLY;BYDAY=MO",
+                RecurrenceError::UnsupportedByDay(Frequency::Monthly),
+            ),
+            (
+                "FREQ=WEEKLY;BYDAY=1MO",
+                RecurrenceError::InvalidWeekday("1MO".to_string()),
+            ),
+            (
+                "FREQ=DAILY;COUNT=3;UNTIL=20250101",
+                RecurrenceError::UntilAndCount,
+            ),
+            (
+                "FREQ=DAILY;FREQ=WEEKLY",
+                RecurrenceError::DuplicatePart("FREQ".to_string()),
+            ),
+            (
+                "FREQ=DAILY;BYMONTHDAY=1",
+                RecurrenceError::UnsupportedPart("BYMONTHDAY".to_string()),
+            ),
+            (
+                "FREQ=DAILY;UNTIL=2025-01-01",
+                RecurrenceError::InvalidUntil("2025-01-01".to_string()),
+            ),
+        ];
+        for (rule, expected) in cases {
+            assert_eq!(Recurrence::parse(rule), Err(expected), "rule {:?}", rule);
+        }
+    }
+
+    #[test]
+    fn test_next_occurrence_daily_with_interval() {
+        assert_eq!(
+            occurrences("FREQ=DAILY;INTERVAL=3", "2025-01-30T08:00:00Z", 3),
+            vec!["2025-02-02", "2025-02-05", "2025-02-08"]
+        );
+    }
+
+    #[test]
+    fn test_next_occurrence_weekly_by_weekday() {
+        // 2025-01-01 is a Wednesday
+        assert_eq!(
+            occurrences(
+                "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH",
+                "2025-01-01T08:00:00Z",
+                4
+            ),
+            vec!["2025-01-02", "2025-01-13", "2025-01-16", "2025-01-27"]
+        );
+    }
+
+    #[test]
+    fn test_next_occurrence_monthly_skips_missing_days() {
+        assert_eq!(
+            occurrences("FREQ=MONTHLY", "2025-01-31T08:00:00Z", 3),
+            vec!["2025-03-31", "2025-05-31", "2025-07-31"]
+        );
+    }
+
+    #[test]
+    fn test_next_occurrence_yearly_on_leap_day() {
+        assert_eq!(
+            occurrences("FREQ=YEARLY", "2024-02-29T08:00:00Z", 2),
+            vec!["2028-02-29", "2032-02-29"]
+        );
+    }
+
+    #[test]
+    fn test_next_occurrence_respects_count_and_until() {
+        assert_eq!(
+            occurrences("FREQ=DAILY;COUNT=3", "2025-01-01T08:00:00Z", 10),
+            vec!["2025-01-02", "2025-01-03"]
+        );
+        assert_eq!(
+            occurrences("FREQ=WEEKLY;UNTIL=20250115", "2025-01-01T08:00:00Z", 10),
+            vec!["2025-01-08", "2025-01-15"]
+        );
+    }
+}
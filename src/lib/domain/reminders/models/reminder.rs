@@ -0,0 +1,62 @@
+use crate::domain::reminders::models::task::Task;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// A reminder whose time has come, claimed for delivery.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DueReminder {
+    pub task: Task,
+    pub remind_at: DateTime<Utc>,
+    /// The user to remind: the assignee of the task, or else the owner of its list or inbox.
+    pub recipient: Uuid,
+    /// The number of earlier delivery attempts that failed.
+    pub attempts: u32,
+}
+
+impl DueReminder {
+    /// The number of times delivery of a reminder is attempted before it is abandoned.
+    pub const MAX_DELIVERY_ATTEMPTS: u32 = 5;
+
+    pub fn task(&self) -> &Task {
+        &self.task
+    }
+
+    pub fn remind_at(&self) -> &DateTime<Utc> {
+        &self.remind_at
+    }
+
+    pub fn recipient(&self) -> Uuid {
+        self.recipient
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Whether delivery should be attempted again after the current attempt fails.
+    pub fn should_retry(&self) -> bool {
+        self.attempts + 1 < Self::MAX_DELIVERY_ATTEMPTS
+    }
+}
+
+/// The outcome of a single pass over the reminders that are due.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FiredReminders {
+    pub delivered: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Error)]
+pub enum FireRemindersError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum NotifyReminderError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
@@ -0,0 +1,209 @@
+use crate::domain::reminders::models::member::ListRole;
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// A valid tag: trimmed, lowercase, and free of commas so that tags can be listed in a single
+/// comma-separated query parameter. Two tags that differ only in case or surrounding whitespace
+/// are the same tag.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tag(String);
+
+#[derive(Clone, Debug, Error)]
+#[error(
+    "tag {0:?} must be between 1 and {max} characters long and cannot contain commas",
+    max = Tag::MAX_LENGTH
+)]
+pub struct TagInvalidError(String);
+
+impl Tag {
+    pub const MAX_LENGTH: usize = 32;
+
+    pub fn new(raw: &str) -> Result<Self, TagInvalidError> {
+        let normalised = raw.trim().to_lowercase();
+        let length = normalised.chars().count();
+        if length == 0 || length > Self::MAX_LENGTH || normalised.contains(',') {
+            Err(TagInvalidError(raw.to_string()))
+        } else {
+            Ok(Self(normalised))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Tag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A valid set of [Tag]s for a task: deduplicated, in alphabetical order, and bounded in size.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskTags(Vec<Tag>);
+
+#[derive(Clone, Debug, Error)]
+#[error("a task cannot have more than {max} tags", max = TaskTags::MAX_TAGS)]
+pub struct TaskTagsTooManyError;
+
+impl TaskTags {
+    pub const MAX_TAGS: usize = 20;
+
+    pub fn new(tags: Vec<Tag>) -> Result<Self, TaskTagsTooManyError> {
+        let mut tags = tags;
+        tags.sort();
+        tags.dedup();
+        if tags.len() > Self::MAX_TAGS {
+            Err(TaskTagsTooManyError)
+        } else {
+            Ok(Self(tags))
+        }
+    }
+
+    pub fn tags(&self) -> &[Tag] {
+        &self.0
+    }
+
+    /// These tags together with `tags`.
+    pub fn with(&self, tags: &[Tag]) -> Result<Self, TaskTagsTooManyError> {
+        Self::new(self.0.iter().chain(tags).cloned().collect())
+    }
+
+    /// These tags except for `tags`.
+    pub fn without(&self, tags: &[Tag]) -> Self {
+        Self(
+            self.0
+                .iter()
+                .filter(|tag| !tags.contains(tag))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// These tags with each of `tags` replaced by `into`.
+    pub fn replace(&self, tags: &[Tag], into: &Tag) -> Self {
+        if !self.0.iter().any(|tag| tags.contains(tag)) {
+            return self.clone();
+        }
+        let mut replaced = self.without(tags);
+        replaced.0.push(into.clone());
+        replaced.0.sort();
+        replaced.0.dedup();
+        replaced
+    }
+
+    pub fn contains(&self, tag: &Tag) -> bool {
+        self.0.binary_search(tag).is_ok()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// A [Tag] together with the number of tasks that carry it. Tags only exist while at least one
+/// task carries them.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TagUsage {
+    pub tag: Tag,
+    pub tasks: u64,
+}
+
+impl TagUsage {
+    pub fn tag(&self) -> &Tag {
+        &self.tag
+    }
+
+    pub fn tasks(&self) -> u64 {
+        self.tasks
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TagTaskError {
+    #[error("task with id {id} not found")]
+    NotFound { id: Uuid },
+    #[error("your role in task list {id}, {role}, does not let you change its tasks")]
+    Forbidden { id: Uuid, role: ListRole },
+    #[error("task with id {id} kept changing while its tags were being changed")]
+    Conflict { id: Uuid },
+    #[error(transparent)]
+    TooMany(#[from] TaskTagsTooManyError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum UntagTaskError {
+    #[error("task with id {id} not found")]
+    NotFound { id: Uuid },
+    #[error("your role in task list {id}, {role}, does not let you change its tasks")]
+    Forbidden { id: Uuid, role: ListRole },
+    #[error("task with id {id} kept changing while its tags were being changed")]
+    Conflict { id: Uuid },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum ListTagsError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum RenameTagError {
+    #[error("tag {tag} not found")]
+    NotFound { tag: Tag },
+    #[error("tag {tag} already exists, merge the tags instead")]
+    Duplicate { tag: Tag },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum MergeTagsError {
+    #[error("tag {tag} not found")]
+    NotFound { tag: Tag },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_is_trimmed_and_lowercase() {
+        assert_eq!(Tag::new("  Work ").unwrap().as_str(), "work");
+        assert_eq!(Tag::new("Deep Work").unwrap().as_str(), "deep work");
+        assert!(Tag::new("   ").is_err());
+        assert!(Tag::new("home,garden").is_err());
+        assert!(Tag::new(&"x".repeat(Tag::MAX_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_task_tags_are_sorted_and_bounded() {
+        let tags = TaskTags::new(vec![
+            Tag::new("work").unwrap(),
+            Tag::new("Errands").unwrap(),
+            Tag::new("WORK").unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(
+            tags.tags(),
+            [Tag::new("errands").unwrap(), Tag::new("work").unwrap()]
+        );
+        let too_many = (0..=TaskTags::MAX_TAGS)
+            .map(|i| Tag::new(&format!("tag{i}")).unwrap())
+            .collect();
+        assert!(TaskTags::new(too_many).is_err());
+    }
+}
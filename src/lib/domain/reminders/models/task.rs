@@ -1,4 +1,8 @@
-use derive_more::From;
+use crate::domain::reminders::models::member::ListRole;
+use crate::domain::reminders::models::recurrence::Recurrence;
+use crate::domain::reminders::models::tag::TaskTags;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use thiserror::Error;
 use uuid::Uuid;
@@ -11,11 +15,28 @@ pub struct TaskTitle(String);
 #[error("task title cannot be empty")]
 pub struct TaskTitleEmptyError;
 
+/// A [TaskTitle], [Priority] or [TaskNotes] that failed validation.
+#[derive(Clone, Debug, Error)]
+pub enum TaskFieldError {
+    #[error(transparent)]
+    TitleEmpty(#[from] TaskTitleEmptyError),
+    #[error("task title cannot be longer than {max} characters", max = TaskTitle::MAX_LENGTH)]
+    TitleTooLong,
+    #[error("priority {0:?} must be one of none, low, medium, high or urgent")]
+    PriorityInvalid(String),
+    #[error("task notes cannot be longer than {max} characters", max = TaskNotes::MAX_LENGTH)]
+    NotesTooLong,
+}
+
 impl TaskTitle {
-    pub fn new(raw: &str) -> Result<Self, TaskTitleEmptyError> {
+    pub const MAX_LENGTH: usize = 200;
+
+    pub fn new(raw: &str) -> Result<Self, TaskFieldError> {
         let trimmed = raw.trim();
         if trimmed.is_empty() {
-            Err(TaskTitleEmptyError)
+            Err(TaskTitleEmptyError.into())
+        } else if trimmed.chars().count() > Self::MAX_LENGTH {
+            Err(TaskFieldError::TitleTooLong)
         } else {
             Ok(Self(trimmed.to_string()))
         }
@@ -28,20 +49,262 @@ impl Display for TaskTitle {
     }
 }
 
+/// How important a task is, in ascending order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+impl Priority {
+    /// Parse the lowercase name of a priority.
+    pub fn parse(raw: &str) -> Result<Self, TaskFieldError> {
+        match raw {
+            "none" => Ok(Self::None),
+            "low" => Ok(Self::Low),
+            "medium" => Ok(Self::Medium),
+            "high" => Ok(Self::High),
+            "urgent" => Ok(Self::Urgent),
+            _ => Err(TaskFieldError::PriorityInvalid(raw.to_string())),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Urgent => "urgent",
+        }
+    }
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Valid free-form notes for a task, in Markdown. Notes are kept as written, apart from trailing
+/// whitespace.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskNotes(String);
+
+impl TaskNotes {
+    pub const MAX_LENGTH: usize = 10_000;
+
+    pub fn new(raw: &str) -> Result<Self, TaskFieldError> {
+        let trimmed = raw.trim_end();
+        if trimmed.chars().count() > Self::MAX_LENGTH {
+            Err(TaskFieldError::NotesTooLong)
+        } else {
+            Ok(Self(trimmed.to_string()))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for TaskNotes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The moment a task is due, normalised to UTC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DueAt(DateTime<Utc>);
+
+#[derive(Clone, Debug, Error)]
+#[error("due date {0:?} must be an RFC 3339 timestamp with a timezone offset")]
+pub struct DueAtInvalidError(String);
+
+impl DueAt {
+    pub fn new(at: DateTime<Utc>) -> Self {
+        Self(at)
+    }
+
+    /// Parse an RFC 3339 timestamp. The timezone offset is mandatory so that a due date is never
+    /// interpreted in an implicit local time.
+    pub fn parse(raw: &str) -> Result<Self, DueAtInvalidError> {
+        DateTime::parse_from_rfc3339(raw.trim())
+            .map(|at| Self(at.with_timezone(&Utc)))
+            .map_err(|_| DueAtInvalidError(raw.to_string()))
+    }
+
+    pub fn as_datetime(&self) -> &DateTime<Utc> {
+        &self.0
+    }
+}
+
+impl Display for DueAt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.to_rfc3339())
+    }
+}
+
+/// How long before a task's [DueAt] a reminder should fire, with minute precision.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReminderOffset(u32);
+
+#[derive(Clone, Debug, Error)]
+pub enum ReminderOffsetError {
+    #[error("reminder offset {0:?} must be a whole number followed by one of m, h, d or w")]
+    Invalid(String),
+    #[error("reminder offset {0:?} exceeds the maximum of 4 weeks")]
+    TooLarge(String),
+}
+
+impl ReminderOffset {
+    /// The largest supported offset, in minutes.
+    pub const MAX_MINUTES: u32 = 4 * 7 * 24 * 60;
+
+    pub fn from_minutes(minutes: u32) -> Result<Self, ReminderOffsetError> {
+        if minutes > Self::MAX_MINUTES {
+            Err(ReminderOffsetError::TooLarge(format!("{}m", minutes)))
+        } else {
+            Ok(Self(minutes))
+        }
+    }
+
+    /// Parse a duration such as `15m`, `2h`, `1d` or `1w`.
+    pub fn parse(raw: &str) -> Result<Self, ReminderOffsetError> {
+        let trimmed = raw.trim();
+        let invalid = || ReminderOffsetError::Invalid(raw.to_string());
+        let unit_index = trimmed
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let (amount, unit) = trimmed.split_at(unit_index);
+        let amount: u32 = amount.parse().map_err(|_| invalid())?;
+        let multiplier = match unit {
+            "m" => 1,
+            "h" => 60,
+            "d" => 24 * 60,
+            "w" => 7 * 24 * 60,
+            _ => return Err(invalid()),
+        };
+        let minutes = amount
+            .checked_mul(multiplier)
+            .ok_or_else(|| ReminderOffsetError::TooLarge(raw.to_string()))?;
+        Self::from_minutes(minutes).map_err(|_| ReminderOffsetError::TooLarge(raw.to_string()))
+    }
+
+    pub fn minutes(&self) -> u32 {
+        self.0
+    }
+
+    /// The moment this reminder fires for a task due at `due_at`.
+    pub fn remind_at(&self, due_at: &DueAt) -> DateTime<Utc> {
+        due_at.0 - chrono::Duration::minutes(i64::from(self.0))
+    }
+}
+
+impl Display for ReminderOffset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (amount, unit) = match self.0 {
+            0 => (0, "m"),
+            m if m % (7 * 24 * 60) == 0 => (m / (7 * 24 * 60), "w"),
+            m if m % (24 * 60) == 0 => (m / (24 * 60), "d"),
+            m if m % 60 == 0 => (m / 60, "h"),
+            m => (m, "m"),
+        };
+        write!(f, "{}{}", amount, unit)
+    }
+}
+
+/// A valid set of [ReminderOffset]s for a task: deduplicated, ordered from the earliest reminder
+/// to the latest, and bounded in size. Reminders only fire for tasks that have a [DueAt].
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RemindAt(Vec<ReminderOffset>);
+
+#[derive(Clone, Debug, Error)]
+#[error("a task cannot have more than {max} reminders", max = RemindAt::MAX_REMINDERS)]
+pub struct RemindAtTooManyError;
+
+impl RemindAt {
+    pub const MAX_REMINDERS: usize = 10;
+
+    pub fn new(offsets: Vec<ReminderOffset>) -> Result<Self, RemindAtTooManyError> {
+        let mut offsets = offsets;
+        offsets.sort_by(|a, b| b.cmp(a));
+        offsets.dedup();
+        if offsets.len() > Self::MAX_REMINDERS {
+            Err(RemindAtTooManyError)
+        } else {
+            Ok(Self(offsets))
+        }
+    }
+
+    pub fn offsets(&self) -> &[ReminderOffset] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
 /// A uniquely identifiable task of reminders reminders.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Task {
     pub id: Uuid,
     pub title: TaskTitle,
     pub completed: bool,
+    pub priority: Priority,
+    pub notes: Option<TaskNotes>,
+    pub due_at: Option<DueAt>,
+    pub remind_at: RemindAt,
+    pub recurrence: Option<Recurrence>,
+    /// The [TaskList](crate::domain::reminders::models::list::TaskList) the task belongs to, or
+    /// `None` if it is in the inbox.
+    pub list_id: Option<Uuid>,
+    /// The task this is a subtask of, or `None` for a top-level task.
+    pub parent_id: Option<Uuid>,
+    /// The order of the task among its siblings, ascending, then in the order they were created.
+    pub position: i32,
+    pub tags: TaskTags,
+    /// The owner or a member of the task's list the task is assigned to, who receives its
+    /// reminders instead of the owner, or `None` if it is unassigned.
+    pub assignee_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    /// The user who last created or changed the task, if known.
+    pub updated_by: Option<Uuid>,
+    /// The user who completed the task, or `None` while it is incomplete.
+    pub completed_by: Option<Uuid>,
+    /// Starts at 1 and is incremented on every change to the task.
+    pub version: i64,
 }
 
 impl Task {
+    /// How many levels of subtasks can be nested below a top-level task.
+    pub const MAX_DEPTH: usize = 3;
+
     pub fn new(id: Uuid, title: TaskTitle) -> Self {
         Self {
             id,
             title,
             completed: false,
+            priority: Priority::default(),
+            notes: None,
+            due_at: None,
+            remind_at: RemindAt::default(),
+            recurrence: None,
+            list_id: None,
+            parent_id: None,
+            position: 0,
+            tags: TaskTags::default(),
+            assignee_id: None,
+            created_at: Utc::now(),
+            updated_by: None,
+            completed_by: None,
+            version: 1,
         }
     }
 
@@ -52,29 +315,593 @@ impl Task {
     pub fn title(&self) -> &TaskTitle {
         &self.title
     }
+
+    pub fn completed(&self) -> bool {
+        self.completed
+    }
+
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    pub fn notes(&self) -> Option<&TaskNotes> {
+        self.notes.as_ref()
+    }
+
+    pub fn due_at(&self) -> Option<&DueAt> {
+        self.due_at.as_ref()
+    }
+
+    pub fn remind_at(&self) -> &RemindAt {
+        &self.remind_at
+    }
+
+    pub fn recurrence(&self) -> Option<&Recurrence> {
+        self.recurrence.as_ref()
+    }
+
+    pub fn list_id(&self) -> Option<Uuid> {
+        self.list_id
+    }
+
+    pub fn parent_id(&self) -> Option<Uuid> {
+        self.parent_id
+    }
+
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    pub fn tags(&self) -> &TaskTags {
+        &self.tags
+    }
+
+    pub fn assignee_id(&self) -> Option<Uuid> {
+        self.assignee_id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_by(&self) -> Option<Uuid> {
+        self.updated_by
+    }
+
+    pub fn completed_by(&self) -> Option<Uuid> {
+        self.completed_by
+    }
+
+    pub fn version(&self) -> i64 {
+        self.version
+    }
+
+    /// The request that creates the occurrence following this task once it is completed at
+    /// `completed_at`, or `None` if the task does not recur or its recurrence is exhausted. Tasks
+    /// without a due date recur relative to their completion. The next occurrence of a subtask is
+    /// placed last among its siblings.
+    pub fn next_occurrence(&self, completed_at: DateTime<Utc>) -> Option<CreateTaskRequest> {
+        let recurrence = self.recurrence.as_ref()?;
+        let anchor = self
+            .due_at
+            .map_or(completed_at, |due_at| *due_at.as_datetime());
+        let (next, following) = recurrence.next_occurrence(&anchor)?;
+        let mut req = CreateTaskRequest::new(self.title.clone())
+            .with_priority(self.priority)
+            .with_due_at(DueAt::new(next))
+            .with_remind_at(self.remind_at.clone())
+            .with_recurrence(following)
+            .with_tags(self.tags.clone());
+        if let Some(list_id) = self.list_id {
+            req = req.with_list_id(list_id);
+        }
+        if let Some(parent_id) = self.parent_id {
+            req = req.with_parent_id(parent_id);
+        }
+        if let Some(notes) = &self.notes {
+            req = req.with_notes(notes.clone());
+        }
+        if let Some(assignee_id) = self.assignee_id {
+            req = req.with_assignee_id(assignee_id);
+        }
+        Some(req)
+    }
+}
+
+/// A [Task] together with its subtasks, recursively, each level in sibling order.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TaskTree {
+    task: Task,
+    subtasks: Vec<TaskTree>,
 }
 
-/// The fields required by the domain to create a [Task].
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, From)]
+impl TaskTree {
+    /// Arrange `subtasks`, which are nested below `task` at any depth, into a tree. Siblings keep
+    /// the order they are given in.
+    pub fn new(task: Task, subtasks: Vec<Task>) -> Self {
+        let mut children = HashMap::<Uuid, Vec<Task>>::new();
+        for subtask in subtasks {
+            if let Some(parent_id) = subtask.parent_id {
+                children.entry(parent_id).or_default().push(subtask);
+            }
+        }
+        Self::build(task, &mut children)
+    }
+
+    fn build(task: Task, children: &mut HashMap<Uuid, Vec<Task>>) -> Self {
+        let subtasks = children
+            .remove(&task.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| Self::build(child, children))
+            .collect();
+        Self { task, subtasks }
+    }
+
+    pub fn task(&self) -> &Task {
+        &self.task
+    }
+
+    pub fn subtasks(&self) -> &[TaskTree] {
+        &self.subtasks
+    }
+
+    /// The number of levels of subtasks below the task.
+    pub fn height(&self) -> usize {
+        self.subtasks
+            .iter()
+            .map(|subtask| subtask.height() + 1)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Whether the task with the given `id` is this task or one of its subtasks.
+    pub fn contains(&self, id: &Uuid) -> bool {
+        self.task.id == *id || self.subtasks.iter().any(|subtask| subtask.contains(id))
+    }
+}
+
+/// The fields required by the domain to create a [Task]. Without a position, the task is placed
+/// after its existing siblings.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CreateTaskRequest {
     title: TaskTitle,
+    priority: Priority,
+    notes: Option<TaskNotes>,
+    due_at: Option<DueAt>,
+    remind_at: RemindAt,
+    recurrence: Option<Recurrence>,
+    list_id: Option<Uuid>,
+    parent_id: Option<Uuid>,
+    position: Option<i32>,
+    tags: TaskTags,
+    assignee_id: Option<Uuid>,
+    updated_by: Option<Uuid>,
 }
 
 impl CreateTaskRequest {
     pub fn new(title: TaskTitle) -> Self {
-        Self { title }
+        Self {
+            title,
+            priority: Priority::default(),
+            notes: None,
+            due_at: None,
+            remind_at: RemindAt::default(),
+            recurrence: None,
+            list_id: None,
+            parent_id: None,
+            position: None,
+            tags: TaskTags::default(),
+            assignee_id: None,
+            updated_by: None,
+        }
+    }
+
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_notes(mut self, notes: TaskNotes) -> Self {
+        self.notes = Some(notes);
+        self
+    }
+
+    pub fn with_due_at(mut self, due_at: DueAt) -> Self {
+        self.due_at = Some(due_at);
+        self
+    }
+
+    pub fn with_remind_at(mut self, remind_at: RemindAt) -> Self {
+        self.remind_at = remind_at;
+        self
+    }
+
+    pub fn with_recurrence(mut self, recurrence: Recurrence) -> Self {
+        self.recurrence = Some(recurrence);
+        self
+    }
+
+    pub fn with_list_id(mut self, list_id: Uuid) -> Self {
+        self.list_id = Some(list_id);
+        self
+    }
+
+    /// Create the task as a subtask of the task with the given id.
+    pub fn with_parent_id(mut self, parent_id: Uuid) -> Self {
+        self.parent_id = Some(parent_id);
+        self
+    }
+
+    pub fn with_position(mut self, position: i32) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    pub fn with_tags(mut self, tags: TaskTags) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Assign the task to the user with the given id.
+    pub fn with_assignee_id(mut self, assignee_id: Uuid) -> Self {
+        self.assignee_id = Some(assignee_id);
+        self
+    }
+
+    /// Record `user` as the creator of the task.
+    pub fn with_updated_by(mut self, user: Uuid) -> Self {
+        self.updated_by = Some(user);
+        self
     }
 
     pub fn title(&self) -> &TaskTitle {
         &self.title
     }
+
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    pub fn notes(&self) -> Option<&TaskNotes> {
+        self.notes.as_ref()
+    }
+
+    pub fn due_at(&self) -> Option<&DueAt> {
+        self.due_at.as_ref()
+    }
+
+    pub fn remind_at(&self) -> &RemindAt {
+        &self.remind_at
+    }
+
+    pub fn recurrence(&self) -> Option<&Recurrence> {
+        self.recurrence.as_ref()
+    }
+
+    pub fn list_id(&self) -> Option<Uuid> {
+        self.list_id
+    }
+
+    pub fn parent_id(&self) -> Option<Uuid> {
+        self.parent_id
+    }
+
+    pub fn position(&self) -> Option<i32> {
+        self.position
+    }
+
+    pub fn tags(&self) -> &TaskTags {
+        &self.tags
+    }
+
+    pub fn assignee_id(&self) -> Option<Uuid> {
+        self.assignee_id
+    }
+
+    pub fn updated_by(&self) -> Option<Uuid> {
+        self.updated_by
+    }
 }
 
 #[derive(Debug, Error)]
 pub enum CreateTaskError {
     #[error("task with title {title} already exists")]
     Duplicate { title: TaskTitle },
+    #[error("task list with id {id} not found")]
+    ListNotFound { id: Uuid },
+    #[error("parent task with id {id} not found")]
+    ParentNotFound { id: Uuid },
+    #[error("subtasks cannot be nested more than {max} levels deep", max = Task::MAX_DEPTH)]
+    TooDeep,
+    #[error("your role in task list {id}, {role}, does not let you change its tasks")]
+    Forbidden { id: Uuid, role: ListRole },
+    #[error("user with id {id} is not a member of the task's list")]
+    AssigneeNotMember { id: Uuid },
+    #[error("reminders cannot be set on a task without a due date")]
+    RemindAtWithoutDueAt,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum GetTaskError {
+    #[error("task with id {id} not found")]
+    NotFound { id: Uuid },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum ListTasksError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+/// The fields required by the domain to update a [Task]. Fields that are not set are left
+/// unchanged; optional fields are cleared by setting them to `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UpdateTaskRequest {
+    title: Option<TaskTitle>,
+    completed: Option<bool>,
+    priority: Option<Priority>,
+    notes: Option<Option<TaskNotes>>,
+    due_at: Option<Option<DueAt>>,
+    remind_at: Option<RemindAt>,
+    recurrence: Option<Option<Recurrence>>,
+    list_id: Option<Option<Uuid>>,
+    parent_id: Option<Option<Uuid>>,
+    position: Option<i32>,
+    tags: Option<TaskTags>,
+    assignee_id: Option<Option<Uuid>>,
+    updated_by: Option<Uuid>,
+    version: Option<i64>,
+}
+
+impl UpdateTaskRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_title(mut self, title: TaskTitle) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    pub fn with_completed(mut self, completed: bool) -> Self {
+        self.completed = Some(completed);
+        self
+    }
+
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    pub fn with_notes(mut self, notes: Option<TaskNotes>) -> Self {
+        self.notes = Some(notes);
+        self
+    }
+
+    pub fn with_due_at(mut self, due_at: Option<DueAt>) -> Self {
+        self.due_at = Some(due_at);
+        self
+    }
+
+    pub fn with_remind_at(mut self, remind_at: RemindAt) -> Self {
+        self.remind_at = Some(remind_at);
+        self
+    }
+
+    pub fn with_recurrence(mut self, recurrence: Option<Recurrence>) -> Self {
+        self.recurrence = Some(recurrence);
+        self
+    }
+
+    /// Move the task to the given list, or to the inbox with `None`.
+    pub fn with_list_id(mut self, list_id: Option<Uuid>) -> Self {
+        self.list_id = Some(list_id);
+        self
+    }
+
+    /// Move the task, with its subtasks, below the given task, or to the top level with `None`.
+    /// Unless a position is also set, the task is placed after its new siblings.
+    pub fn with_parent_id(mut self, parent_id: Option<Uuid>) -> Self {
+        self.parent_id = Some(parent_id);
+        self
+    }
+
+    pub fn with_position(mut self, position: i32) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// Replace the tags of the task.
+    pub fn with_tags(mut self, tags: TaskTags) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+
+    /// Assign the task to the user with the given id, or unassign it with `None`.
+    pub fn with_assignee_id(mut self, assignee_id: Option<Uuid>) -> Self {
+        self.assignee_id = Some(assignee_id);
+        self
+    }
+
+    /// Record `user` as the one who changed the task, and who completed it if the change
+    /// completes it.
+    pub fn with_updated_by(mut self, user: Uuid) -> Self {
+        self.updated_by = Some(user);
+        self
+    }
+
+    /// Only change the task if it is still at `version`, so that changes made since the task
+    /// was read are not overwritten.
+    pub fn with_version(mut self, version: i64) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    pub fn title(&self) -> Option<&TaskTitle> {
+        self.title.as_ref()
+    }
+
+    pub fn completed(&self) -> Option<bool> {
+        self.completed
+    }
+
+    pub fn priority(&self) -> Option<Priority> {
+        self.priority
+    }
+
+    pub fn notes(&self) -> Option<Option<&TaskNotes>> {
+        self.notes.as_ref().map(Option::as_ref)
+    }
+
+    pub fn due_at(&self) -> Option<Option<&DueAt>> {
+        self.due_at.as_ref().map(Option::as_ref)
+    }
+
+    pub fn remind_at(&self) -> Option<&RemindAt> {
+        self.remind_at.as_ref()
+    }
+
+    pub fn recurrence(&self) -> Option<Option<&Recurrence>> {
+        self.recurrence.as_ref().map(Option::as_ref)
+    }
+
+    pub fn list_id(&self) -> Option<Option<Uuid>> {
+        self.list_id
+    }
+
+    pub fn parent_id(&self) -> Option<Option<Uuid>> {
+        self.parent_id
+    }
+
+    pub fn position(&self) -> Option<i32> {
+        self.position
+    }
+
+    pub fn tags(&self) -> Option<&TaskTags> {
+        self.tags.as_ref()
+    }
+
+    pub fn assignee_id(&self) -> Option<Option<Uuid>> {
+        self.assignee_id
+    }
+
+    pub fn updated_by(&self) -> Option<Uuid> {
+        self.updated_by
+    }
+
+    pub fn version(&self) -> Option<i64> {
+        self.version
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateTaskError {
+    #[error("task with id {id} not found")]
+    NotFound { id: Uuid },
+    #[error("task with title {title} already exists")]
+    Duplicate { title: TaskTitle },
+    #[error("task list with id {id} not found")]
+    ListNotFound { id: Uuid },
+    #[error("parent task with id {id} not found")]
+    ParentNotFound { id: Uuid },
+    #[error("subtasks cannot be nested more than {max} levels deep", max = Task::MAX_DEPTH)]
+    TooDeep,
+    #[error("task with id {id} cannot be moved below itself or one of its subtasks")]
+    ParentCycle { id: Uuid },
+    #[error("your role in task list {id}, {role}, does not let you change its tasks")]
+    Forbidden { id: Uuid, role: ListRole },
+    #[error("user with id {id} is not a member of the task's list")]
+    AssigneeNotMember { id: Uuid },
+    #[error("task with id {id} was changed since version {version}")]
+    Conflict { id: Uuid, version: i64 },
+    #[error("reminders cannot be set on a task without a due date")]
+    RemindAtWithoutDueAt,
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
     // to be extended as new error scenarios are introduced
 }
+
+#[derive(Debug, Error)]
+pub enum DeleteTaskError {
+    #[error("task with id {id} not found")]
+    NotFound { id: Uuid },
+    #[error("your role in task list {id}, {role}, does not let you change its tasks")]
+    Forbidden { id: Uuid, role: ListRole },
+    #[error("task with id {id} was changed since version {version}")]
+    Conflict { id: Uuid, version: i64 },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum RestoreTaskError {
+    #[error("task with id {id} not found in the trash")]
+    NotFound { id: Uuid },
+    #[error("task with title {title} already exists")]
+    Duplicate { title: TaskTitle },
+    #[error("parent task with id {id} is in the trash")]
+    ParentDeleted { id: Uuid },
+    #[error("task list with id {id} is in the trash")]
+    ListDeleted { id: Uuid },
+    #[error("your role in task list {id}, {role}, does not let you change its tasks")]
+    Forbidden { id: Uuid, role: ListRole },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_fields_are_bounded() {
+        assert_eq!(
+            TaskTitle::new("  Call mum ").unwrap().to_string(),
+            "Call mum"
+        );
+        assert!(matches!(
+            TaskTitle::new(" "),
+            Err(TaskFieldError::TitleEmpty(_))
+        ));
+        assert!(TaskTitle::new(&"x".repeat(TaskTitle::MAX_LENGTH)).is_ok());
+        assert!(matches!(
+            TaskTitle::new(&"x".repeat(TaskTitle::MAX_LENGTH + 1)),
+            Err(TaskFieldError::TitleTooLong)
+        ));
+        assert_eq!(
+            TaskNotes::new("- [ ] milk\n- [ ] eggs\n\n")
+                .unwrap()
+                .as_str(),
+            "- [ ] milk\n- [ ] eggs"
+        );
+        assert!(matches!(
+            TaskNotes::new(&"x".repeat(TaskNotes::MAX_LENGTH + 1)),
+            Err(TaskFieldError::NotesTooLong)
+        ));
+    }
+
+    #[test]
+    fn test_priority_round_trips_and_is_ordered() {
+        for priority in [
+            Priority::None,
+            Priority::Low,
+            Priority::Medium,
+            Priority::High,
+            Priority::Urgent,
+        ] {
+            assert_eq!(Priority::parse(priority.as_str()).unwrap(), priority);
+        }
+        assert!(Priority::Urgent > Priority::Low);
+        assert!(Priority::parse("High").is_err());
+    }
+}
@@ -0,0 +1,63 @@
+use crate::domain::reminders::models::list::TaskList;
+use crate::domain::reminders::models::task::Task;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+/// A [Task] or [TaskList] that was deleted. It stays in the trash, hidden from everything else,
+/// until it is restored or purged.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Trashed<T> {
+    pub item: T,
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl<T> Trashed<T> {
+    pub fn item(&self) -> &T {
+        &self.item
+    }
+
+    pub fn deleted_at(&self) -> DateTime<Utc> {
+        self.deleted_at
+    }
+}
+
+/// The deleted lists and tasks a user can restore, most recently deleted first.
+///
+/// Subtasks deleted along with their parent, and tasks deleted along with their list, are listed
+/// too, although they are restored together with the parent or list.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trash {
+    pub lists: Vec<Trashed<TaskList>>,
+    pub tasks: Vec<Trashed<Task>>,
+}
+
+impl Trash {
+    pub fn lists(&self) -> &[Trashed<TaskList>] {
+        &self.lists
+    }
+
+    pub fn tasks(&self) -> &[Trashed<Task>] {
+        &self.tasks
+    }
+}
+
+/// The number of tasks and lists permanently deleted from the trash.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PurgedTrash {
+    pub tasks: u64,
+    pub lists: u64,
+}
+
+#[derive(Debug, Error)]
+pub enum ListTrashError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum PurgeTrashError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
@@ -1,31 +1,700 @@
-use crate::domain::reminders::models::task::CreateTaskError;
+#[allow(unused_imports)]
+use crate::domain::reminders::models::list::TaskListTitle;
+use crate::domain::reminders::models::list::{
+    CreateTaskListError, DeleteTaskListError, GetTaskListError, ListTaskListsError,
+    RestoreTaskListError, UpdateTaskListError,
+};
+use crate::domain::reminders::models::list::{
+    CreateTaskListRequest, DeleteTaskListMode, TaskList, UpdateTaskListRequest,
+};
+#[allow(unused_imports)]
+use crate::domain::reminders::models::member::ListRole;
+use crate::domain::reminders::models::member::{
+    ListTaskListMembersError, ShareTaskListError, UnshareTaskListError,
+};
+use crate::domain::reminders::models::member::{ShareTaskListRequest, TaskListMember};
+use crate::domain::reminders::models::query::{ListTasksRequest, TaskPage};
+use crate::domain::reminders::models::reminder::{
+    DueReminder, FireRemindersError, FiredReminders, NotifyReminderError,
+};
+#[allow(unused_imports)]
+use crate::domain::reminders::models::tag::TaskTags;
+use crate::domain::reminders::models::tag::{
+    ListTagsError, MergeTagsError, RenameTagError, TagTaskError, UntagTaskError,
+};
+use crate::domain::reminders::models::tag::{Tag, TagUsage};
 #[allow(unused_imports)]
 use crate::domain::reminders::models::task::TaskTitle;
-use crate::domain::reminders::models::task::{CreateTaskRequest, Task};
+use crate::domain::reminders::models::task::{
+    CreateTaskError, DeleteTaskError, GetTaskError, ListTasksError, RestoreTaskError,
+    UpdateTaskError,
+};
+use crate::domain::reminders::models::task::{
+    CreateTaskRequest, Task, TaskTree, UpdateTaskRequest,
+};
+use crate::domain::reminders::models::trash::{
+    ListTrashError, PurgeTrashError, PurgedTrash, Trash,
+};
+use chrono::{DateTime, Utc};
 use std::future::Future;
+use uuid::Uuid;
 
 /// `ReminderService` is the public API for the reminders domain.
+///
+/// Tasks, lists and tags belong to a single user, who may share a [TaskList] and its tasks with
+/// other users. Methods that take a `user` act on behalf of the user with that id: they only see
+/// the user's own data and the lists shared with them, so other data is reported as not found,
+/// and they check the [ListRole] of the user before changing a shared list or its tasks.
 pub trait ReminderService: Clone + Send + Sync + 'static {
     /// Asynchronously create a new [Task].
     ///
     /// # Errors
     ///
-    /// - [CreateTaskError::Duplicate] if a [Task] with the same [TaskTitle] already exists.
+    /// - [CreateTaskError::Duplicate] if an incomplete [Task] with the same [TaskTitle], ignoring
+    ///   case, already exists.
+    /// - [CreateTaskError::ListNotFound] if the [TaskList] the [Task] should belong to does not
+    ///   exist.
+    /// - [CreateTaskError::ParentNotFound] if the parent [Task] does not exist.
+    /// - [CreateTaskError::TooDeep] if the [Task] would be nested deeper than [Task::MAX_DEPTH].
+    /// - [CreateTaskError::Forbidden] if the [TaskList] is shared with the user as a
+    ///   [ListRole::Viewer].
     fn create_task(
         &self,
+        user: &Uuid,
         req: &CreateTaskRequest,
     ) -> impl Future<Output = Result<Task, CreateTaskError>> + Send;
+
+    /// Asynchronously retrieve the [Task] with the given `id`.
+    ///
+    /// # Errors
+    ///
+    /// - [GetTaskError::NotFound] if no [Task] with the given `id` exists.
+    fn get_task(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Task, GetTaskError>> + Send;
+
+    /// Asynchronously retrieve the [Task] with the given `id` together with all of its subtasks.
+    ///
+    /// # Errors
+    ///
+    /// - [GetTaskError::NotFound] if no [Task] with the given `id` exists.
+    fn get_task_tree(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<TaskTree, GetTaskError>> + Send;
+
+    /// Asynchronously list a page of the [Task]s matching the `req`, including those in lists
+    /// shared with the user.
+    fn list_tasks(
+        &self,
+        user: &Uuid,
+        req: &ListTasksRequest,
+    ) -> impl Future<Output = Result<TaskPage, ListTasksError>> + Send;
+
+    /// Asynchronously update the [Task] with the given `id`.
+    ///
+    /// # Errors
+    ///
+    /// - [UpdateTaskError::NotFound] if no [Task] with the given `id` exists.
+    /// - [UpdateTaskError::Duplicate] if the [Task] would be incomplete while another incomplete
+    ///   [Task] has the same [TaskTitle], ignoring case.
+    /// - [UpdateTaskError::ListNotFound] if the [TaskList] the [Task] should move to does not
+    ///   exist.
+    /// - [UpdateTaskError::ParentNotFound] if the parent [Task] it should move below does not
+    ///   exist.
+    /// - [UpdateTaskError::TooDeep] if the [Task] or its subtasks would be nested deeper than
+    ///   [Task::MAX_DEPTH].
+    /// - [UpdateTaskError::ParentCycle] if the [Task] would move below itself or one of its
+    ///   subtasks.
+    /// - [UpdateTaskError::Forbidden] if the [TaskList] the [Task] is in, or should move to, is
+    ///   shared with the user as a [ListRole::Viewer].
+    /// - [UpdateTaskError::Conflict] if the `req` has a version and the [Task] is no longer at
+    ///   it.
+    fn update_task(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        req: &UpdateTaskRequest,
+    ) -> impl Future<Output = Result<Task, UpdateTaskError>> + Send;
+
+    /// Asynchronously move the [Task] with the given `id` and its subtasks to the trash, only if
+    /// it is still at `version` when one is given.
+    ///
+    /// # Errors
+    ///
+    /// - [DeleteTaskError::NotFound] if no [Task] with the given `id` exists.
+    /// - [DeleteTaskError::Forbidden] if the [TaskList] of the [Task] is shared with the user as
+    ///   a [ListRole::Viewer].
+    /// - [DeleteTaskError::Conflict] if the [Task] is no longer at `version`.
+    fn delete_task(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        version: Option<i64>,
+    ) -> impl Future<Output = Result<(), DeleteTaskError>> + Send;
+
+    /// Asynchronously create a new [TaskList].
+    ///
+    /// # Errors
+    ///
+    /// - [CreateTaskListError::Duplicate] if an unarchived [TaskList] with the same
+    ///   [TaskListTitle], ignoring case, already exists.
+    fn create_list(
+        &self,
+        user: &Uuid,
+        req: &CreateTaskListRequest,
+    ) -> impl Future<Output = Result<TaskList, CreateTaskListError>> + Send;
+
+    /// Asynchronously retrieve the [TaskList] with the given `id`.
+    ///
+    /// # Errors
+    ///
+    /// - [GetTaskListError::NotFound] if no [TaskList] with the given `id` exists.
+    fn get_list(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<TaskList, GetTaskListError>> + Send;
+
+    /// Asynchronously list all [TaskList]s of the user and those shared with them in order,
+    /// leaving out archived lists unless `include_archived` is set.
+    fn list_lists(
+        &self,
+        user: &Uuid,
+        include_archived: bool,
+    ) -> impl Future<Output = Result<Vec<TaskList>, ListTaskListsError>> + Send;
+
+    /// Asynchronously update the [TaskList] with the given `id`.
+    ///
+    /// # Errors
+    ///
+    /// - [UpdateTaskListError::NotFound] if no [TaskList] with the given `id` exists.
+    /// - [UpdateTaskListError::Duplicate] if the [TaskList] would be unarchived while another
+    ///   unarchived [TaskList] has the same [TaskListTitle], ignoring case.
+    /// - [UpdateTaskListError::Forbidden] if the user is not an owner of the [TaskList].
+    fn update_list(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        req: &UpdateTaskListRequest,
+    ) -> impl Future<Output = Result<TaskList, UpdateTaskListError>> + Send;
+
+    /// Asynchronously move the [TaskList] with the given `id` to the trash, handling its [Task]s
+    /// according to `mode`.
+    ///
+    /// # Errors
+    ///
+    /// - [DeleteTaskListError::NotFound] if no [TaskList] with the given `id` exists.
+    /// - [DeleteTaskListError::NotEmpty] if the `mode` is [DeleteTaskListMode::OnlyIfEmpty] and
+    ///   the [TaskList] has [Task]s.
+    /// - [DeleteTaskListError::Forbidden] if the user is not an owner of the [TaskList].
+    fn delete_list(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        mode: DeleteTaskListMode,
+    ) -> impl Future<Output = Result<(), DeleteTaskListError>> + Send;
+
+    /// Asynchronously list the users who may access the [TaskList] with the given `id`, starting
+    /// with its creator and then in the order it was shared with them.
+    ///
+    /// # Errors
+    ///
+    /// - [ListTaskListMembersError::NotFound] if no [TaskList] with the given `id` exists.
+    fn list_members(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Vec<TaskListMember>, ListTaskListMembersError>> + Send;
+
+    /// Asynchronously share the [TaskList] with the given `id` as specified in the `req`, or
+    /// change the role of a user it is already shared with.
+    ///
+    /// # Errors
+    ///
+    /// - [ShareTaskListError::NotFound] if no [TaskList] with the given `id` exists.
+    /// - [ShareTaskListError::Forbidden] if the user is not an owner of the [TaskList].
+    /// - [ShareTaskListError::UserNotFound] if no user is registered with the email address.
+    /// - [ShareTaskListError::Creator] if the email address is that of the creator of the
+    ///   [TaskList].
+    fn share_list(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        req: &ShareTaskListRequest,
+    ) -> impl Future<Output = Result<TaskListMember, ShareTaskListError>> + Send;
+
+    /// Asynchronously stop sharing the [TaskList] with the given `id` with the user with id
+    /// `member`. Any member may leave a list; only owners may remove others.
+    ///
+    /// # Errors
+    ///
+    /// - [UnshareTaskListError::NotFound] if no [TaskList] with the given `id` exists.
+    /// - [UnshareTaskListError::MemberNotFound] if the [TaskList] is not shared with `member`.
+    /// - [UnshareTaskListError::Forbidden] if `member` is another user and the user is not an
+    ///   owner of the [TaskList].
+    fn unshare_list(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        member: &Uuid,
+    ) -> impl Future<Output = Result<(), UnshareTaskListError>> + Send;
+
+    /// Asynchronously add `tags` to the [Task] with the given `id`. Tags the [Task] already
+    /// carries are left as they are.
+    ///
+    /// # Errors
+    ///
+    /// - [TagTaskError::NotFound] if no [Task] with the given `id` exists.
+    /// - [TagTaskError::TooMany] if the [Task] would carry more than [TaskTags::MAX_TAGS] tags.
+    /// - [TagTaskError::Forbidden] if the [TaskList] of the [Task] is shared with the user as a
+    ///   [ListRole::Viewer].
+    fn tag_task(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        tags: &[Tag],
+    ) -> impl Future<Output = Result<Task, TagTaskError>> + Send;
+
+    /// Asynchronously remove `tags` from the [Task] with the given `id`. Tags the [Task] does not
+    /// carry are ignored.
+    ///
+    /// # Errors
+    ///
+    /// - [UntagTaskError::NotFound] if no [Task] with the given `id` exists.
+    /// - [UntagTaskError::Forbidden] if the [TaskList] of the [Task] is shared with the user as a
+    ///   [ListRole::Viewer].
+    fn untag_task(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        tags: &[Tag],
+    ) -> impl Future<Output = Result<Task, UntagTaskError>> + Send;
+
+    /// Asynchronously list every [Tag] in use on the user's own [Task]s, in alphabetical order,
+    /// with the number of [Task]s that carry it. Tags are managed by each user separately, so
+    /// this and the other tag operations leave the tasks of lists shared with the user alone.
+    fn list_tags(
+        &self,
+        user: &Uuid,
+    ) -> impl Future<Output = Result<Vec<TagUsage>, ListTagsError>> + Send;
+
+    /// Asynchronously rename the [Tag] `from` to `to` on every [Task] that carries it.
+    ///
+    /// # Errors
+    ///
+    /// - [RenameTagError::NotFound] if no [Task] carries `from`.
+    /// - [RenameTagError::Duplicate] if a [Task] already carries `to`.
+    fn rename_tag(
+        &self,
+        user: &Uuid,
+        from: &Tag,
+        to: &Tag,
+    ) -> impl Future<Output = Result<TagUsage, RenameTagError>> + Send;
+
+    /// Asynchronously replace each of `tags` with `into` on every [Task] that carries it, so that
+    /// only `into` remains. `into` may be a new [Tag] or one that is already in use.
+    ///
+    /// # Errors
+    ///
+    /// - [MergeTagsError::NotFound] if no [Task] carries one of `tags`.
+    fn merge_tags(
+        &self,
+        user: &Uuid,
+        tags: &[Tag],
+        into: &Tag,
+    ) -> impl Future<Output = Result<TagUsage, MergeTagsError>> + Send;
+
+    /// Asynchronously list the [Trash] of the user: the [TaskList]s they deleted, and the
+    /// deleted [Task]s of their own and of the lists shared with them.
+    fn list_trash(&self, user: &Uuid)
+        -> impl Future<Output = Result<Trash, ListTrashError>> + Send;
+
+    /// Asynchronously restore the [Task] with the given `id` from the trash, together with the
+    /// subtasks that were deleted along with it.
+    ///
+    /// # Errors
+    ///
+    /// - [RestoreTaskError::NotFound] if no [Task] with the given `id` is in the trash.
+    /// - [RestoreTaskError::Duplicate] if a restored [Task] is incomplete while another
+    ///   incomplete [Task] has the same [TaskTitle], ignoring case.
+    /// - [RestoreTaskError::ParentDeleted] if the parent [Task] is still in the trash.
+    /// - [RestoreTaskError::ListDeleted] if the [TaskList] of the [Task] is still in the trash.
+    /// - [RestoreTaskError::Forbidden] if the [TaskList] of the [Task] is shared with the user as
+    ///   a [ListRole::Viewer].
+    fn restore_task(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Task, RestoreTaskError>> + Send;
+
+    /// Asynchronously restore the [TaskList] with the given `id` from the trash, together with
+    /// the [Task]s that were deleted along with it. Only the creator of a list can restore it.
+    ///
+    /// # Errors
+    ///
+    /// - [RestoreTaskListError::NotFound] if no [TaskList] with the given `id` is in the trash.
+    /// - [RestoreTaskListError::Duplicate] if the [TaskList] is unarchived while another
+    ///   unarchived [TaskList] has the same [TaskListTitle], ignoring case.
+    /// - [RestoreTaskListError::TaskDuplicate] if a restored [Task] is incomplete while another
+    ///   incomplete [Task] has the same [TaskTitle], ignoring case.
+    fn restore_list(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<TaskList, RestoreTaskListError>> + Send;
+
+    /// Asynchronously delete the tasks and lists in the trash that the `user` owns for good.
+    fn empty_trash(
+        &self,
+        user: &Uuid,
+    ) -> impl Future<Output = Result<PurgedTrash, PurgeTrashError>> + Send;
+
+    /// Asynchronously delete every [Task] and [TaskList] that was moved to the trash before
+    /// `deleted_before` for good, whoever it belongs to.
+    fn purge_trash(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> impl Future<Output = Result<PurgedTrash, PurgeTrashError>> + Send;
+
+    /// Asynchronously deliver every reminder that is due at `now` through the
+    /// [ReminderNotifier]. Each reminder is delivered at most once, even when several instances
+    /// of the service fire reminders concurrently.
+    fn fire_due_reminders(
+        &self,
+        now: DateTime<Utc>,
+    ) -> impl Future<Output = Result<FiredReminders, FireRemindersError>> + Send;
 }
 
-/// `ReminderRepository` represents a store of reminder data.
+/// `ReminderRepository` represents a store of reminder data, where every task and list belongs
+/// to an `owner`. Methods that take an `owner` only see the data of that user, and a task can only
+/// reference lists and parent tasks of its own owner. Methods that take a `user` also see the
+/// lists shared with that user and the tasks in them. Roles are checked by the caller.
+///
+/// Deleted tasks and lists are kept in the trash, where only the methods for the trash see them,
+/// until they are restored or purged.
+///
+/// Methods that change tasks keep a task with subtasks completed exactly when all of its
+/// subtasks are, completing or reopening its ancestors in the same transaction as the change.
 pub trait ReminderRepository: Clone + Send + Sync + 'static {
-    /// Asynchronously create a new [Task].
+    /// Asynchronously create a new [Task], reopening its ancestors.
     ///
     /// # Errors
     ///
-    /// - [CreateTaskError::Duplicate] if a [Task] with the same [TaskTitle] already exists.
+    /// - [CreateTaskError::Duplicate] if an incomplete [Task] with the same [TaskTitle], ignoring
+    ///   case, already exists, or an ancestor cannot be reopened because of one.
+    /// - [CreateTaskError::ListNotFound] if the [TaskList] the [Task] should belong to does not
+    ///   exist.
+    /// - [CreateTaskError::ParentNotFound] if the parent [Task] does not exist.
     fn create_task(
         &self,
+        owner: &Uuid,
         req: &CreateTaskRequest,
     ) -> impl Future<Output = Result<Task, CreateTaskError>> + Send;
+
+    /// Asynchronously retrieve the [Task] with the given `id`, if it belongs to the `user` or is in
+    /// a list shared with them.
+    ///
+    /// # Errors
+    ///
+    /// - [GetTaskError::NotFound] if no [Task] with the given `id` exists.
+    fn get_task(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Task, GetTaskError>> + Send;
+
+    /// Asynchronously list a page of the [Task]s matching the filter of the `req` that belong to
+    /// the `user` or are in lists shared with them, in its sort order and continuing after its
+    /// cursor.
+    fn list_tasks(
+        &self,
+        user: &Uuid,
+        req: &ListTasksRequest,
+    ) -> impl Future<Output = Result<TaskPage, ListTasksError>> + Send;
+
+    /// Asynchronously list every [Task] nested below the [Task] with the given `id`, at any
+    /// depth, ordered by position and then by creation so that siblings are in order.
+    fn list_subtasks(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Vec<Task>, ListTasksError>> + Send;
+
+    /// Asynchronously update the [Task] with the given `id`. Nesting limits and cycles are
+    /// checked by the caller. Completing the [Task] also completes all of its subtasks, and
+    /// creates the next occurrence of a recurring [Task] on behalf of the updating user. Of
+    /// concurrent completions of the same [Task], only one creates the next occurrence.
+    ///
+    /// # Errors
+    ///
+    /// - [UpdateTaskError::NotFound] if no [Task] with the given `id` exists.
+    /// - [UpdateTaskError::Duplicate] if the [Task], one of its ancestors or its next occurrence
+    ///   would be incomplete while another incomplete [Task] has the same [TaskTitle], ignoring
+    ///   case.
+    /// - [UpdateTaskError::ListNotFound] if the [TaskList] the [Task] should move to does not
+    ///   exist.
+    /// - [UpdateTaskError::ParentNotFound] if the parent [Task] it should move below does not
+    ///   exist.
+    /// - [UpdateTaskError::Conflict] if the `req` has a version and the [Task] is no longer at
+    ///   it.
+    fn update_task(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &UpdateTaskRequest,
+    ) -> impl Future<Output = Result<Task, UpdateTaskError>> + Send;
+
+    /// Asynchronously move the [Task] with the given `id` and its subtasks to the trash, only if
+    /// it is still at `version` when one is given. Ancestors completed as a result are recorded
+    /// as completed by `updated_by`.
+    ///
+    /// # Errors
+    ///
+    /// - [DeleteTaskError::NotFound] if no [Task] with the given `id` exists.
+    /// - [DeleteTaskError::Conflict] if the [Task] is no longer at `version`.
+    fn delete_task(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        version: Option<i64>,
+        updated_by: &Uuid,
+    ) -> impl Future<Output = Result<(), DeleteTaskError>> + Send;
+
+    /// Asynchronously create a new [TaskList].
+    ///
+    /// # Errors
+    ///
+    /// - [CreateTaskListError::Duplicate] if an unarchived [TaskList] with the same
+    ///   [TaskListTitle], ignoring case, already exists.
+    fn create_list(
+        &self,
+        owner: &Uuid,
+        req: &CreateTaskListRequest,
+    ) -> impl Future<Output = Result<TaskList, CreateTaskListError>> + Send;
+
+    /// Asynchronously retrieve the [TaskList] with the given `id`, if it belongs to the `user` or
+    /// is shared with them, together with the role of the `user`.
+    ///
+    /// # Errors
+    ///
+    /// - [GetTaskListError::NotFound] if no [TaskList] with the given `id` exists.
+    fn get_list(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<TaskList, GetTaskListError>> + Send;
+
+    /// Asynchronously list all [TaskList]s of the `user` and those shared with them in order,
+    /// together with the role of the `user`, leaving out archived lists unless
+    /// `include_archived` is set.
+    fn list_lists(
+        &self,
+        user: &Uuid,
+        include_archived: bool,
+    ) -> impl Future<Output = Result<Vec<TaskList>, ListTaskListsError>> + Send;
+
+    /// Asynchronously update the [TaskList] with the given `id`.
+    ///
+    /// # Errors
+    ///
+    /// - [UpdateTaskListError::NotFound] if no [TaskList] with the given `id` exists.
+    /// - [UpdateTaskListError::Duplicate] if the [TaskList] would be unarchived while another
+    ///   unarchived [TaskList] has the same [TaskListTitle], ignoring case.
+    fn update_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &UpdateTaskListRequest,
+    ) -> impl Future<Output = Result<TaskList, UpdateTaskListError>> + Send;
+
+    /// Asynchronously move the [TaskList] with the given `id` to the trash, handling its [Task]s
+    /// according to `mode`, and revoke the API tokens restricted to it.
+    ///
+    /// # Errors
+    ///
+    /// - [DeleteTaskListError::NotFound] if no [TaskList] with the given `id` exists.
+    /// - [DeleteTaskListError::NotEmpty] if the `mode` is [DeleteTaskListMode::OnlyIfEmpty] and
+    ///   the [TaskList] has [Task]s.
+    fn delete_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        mode: DeleteTaskListMode,
+    ) -> impl Future<Output = Result<(), DeleteTaskListError>> + Send;
+
+    /// Asynchronously list the users who may access the [TaskList] with the given `id`, starting
+    /// with its `owner` and then in the order it was shared with them.
+    ///
+    /// # Errors
+    ///
+    /// - [ListTaskListMembersError::NotFound] if no [TaskList] with the given `id` exists.
+    fn list_members(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Vec<TaskListMember>, ListTaskListMembersError>> + Send;
+
+    /// Asynchronously share the [TaskList] with the given `id` as specified in the `req`, or
+    /// change the role of a user it is already shared with.
+    ///
+    /// # Errors
+    ///
+    /// - [ShareTaskListError::NotFound] if no [TaskList] with the given `id` exists.
+    /// - [ShareTaskListError::UserNotFound] if no user is registered with the email address.
+    /// - [ShareTaskListError::Creator] if the email address is that of the `owner`.
+    fn share_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &ShareTaskListRequest,
+    ) -> impl Future<Output = Result<TaskListMember, ShareTaskListError>> + Send;
+
+    /// Asynchronously stop sharing the [TaskList] with the given `id` with the user with id
+    /// `member`, unassigning them from its [Task]s.
+    ///
+    /// # Errors
+    ///
+    /// - [UnshareTaskListError::NotFound] if no [TaskList] with the given `id` exists.
+    /// - [UnshareTaskListError::MemberNotFound] if the [TaskList] is not shared with `member`.
+    fn unshare_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        member: &Uuid,
+    ) -> impl Future<Output = Result<(), UnshareTaskListError>> + Send;
+
+    /// Asynchronously list every [Tag] in use, in alphabetical order, with the number of [Task]s
+    /// that carry it.
+    fn list_tags(
+        &self,
+        owner: &Uuid,
+    ) -> impl Future<Output = Result<Vec<TagUsage>, ListTagsError>> + Send;
+
+    /// Asynchronously rename the [Tag] `from` to `to` on every [Task] that carries it.
+    ///
+    /// # Errors
+    ///
+    /// - [RenameTagError::NotFound] if no [Task] carries `from`.
+    /// - [RenameTagError::Duplicate] if a [Task] already carries `to`.
+    fn rename_tag(
+        &self,
+        owner: &Uuid,
+        from: &Tag,
+        to: &Tag,
+    ) -> impl Future<Output = Result<TagUsage, RenameTagError>> + Send;
+
+    /// Asynchronously replace each of `tags` with `into` on every [Task] that carries it.
+    ///
+    /// # Errors
+    ///
+    /// - [MergeTagsError::NotFound] if no [Task] carries one of `tags`.
+    fn merge_tags(
+        &self,
+        owner: &Uuid,
+        tags: &[Tag],
+        into: &Tag,
+    ) -> impl Future<Output = Result<TagUsage, MergeTagsError>> + Send;
+
+    /// Asynchronously claim up to `limit` reminders that are due at `now`, oldest first. A
+    /// claimed reminder is recorded as fired and is never returned again unless it is released
+    /// with [ReminderRepository::record_reminder_failure]. Each is addressed to the assignee of
+    /// its [Task], or else to the owner of the [Task].
+    fn claim_due_reminders(
+        &self,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> impl Future<Output = Result<Vec<DueReminder>, FireRemindersError>> + Send;
+
+    /// Asynchronously record that delivering a claimed reminder failed with `error`. When `retry`
+    /// is true the reminder is released so that it is claimed again on a later pass.
+    fn record_reminder_failure(
+        &self,
+        reminder: &DueReminder,
+        error: &str,
+        retry: bool,
+    ) -> impl Future<Output = Result<(), FireRemindersError>> + Send;
+
+    /// Asynchronously list the [TaskList]s in the trash that belong to the `user`, and the
+    /// [Task]s in the trash that belong to the `user` or are in lists shared with them, most
+    /// recently deleted first.
+    fn list_trash(&self, user: &Uuid)
+        -> impl Future<Output = Result<Trash, ListTrashError>> + Send;
+
+    /// Asynchronously retrieve the [Task] in the trash with the given `id`, if it belongs to the
+    /// `user` or is in a list shared with them.
+    ///
+    /// # Errors
+    ///
+    /// - [GetTaskError::NotFound] if no [Task] with the given `id` is in the trash.
+    fn get_trashed_task(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Task, GetTaskError>> + Send;
+
+    /// Asynchronously restore the [Task] with the given `id` from the trash, together with the
+    /// subtasks that were deleted along with it. Ancestors reopened as a result are recorded as
+    /// updated by `updated_by`.
+    ///
+    /// # Errors
+    ///
+    /// - [RestoreTaskError::NotFound] if no [Task] with the given `id` is in the trash.
+    /// - [RestoreTaskError::Duplicate] if a restored [Task] or one of its ancestors is incomplete
+    ///   while another incomplete [Task] has the same [TaskTitle], ignoring case.
+    /// - [RestoreTaskError::ParentDeleted] if the parent [Task] is still in the trash.
+    /// - [RestoreTaskError::ListDeleted] if the [TaskList] of the [Task] is still in the trash.
+    fn restore_task(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        updated_by: &Uuid,
+    ) -> impl Future<Output = Result<Task, RestoreTaskError>> + Send;
+
+    /// Asynchronously restore the [TaskList] with the given `id` from the trash, together with
+    /// the [Task]s that were deleted along with it. API tokens revoked when it was deleted stay
+    /// revoked.
+    ///
+    /// # Errors
+    ///
+    /// - [RestoreTaskListError::NotFound] if no [TaskList] with the given `id` is in the trash.
+    /// - [RestoreTaskListError::Duplicate] if the [TaskList] is unarchived while another
+    ///   unarchived [TaskList] has the same [TaskListTitle], ignoring case.
+    /// - [RestoreTaskListError::TaskDuplicate] if a restored [Task] is incomplete while another
+    ///   incomplete [Task] has the same [TaskTitle], ignoring case.
+    fn restore_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<TaskList, RestoreTaskListError>> + Send;
+
+    /// Asynchronously delete every [Task] and [TaskList] in the trash that belongs to the `owner`
+    /// for good.
+    fn empty_trash(
+        &self,
+        owner: &Uuid,
+    ) -> impl Future<Output = Result<PurgedTrash, PurgeTrashError>> + Send;
+
+    /// Asynchronously delete every [Task] and [TaskList] that was moved to the trash before
+    /// `deleted_before` for good.
+    fn purge_trash(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> impl Future<Output = Result<PurgedTrash, PurgeTrashError>> + Send;
+}
+
+/// `ReminderNotifier` delivers reminders to the people they are meant for.
+pub trait ReminderNotifier: Clone + Send + Sync + 'static {
+    /// Asynchronously deliver a single reminder to its [recipient](DueReminder::recipient).
+    ///
+    /// # Errors
+    ///
+    /// - [NotifyReminderError::Unknown] if the reminder could not be delivered.
+    fn notify(
+        &self,
+        reminder: &DueReminder,
+    ) -> impl Future<Output = Result<(), NotifyReminderError>> + Send;
 }
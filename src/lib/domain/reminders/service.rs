@@ -1,44 +1,1506 @@
-use crate::domain::reminders::models::task::CreateTaskError;
-use crate::domain::reminders::models::task::{CreateTaskRequest, Task};
-use crate::domain::reminders::ports::{ReminderRepository, ReminderService};
+use crate::domain::metrics::ports::Metrics;
+use crate::domain::reminders::models::list::{
+    CreateTaskListError, DeleteTaskListError, GetTaskListError, ListTaskListsError,
+    RestoreTaskListError, UpdateTaskListError,
+};
+use crate::domain::reminders::models::list::{
+    CreateTaskListRequest, DeleteTaskListMode, TaskList, UpdateTaskListRequest,
+};
+use crate::domain::reminders::models::member::{ListRole, ShareTaskListRequest, TaskListMember};
+use crate::domain::reminders::models::member::{
+    ListTaskListMembersError, ShareTaskListError, UnshareTaskListError,
+};
+use crate::domain::reminders::models::query::{ListTasksRequest, TaskPage};
+use crate::domain::reminders::models::reminder::{FireRemindersError, FiredReminders};
+#[allow(unused_imports)]
+use crate::domain::reminders::models::tag::TaskTags;
+use crate::domain::reminders::models::tag::{
+    ListTagsError, MergeTagsError, RenameTagError, TagTaskError, UntagTaskError,
+};
+use crate::domain::reminders::models::tag::{Tag, TagUsage};
+use crate::domain::reminders::models::task::{
+    CreateTaskError, DeleteTaskError, GetTaskError, ListTasksError, RestoreTaskError,
+    UpdateTaskError,
+};
+use crate::domain::reminders::models::task::{
+    CreateTaskRequest, Task, TaskTree, UpdateTaskRequest,
+};
+use crate::domain::reminders::models::trash::{
+    ListTrashError, PurgeTrashError, PurgedTrash, Trash,
+};
+use crate::domain::reminders::ports::{ReminderNotifier, ReminderRepository, ReminderService};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use tracing::instrument;
+use uuid::Uuid;
+
+/// The number of reminders claimed from the [ReminderRepository] at a time.
+const REMINDER_BATCH_SIZE: u32 = 100;
+
+/// How many times the tags of a [Task] are read and written back before giving up, when other
+/// changes to the [Task] keep getting in between.
+const TAG_ATTEMPTS: usize = 3;
 
 /// Cannonical implementation of the [ReminderService] port, through which the reminder
 /// domain is consumed
 #[derive(Debug, Clone)]
-pub struct Service<R>
+pub struct Service<R, N, M>
 where
     R: ReminderRepository,
+    N: ReminderNotifier,
+    M: Metrics,
 {
     repo: R,
+    notifier: N,
+    metrics: M,
+}
+
+/// Whose tasks a list, or an inbox, holds and what the acting user may do with them.
+#[derive(Debug, Clone, Copy)]
+struct Access {
+    /// The user the tasks belong to.
+    owner: Uuid,
+    /// The list, or `None` for the inbox of the acting user.
+    list_id: Option<Uuid>,
+    role: ListRole,
 }
 
-impl<R> Service<R>
+impl Access {
+    /// The list and the role of the acting user in it, if that role is below `needed`.
+    fn denied(&self, needed: ListRole) -> Option<(Uuid, ListRole)> {
+        self.list_id
+            .filter(|_| self.role < needed)
+            .map(|id| (id, self.role))
+    }
+}
+
+impl<R, N, M> Service<R, N, M>
 where
     R: ReminderRepository,
+    N: ReminderNotifier,
+    M: Metrics,
 {
-    /// Create a new instance of the [Service] with the provided [ReminderRepository]
-    pub fn new(repo: R) -> Self {
-        Self { repo }
+    /// Create a new instance of the [Service] with the provided [ReminderRepository],
+    /// [ReminderNotifier] and [Metrics]
+    pub fn new(repo: R, notifier: N, metrics: M) -> Self {
+        Self {
+            repo,
+            notifier,
+            metrics,
+        }
+    }
+
+    /// The [Access] of `user` to the list with `list_id`, or to their inbox if it is `None`.
+    async fn list_access(
+        &self,
+        user: &Uuid,
+        list_id: Option<Uuid>,
+    ) -> Result<Access, GetTaskListError> {
+        let Some(id) = list_id else {
+            return Ok(Access {
+                owner: *user,
+                list_id: None,
+                role: ListRole::Owner,
+            });
+        };
+        let list = self.repo.get_list(user, &id).await?;
+        Ok(Access {
+            owner: list.owner_id(),
+            list_id: Some(id),
+            role: list.role(),
+        })
+    }
+
+    /// The [Access] of `user` to the list of the [Task] with the given `id`. Only the owner of a
+    /// task in an inbox can see it.
+    async fn task_access(&self, user: &Uuid, id: &Uuid) -> Result<(Task, Access), GetTaskError> {
+        let task = self.repo.get_task(user, id).await?;
+        let access = self
+            .list_access(user, task.list_id())
+            .await
+            .map_err(|e| match e {
+                GetTaskListError::NotFound { .. } => GetTaskError::NotFound { id: *id },
+                GetTaskListError::Unknown(e) => GetTaskError::Unknown(e),
+            })?;
+        Ok((task, access))
+    }
+
+    /// Whether `assignee` may be assigned tasks in the list of the `access`: the owner or a
+    /// member of the list, or only the owner for an inbox.
+    async fn can_assign(&self, access: &Access, assignee: &Uuid) -> anyhow::Result<bool> {
+        if *assignee == access.owner {
+            return Ok(true);
+        }
+        let Some(list_id) = access.list_id else {
+            return Ok(false);
+        };
+        let members = self
+            .repo
+            .list_members(&access.owner, &list_id)
+            .await
+            .map_err(|e| anyhow!(e).context(format!("failed to list members of {}", list_id)))?;
+        Ok(members.iter().any(|member| member.user_id() == *assignee))
+    }
+
+    /// The number of ancestors of `task`.
+    async fn depth(&self, user: &Uuid, task: &Task) -> Result<usize, GetTaskError> {
+        let mut depth = 0;
+        let mut parent_id = task.parent_id();
+        // Stored trees are never deeper than the limit, so this also stops on corrupt cycles
+        while let Some(id) = parent_id.filter(|_| depth <= Task::MAX_DEPTH) {
+            depth += 1;
+            parent_id = self.repo.get_task(user, &id).await?.parent_id();
+        }
+        Ok(depth)
+    }
+
+    /// Retrieve the [Task] with the given `id` with all of its subtasks.
+    async fn tree(&self, owner: &Uuid, id: &Uuid) -> Result<TaskTree, GetTaskError> {
+        let task = self.repo.get_task(owner, id).await?;
+        let subtasks =
+            self.repo.list_subtasks(owner, id).await.map_err(|e| {
+                anyhow!(e).context(format!("failed to list subtasks of task {}", id))
+            })?;
+        Ok(TaskTree::new(task, subtasks))
+    }
+
+    /// Retrieve the [TaskList] with the given `id`, failing with `forbidden` unless `user` is one
+    /// of its owners.
+    async fn owned_list<E>(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        forbidden: impl FnOnce(ListRole) -> E,
+    ) -> Result<TaskList, E>
+    where
+        E: From<GetTaskListError>,
+    {
+        let list = self.repo.get_list(user, id).await?;
+        if list.role() < ListRole::Owner {
+            return Err(forbidden(list.role()));
+        }
+        Ok(list)
     }
 }
 
-impl<R> ReminderService for Service<R>
+impl<R, N, M> ReminderService for Service<R, N, M>
 where
     R: ReminderRepository,
+    N: ReminderNotifier,
+    M: Metrics,
 {
-    /// Create the [Task] specified in the `req` and perform side effects
+    /// Create the [Task] specified in the `req` and perform side effects. A subtask is created in
+    /// the list of its parent unless the `req` names another, and adding it to a completed
+    /// parent reopens the parent. A [Task] in a shared list belongs to the owner of the list.
     ///
     /// # Errors
     ///
+    /// - [CreateTaskError::ParentNotFound] if the parent [Task] does not exist.
+    /// - [CreateTaskError::ListNotFound] if the [TaskList] does not exist.
+    /// - [CreateTaskError::Forbidden] if the user is a [ListRole::Viewer] of the [TaskList].
+    /// - [CreateTaskError::TooDeep] if the [Task] would be nested deeper than [Task::MAX_DEPTH].
+    /// - [CreateTaskError::AssigneeNotMember] if the [Task] is assigned to a user who is neither
+    ///   the owner nor a member of its [TaskList].
+    /// - [CreateTaskError::RemindAtWithoutDueAt] if the [Task] has reminders but no due date.
     /// - Propagates any [CreateTaskError] returned by the [ReminderRepository].
-    async fn create_task(&self, req: &CreateTaskRequest) -> Result<Task, CreateTaskError> {
-        let result = self.repo.create_task(req).await;
+    #[instrument(skip(self, req))]
+    async fn create_task(
+        &self,
+        user: &Uuid,
+        req: &CreateTaskRequest,
+    ) -> Result<Task, CreateTaskError> {
+        // Every outcome is counted, including requests rejected before reaching the repository
+        let result = async {
+            if !req.remind_at().is_empty() && req.due_at().is_none() {
+                return Err(CreateTaskError::RemindAtWithoutDueAt);
+            }
+            let mut req = req.clone().with_updated_by(*user);
+            let mut parent = None;
+            if let Some(parent_id) = req.parent_id() {
+                let task = self
+                    .repo
+                    .get_task(user, &parent_id)
+                    .await
+                    .map_err(|e| match e {
+                        GetTaskError::NotFound { id } => CreateTaskError::ParentNotFound { id },
+                        GetTaskError::Unknown(e) => CreateTaskError::Unknown(e),
+                    })?;
+                if let Some(list_id) = task.list_id().filter(|_| req.list_id().is_none()) {
+                    req = req.with_list_id(list_id);
+                }
+                parent = Some(task);
+            }
+
+            let access = self
+                .list_access(user, req.list_id())
+                .await
+                .map_err(|e| match e {
+                    GetTaskListError::NotFound { id } => CreateTaskError::ListNotFound { id },
+                    GetTaskListError::Unknown(e) => CreateTaskError::Unknown(e),
+                })?;
+            if let Some((id, role)) = access.denied(ListRole::Editor) {
+                return Err(CreateTaskError::Forbidden { id, role });
+            }
+            if let Some(id) = req.assignee_id() {
+                if !self.can_assign(&access, &id).await? {
+                    return Err(CreateTaskError::AssigneeNotMember { id });
+                }
+            }
+            if let Some(parent) = &parent {
+                if self.depth(user, parent).await.map_err(|e| anyhow!(e))? >= Task::MAX_DEPTH {
+                    return Err(CreateTaskError::TooDeep);
+                }
+            }
+
+            self.repo.create_task(&access.owner, &req).await
+        }
+        .await;
         if result.is_err() {
-            // self.metrics.record_task_creation_failure();
+            self.metrics.record_task_creation_failure();
         } else {
-            // self.metrics.record_task_creation_success();
-            // self.task_notifier.task_created(result.as_ref().unwrap()).await;
+            self.metrics.record_task_creation_success();
         }
         result
     }
+
+    /// Retrieve the [Task] with the given `id`
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [GetTaskError] returned by the [ReminderRepository].
+    #[instrument(skip(self))]
+    async fn get_task(&self, user: &Uuid, id: &Uuid) -> Result<Task, GetTaskError> {
+        self.repo.get_task(user, id).await
+    }
+
+    /// Retrieve the [Task] with the given `id` together with its subtasks
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [GetTaskError] returned by the [ReminderRepository].
+    #[instrument(skip(self))]
+    async fn get_task_tree(&self, user: &Uuid, id: &Uuid) -> Result<TaskTree, GetTaskError> {
+        let (_, access) = self.task_access(user, id).await?;
+        self.tree(&access.owner, id).await
+    }
+
+    /// List a page of the [Task]s matching the `req`, among the user's own and those in lists
+    /// shared with them
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [ListTasksError] returned by the [ReminderRepository].
+    #[instrument(skip(self, req))]
+    async fn list_tasks(
+        &self,
+        user: &Uuid,
+        req: &ListTasksRequest,
+    ) -> Result<TaskPage, ListTasksError> {
+        self.repo.list_tasks(user, req).await
+    }
+
+    /// Apply the changes specified in the `req` to the [Task] with the given `id`. Completing a
+    /// recurring [Task] creates its next occurrence.
+    ///
+    /// Completing a [Task] also completes all of its subtasks, without creating next
+    /// occurrences for them. A [Task] with subtasks is completed once all of them are, and is
+    /// reopened as soon as one of them is reopened or an incomplete one is moved below it.
+    ///
+    /// A [Task] only moves between lists of the same owner, and moving a [Task] out of a shared
+    /// list to the inbox puts it in the inbox of the owner of the list. A moved [Task] is
+    /// unassigned if its assignee may not be assigned tasks in its new list.
+    ///
+    /// # Errors
+    ///
+    /// - [UpdateTaskError::NotFound] if no [Task] with the given `id` exists.
+    /// - [UpdateTaskError::ListNotFound] if the [TaskList] it should move to does not exist.
+    /// - [UpdateTaskError::Forbidden] if the user is a [ListRole::Viewer] of the [TaskList] the
+    ///   [Task] is in or should move to.
+    /// - [UpdateTaskError::ParentNotFound] if the parent [Task] it should move below does not
+    ///   exist.
+    /// - [UpdateTaskError::TooDeep] if the [Task] or its subtasks would be nested deeper than
+    ///   [Task::MAX_DEPTH].
+    /// - [UpdateTaskError::ParentCycle] if the [Task] would move below itself or one of its
+    ///   subtasks.
+    /// - [UpdateTaskError::AssigneeNotMember] if the [Task] is assigned to a user who is neither
+    ///   the owner nor a member of its [TaskList].
+    /// - [UpdateTaskError::RemindAtWithoutDueAt] if the [Task] would have reminders but no due
+    ///   date.
+    /// - Propagates any [UpdateTaskError] returned by the [ReminderRepository].
+    #[instrument(skip(self, req))]
+    async fn update_task(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        req: &UpdateTaskRequest,
+    ) -> Result<Task, UpdateTaskError> {
+        let (current, access) = self.task_access(user, id).await.map_err(|e| match e {
+            GetTaskError::NotFound { id } => UpdateTaskError::NotFound { id },
+            GetTaskError::Unknown(e) => UpdateTaskError::Unknown(e),
+        })?;
+        if let Some((id, role)) = access.denied(ListRole::Editor) {
+            return Err(UpdateTaskError::Forbidden { id, role });
+        }
+        let due_at = req.due_at().unwrap_or(current.due_at());
+        if !req.remind_at().unwrap_or(current.remind_at()).is_empty() && due_at.is_none() {
+            return Err(UpdateTaskError::RemindAtWithoutDueAt);
+        }
+        let target = match req.list_id() {
+            Some(Some(list_id)) => {
+                let target = self
+                    .list_access(user, Some(list_id))
+                    .await
+                    .map_err(|e| match e {
+                        GetTaskListError::NotFound { id } => UpdateTaskError::ListNotFound { id },
+                        GetTaskListError::Unknown(e) => UpdateTaskError::Unknown(e),
+                    })?;
+                if let Some((id, role)) = target.denied(ListRole::Editor) {
+                    return Err(UpdateTaskError::Forbidden { id, role });
+                }
+                target
+            }
+            Some(None) => Access {
+                list_id: None,
+                role: ListRole::Owner,
+                ..access
+            },
+            None => access,
+        };
+
+        let mut req = req.clone().with_updated_by(*user);
+        match req.assignee_id() {
+            Some(Some(id)) => {
+                if !self.can_assign(&target, &id).await? {
+                    return Err(UpdateTaskError::AssigneeNotMember { id });
+                }
+            }
+            Some(None) => {}
+            None => {
+                if let Some(assignee) = current.assignee_id().filter(|_| req.list_id().is_some()) {
+                    if !self.can_assign(&target, &assignee).await? {
+                        req = req.with_assignee_id(None);
+                    }
+                }
+            }
+        }
+
+        let owner = &access.owner;
+        if let Some(Some(parent_id)) = req.parent_id() {
+            let before = self.tree(owner, id).await.map_err(|e| match e {
+                GetTaskError::NotFound { id } => UpdateTaskError::NotFound { id },
+                GetTaskError::Unknown(e) => UpdateTaskError::Unknown(e),
+            })?;
+            if before.contains(&parent_id) {
+                return Err(UpdateTaskError::ParentCycle { id: *id });
+            }
+            let parent = self
+                .repo
+                .get_task(owner, &parent_id)
+                .await
+                .map_err(|e| match e {
+                    GetTaskError::NotFound { id } => UpdateTaskError::ParentNotFound { id },
+                    GetTaskError::Unknown(e) => UpdateTaskError::Unknown(e),
+                })?;
+            let depth = self.depth(owner, &parent).await.map_err(|e| anyhow!(e))? + 1;
+            if depth + before.height() > Task::MAX_DEPTH {
+                return Err(UpdateTaskError::TooDeep);
+            }
+        }
+
+        let task = self.repo.update_task(owner, id, &req).await?;
+        if !current.completed() && task.completed() {
+            self.metrics.record_task_completion();
+        }
+
+        Ok(task)
+    }
+
+    /// Move the [Task] with the given `id` along with its subtasks to the trash. Deleting the
+    /// last incomplete subtask of a [Task] completes it.
+    ///
+    /// # Errors
+    ///
+    /// - [DeleteTaskError::Forbidden] if the user is a [ListRole::Viewer] of the [TaskList] of the
+    ///   [Task].
+    /// - Propagates any [DeleteTaskError] returned by the [ReminderRepository].
+    #[instrument(skip(self))]
+    async fn delete_task(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        version: Option<i64>,
+    ) -> Result<(), DeleteTaskError> {
+        let (_, access) = self.task_access(user, id).await.map_err(|e| match e {
+            GetTaskError::NotFound { id } => DeleteTaskError::NotFound { id },
+            GetTaskError::Unknown(e) => DeleteTaskError::Unknown(e),
+        })?;
+        if let Some((id, role)) = access.denied(ListRole::Editor) {
+            return Err(DeleteTaskError::Forbidden { id, role });
+        }
+        self.repo
+            .delete_task(&access.owner, id, version, user)
+            .await?;
+        self.metrics.record_task_deletion();
+        Ok(())
+    }
+
+    /// Create the [TaskList] specified in the `req`, owned by the user
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [CreateTaskListError] returned by the [ReminderRepository].
+    #[instrument(skip(self, req))]
+    async fn create_list(
+        &self,
+        user: &Uuid,
+        req: &CreateTaskListRequest,
+    ) -> Result<TaskList, CreateTaskListError> {
+        self.repo.create_list(user, req).await
+    }
+
+    /// Retrieve the [TaskList] with the given `id`
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [GetTaskListError] returned by the [ReminderRepository].
+    #[instrument(skip(self))]
+    async fn get_list(&self, user: &Uuid, id: &Uuid) -> Result<TaskList, GetTaskListError> {
+        self.repo.get_list(user, id).await
+    }
+
+    /// List all [TaskList]s, including archived ones if `include_archived` is set
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [ListTaskListsError] returned by the [ReminderRepository].
+    #[instrument(skip(self))]
+    async fn list_lists(
+        &self,
+        user: &Uuid,
+        include_archived: bool,
+    ) -> Result<Vec<TaskList>, ListTaskListsError> {
+        self.repo.list_lists(user, include_archived).await
+    }
+
+    /// Apply the changes specified in the `req` to the [TaskList] with the given `id`
+    ///
+    /// # Errors
+    ///
+    /// - [UpdateTaskListError::Forbidden] if the user is not an owner of the [TaskList].
+    /// - Propagates any [UpdateTaskListError] returned by the [ReminderRepository].
+    #[instrument(skip(self, req))]
+    async fn update_list(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        req: &UpdateTaskListRequest,
+    ) -> Result<TaskList, UpdateTaskListError> {
+        let list = self
+            .owned_list(user, id, |role| UpdateTaskListError::Forbidden {
+                id: *id,
+                role,
+            })
+            .await?;
+        self.repo.update_list(&list.owner_id(), id, req).await
+    }
+
+    /// Move the [TaskList] with the given `id` to the trash, along with its tasks or after moving
+    /// them to the inbox of its creator according to `mode`
+    ///
+    /// # Errors
+    ///
+    /// - [DeleteTaskListError::Forbidden] if the user is not an owner of the [TaskList].
+    /// - Propagates any [DeleteTaskListError] returned by the [ReminderRepository].
+    #[instrument(skip(self))]
+    async fn delete_list(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        mode: DeleteTaskListMode,
+    ) -> Result<(), DeleteTaskListError> {
+        let list = self
+            .owned_list(user, id, |role| DeleteTaskListError::Forbidden {
+                id: *id,
+                role,
+            })
+            .await?;
+        self.repo.delete_list(&list.owner_id(), id, mode).await
+    }
+
+    /// List the users who may access the [TaskList] with the given `id`
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [ListTaskListMembersError] returned by the [ReminderRepository].
+    #[instrument(skip(self))]
+    async fn list_members(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> Result<Vec<TaskListMember>, ListTaskListMembersError> {
+        let list = self.repo.get_list(user, id).await?;
+        self.repo.list_members(&list.owner_id(), id).await
+    }
+
+    /// Share the [TaskList] with the given `id` as specified in the `req`
+    ///
+    /// # Errors
+    ///
+    /// - [ShareTaskListError::Forbidden] if the user is not an owner of the [TaskList].
+    /// - Propagates any [ShareTaskListError] returned by the [ReminderRepository].
+    #[instrument(skip(self, req))]
+    async fn share_list(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        req: &ShareTaskListRequest,
+    ) -> Result<TaskListMember, ShareTaskListError> {
+        let list = self
+            .owned_list(user, id, |role| ShareTaskListError::Forbidden {
+                id: *id,
+                role,
+            })
+            .await?;
+        self.repo.share_list(&list.owner_id(), id, req).await
+    }
+
+    /// Stop sharing the [TaskList] with the given `id` with `member`
+    ///
+    /// # Errors
+    ///
+    /// - [UnshareTaskListError::Forbidden] if `member` is another user and the user is not an
+    ///   owner of the [TaskList].
+    /// - Propagates any [UnshareTaskListError] returned by the [ReminderRepository].
+    #[instrument(skip(self))]
+    async fn unshare_list(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        member: &Uuid,
+    ) -> Result<(), UnshareTaskListError> {
+        let list = self.repo.get_list(user, id).await?;
+        if member != user && list.role() < ListRole::Owner {
+            return Err(UnshareTaskListError::Forbidden {
+                id: *id,
+                role: list.role(),
+            });
+        }
+        self.repo.unshare_list(&list.owner_id(), id, member).await
+    }
+
+    /// Add `tags` to the [Task] with the given `id`
+    ///
+    /// # Errors
+    ///
+    /// - [TagTaskError::TooMany] if the [Task] would carry more than [TaskTags::MAX_TAGS] tags.
+    /// - [TagTaskError::Forbidden] if the user is a [ListRole::Viewer] of the [TaskList] of the
+    ///   [Task].
+    /// - [TagTaskError::Conflict] if the [Task] kept changing while its tags were written.
+    /// - Propagates any [GetTaskError::NotFound] returned by the [ReminderRepository].
+    #[instrument(skip(self, tags))]
+    async fn tag_task(&self, user: &Uuid, id: &Uuid, tags: &[Tag]) -> Result<Task, TagTaskError> {
+        for _ in 0..TAG_ATTEMPTS {
+            let (task, access) = self.task_access(user, id).await.map_err(|e| match e {
+                GetTaskError::NotFound { id } => TagTaskError::NotFound { id },
+                GetTaskError::Unknown(e) => TagTaskError::Unknown(e),
+            })?;
+            if let Some((id, role)) = access.denied(ListRole::Editor) {
+                return Err(TagTaskError::Forbidden { id, role });
+            }
+            // Only write the tags back over the version they were read from
+            let req = UpdateTaskRequest::new()
+                .with_tags(task.tags().with(tags)?)
+                .with_version(task.version())
+                .with_updated_by(*user);
+            match self.repo.update_task(&access.owner, id, &req).await {
+                Err(UpdateTaskError::Conflict { .. }) => continue,
+                result => {
+                    return result.map_err(|e| match e {
+                        UpdateTaskError::NotFound { id } => TagTaskError::NotFound { id },
+                        e => TagTaskError::Unknown(anyhow!(e)),
+                    })
+                }
+            }
+        }
+        Err(TagTaskError::Conflict { id: *id })
+    }
+
+    /// Remove `tags` from the [Task] with the given `id`
+    ///
+    /// # Errors
+    ///
+    /// - [UntagTaskError::Forbidden] if the user is a [ListRole::Viewer] of the [TaskList] of the
+    ///   [Task].
+    /// - [UntagTaskError::Conflict] if the [Task] kept changing while its tags were written.
+    /// - Propagates any [GetTaskError::NotFound] returned by the [ReminderRepository].
+    #[instrument(skip(self, tags))]
+    async fn untag_task(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+        tags: &[Tag],
+    ) -> Result<Task, UntagTaskError> {
+        for _ in 0..TAG_ATTEMPTS {
+            let (task, access) = self.task_access(user, id).await.map_err(|e| match e {
+                GetTaskError::NotFound { id } => UntagTaskError::NotFound { id },
+                GetTaskError::Unknown(e) => UntagTaskError::Unknown(e),
+            })?;
+            if let Some((id, role)) = access.denied(ListRole::Editor) {
+                return Err(UntagTaskError::Forbidden { id, role });
+            }
+            let req = UpdateTaskRequest::new()
+                .with_tags(task.tags().without(tags))
+                .with_version(task.version())
+                .with_updated_by(*user);
+            match self.repo.update_task(&access.owner, id, &req).await {
+                Err(UpdateTaskError::Conflict { .. }) => continue,
+                result => {
+                    return result.map_err(|e| match e {
+                        UpdateTaskError::NotFound { id } => UntagTaskError::NotFound { id },
+                        e => UntagTaskError::Unknown(anyhow!(e)),
+                    })
+                }
+            }
+        }
+        Err(UntagTaskError::Conflict { id: *id })
+    }
+
+    /// List every [Tag] in use with its number of [Task]s
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [ListTagsError] returned by the [ReminderRepository].
+    #[instrument(skip(self))]
+    async fn list_tags(&self, user: &Uuid) -> Result<Vec<TagUsage>, ListTagsError> {
+        self.repo.list_tags(user).await
+    }
+
+    /// Rename the [Tag] `from` to `to` on every [Task]
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [RenameTagError] returned by the [ReminderRepository].
+    #[instrument(skip(self, from, to))]
+    async fn rename_tag(
+        &self,
+        user: &Uuid,
+        from: &Tag,
+        to: &Tag,
+    ) -> Result<TagUsage, RenameTagError> {
+        self.repo.rename_tag(user, from, to).await
+    }
+
+    /// Merge `tags` into the [Tag] `into` on every [Task]
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [MergeTagsError] returned by the [ReminderRepository].
+    #[instrument(skip(self, tags, into))]
+    async fn merge_tags(
+        &self,
+        user: &Uuid,
+        tags: &[Tag],
+        into: &Tag,
+    ) -> Result<TagUsage, MergeTagsError> {
+        self.repo.merge_tags(user, tags, into).await
+    }
+
+    /// List the deleted [TaskList]s and [Task]s the user can restore
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [ListTrashError] returned by the [ReminderRepository].
+    #[instrument(skip(self))]
+    async fn list_trash(&self, user: &Uuid) -> Result<Trash, ListTrashError> {
+        self.repo.list_trash(user).await
+    }
+
+    /// Restore the [Task] with the given `id` along with its subtasks from the trash. Restoring
+    /// an incomplete subtask reopens its parent.
+    ///
+    /// # Errors
+    ///
+    /// - [RestoreTaskError::NotFound] if no [Task] with the given `id` is in the trash.
+    /// - [RestoreTaskError::ListDeleted] if the [TaskList] of the [Task] is in the trash.
+    /// - [RestoreTaskError::Forbidden] if the user is a [ListRole::Viewer] of the [TaskList] of
+    ///   the [Task].
+    /// - Propagates any [RestoreTaskError] returned by the [ReminderRepository].
+    #[instrument(skip(self))]
+    async fn restore_task(&self, user: &Uuid, id: &Uuid) -> Result<Task, RestoreTaskError> {
+        let task = self
+            .repo
+            .get_trashed_task(user, id)
+            .await
+            .map_err(|e| match e {
+                GetTaskError::NotFound { id } => RestoreTaskError::NotFound { id },
+                GetTaskError::Unknown(e) => RestoreTaskError::Unknown(e),
+            })?;
+        let access = self
+            .list_access(user, task.list_id())
+            .await
+            .map_err(|e| match e {
+                GetTaskListError::NotFound { id } => RestoreTaskError::ListDeleted { id },
+                GetTaskListError::Unknown(e) => RestoreTaskError::Unknown(e),
+            })?;
+        if let Some((id, role)) = access.denied(ListRole::Editor) {
+            return Err(RestoreTaskError::Forbidden { id, role });
+        }
+        self.repo.restore_task(&access.owner, id, user).await
+    }
+
+    /// Restore the [TaskList] with the given `id` along with its tasks from the trash
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [RestoreTaskListError] returned by the [ReminderRepository].
+    #[instrument(skip(self))]
+    async fn restore_list(&self, user: &Uuid, id: &Uuid) -> Result<TaskList, RestoreTaskListError> {
+        self.repo.restore_list(user, id).await
+    }
+
+    /// Permanently delete everything in the trash of the user
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [PurgeTrashError] returned by the [ReminderRepository].
+    #[instrument(skip(self))]
+    async fn empty_trash(&self, user: &Uuid) -> Result<PurgedTrash, PurgeTrashError> {
+        self.repo.empty_trash(user).await
+    }
+
+    /// Permanently delete everything moved to the trash before `deleted_before`
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [PurgeTrashError] returned by the [ReminderRepository].
+    #[instrument(skip(self))]
+    async fn purge_trash(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<PurgedTrash, PurgeTrashError> {
+        self.repo.purge_trash(deleted_before).await
+    }
+
+    /// Claim due reminders in batches and deliver each through the [ReminderNotifier]. Failed
+    /// deliveries are released for a later pass until they run out of attempts.
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [FireRemindersError] returned by the [ReminderRepository].
+    #[instrument(skip(self))]
+    async fn fire_due_reminders(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<FiredReminders, FireRemindersError> {
+        let mut fired = FiredReminders::default();
+        loop {
+            let batch = self
+                .repo
+                .claim_due_reminders(now, REMINDER_BATCH_SIZE)
+                .await?;
+            let mut failed_in_batch = false;
+            for reminder in &batch {
+                let lag = (now - *reminder.remind_at()).to_std().unwrap_or_default();
+                self.metrics.record_scheduler_lag(lag);
+                match self.notifier.notify(reminder).await {
+                    Ok(()) => {
+                        fired.delivered += 1;
+                        self.metrics.record_reminder_delivery();
+                    }
+                    Err(e) => {
+                        fired.failed += 1;
+                        self.metrics.record_reminder_failure();
+                        failed_in_batch = true;
+                        self.repo
+                            .record_reminder_failure(
+                                reminder,
+                                &format!("{:#}", e),
+                                reminder.should_retry(),
+                            )
+                            .await?;
+                    }
+                }
+            }
+            // Released reminders are retried on the next pass rather than immediately
+            if failed_in_batch || batch.len() < REMINDER_BATCH_SIZE as usize {
+                return Ok(fired);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::reminders::models::list::TaskListTitle;
+    use crate::domain::reminders::models::query::TaskFilter;
+    use crate::domain::reminders::models::recurrence::Recurrence;
+    use crate::domain::reminders::models::reminder::{DueReminder, NotifyReminderError};
+    use crate::domain::reminders::models::task::{DueAt, RemindAt, ReminderOffset, TaskTitle};
+    use crate::domain::users::models::user::{EmailAddress, PasswordHash};
+    use crate::domain::users::ports::UserRepository;
+    use crate::outbound::memory::Memory;
+    use chrono::Duration;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    /// A [ReminderNotifier] that records delivered reminders, or fails every delivery.
+    #[derive(Clone, Default)]
+    struct RecordingNotifier {
+        delivered: Arc<Mutex<Vec<DueReminder>>>,
+        fail: bool,
+    }
+
+    impl ReminderNotifier for RecordingNotifier {
+        async fn notify(&self, reminder: &DueReminder) -> Result<(), NotifyReminderError> {
+            if self.fail {
+                return Err(anyhow!("notifier unavailable").into());
+            }
+            self.delivered.lock().unwrap().push(reminder.clone());
+            Ok(())
+        }
+    }
+
+    /// [Metrics] that count how often each event was recorded.
+    #[derive(Clone, Default)]
+    struct RecordingMetrics {
+        counts: Arc<Mutex<HashMap<&'static str, usize>>>,
+    }
+
+    impl RecordingMetrics {
+        fn record(&self, event: &'static str) {
+            *self.counts.lock().unwrap().entry(event).or_default() += 1;
+        }
+
+        fn count(&self, event: &str) -> usize {
+            self.counts.lock().unwrap().get(event).copied().unwrap_or(0)
+        }
+    }
+
+    impl Metrics for RecordingMetrics {
+        fn record_http_request(&self, _: &str, _: &str, _: u16, _: std::time::Duration) {
+            self.record("http_request");
+        }
+
+        fn record_task_creation_success(&self) {
+            self.record("task_creation_success");
+        }
+
+        fn record_task_creation_failure(&self) {
+            self.record("task_creation_failure");
+        }
+
+        fn record_task_completion(&self) {
+            self.record("task_completion");
+        }
+
+        fn record_task_deletion(&self) {
+            self.record("task_deletion");
+        }
+
+        fn record_reminder_delivery(&self) {
+            self.record("reminder_delivery");
+        }
+
+        fn record_reminder_failure(&self) {
+            self.record("reminder_failure");
+        }
+
+        fn record_scheduler_lag(&self, _: std::time::Duration) {
+            self.record("scheduler_lag");
+        }
+
+        fn render(&self) -> anyhow::Result<String> {
+            Ok(format!("{:?}", self.counts.lock().unwrap()))
+        }
+    }
+
+    /// Register a user with the given email address in `memory`, returning their id.
+    async fn register(memory: &Memory, email: &str) -> Uuid {
+        let email = EmailAddress::new(email).unwrap();
+        let hash = PasswordHash::from_stored("!");
+        memory.create_user(&email, &hash).await.unwrap().id()
+    }
+
+    fn task_due_in(minutes: i64, offsets: &[u32]) -> CreateTaskRequest {
+        let offsets = offsets
+            .iter()
+            .map(|&m| ReminderOffset::from_minutes(m).unwrap())
+            .collect();
+        CreateTaskRequest::new(TaskTitle::new("Water plants").unwrap())
+            .with_due_at(DueAt::new(Utc::now() + Duration::minutes(minutes)))
+            .with_remind_at(RemindAt::new(offsets).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_completing_recurring_task_creates_next_occurrence() {
+        let metrics = RecordingMetrics::default();
+        let service = Service::new(Memory::new(), RecordingNotifier::default(), metrics.clone());
+        let owner = Uuid::new_v4();
+        let req = task_due_in(60, &[15])
+            .with_recurrence(Recurrence::parse("FREQ=DAILY;COUNT=2").unwrap());
+        let task = service.create_task(&owner, &req).await.unwrap();
+
+        let completed = UpdateTaskRequest::new().with_completed(true);
+        service
+            .update_task(&owner, &task.id(), &completed)
+            .await
+            .unwrap();
+        // Completing an already completed task does not create another occurrence
+        service
+            .update_task(&owner, &task.id(), &completed)
+            .await
+            .unwrap();
+
+        let tasks = service
+            .list_tasks(&owner, &ListTasksRequest::new())
+            .await
+            .unwrap()
+            .tasks;
+        assert_eq!(tasks.len(), 2);
+        let next = &tasks[1];
+        assert!(!next.completed());
+        assert_eq!(
+            *next.due_at().unwrap().as_datetime(),
+            *task.due_at().unwrap().as_datetime() + Duration::days(1)
+        );
+        assert_eq!(next.remind_at(), task.remind_at());
+        assert_eq!(next.recurrence().unwrap().to_string(), "FREQ=DAILY;COUNT=1");
+        assert_eq!(metrics.count("task_completion"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_task_records_each_outcome_once() {
+        let metrics = RecordingMetrics::default();
+        let service = Service::new(Memory::new(), RecordingNotifier::default(), metrics.clone());
+        let owner = Uuid::new_v4();
+        let fifteen_minutes =
+            RemindAt::new(vec![ReminderOffset::from_minutes(15).unwrap()]).unwrap();
+        let undated = CreateTaskRequest::new(TaskTitle::new("Call mom").unwrap())
+            .with_remind_at(fifteen_minutes);
+        assert!(service.create_task(&owner, &undated).await.is_err());
+        let orphan = CreateTaskRequest::new(TaskTitle::new("Pack socks").unwrap())
+            .with_parent_id(Uuid::new_v4());
+        assert!(matches!(
+            service.create_task(&owner, &orphan).await,
+            Err(CreateTaskError::ParentNotFound { .. })
+        ));
+        service
+            .create_task(&owner, &task_due_in(60, &[15]))
+            .await
+            .unwrap();
+
+        assert_eq!(metrics.count("task_creation_failure"), 2);
+        assert_eq!(metrics.count("task_creation_success"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reminders_require_a_due_date() {
+        let service = Service::new(
+            Memory::new(),
+            RecordingNotifier::default(),
+            RecordingMetrics::default(),
+        );
+        let owner = Uuid::new_v4();
+        let fifteen_minutes =
+            RemindAt::new(vec![ReminderOffset::from_minutes(15).unwrap()]).unwrap();
+        let undated = CreateTaskRequest::new(TaskTitle::new("Call mom").unwrap())
+            .with_remind_at(fifteen_minutes.clone());
+        assert!(matches!(
+            service.create_task(&owner, &undated).await,
+            Err(CreateTaskError::RemindAtWithoutDueAt)
+        ));
+
+        let task = service
+            .create_task(&owner, &task_due_in(60, &[15]))
+            .await
+            .unwrap();
+        let clear_due_at = UpdateTaskRequest::new().with_due_at(None);
+        assert!(matches!(
+            service.update_task(&owner, &task.id(), &clear_due_at).await,
+            Err(UpdateTaskError::RemindAtWithoutDueAt)
+        ));
+        let clear_both = clear_due_at.with_remind_at(RemindAt::default());
+        let task = service
+            .update_task(&owner, &task.id(), &clear_both)
+            .await
+            .unwrap();
+        assert!(task.due_at().is_none());
+
+        let remind = UpdateTaskRequest::new().with_remind_at(fifteen_minutes);
+        assert!(matches!(
+            service.update_task(&owner, &task.id(), &remind).await,
+            Err(UpdateTaskError::RemindAtWithoutDueAt)
+        ));
+    }
+
+    fn subtask(title: &str, parent: &Task) -> CreateTaskRequest {
+        CreateTaskRequest::new(TaskTitle::new(title).unwrap()).with_parent_id(parent.id())
+    }
+
+    #[tokio::test]
+    async fn test_parent_is_completed_exactly_when_its_subtasks_are() {
+        let service = Service::new(
+            Memory::new(),
+            RecordingNotifier::default(),
+            RecordingMetrics::default(),
+        );
+        let owner = Uuid::new_v4();
+        let trip = service
+            .create_task(
+                &owner,
+                &CreateTaskRequest::new(TaskTitle::new("Prepare trip").unwrap()),
+            )
+            .await
+            .unwrap();
+        let hotel = service
+            .create_task(&owner, &subtask("Book hotel", &trip))
+            .await
+            .unwrap();
+        let pack = service
+            .create_task(&owner, &subtask("Pack", &trip))
+            .await
+            .unwrap();
+        let completed = |completed| UpdateTaskRequest::new().with_completed(completed);
+        let is_completed = |id: Uuid| {
+            let service = service.clone();
+            async move { service.get_task(&owner, &id).await.unwrap().completed() }
+        };
+
+        service
+            .update_task(&owner, &hotel.id(), &completed(true))
+            .await
+            .unwrap();
+        assert!(!is_completed(trip.id()).await);
+        service
+            .update_task(&owner, &pack.id(), &completed(true))
+            .await
+            .unwrap();
+        assert!(is_completed(trip.id()).await);
+
+        service
+            .update_task(&owner, &pack.id(), &completed(false))
+            .await
+            .unwrap();
+        assert!(!is_completed(trip.id()).await);
+        assert!(is_completed(hotel.id()).await);
+
+        service
+            .update_task(&owner, &trip.id(), &completed(true))
+            .await
+            .unwrap();
+        assert!(is_completed(pack.id()).await);
+
+        // Adding a step to a completed checklist reopens it
+        service
+            .create_task(&owner, &subtask("Renew passport", &trip))
+            .await
+            .unwrap();
+        assert!(!is_completed(trip.id()).await);
+        let tree = service.get_task_tree(&owner, &trip.id()).await.unwrap();
+        let titles = tree
+            .subtasks()
+            .iter()
+            .map(|subtask| subtask.task().title().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(titles, vec!["Book hotel", "Pack", "Renew passport"]);
+    }
+
+    #[tokio::test]
+    async fn test_subtasks_are_nested_within_limits_and_without_cycles() {
+        let service = Service::new(
+            Memory::new(),
+            RecordingNotifier::default(),
+            RecordingMetrics::default(),
+        );
+        let owner = Uuid::new_v4();
+        let mut chain = vec![service
+            .create_task(
+                &owner,
+                &CreateTaskRequest::new(TaskTitle::new("Level 0").unwrap()),
+            )
+            .await
+            .unwrap()];
+        for level in 1..=Task::MAX_DEPTH {
+            let req = subtask(&format!("Level {}", level), chain.last().unwrap());
+            chain.push(service.create_task(&owner, &req).await.unwrap());
+        }
+
+        let result = service
+            .create_task(&owner, &subtask("Too deep", chain.last().unwrap()))
+            .await;
+        assert!(matches!(result, Err(CreateTaskError::TooDeep)));
+
+        let move_below = |parent: &Task| UpdateTaskRequest::new().with_parent_id(Some(parent.id()));
+        let result = service
+            .update_task(&owner, &chain[1].id(), &move_below(&chain[2]))
+            .await;
+        assert!(matches!(result, Err(UpdateTaskError::ParentCycle { .. })));
+
+        let other = service
+            .create_task(
+                &owner,
+                &CreateTaskRequest::new(TaskTitle::new("Other").unwrap()),
+            )
+            .await
+            .unwrap();
+        let result = service
+            .update_task(&owner, &chain[0].id(), &move_below(&other))
+            .await;
+        assert!(matches!(result, Err(UpdateTaskError::TooDeep)));
+        let moved = service
+            .update_task(&owner, &chain[2].id(), &move_below(&other))
+            .await
+            .unwrap();
+        assert_eq!(moved.parent_id(), Some(other.id()));
+        let tree = service.get_task_tree(&owner, &other.id()).await.unwrap();
+        assert_eq!(tree.height(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_tagging_adds_and_removes_tags_within_limit() {
+        let service = Service::new(
+            Memory::new(),
+            RecordingNotifier::default(),
+            RecordingMetrics::default(),
+        );
+        let owner = Uuid::new_v4();
+        let tag = |raw: &str| Tag::new(raw).unwrap();
+        let task = service
+            .create_task(
+                &owner,
+                &CreateTaskRequest::new(TaskTitle::new("Buy stamps").unwrap()),
+            )
+            .await
+            .unwrap();
+
+        service
+            .tag_task(&owner, &task.id(), &[tag("errands"), tag("post")])
+            .await
+            .unwrap();
+        let tagged = service
+            .tag_task(&owner, &task.id(), &[tag("Errands"), tag("urgent")])
+            .await
+            .unwrap();
+        assert_eq!(
+            tagged.tags().tags(),
+            [tag("errands"), tag("post"), tag("urgent")]
+        );
+        let untagged = service
+            .untag_task(&owner, &task.id(), &[tag("post"), tag("unknown")])
+            .await
+            .unwrap();
+        assert_eq!(untagged.tags().tags(), [tag("errands"), tag("urgent")]);
+
+        let many = (0..TaskTags::MAX_TAGS)
+            .map(|i| tag(&format!("tag{}", i)))
+            .collect::<Vec<_>>();
+        let result = service.tag_task(&owner, &task.id(), &many).await;
+        assert!(matches!(result, Err(TagTaskError::TooMany(_))));
+        let result = service.tag_task(&owner, &Uuid::new_v4(), &many).await;
+        assert!(matches!(result, Err(TagTaskError::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_collaborators_are_limited_by_their_role() {
+        let memory = Memory::new();
+        let service = Service::new(
+            memory.clone(),
+            RecordingNotifier::default(),
+            RecordingMetrics::default(),
+        );
+        let (ada, bob, cy) = (
+            register(&memory, "ada@example.com").await,
+            register(&memory, "bob@example.com").await,
+            register(&memory, "cy@example.com").await,
+        );
+        let share =
+            |email: &str, role| ShareTaskListRequest::new(EmailAddress::new(email).unwrap(), role);
+        let list = service
+            .create_list(
+                &ada,
+                &CreateTaskListRequest::new(TaskListTitle::new("Groceries").unwrap()),
+            )
+            .await
+            .unwrap();
+        let req =
+            CreateTaskRequest::new(TaskTitle::new("Buy milk").unwrap()).with_list_id(list.id());
+        let milk = service.create_task(&ada, &req).await.unwrap();
+        service
+            .share_list(
+                &ada,
+                &list.id(),
+                &share("bob@example.com", ListRole::Viewer),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            service.get_list(&bob, &list.id()).await.unwrap().role(),
+            ListRole::Viewer
+        );
+        assert_eq!(service.get_task(&bob, &milk.id()).await.unwrap(), milk);
+        let complete = UpdateTaskRequest::new().with_completed(true);
+        let result = service.update_task(&bob, &milk.id(), &complete).await;
+        assert!(matches!(
+            result,
+            Err(UpdateTaskError::Forbidden {
+                role: ListRole::Viewer,
+                ..
+            })
+        ));
+        let result = service
+            .share_list(&bob, &list.id(), &share("cy@example.com", ListRole::Viewer))
+            .await;
+        assert!(matches!(result, Err(ShareTaskListError::Forbidden { .. })));
+        let result = service.get_task(&cy, &milk.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+
+        service
+            .share_list(
+                &ada,
+                &list.id(),
+                &share("bob@example.com", ListRole::Editor),
+            )
+            .await
+            .unwrap();
+        let completed = service
+            .update_task(&bob, &milk.id(), &complete)
+            .await
+            .unwrap();
+        assert_eq!(completed.completed_by(), Some(bob));
+        assert_eq!(completed.updated_by(), Some(bob));
+        let result = service
+            .delete_list(&bob, &list.id(), DeleteTaskListMode::Cascade)
+            .await;
+        assert!(matches!(result, Err(DeleteTaskListError::Forbidden { .. })));
+        let result = service.unshare_list(&bob, &list.id(), &ada).await;
+        assert!(matches!(
+            result,
+            Err(UnshareTaskListError::Forbidden { .. })
+        ));
+        let result = service.unshare_list(&ada, &list.id(), &ada).await;
+        assert!(matches!(
+            result,
+            Err(UnshareTaskListError::MemberNotFound { .. })
+        ));
+
+        let members = service.list_members(&bob, &list.id()).await.unwrap();
+        let members = members
+            .iter()
+            .map(|member| (member.user_id(), member.role()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            members,
+            vec![(ada, ListRole::Owner), (bob, ListRole::Editor)]
+        );
+        service.unshare_list(&bob, &list.id(), &bob).await.unwrap();
+        let result = service.get_task(&bob, &milk.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_restored_tasks_are_limited_by_role_and_reopen_their_parent() {
+        let memory = Memory::new();
+        let service = Service::new(
+            memory.clone(),
+            RecordingNotifier::default(),
+            RecordingMetrics::default(),
+        );
+        let (ada, bob) = (
+            register(&memory, "ada@example.com").await,
+            register(&memory, "bob@example.com").await,
+        );
+        let list = service
+            .create_list(
+                &ada,
+                &CreateTaskListRequest::new(TaskListTitle::new("Groceries").unwrap()),
+            )
+            .await
+            .unwrap();
+        let share = ShareTaskListRequest::new(
+            EmailAddress::new("bob@example.com").unwrap(),
+            ListRole::Viewer,
+        );
+        service.share_list(&ada, &list.id(), &share).await.unwrap();
+        let req = CreateTaskRequest::new(TaskTitle::new("Shop").unwrap()).with_list_id(list.id());
+        let shop = service.create_task(&ada, &req).await.unwrap();
+        let subtask = |raw| {
+            CreateTaskRequest::new(TaskTitle::new(raw).unwrap())
+                .with_list_id(list.id())
+                .with_parent_id(shop.id())
+        };
+        let milk = service
+            .create_task(&ada, &subtask("Buy milk"))
+            .await
+            .unwrap();
+        let eggs = service
+            .create_task(&ada, &subtask("Buy eggs"))
+            .await
+            .unwrap();
+
+        service.delete_task(&ada, &eggs.id(), None).await.unwrap();
+        let complete = UpdateTaskRequest::new().with_completed(true);
+        service
+            .update_task(&ada, &milk.id(), &complete)
+            .await
+            .unwrap();
+        assert!(service
+            .get_task(&ada, &shop.id())
+            .await
+            .unwrap()
+            .completed());
+
+        let trash = service.list_trash(&bob).await.unwrap();
+        assert_eq!(trash.tasks()[0].item().id(), eggs.id());
+        let result = service.restore_task(&bob, &eggs.id()).await;
+        assert!(matches!(
+            result,
+            Err(RestoreTaskError::Forbidden {
+                role: ListRole::Viewer,
+                ..
+            })
+        ));
+
+        service.restore_task(&ada, &eggs.id()).await.unwrap();
+        assert!(!service
+            .get_task(&ada, &shop.id())
+            .await
+            .unwrap()
+            .completed());
+    }
+
+    #[tokio::test]
+    async fn test_assigned_tasks_remind_their_assignee() {
+        let memory = Memory::new();
+        let notifier = RecordingNotifier::default();
+        let service = Service::new(
+            memory.clone(),
+            notifier.clone(),
+            RecordingMetrics::default(),
+        );
+        let (ada, bob, cy) = (
+            register(&memory, "ada@example.com").await,
+            register(&memory, "bob@example.com").await,
+            register(&memory, "cy@example.com").await,
+        );
+        let list = service
+            .create_list(
+                &ada,
+                &CreateTaskListRequest::new(TaskListTitle::new("Chores").unwrap()),
+            )
+            .await
+            .unwrap();
+        let share = ShareTaskListRequest::new(
+            EmailAddress::new("bob@example.com").unwrap(),
+            ListRole::Editor,
+        );
+        service.share_list(&ada, &list.id(), &share).await.unwrap();
+
+        let req = task_due_in(30, &[60]).with_list_id(list.id());
+        let result = service
+            .create_task(&ada, &req.clone().with_assignee_id(cy))
+            .await;
+        assert!(matches!(
+            result,
+            Err(CreateTaskError::AssigneeNotMember { id }) if id == cy
+        ));
+        let result = service
+            .create_task(&ada, &task_due_in(30, &[]).with_assignee_id(bob))
+            .await;
+        assert!(matches!(
+            result,
+            Err(CreateTaskError::AssigneeNotMember { .. })
+        ));
+        let task = service
+            .create_task(&ada, &req.with_assignee_id(bob))
+            .await
+            .unwrap();
+        assert_eq!(task.assignee_id(), Some(bob));
+
+        let mine = ListTasksRequest::new().with_filter(TaskFilter::new().with_assignee(bob));
+        let page = service.list_tasks(&bob, &mine).await.unwrap();
+        assert_eq!(page.tasks, vec![task.clone()]);
+        let page = service.list_tasks(&ada, &mine).await.unwrap();
+        assert_eq!(page.tasks, vec![task.clone()]);
+
+        service.fire_due_reminders(Utc::now()).await.unwrap();
+        let recipients = notifier
+            .delivered
+            .lock()
+            .unwrap()
+            .iter()
+            .map(DueReminder::recipient)
+            .collect::<Vec<_>>();
+        assert_eq!(recipients, vec![bob]);
+
+        let reassign = UpdateTaskRequest::new().with_assignee_id(Some(cy));
+        let result = service.update_task(&bob, &task.id(), &reassign).await;
+        assert!(matches!(
+            result,
+            Err(UpdateTaskError::AssigneeNotMember { .. })
+        ));
+        service.unshare_list(&ada, &list.id(), &bob).await.unwrap();
+        let task = service.get_task(&ada, &task.id()).await.unwrap();
+        assert_eq!(task.assignee_id(), None);
+    }
+
+    #[tokio::test]
+    async fn test_fire_due_reminders_delivers_each_reminder_once() {
+        let notifier = RecordingNotifier::default();
+        let metrics = RecordingMetrics::default();
+        let service = Service::new(Memory::new(), notifier.clone(), metrics.clone());
+        let owner = Uuid::new_v4();
+        let due = service
+            .create_task(&owner, &task_due_in(30, &[60, 45, 15]))
+            .await
+            .unwrap();
+        let duplicate = service.create_task(&owner, &task_due_in(30, &[60])).await;
+        assert!(duplicate.is_err(), "titles of incomplete tasks are unique");
+
+        let fired = service.fire_due_reminders(Utc::now()).await.unwrap();
+        assert_eq!(
+            fired,
+            FiredReminders {
+                delivered: 2,
+                failed: 0
+            }
+        );
+        let fired = service.fire_due_reminders(Utc::now()).await.unwrap();
+        assert_eq!(fired, FiredReminders::default());
+
+        let delivered = notifier.delivered.lock().unwrap();
+        assert!(delivered.iter().all(|r| r.task().id() == due.id()));
+        assert_eq!(metrics.count("task_creation_success"), 1);
+        assert_eq!(metrics.count("task_creation_failure"), 1);
+        assert_eq!(metrics.count("reminder_delivery"), 2);
+        assert_eq!(metrics.count("scheduler_lag"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_reminders_are_retried_until_attempts_run_out() {
+        let notifier = RecordingNotifier {
+            fail: true,
+            ..Default::default()
+        };
+        let service = Service::new(Memory::new(), notifier, RecordingMetrics::default());
+        let owner = Uuid::new_v4();
+        service
+            .create_task(&owner, &task_due_in(5, &[10]))
+            .await
+            .unwrap();
+
+        for _ in 0..DueReminder::MAX_DELIVERY_ATTEMPTS {
+            let fired = service.fire_due_reminders(Utc::now()).await.unwrap();
+            assert_eq!(
+                fired,
+                FiredReminders {
+                    delivered: 0,
+                    failed: 1
+                }
+            );
+        }
+        let fired = service.fire_due_reminders(Utc::now()).await.unwrap();
+        assert_eq!(fired, FiredReminders::default());
+    }
 }
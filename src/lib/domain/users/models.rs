@@ -0,0 +1,3 @@
+pub mod api_token;
+pub mod session;
+pub mod user;
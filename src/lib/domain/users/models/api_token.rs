@@ -0,0 +1,316 @@
+use crate::domain::users::models::session::{digest, random_secret};
+use crate::domain::users::models::user::User;
+use chrono::{DateTime, Utc};
+use std::fmt::{Debug, Display, Formatter};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// The secret of an [ApiToken], shown to its [User] once when the token is created. Only its
+/// [digest](ApiTokenSecret::digest) is stored. It is never printed, not even in debug output.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ApiTokenSecret(String);
+
+impl ApiTokenSecret {
+    /// Sets API token secrets apart from session tokens, and makes them easy to spot in leaks.
+    pub const PREFIX: &'static str = "modus_";
+
+    /// The number of random bytes in a secret.
+    const BYTES: usize = 32;
+
+    /// Generate a new random secret.
+    pub fn generate() -> Self {
+        Self(format!("{}{}", Self::PREFIX, random_secret(Self::BYTES)))
+    }
+
+    /// A secret presented by a client. Secrets are not validated beyond being looked up.
+    pub fn from_client(raw: &str) -> Self {
+        Self(raw.to_string())
+    }
+
+    /// Whether a bearer token presented by a client is an API token secret rather than a
+    /// [SessionToken](crate::domain::users::models::session::SessionToken).
+    pub fn is_api_token(raw: &str) -> bool {
+        raw.starts_with(Self::PREFIX)
+    }
+
+    /// The SHA-256 digest of the secret, under which its [ApiToken] is stored.
+    pub fn digest(&self) -> String {
+        digest(&self.0)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for ApiTokenSecret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ApiTokenSecret(..)")
+    }
+}
+
+/// A valid name for an [ApiToken], to tell tokens apart when listing or revoking them.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiTokenName(String);
+
+#[derive(Clone, Debug, Error)]
+#[error(
+    "API token name must be between 1 and {max} characters long",
+    max = ApiTokenName::MAX_LENGTH
+)]
+pub struct ApiTokenNameInvalidError;
+
+impl ApiTokenName {
+    pub const MAX_LENGTH: usize = 100;
+
+    pub fn new(raw: &str) -> Result<Self, ApiTokenNameInvalidError> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.chars().count() > Self::MAX_LENGTH {
+            Err(ApiTokenNameInvalidError)
+        } else {
+            Ok(Self(trimmed.to_string()))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for ApiTokenName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Whether a token may change data or only read it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TokenAccess {
+    ReadOnly,
+    #[default]
+    ReadWrite,
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("access {0:?} must be read_only or read_write")]
+pub struct TokenAccessInvalidError(String);
+
+impl TokenAccess {
+    /// Parse the name of an access level.
+    pub fn parse(raw: &str) -> Result<Self, TokenAccessInvalidError> {
+        match raw {
+            "read_only" => Ok(Self::ReadOnly),
+            "read_write" => Ok(Self::ReadWrite),
+            _ => Err(TokenAccessInvalidError(raw.to_string())),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ReadOnly => "read_only",
+            Self::ReadWrite => "read_write",
+        }
+    }
+}
+
+impl Display for TokenAccess {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What a bearer token lets its [User] do. Sessions have the [full](TokenScope::full) scope;
+/// [ApiToken]s may be restricted to reading, to a single
+/// [TaskList](crate::domain::reminders::models::list::TaskList), or both.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TokenScope {
+    pub access: TokenAccess,
+    /// The list the token is restricted to, or `None` if it may access all of the user's data.
+    pub list_id: Option<Uuid>,
+}
+
+impl TokenScope {
+    /// Read and write access to all of the user's data.
+    pub fn full() -> Self {
+        Self::default()
+    }
+
+    pub fn with_access(mut self, access: TokenAccess) -> Self {
+        self.access = access;
+        self
+    }
+
+    pub fn with_list_id(mut self, list_id: Uuid) -> Self {
+        self.list_id = Some(list_id);
+        self
+    }
+
+    pub fn access(&self) -> TokenAccess {
+        self.access
+    }
+
+    pub fn list_id(&self) -> Option<Uuid> {
+        self.list_id
+    }
+
+    /// Whether the token may change data.
+    pub fn allows_writes(&self) -> bool {
+        self.access == TokenAccess::ReadWrite
+    }
+
+    /// Whether the token may access tasks in the list with `list_id`, or in the inbox if
+    /// `list_id` is `None`.
+    pub fn allows_list(&self, list_id: Option<Uuid>) -> bool {
+        self.list_id.is_none_or(|scoped| list_id == Some(scoped))
+    }
+}
+
+/// A long-lived, revocable token with which scripts and integrations act as its [User].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub name: ApiTokenName,
+    pub scope: TokenScope,
+    pub created_at: DateTime<Utc>,
+    /// When the token last authenticated a request, or `None` if it never has.
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl ApiToken {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &ApiTokenName {
+        &self.name
+    }
+
+    pub fn scope(&self) -> TokenScope {
+        self.scope
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn last_used_at(&self) -> Option<DateTime<Utc>> {
+        self.last_used_at
+    }
+}
+
+/// A freshly created [ApiToken] together with its secret, which is never available again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewApiToken {
+    pub token: ApiToken,
+    pub secret: ApiTokenSecret,
+}
+
+impl NewApiToken {
+    /// Issue a new token as specified in the `req` at `now`, with a fresh secret.
+    pub fn issue(req: &CreateApiTokenRequest, now: DateTime<Utc>) -> Self {
+        Self {
+            token: ApiToken {
+                id: Uuid::new_v4(),
+                name: req.name.clone(),
+                scope: req.scope,
+                created_at: now,
+                last_used_at: None,
+            },
+            secret: ApiTokenSecret::generate(),
+        }
+    }
+
+    pub fn token(&self) -> &ApiToken {
+        &self.token
+    }
+
+    pub fn secret(&self) -> &ApiTokenSecret {
+        &self.secret
+    }
+}
+
+/// A [User] authenticated by a bearer token, together with what the token lets them do.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Principal {
+    pub user: User,
+    pub scope: TokenScope,
+}
+
+/// The fields required by the domain to create an [ApiToken].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateApiTokenRequest {
+    name: ApiTokenName,
+    scope: TokenScope,
+}
+
+impl CreateApiTokenRequest {
+    pub fn new(name: ApiTokenName, scope: TokenScope) -> Self {
+        Self { name, scope }
+    }
+
+    pub fn name(&self) -> &ApiTokenName {
+        &self.name
+    }
+
+    pub fn scope(&self) -> TokenScope {
+        self.scope
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CreateApiTokenError {
+    #[error("task list with id {id} does not exist")]
+    ListNotFound { id: Uuid },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum ListApiTokensError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum RevokeApiTokenError {
+    #[error("API token with id {id} does not exist")]
+    NotFound { id: Uuid },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secrets_are_prefixed_and_stored_as_digests() {
+        let secret = ApiTokenSecret::generate();
+        assert!(ApiTokenSecret::is_api_token(secret.as_str()));
+        assert!(!ApiTokenSecret::is_api_token("cdcMBa0qbjbgAO5YmYlmX6TW"));
+        assert_eq!(
+            secret.digest(),
+            ApiTokenSecret::from_client(secret.as_str()).digest()
+        );
+        assert_eq!(format!("{:?}", secret), "ApiTokenSecret(..)");
+    }
+
+    #[test]
+    fn test_scope_restricts_writes_and_lists() {
+        let list_id = Uuid::new_v4();
+        let full = TokenScope::full();
+        assert!(full.allows_writes());
+        assert!(full.allows_list(None) && full.allows_list(Some(list_id)));
+
+        let scoped = TokenScope::full()
+            .with_access(TokenAccess::ReadOnly)
+            .with_list_id(list_id);
+        assert!(!scoped.allows_writes());
+        assert!(scoped.allows_list(Some(list_id)));
+        assert!(!scoped.allows_list(None));
+        assert!(!scoped.allows_list(Some(Uuid::new_v4())));
+    }
+}
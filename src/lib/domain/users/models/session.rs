@@ -0,0 +1,157 @@
+use crate::domain::users::models::user::{EmailAddress, Password, User};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+use std::fmt::{Debug, Formatter};
+use thiserror::Error;
+
+/// An opaque bearer token that identifies a [Session]. Only its [digest](SessionToken::digest)
+/// is stored, so a leaked store does not leak usable tokens. It is never printed, not even in
+/// debug output.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SessionToken(String);
+
+impl SessionToken {
+    /// The number of random bytes in a token.
+    const BYTES: usize = 32;
+
+    /// Generate a new random token.
+    pub fn generate() -> Self {
+        Self(random_secret(Self::BYTES))
+    }
+
+    /// A token presented by a client. Tokens are not validated beyond being looked up.
+    pub fn from_client(raw: &str) -> Self {
+        Self(raw.to_string())
+    }
+
+    /// The SHA-256 digest of the token, under which its [Session] is stored.
+    pub fn digest(&self) -> String {
+        digest(&self.0)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// `bytes` random bytes, encoded as URL safe base64 without padding.
+pub(super) fn random_secret(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    OsRng.fill_bytes(&mut buf);
+    URL_SAFE_NO_PAD.encode(buf)
+}
+
+/// The SHA-256 digest of a secret, encoded as URL safe base64 without padding.
+pub(super) fn digest(secret: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(secret.as_bytes()))
+}
+
+impl Debug for SessionToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SessionToken(..)")
+    }
+}
+
+/// A signed in [User], identified by a [SessionToken] until it expires or the user signs out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Session {
+    pub token: SessionToken,
+    pub user: User,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Session {
+    /// How many days a session lasts.
+    pub const LIFETIME_DAYS: i64 = 30;
+
+    /// Start a new session for `user` at `now`, with a fresh token.
+    pub fn start(user: User, now: DateTime<Utc>) -> Self {
+        Self {
+            token: SessionToken::generate(),
+            user,
+            expires_at: now + Duration::days(Self::LIFETIME_DAYS),
+        }
+    }
+
+    pub fn token(&self) -> &SessionToken {
+        &self.token
+    }
+
+    pub fn user(&self) -> &User {
+        &self.user
+    }
+
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+}
+
+/// The fields required by the domain to sign a [User] in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogInRequest {
+    email: EmailAddress,
+    password: Password,
+}
+
+impl LogInRequest {
+    pub fn new(email: EmailAddress, password: Password) -> Self {
+        Self { email, password }
+    }
+
+    pub fn email(&self) -> &EmailAddress {
+        &self.email
+    }
+
+    pub fn password(&self) -> &Password {
+        &self.password
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum LogInError {
+    /// Deliberately does not say whether the email address or the password was wrong.
+    #[error("invalid email address or password")]
+    InvalidCredentials,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum AuthenticateError {
+    #[error("invalid or expired session token")]
+    InvalidToken,
+    #[error("invalid or revoked API token")]
+    InvalidApiToken,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[derive(Debug, Error)]
+pub enum LogOutError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_are_random_and_stored_as_digests() {
+        let token = SessionToken::generate();
+        assert_ne!(token, SessionToken::generate());
+        assert_eq!(token.as_str().len(), 43);
+        assert_eq!(
+            token.digest(),
+            SessionToken::from_client(token.as_str()).digest()
+        );
+        assert_ne!(token.digest(), token.as_str());
+        assert_eq!(format!("{:?}", token), "SessionToken(..)");
+    }
+}
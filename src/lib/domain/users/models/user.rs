@@ -0,0 +1,217 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::{DateTime, Utc};
+use std::fmt::{Debug, Display, Formatter};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// A valid email address, trimmed and normalised to lowercase. Only the shape is checked; whether
+/// the address receives mail is not.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EmailAddress(String);
+
+#[derive(Clone, Debug, Error)]
+#[error("email address {0:?} is invalid")]
+pub struct EmailAddressInvalidError(String);
+
+impl EmailAddress {
+    pub const MAX_LENGTH: usize = 254;
+
+    pub fn new(raw: &str) -> Result<Self, EmailAddressInvalidError> {
+        let normalised = raw.trim().to_lowercase();
+        let valid = match normalised.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty()
+                    && !domain.is_empty()
+                    && !domain.contains('@')
+                    && !normalised.chars().any(char::is_whitespace)
+                    && normalised.chars().count() <= Self::MAX_LENGTH
+            }
+            None => false,
+        };
+        if valid {
+            Ok(Self(normalised))
+        } else {
+            Err(EmailAddressInvalidError(raw.to_string()))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for EmailAddress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A password as typed by a user, of a valid length. It is never printed, not even in debug
+/// output.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Password(String);
+
+#[derive(Clone, Debug, Error)]
+#[error(
+    "password must be between {min} and {max} characters long",
+    min = Password::MIN_LENGTH,
+    max = Password::MAX_LENGTH
+)]
+pub struct PasswordInvalidError;
+
+impl Password {
+    pub const MIN_LENGTH: usize = 8;
+    pub const MAX_LENGTH: usize = 128;
+
+    pub fn new(raw: &str) -> Result<Self, PasswordInvalidError> {
+        let length = raw.chars().count();
+        if (Self::MIN_LENGTH..=Self::MAX_LENGTH).contains(&length) {
+            Ok(Self(raw.to_string()))
+        } else {
+            Err(PasswordInvalidError)
+        }
+    }
+}
+
+impl Debug for Password {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Password(..)")
+    }
+}
+
+/// A salted argon2 hash of a [Password] in PHC string format, which records the parameters it
+/// was computed with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PasswordHash(String);
+
+impl PasswordHash {
+    /// Hash `password` with a fresh random salt. Hashing is deliberately slow.
+    pub fn new(password: &Password) -> anyhow::Result<Self> {
+        let salt = SaltString::generate(&mut OsRng);
+        let hash = Argon2::default()
+            .hash_password(password.0.as_bytes(), &salt)
+            .map_err(|e| anyhow::anyhow!("failed to hash password: {}", e))?;
+        Ok(Self(hash.to_string()))
+    }
+
+    /// A hash read back from storage.
+    pub fn from_stored(raw: &str) -> Self {
+        Self(raw.to_string())
+    }
+
+    /// Whether `password` is the one this hash was computed from. A stored hash that cannot be
+    /// parsed matches no password.
+    pub fn verify(&self, password: &Password) -> bool {
+        argon2::PasswordHash::new(&self.0).is_ok_and(|hash| {
+            Argon2::default()
+                .verify_password(password.0.as_bytes(), &hash)
+                .is_ok()
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A person who signs in to manage their own tasks.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct User {
+    pub id: Uuid,
+    pub email: EmailAddress,
+    pub created_at: DateTime<Utc>,
+}
+
+impl User {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn email(&self) -> &EmailAddress {
+        &self.email
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+/// A [User] together with the hash of their password, for checking a sign in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UserCredentials {
+    pub user: User,
+    pub password_hash: PasswordHash,
+}
+
+/// The fields required by the domain to register a [User].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegisterUserRequest {
+    email: EmailAddress,
+    password: Password,
+}
+
+impl RegisterUserRequest {
+    pub fn new(email: EmailAddress, password: Password) -> Self {
+        Self { email, password }
+    }
+
+    pub fn email(&self) -> &EmailAddress {
+        &self.email
+    }
+
+    pub fn password(&self) -> &Password {
+        &self.password
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RegisterUserError {
+    #[error("user with email {email} already exists")]
+    Duplicate { email: EmailAddress },
+    #[error("registration is closed")]
+    Closed,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+    // to be extended as new error scenarios are introduced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_email_address_is_normalised_and_checked() {
+        assert_eq!(
+            EmailAddress::new(" Ada@Example.COM ").unwrap().as_str(),
+            "ada@example.com"
+        );
+        for raw in [
+            "",
+            "ada",
+            "@example.com",
+            "ada@",
+            "a@b@c",
+            "ada lovelace@example.com",
+        ] {
+            assert!(
+                EmailAddress::new(raw).is_err(),
+                "{:?} should be invalid",
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn test_password_hash_verifies_only_the_hashed_password() {
+        let password = Password::new("correct horse").unwrap();
+        let hash = PasswordHash::new(&password).unwrap();
+        assert!(hash.as_str().starts_with("$argon2id$"));
+        assert!(hash.verify(&password));
+        assert!(!hash.verify(&Password::new("battery staple").unwrap()));
+        assert!(!PasswordHash::from_stored("!").verify(&password));
+        assert!(Password::new("short").is_err());
+        assert_eq!(format!("{:?}", password), "Password(..)");
+    }
+}
@@ -0,0 +1,181 @@
+use crate::domain::users::models::api_token::{
+    ApiToken, ApiTokenSecret, CreateApiTokenError, CreateApiTokenRequest, ListApiTokensError,
+    NewApiToken, Principal, RevokeApiTokenError,
+};
+use crate::domain::users::models::session::{
+    AuthenticateError, LogInError, LogInRequest, LogOutError, Session, SessionToken,
+};
+use crate::domain::users::models::user::{
+    EmailAddress, PasswordHash, RegisterUserError, RegisterUserRequest, User, UserCredentials,
+};
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use uuid::Uuid;
+
+/// `UserService` is the public API for the users domain.
+pub trait UserService: Clone + Send + Sync + 'static {
+    /// Asynchronously register a new [User].
+    ///
+    /// # Errors
+    ///
+    /// - [RegisterUserError::Duplicate] if a [User] with the same [EmailAddress] already exists.
+    /// - [RegisterUserError::Closed] if registration is closed.
+    fn register_user(
+        &self,
+        req: &RegisterUserRequest,
+    ) -> impl Future<Output = Result<User, RegisterUserError>> + Send;
+
+    /// Asynchronously sign a [User] in, starting a new [Session].
+    ///
+    /// # Errors
+    ///
+    /// - [LogInError::InvalidCredentials] if no [User] has the email address of the `req`, or
+    ///   the password is wrong.
+    fn log_in(
+        &self,
+        req: &LogInRequest,
+    ) -> impl Future<Output = Result<Session, LogInError>> + Send;
+
+    /// Asynchronously resolve the [User] signed in with `token`.
+    ///
+    /// # Errors
+    ///
+    /// - [AuthenticateError::InvalidToken] if no unexpired [Session] has the `token`.
+    fn authenticate(
+        &self,
+        token: &SessionToken,
+    ) -> impl Future<Output = Result<User, AuthenticateError>> + Send;
+
+    /// Asynchronously end the [Session] with `token`. Ending a session that does not exist
+    /// succeeds.
+    fn log_out(&self, token: &SessionToken)
+        -> impl Future<Output = Result<(), LogOutError>> + Send;
+
+    /// Asynchronously create an [ApiToken] for the [User] with id `user`. The secret of the
+    /// token is only ever returned here.
+    ///
+    /// # Errors
+    ///
+    /// - [CreateApiTokenError::ListNotFound] if the token is restricted to a list the [User]
+    ///   does not have.
+    fn create_api_token(
+        &self,
+        user: &Uuid,
+        req: &CreateApiTokenRequest,
+    ) -> impl Future<Output = Result<NewApiToken, CreateApiTokenError>> + Send;
+
+    /// Asynchronously list the [ApiToken]s of the [User] with id `user`, oldest first.
+    fn list_api_tokens(
+        &self,
+        user: &Uuid,
+    ) -> impl Future<Output = Result<Vec<ApiToken>, ListApiTokensError>> + Send;
+
+    /// Asynchronously revoke the [ApiToken] with `id` of the [User] with id `user`.
+    ///
+    /// # Errors
+    ///
+    /// - [RevokeApiTokenError::NotFound] if the [User] has no [ApiToken] with `id`.
+    fn revoke_api_token(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<(), RevokeApiTokenError>> + Send;
+
+    /// Asynchronously resolve the [Principal] of the [ApiToken] with `secret`, recording that
+    /// the token was used.
+    ///
+    /// # Errors
+    ///
+    /// - [AuthenticateError::InvalidApiToken] if no [ApiToken] has the `secret`.
+    fn authenticate_api_token(
+        &self,
+        secret: &ApiTokenSecret,
+    ) -> impl Future<Output = Result<Principal, AuthenticateError>> + Send;
+}
+
+/// `UserRepository` represents a store of users, their sessions and their API tokens.
+pub trait UserRepository: Clone + Send + Sync + 'static {
+    /// Asynchronously create a new [User].
+    ///
+    /// # Errors
+    ///
+    /// - [RegisterUserError::Duplicate] if a [User] with the same [EmailAddress] already exists.
+    fn create_user(
+        &self,
+        email: &EmailAddress,
+        password_hash: &PasswordHash,
+    ) -> impl Future<Output = Result<User, RegisterUserError>> + Send;
+
+    /// Asynchronously retrieve the [User] with the given `email` and their password hash, or
+    /// `None` if no such [User] exists.
+    fn get_credentials(
+        &self,
+        email: &EmailAddress,
+    ) -> impl Future<Output = Result<Option<UserCredentials>, LogInError>> + Send;
+
+    /// Asynchronously store a new [Session] under the digest of its token.
+    fn create_session(
+        &self,
+        session: &Session,
+    ) -> impl Future<Output = Result<(), LogInError>> + Send;
+
+    /// Asynchronously retrieve the [User] of the [Session] with `token`, if it has not expired
+    /// by `now`.
+    ///
+    /// # Errors
+    ///
+    /// - [AuthenticateError::InvalidToken] if no unexpired [Session] has the `token`.
+    fn get_session_user(
+        &self,
+        token: &SessionToken,
+        now: DateTime<Utc>,
+    ) -> impl Future<Output = Result<User, AuthenticateError>> + Send;
+
+    /// Asynchronously delete the [Session] with `token`, if it exists.
+    fn delete_session(
+        &self,
+        token: &SessionToken,
+    ) -> impl Future<Output = Result<(), LogOutError>> + Send;
+
+    /// Asynchronously store a new [ApiToken] of the [User] with id `user` under the digest of
+    /// its secret.
+    ///
+    /// # Errors
+    ///
+    /// - [CreateApiTokenError::ListNotFound] if the token is restricted to a list the [User]
+    ///   does not have.
+    fn create_api_token(
+        &self,
+        user: &Uuid,
+        token: &NewApiToken,
+    ) -> impl Future<Output = Result<(), CreateApiTokenError>> + Send;
+
+    /// Asynchronously retrieve the [ApiToken]s of the [User] with id `user`, oldest first.
+    fn list_api_tokens(
+        &self,
+        user: &Uuid,
+    ) -> impl Future<Output = Result<Vec<ApiToken>, ListApiTokensError>> + Send;
+
+    /// Asynchronously delete the [ApiToken] with `id` of the [User] with id `user`.
+    ///
+    /// # Errors
+    ///
+    /// - [RevokeApiTokenError::NotFound] if the [User] has no [ApiToken] with `id`.
+    fn delete_api_token(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<(), RevokeApiTokenError>> + Send;
+
+    /// Asynchronously retrieve the [Principal] of the [ApiToken] with `secret`, setting its
+    /// last use to `now`.
+    ///
+    /// # Errors
+    ///
+    /// - [AuthenticateError::InvalidApiToken] if no [ApiToken] has the `secret`.
+    fn use_api_token(
+        &self,
+        secret: &ApiTokenSecret,
+        now: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Principal, AuthenticateError>> + Send;
+}
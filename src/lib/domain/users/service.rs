@@ -0,0 +1,252 @@
+use crate::domain::users::models::api_token::{
+    ApiToken, ApiTokenSecret, CreateApiTokenError, CreateApiTokenRequest, ListApiTokensError,
+    NewApiToken, Principal, RevokeApiTokenError,
+};
+use crate::domain::users::models::session::{
+    AuthenticateError, LogInError, LogInRequest, LogOutError, Session, SessionToken,
+};
+use crate::domain::users::models::user::{
+    Password, PasswordHash, RegisterUserError, RegisterUserRequest, User,
+};
+use crate::domain::users::ports::{UserRepository, UserService};
+use anyhow::Context;
+use chrono::Utc;
+use uuid::Uuid;
+
+/// Cannonical implementation of the [UserService] port, through which the users domain is
+/// consumed
+#[derive(Debug, Clone)]
+pub struct Service<R>
+where
+    R: UserRepository,
+{
+    repo: R,
+    registration_open: bool,
+}
+
+impl<R> Service<R>
+where
+    R: UserRepository,
+{
+    /// Create a new instance of the [Service] with the provided [UserRepository]. Anyone may
+    /// register unless registration is closed.
+    pub fn new(repo: R) -> Self {
+        Self {
+            repo,
+            registration_open: true,
+        }
+    }
+
+    /// Reject all new registrations, for servers whose users are all known.
+    pub fn with_registration_closed(mut self) -> Self {
+        self.registration_open = false;
+        self
+    }
+}
+
+/// Hash `password` on the blocking thread pool, as hashing is deliberately slow.
+async fn hash_password(password: &Password) -> anyhow::Result<PasswordHash> {
+    let password = password.clone();
+    tokio::task::spawn_blocking(move || PasswordHash::new(&password))
+        .await
+        .context("password hashing task failed")?
+}
+
+/// Check `password` against `hash` on the blocking thread pool.
+async fn verify_password(hash: &PasswordHash, password: &Password) -> anyhow::Result<bool> {
+    let (hash, password) = (hash.clone(), password.clone());
+    tokio::task::spawn_blocking(move || hash.verify(&password))
+        .await
+        .context("password verification task failed")
+}
+
+impl<R> UserService for Service<R>
+where
+    R: UserRepository,
+{
+    /// Register the [User] specified in the `req`, storing only a hash of their password
+    ///
+    /// # Errors
+    ///
+    /// - [RegisterUserError::Closed] if registration is closed.
+    /// - Propagates any [RegisterUserError] returned by the [UserRepository].
+    async fn register_user(&self, req: &RegisterUserRequest) -> Result<User, RegisterUserError> {
+        if !self.registration_open {
+            return Err(RegisterUserError::Closed);
+        }
+        let password_hash = hash_password(req.password()).await?;
+        self.repo.create_user(req.email(), &password_hash).await
+    }
+
+    /// Check the credentials in the `req` and start a new [Session] for the [User]
+    ///
+    /// # Errors
+    ///
+    /// - [LogInError::InvalidCredentials] if the email address is unknown or the password is
+    ///   wrong.
+    /// - Propagates any [LogInError] returned by the [UserRepository].
+    async fn log_in(&self, req: &LogInRequest) -> Result<Session, LogInError> {
+        let Some(credentials) = self.repo.get_credentials(req.email()).await? else {
+            // Hash anyway, so that unknown addresses take as long to reject as wrong passwords
+            hash_password(req.password()).await?;
+            return Err(LogInError::InvalidCredentials);
+        };
+        if !verify_password(&credentials.password_hash, req.password()).await? {
+            return Err(LogInError::InvalidCredentials);
+        }
+        let session = Session::start(credentials.user, Utc::now());
+        self.repo.create_session(&session).await?;
+        Ok(session)
+    }
+
+    /// Resolve the [User] of the unexpired [Session] with `token`
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [AuthenticateError] returned by the [UserRepository].
+    async fn authenticate(&self, token: &SessionToken) -> Result<User, AuthenticateError> {
+        self.repo.get_session_user(token, Utc::now()).await
+    }
+
+    /// End the [Session] with `token`
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [LogOutError] returned by the [UserRepository].
+    async fn log_out(&self, token: &SessionToken) -> Result<(), LogOutError> {
+        self.repo.delete_session(token).await
+    }
+
+    /// Issue a new [ApiToken] as specified in the `req`, storing only a digest of its secret
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [CreateApiTokenError] returned by the [UserRepository].
+    async fn create_api_token(
+        &self,
+        user: &Uuid,
+        req: &CreateApiTokenRequest,
+    ) -> Result<NewApiToken, CreateApiTokenError> {
+        let token = NewApiToken::issue(req, Utc::now());
+        self.repo.create_api_token(user, &token).await?;
+        Ok(token)
+    }
+
+    /// List the [ApiToken]s of the [User] with id `user`
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [ListApiTokensError] returned by the [UserRepository].
+    async fn list_api_tokens(&self, user: &Uuid) -> Result<Vec<ApiToken>, ListApiTokensError> {
+        self.repo.list_api_tokens(user).await
+    }
+
+    /// Revoke the [ApiToken] with `id`
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [RevokeApiTokenError] returned by the [UserRepository].
+    async fn revoke_api_token(&self, user: &Uuid, id: &Uuid) -> Result<(), RevokeApiTokenError> {
+        self.repo.delete_api_token(user, id).await
+    }
+
+    /// Resolve the [Principal] of the [ApiToken] with `secret`
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [AuthenticateError] returned by the [UserRepository].
+    async fn authenticate_api_token(
+        &self,
+        secret: &ApiTokenSecret,
+    ) -> Result<Principal, AuthenticateError> {
+        self.repo.use_api_token(secret, Utc::now()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::users::models::api_token::{ApiTokenName, TokenAccess, TokenScope};
+    use crate::domain::users::models::user::EmailAddress;
+    use crate::outbound::memory::Memory;
+
+    fn email() -> EmailAddress {
+        EmailAddress::new("ada@example.com").unwrap()
+    }
+
+    fn password(raw: &str) -> Password {
+        Password::new(raw).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_registered_user_logs_in_and_out() {
+        let service = Service::new(Memory::new());
+        let req = RegisterUserRequest::new(email(), password("correct horse"));
+        let user = service.register_user(&req).await.unwrap();
+        assert!(matches!(
+            service.register_user(&req).await,
+            Err(RegisterUserError::Duplicate { .. })
+        ));
+
+        let wrong = LogInRequest::new(email(), password("battery staple"));
+        assert!(matches!(
+            service.log_in(&wrong).await,
+            Err(LogInError::InvalidCredentials)
+        ));
+        let session = service
+            .log_in(&LogInRequest::new(email(), password("correct horse")))
+            .await
+            .unwrap();
+        assert_eq!(service.authenticate(session.token()).await.unwrap(), user);
+
+        service.log_out(session.token()).await.unwrap();
+        assert!(matches!(
+            service.authenticate(session.token()).await,
+            Err(AuthenticateError::InvalidToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_api_tokens_authenticate_until_revoked() {
+        let service = Service::new(Memory::new());
+        let req = RegisterUserRequest::new(email(), password("correct horse"));
+        let user = service.register_user(&req).await.unwrap();
+        let scope = TokenScope::full().with_access(TokenAccess::ReadOnly);
+        let req = CreateApiTokenRequest::new(ApiTokenName::new("cron").unwrap(), scope);
+        let created = service.create_api_token(&user.id(), &req).await.unwrap();
+        assert_eq!(created.token().last_used_at(), None);
+
+        let principal = service
+            .authenticate_api_token(created.secret())
+            .await
+            .unwrap();
+        assert_eq!(principal, Principal { user, scope });
+        let tokens = service.list_api_tokens(&principal.user.id()).await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert!(tokens[0].last_used_at().is_some());
+
+        let other = Uuid::new_v4();
+        let result = service
+            .revoke_api_token(&other, &created.token().id())
+            .await;
+        assert!(matches!(result, Err(RevokeApiTokenError::NotFound { .. })));
+        service
+            .revoke_api_token(&principal.user.id(), &created.token().id())
+            .await
+            .unwrap();
+        assert!(matches!(
+            service.authenticate_api_token(created.secret()).await,
+            Err(AuthenticateError::InvalidApiToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_closed_registration_is_rejected() {
+        let service = Service::new(Memory::new()).with_registration_closed();
+        let req = RegisterUserRequest::new(email(), password("correct horse"));
+        assert!(matches!(
+            service.register_user(&req).await,
+            Err(RegisterUserError::Closed)
+        ));
+    }
+}
@@ -1,16 +1,69 @@
+use crate::domain::idempotency::ports::IdempotencyService;
+use crate::domain::metrics::ports::Metrics;
 use crate::domain::readiness::ports::ReadinessService;
 use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::authenticate;
+use crate::inbound::http::handlers::create_api_token::create_api_token;
+use crate::inbound::http::handlers::create_list::create_list;
 use crate::inbound::http::handlers::create_task::create_task;
+use crate::inbound::http::handlers::delete_list::delete_list;
+use crate::inbound::http::handlers::delete_task::delete_task;
+use crate::inbound::http::handlers::empty_trash::empty_trash;
+use crate::inbound::http::handlers::get_current_user::get_current_user;
+use crate::inbound::http::handlers::get_list::get_list;
+use crate::inbound::http::handlers::get_task::get_task;
+use crate::inbound::http::handlers::list_api_tokens::list_api_tokens;
+use crate::inbound::http::handlers::list_lists::list_lists;
+use crate::inbound::http::handlers::list_members::list_members;
+use crate::inbound::http::handlers::list_tags::list_tags;
+use crate::inbound::http::handlers::list_tasks::list_tasks;
+use crate::inbound::http::handlers::list_trash::list_trash;
 use crate::inbound::http::handlers::liveness::liveness;
+use crate::inbound::http::handlers::log_in::log_in;
+use crate::inbound::http::handlers::log_out::log_out;
+use crate::inbound::http::handlers::merge_tags::merge_tags;
+use crate::inbound::http::handlers::metrics::{record_request, render_metrics};
 use crate::inbound::http::handlers::readiness::readiness;
+use crate::inbound::http::handlers::register_user::register_user;
+use crate::inbound::http::handlers::rename_tag::rename_tag;
+use crate::inbound::http::handlers::restore_list::restore_list;
+use crate::inbound::http::handlers::restore_task::restore_task;
+use crate::inbound::http::handlers::revoke_api_token::revoke_api_token;
+use crate::inbound::http::handlers::share_list::share_list;
+use crate::inbound::http::handlers::tag_task::tag_task;
+use crate::inbound::http::handlers::unshare_list::unshare_list;
+use crate::inbound::http::handlers::untag_task::untag_task;
+use crate::inbound::http::handlers::update_list::update_list;
+use crate::inbound::http::handlers::update_task::update_task;
+use crate::inbound::http::idempotency::IdempotencyLayer;
+use crate::inbound::http::openapi::openapi_json;
+#[cfg(feature = "api-docs")]
+use crate::inbound::http::openapi::ApiDoc;
 use anyhow::Context;
-use axum::routing::{get, post};
+use axum::extract::Request;
+use axum::middleware;
+use axum::routing::{delete, get, patch, post};
 use axum::Router;
+use std::future::Future;
 use std::sync::Arc;
 use tokio::net;
+use tower_http::request_id::{
+    MakeRequestUuid, PropagateRequestIdLayer, RequestId, SetRequestIdLayer,
+};
+use tower_http::trace::{DefaultOnResponse, TraceLayer};
+use tracing::{Level, Span};
+#[cfg(feature = "api-docs")]
+use utoipa::OpenApi;
+#[cfg(feature = "api-docs")]
+use utoipa_scalar::{Scalar, Servable};
 
 mod handlers;
-mod responses;
+mod idempotency;
+mod openapi;
+
+#[cfg(test)]
+pub(crate) use handlers::test_utils::MockReminderService;
 
 /// Configure HTTP server
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -21,9 +74,10 @@ pub struct HttpServerConfig<'a> {
 /// The global application start shared between all request
 /// handlers
 #[derive(Debug, Clone)]
-struct AppState<RS: ReminderService, RD: ReadinessService> {
+struct AppState<RS: ReminderService, RD: ReadinessService, US: UserService> {
     reminder_service: Arc<RS>,
     readiness_service: Arc<RD>,
+    user_service: Arc<US>,
 }
 
 /// The application's HTTP server. The underlying HTTP package
@@ -38,17 +92,42 @@ impl HttpServer {
     pub async fn new(
         reminder_service: impl ReminderService,
         readiness_service: impl ReadinessService,
+        user_service: impl UserService,
+        idempotency_service: impl IdempotencyService,
+        metrics: impl Metrics,
         config: HttpServerConfig<'_>,
     ) -> anyhow::Result<Self> {
         // Construct dependencies to inject into handlers
         let state = AppState {
             reminder_service: Arc::new(reminder_service),
             readiness_service: Arc::new(readiness_service),
+            user_service: Arc::new(user_service),
         };
 
+        // Every request is given an `x-request-id`, unless the client sent one, is traced under
+        // it and is recorded in the metrics. Its bearer token, if any, is resolved before it
+        // reaches a handler of the API. Mutating requests with an `Idempotency-Key` are handled
+        // once, and their retries get the same response
+        let metrics = Arc::new(metrics);
         let router = axum::Router::new()
             .nest("/api", api_routes())
-            .with_state(state);
+            .layer(middleware::from_fn_with_state(state.clone(), authenticate))
+            .with_state(state)
+            .route("/metrics", get(render_metrics).with_state(metrics.clone()))
+            .route("/api/openapi.json", get(openapi_json))
+            .layer(IdempotencyLayer::new(idempotency_service));
+        // An interactive reference of the API, rendered from its OpenAPI document
+        #[cfg(feature = "api-docs")]
+        let router = router.merge(Scalar::with_url("/api/docs", ApiDoc::openapi()));
+        let router = router
+            .layer(middleware::from_fn_with_state(metrics, record_request))
+            .layer(PropagateRequestIdLayer::x_request_id())
+            .layer(
+                TraceLayer::new_for_http()
+                    .make_span_with(request_span)
+                    .on_response(DefaultOnResponse::new().level(Level::INFO)),
+            )
+            .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid));
 
         let listener = net::TcpListener::bind(format!("0.0.0.0:{}", config.port))
             .await
@@ -57,9 +136,14 @@ impl HttpServer {
         Ok(Self { router, listener })
     }
 
-    /// Run the HTTP server
-    pub async fn run(self) -> anyhow::Result<()> {
+    /// Run the HTTP server until `shutdown` completes, then wait for in-flight requests to
+    /// finish
+    pub async fn run(
+        self,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> anyhow::Result<()> {
         axum::serve(self.listener, self.router)
+            .with_graceful_shutdown(shutdown)
             .await
             .context("received error from axum server")?;
 
@@ -67,9 +151,72 @@ impl HttpServer {
     }
 }
 
-fn api_routes<RS: ReminderService, RD: ReadinessService>() -> Router<AppState<RS, RD>> {
+/// The span in which a request is traced, recording the ID that [SetRequestIdLayer] gave it.
+fn request_span(req: &Request) -> Span {
+    let request_id = req
+        .extensions()
+        .get::<RequestId>()
+        .and_then(|id| id.header_value().to_str().ok())
+        .unwrap_or_default();
+    tracing::info_span!(
+        "request",
+        method = %req.method(),
+        uri = %req.uri(),
+        request_id,
+    )
+}
+
+fn api_routes<RS: ReminderService, RD: ReadinessService, US: UserService>(
+) -> Router<AppState<RS, RD, US>> {
     Router::new()
-        .route("/tasks", post(create_task::<RS, RD>))
+        .route(
+            "/tasks",
+            post(create_task::<RS, RD, US>).get(list_tasks::<RS, RD, US>),
+        )
+        .route(
+            "/tasks/:id",
+            get(get_task::<RS, RD, US>)
+                .patch(update_task::<RS, RD, US>)
+                .delete(delete_task::<RS, RD, US>),
+        )
+        .route("/tasks/:id/restore", post(restore_task::<RS, RD, US>))
+        .route("/tasks/:id/tags", post(tag_task::<RS, RD, US>))
+        .route("/tasks/:id/tags/:tag", delete(untag_task::<RS, RD, US>))
+        .route(
+            "/lists",
+            post(create_list::<RS, RD, US>).get(list_lists::<RS, RD, US>),
+        )
+        .route(
+            "/lists/:id",
+            get(get_list::<RS, RD, US>)
+                .patch(update_list::<RS, RD, US>)
+                .delete(delete_list::<RS, RD, US>),
+        )
+        .route("/lists/:id/restore", post(restore_list::<RS, RD, US>))
+        .route(
+            "/lists/:id/members",
+            get(list_members::<RS, RD, US>).post(share_list::<RS, RD, US>),
+        )
+        .route(
+            "/lists/:id/members/:user_id",
+            delete(unshare_list::<RS, RD, US>),
+        )
+        .route("/tags", get(list_tags::<RS, RD, US>))
+        .route("/tags/:tag", patch(rename_tag::<RS, RD, US>))
+        .route("/tags/:tag/merge", post(merge_tags::<RS, RD, US>))
+        .route(
+            "/trash",
+            get(list_trash::<RS, RD, US>).delete(empty_trash::<RS, RD, US>),
+        )
+        .route("/users", post(register_user::<RS, RD, US>))
+        .route("/users/me", get(get_current_user))
+        .route("/sessions", post(log_in::<RS, RD, US>))
+        .route("/sessions/current", delete(log_out::<RS, RD, US>))
+        .route(
+            "/tokens",
+            post(create_api_token::<RS, RD, US>).get(list_api_tokens::<RS, RD, US>),
+        )
+        .route("/tokens/:id", delete(revoke_api_token::<RS, RD, US>))
         .route("/liveness", get(liveness))
-        .route("/readiness", get(readiness::<RS, RD>))
+        .route("/readiness", get(readiness::<RS, RD, US>))
 }
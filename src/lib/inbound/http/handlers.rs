@@ -1,4 +1,38 @@
+pub mod auth;
+pub mod create_api_token;
+pub mod create_list;
 pub mod create_task;
+pub mod delete_list;
+pub mod delete_task;
+pub mod empty_trash;
+pub mod etag;
+pub mod extract;
+pub mod get_current_user;
+pub mod get_list;
+pub mod get_task;
+pub mod list_api_tokens;
+pub mod list_lists;
+pub mod list_members;
+pub mod list_tags;
+pub mod list_tasks;
+pub mod list_trash;
 pub mod liveness;
+pub mod log_in;
+pub mod log_out;
+pub mod merge_tags;
+pub mod metrics;
 pub mod readiness;
+pub mod register_user;
+pub mod rename_tag;
+pub mod restore_list;
+pub mod restore_task;
+pub mod revoke_api_token;
+pub mod share_list;
 pub mod shared;
+pub mod tag_task;
+#[cfg(test)]
+pub mod test_utils;
+pub mod unshare_list;
+pub mod untag_task;
+pub mod update_list;
+pub mod update_task;
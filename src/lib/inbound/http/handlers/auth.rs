@@ -0,0 +1,291 @@
+use axum::async_trait;
+use axum::extract::{FromRequestParts, Request, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, Method};
+use axum::middleware::Next;
+use axum::response::Response;
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::models::api_token::{ApiTokenSecret, Principal, TokenScope};
+use crate::domain::users::models::session::{AuthenticateError, SessionToken};
+use crate::domain::users::models::user::User;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::shared::{ApiError, ErrorDetail};
+use crate::inbound::http::AppState;
+
+impl From<AuthenticateError> for ApiError {
+    fn from(e: AuthenticateError) -> Self {
+        match e {
+            AuthenticateError::InvalidToken | AuthenticateError::InvalidApiToken => {
+                Self::Unauthorized(ErrorDetail::new("auth.invalid_token", e.to_string()))
+            }
+            AuthenticateError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// The token in the `Authorization: Bearer <token>` header, if there is one.
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split_once(' '))
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+        .map(|(_, token)| token.trim())
+        .filter(|token| !token.is_empty())
+}
+
+/// Resolve the bearer token in `headers`, a session token or an API token secret, into the
+/// [Principal] it authenticates, or `None` if there is no token.
+///
+/// # Errors
+///
+/// - [ApiError::Unauthorized] if the token is invalid, expired or revoked.
+/// - [ApiError::Forbidden] if the token is read-only and the `method` may change data.
+async fn resolve_principal<US: UserService>(
+    user_service: &US,
+    method: &Method,
+    headers: &HeaderMap,
+) -> Result<Option<Principal>, ApiError> {
+    let Some(token) = bearer_token(headers) else {
+        return Ok(None);
+    };
+    let principal = if ApiTokenSecret::is_api_token(token) {
+        let secret = ApiTokenSecret::from_client(token);
+        user_service.authenticate_api_token(&secret).await?
+    } else {
+        let user = user_service
+            .authenticate(&SessionToken::from_client(token))
+            .await?;
+        Principal {
+            user,
+            scope: TokenScope::full(),
+        }
+    };
+    if !principal.scope.allows_writes() && !method.is_safe() {
+        return Err(ApiError::Forbidden(ErrorDetail::new(
+            "token.read_only",
+            "this token is read-only",
+        )));
+    }
+    Ok(Some(principal))
+}
+
+/// Middleware that authenticates the bearer token of every request, so that handlers can
+/// extract the [CurrentUser] and the [Scope] of the token. Requests without a token pass
+/// through, and are only rejected by handlers that need a [CurrentUser].
+pub async fn authenticate<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let principal =
+        resolve_principal(state.user_service.as_ref(), req.method(), req.headers()).await?;
+    if let Some(principal) = principal {
+        req.extensions_mut().insert(principal);
+    }
+    Ok(next.run(req).await)
+}
+
+/// The [Principal] that [authenticate] resolved for a request.
+fn principal(parts: &Parts) -> Result<&Principal, ApiError> {
+    parts.extensions.get::<Principal>().ok_or_else(|| {
+        ApiError::Unauthorized(ErrorDetail::new(
+            "auth.missing_token",
+            "missing bearer token",
+        ))
+    })
+}
+
+/// The session token sent in the `Authorization: Bearer <token>` header of a request.
+///
+/// Requests without one are rejected with 401 Unauthorized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BearerToken(pub SessionToken);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for BearerToken {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        let token = bearer_token(&parts.headers).ok_or_else(|| {
+            ApiError::Unauthorized(ErrorDetail::new(
+                "auth.missing_token",
+                "missing bearer token",
+            ))
+        })?;
+        Ok(Self(SessionToken::from_client(token)))
+    }
+}
+
+/// The [User] signed in with the bearer token of a request, whose data the request may access.
+///
+/// Requests without a valid token are rejected with 401 Unauthorized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrentUser(pub User);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for CurrentUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(principal(parts)?.user.clone()))
+    }
+}
+
+/// What the bearer token of a request lets the [CurrentUser] do. Handlers check it for tokens
+/// restricted to a single list; read-only tokens are turned away by [authenticate].
+///
+/// Requests without a valid token are rejected with 401 Unauthorized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scope(pub TokenScope);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Scope {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(principal(parts)?.scope))
+    }
+}
+
+impl Scope {
+    fn restricted(list_id: Uuid) -> ApiError {
+        ApiError::Forbidden(ErrorDetail::new(
+            "token.list_restricted",
+            format!("this token is restricted to task list {}", list_id),
+        ))
+    }
+
+    /// Reject tokens restricted to a single list, for requests that reach beyond one list.
+    pub fn require_all_lists(&self) -> Result<(), ApiError> {
+        match self.0.list_id() {
+            Some(list_id) => Err(Self::restricted(list_id)),
+            None => Ok(()),
+        }
+    }
+
+    /// Reject tokens restricted to a list other than the one with `list_id`, or other than the
+    /// inbox if `list_id` is `None`.
+    pub fn require_list(&self, list_id: Option<Uuid>) -> Result<(), ApiError> {
+        match self.0.list_id() {
+            Some(scoped) if !self.0.allows_list(list_id) => Err(Self::restricted(scoped)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reject tokens restricted to a list other than that of the task with `id`. The task is
+    /// only looked up for such tokens.
+    pub async fn require_task<RS: ReminderService>(
+        &self,
+        reminder_service: &RS,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> Result<(), ApiError> {
+        if self.0.list_id().is_none() {
+            return Ok(());
+        }
+        let task = reminder_service.get_task(owner, id).await?;
+        self.require_list(task.list_id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::users::models::api_token::TokenAccess;
+    use crate::inbound::http::handlers::test_utils::{current_user, MockUserService};
+    use axum::http::HeaderValue;
+    use std::sync::{Arc, Mutex};
+
+    fn headers(authorization: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(value) = authorization {
+            headers.insert(AUTHORIZATION, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_session_and_api_tokens_resolve_their_principal() {
+        let CurrentUser(user) = current_user();
+        let scope = TokenScope::full().with_list_id(Uuid::new_v4());
+        let service = MockUserService {
+            authenticate_result: Arc::new(Mutex::new(Ok(user.clone()))),
+            authenticate_api_token_result: Arc::new(Mutex::new(Ok(Principal {
+                user: user.clone(),
+                scope,
+            }))),
+            ..Default::default()
+        };
+
+        let actual = resolve_principal(&service, &Method::GET, &headers(Some("bearer abc"))).await;
+        let expected = Principal {
+            user: user.clone(),
+            scope: TokenScope::full(),
+        };
+        assert_eq!(actual, Ok(Some(expected)));
+        let actual =
+            resolve_principal(&service, &Method::POST, &headers(Some("Bearer modus_abc"))).await;
+        assert_eq!(actual, Ok(Some(Principal { user, scope })));
+
+        for authorization in [None, Some("Basic YWRhOnB3"), Some("Bearer ")] {
+            let actual = resolve_principal(&service, &Method::GET, &headers(authorization)).await;
+            assert_eq!(actual, Ok(None));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_invalid_and_read_only_tokens_are_rejected() {
+        let CurrentUser(user) = current_user();
+        let service = MockUserService {
+            authenticate_result: Arc::new(Mutex::new(Err(AuthenticateError::InvalidToken))),
+            authenticate_api_token_result: Arc::new(Mutex::new(Ok(Principal {
+                user,
+                scope: TokenScope::full().with_access(TokenAccess::ReadOnly),
+            }))),
+            ..Default::default()
+        };
+
+        let actual = resolve_principal(&service, &Method::GET, &headers(Some("Bearer abc"))).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::Unauthorized(ErrorDetail::new(
+                "auth.invalid_token",
+                "invalid or expired session token"
+            )))
+        );
+        let actual =
+            resolve_principal(&service, &Method::PATCH, &headers(Some("Bearer modus_abc"))).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::Forbidden(ErrorDetail::new(
+                "token.read_only",
+                "this token is read-only"
+            )))
+        );
+    }
+
+    #[test]
+    fn test_scope_rejects_other_lists() {
+        let list_id = Uuid::new_v4();
+        let scope = Scope(TokenScope::full().with_list_id(list_id));
+        let restricted = Err(ApiError::Forbidden(ErrorDetail::new(
+            "token.list_restricted",
+            format!("this token is restricted to task list {}", list_id),
+        )));
+        assert_eq!(scope.require_list(Some(list_id)), Ok(()));
+        assert_eq!(scope.require_list(None), restricted);
+        assert_eq!(scope.require_all_lists(), restricted);
+
+        let scope = Scope(TokenScope::full());
+        assert_eq!(scope.require_list(None), Ok(()));
+        assert_eq!(scope.require_all_lists(), Ok(()));
+    }
+}
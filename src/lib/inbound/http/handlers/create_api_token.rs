@@ -0,0 +1,226 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::models::api_token::{
+    ApiTokenName, ApiTokenNameInvalidError, CreateApiTokenError, CreateApiTokenRequest,
+    NewApiToken, TokenAccess, TokenAccessInvalidError, TokenScope,
+};
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::Json;
+use crate::inbound::http::handlers::list_api_tokens::ApiTokenResponseData;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<CreateApiTokenError> for ApiError {
+    fn from(e: CreateApiTokenError) -> Self {
+        match e {
+            CreateApiTokenError::ListNotFound { id } => {
+                Self::UnprocessableEntity(ErrorDetail::new(
+                    "list.not_found",
+                    format!("task list with id {} not found", id),
+                ))
+            }
+            CreateApiTokenError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<ParseCreateApiTokenHttpRequestError> for ApiError {
+    fn from(e: ParseCreateApiTokenHttpRequestError) -> Self {
+        let field = match e {
+            ParseCreateApiTokenHttpRequestError::Name(_) => "name",
+            ParseCreateApiTokenHttpRequestError::Access(_) => "access",
+        };
+        Self::UnprocessableEntity(ErrorDetail::invalid_field(field, e.to_string()))
+    }
+}
+
+/// The response body data field for a new [ApiToken](crate::domain::users::models::api_token::ApiToken).
+/// This is the only time the secret is returned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct CreateApiTokenResponseData {
+    #[serde(flatten)]
+    token: ApiTokenResponseData,
+    secret: String,
+}
+
+impl From<&NewApiToken> for CreateApiTokenResponseData {
+    fn from(created: &NewApiToken) -> Self {
+        Self {
+            token: created.token().into(),
+            secret: created.secret().as_str().to_string(),
+        }
+    }
+}
+
+/// The body of an API token creation request. Tokens may read and write all of the user's data
+/// unless `access` is `read_only` or `list_id` restricts them to a single task list.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct CreateApiTokenHttpRequestBody {
+    name: String,
+    access: Option<String>,
+    list_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Error)]
+enum ParseCreateApiTokenHttpRequestError {
+    #[error(transparent)]
+    Name(#[from] ApiTokenNameInvalidError),
+    #[error(transparent)]
+    Access(#[from] TokenAccessInvalidError),
+}
+
+impl CreateApiTokenHttpRequestBody {
+    /// Converts the HTTP request body into a domain request.
+    fn try_into_domain(self) -> Result<CreateApiTokenRequest, ParseCreateApiTokenHttpRequestError> {
+        let name = ApiTokenName::new(&self.name)?;
+        let mut scope = TokenScope::full();
+        if let Some(access) = self.access.as_deref() {
+            scope = scope.with_access(TokenAccess::parse(access)?);
+        }
+        if let Some(list_id) = self.list_id {
+            scope = scope.with_list_id(list_id);
+        }
+        Ok(CreateApiTokenRequest::new(name, scope))
+    }
+}
+
+/// Create an [ApiToken](crate::domain::users::models::api_token::ApiToken) for scripts and
+/// integrations, which send its secret as `Authorization: Bearer <secret>`.
+///
+/// # Responses
+///
+/// - 201 Created: the token was created.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token of the request is read-only or restricted to a task list.
+/// - 422 Unprocessable Entity: the request is invalid or the task list does not exist.
+#[utoipa::path(
+    post,
+    path = "/api/tokens",
+    tag = "tokens",
+    request_body = CreateApiTokenHttpRequestBody,
+    responses(
+        (
+            status = 201,
+            description = "The token was created",
+            body = ApiResponseBody<CreateApiTokenResponseData>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token of the request is read-only or restricted to a task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 422,
+            description = "The request is invalid or the task list does not exist",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn create_api_token<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Json(body): Json<CreateApiTokenHttpRequestBody>,
+) -> Result<ApiSuccess<CreateApiTokenResponseData>, ApiError> {
+    scope.require_all_lists()?;
+    let domain_req = body.try_into_domain()?;
+    state
+        .user_service
+        .create_api_token(&user.id(), &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref created| ApiSuccess::new(StatusCode::CREATED, created.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inbound::http::handlers::test_utils::{
+        current_user, full_scope, user_app_state, MockUserService,
+    };
+    use chrono::Utc;
+    use std::sync::{Arc, Mutex};
+
+    fn body(access: Option<&str>, list_id: Option<Uuid>) -> Json<CreateApiTokenHttpRequestBody> {
+        Json(CreateApiTokenHttpRequestBody {
+            name: "cron".to_string(),
+            access: access.map(str::to_string),
+            list_id,
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_api_token_success() {
+        let scope = TokenScope::full()
+            .with_access(TokenAccess::ReadOnly)
+            .with_list_id(Uuid::new_v4());
+        let req = CreateApiTokenRequest::new(ApiTokenName::new("cron").unwrap(), scope);
+        let created = NewApiToken::issue(&req, Utc::now());
+        let service = MockUserService {
+            create_api_token_result: Arc::new(Mutex::new(Ok(created.clone()))),
+            ..Default::default()
+        };
+        let state = State(user_app_state(service));
+        let expected = ApiSuccess::new(StatusCode::CREATED, (&created).into());
+        let body = body(Some("read_only"), scope.list_id());
+        let actual = create_api_token(state, current_user(), full_scope(), body).await;
+        assert_eq!(actual, Ok(expected));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_api_token_invalid_access() {
+        let state = State(user_app_state(MockUserService::default()));
+        let body = body(Some("admin"), None);
+        let actual = create_api_token(state, current_user(), full_scope(), body).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::UnprocessableEntity(ErrorDetail::invalid_field(
+                "access",
+                "access \"admin\" must be read_only or read_write"
+            )))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_api_token_unknown_list() {
+        let id = Uuid::new_v4();
+        let service = MockUserService {
+            create_api_token_result: Arc::new(Mutex::new(Err(CreateApiTokenError::ListNotFound {
+                id,
+            }))),
+            ..Default::default()
+        };
+        let state = State(user_app_state(service));
+        let actual =
+            create_api_token(state, current_user(), full_scope(), body(None, Some(id))).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::UnprocessableEntity(ErrorDetail::new(
+                "list.not_found",
+                format!("task list with id {} not found", id)
+            )))
+        );
+    }
+}
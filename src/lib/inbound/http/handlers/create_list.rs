@@ -0,0 +1,198 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Deserialize;
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::list::CreateTaskListError;
+use crate::domain::reminders::models::list::{
+    CreateTaskListRequest, TaskListColour, TaskListColourInvalidError, TaskListTitle,
+    TaskListTitleEmptyError,
+};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::Json;
+use crate::inbound::http::handlers::get_list::TaskListResponseData;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<CreateTaskListError> for ApiError {
+    fn from(e: CreateTaskListError) -> Self {
+        match e {
+            CreateTaskListError::Duplicate { title } => {
+                Self::UnprocessableEntity(ErrorDetail::new(
+                    "list.duplicate_title",
+                    format!("task list with title {} already exists", title),
+                ))
+            }
+            CreateTaskListError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<ParseCreateTaskListHttpRequestError> for ApiError {
+    fn from(e: ParseCreateTaskListHttpRequestError) -> Self {
+        let field = match e {
+            ParseCreateTaskListHttpRequestError::Title(_) => "title",
+            ParseCreateTaskListHttpRequestError::Colour(_) => "colour",
+        };
+        Self::UnprocessableEntity(ErrorDetail::invalid_field(field, e.to_string()))
+    }
+}
+
+/// The body of a [TaskList](crate::domain::reminders::models::list::TaskList) creation request.
+/// `colour` is a `#rrggbb` hex code. Without a `position`, the list is placed last.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct CreateTaskListHttpRequestBody {
+    title: String,
+    colour: Option<String>,
+    position: Option<i32>,
+}
+
+#[derive(Debug, Clone, Error)]
+enum ParseCreateTaskListHttpRequestError {
+    #[error(transparent)]
+    Title(#[from] TaskListTitleEmptyError),
+    #[error(transparent)]
+    Colour(#[from] TaskListColourInvalidError),
+}
+
+impl CreateTaskListHttpRequestBody {
+    /// Converts the HTTP request body into a domain request.
+    fn try_into_domain(self) -> Result<CreateTaskListRequest, ParseCreateTaskListHttpRequestError> {
+        let mut req = CreateTaskListRequest::new(TaskListTitle::new(&self.title)?);
+        if let Some(colour) = self.colour.as_deref() {
+            req = req.with_colour(TaskListColour::new(colour)?);
+        }
+        if let Some(position) = self.position {
+            req = req.with_position(position);
+        }
+        Ok(req)
+    }
+}
+
+/// Create a new [TaskList](crate::domain::reminders::models::list::TaskList).
+///
+/// # Responses
+///
+/// - 201 Created: the list was successfully created.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is read-only or restricted to another task list.
+/// - 422 Unprocessable Entity: the request is invalid or an unarchived list with the same title
+///   already exists.
+#[utoipa::path(
+    post,
+    path = "/api/lists",
+    tag = "lists",
+    request_body = CreateTaskListHttpRequestBody,
+    responses(
+        (
+            status = 201,
+            description = "The list was successfully created",
+            body = ApiResponseBody<TaskListResponseData>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is read-only or restricted to another task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 422,
+            description = "The request is invalid or an unarchived list with the same title \
+                           already exists",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn create_list<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Json(body): Json<CreateTaskListHttpRequestBody>,
+) -> Result<ApiSuccess<TaskListResponseData>, ApiError> {
+    scope.require_all_lists()?;
+    let domain_req = body.try_into_domain()?;
+    state
+        .reminder_service
+        .create_list(&user.id(), &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref list| ApiSuccess::new(StatusCode::CREATED, list.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::reminders::models::list::TaskList;
+    use crate::domain::reminders::models::member::ListRole;
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use chrono::Utc;
+    use std::sync::{Arc, Mutex};
+    use uuid::Uuid;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_list_success() {
+        let list = TaskList {
+            id: Uuid::new_v4(),
+            title: TaskListTitle::new("Groceries").unwrap(),
+            colour: Some(TaskListColour::new("#1e90ff").unwrap()),
+            position: 0,
+            archived: false,
+            created_at: Utc::now(),
+            owner_id: Uuid::new_v4(),
+            role: ListRole::Owner,
+        };
+        let service = MockReminderService {
+            create_list_result: Arc::new(Mutex::new(Ok(list.clone()))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let body = Json(CreateTaskListHttpRequestBody {
+            title: "Groceries".to_string(),
+            colour: Some("#1E90FF".to_string()),
+            position: None,
+        });
+        let expected = ApiSuccess::new(StatusCode::CREATED, (&list).into());
+        let actual = create_list(state, current_user(), full_scope(), body).await;
+        assert_eq!(actual, Ok(expected));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_list_invalid_colour() {
+        let state = State(app_state(
+            MockReminderService::default(),
+            MockReadinessService::default(),
+        ));
+        let body = Json(CreateTaskListHttpRequestBody {
+            title: "Groceries".to_string(),
+            colour: Some("blue".to_string()),
+            position: None,
+        });
+        let actual = create_list(state, current_user(), full_scope(), body).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::UnprocessableEntity(ErrorDetail::invalid_field(
+                "colour",
+                "colour \"blue\" must be a hex code such as #1e90ff"
+            )))
+        );
+    }
+}
@@ -1,25 +1,57 @@
 use axum::extract::State;
-use axum::{http::StatusCode, Json};
-use serde::{Deserialize, Serialize};
+use axum::http::StatusCode;
+use serde::Deserialize;
 use thiserror::Error;
+use utoipa::ToSchema;
+use uuid::Uuid;
 
 use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::recurrence::{Recurrence, RecurrenceError};
+use crate::domain::reminders::models::tag::{Tag, TagInvalidError, TaskTags, TaskTagsTooManyError};
 use crate::domain::reminders::models::task::CreateTaskError;
 use crate::domain::reminders::models::task::{
-    CreateTaskRequest, Task, TaskTitle, TaskTitleEmptyError,
+    CreateTaskRequest, DueAt, DueAtInvalidError, Priority, RemindAt, RemindAtTooManyError,
+    ReminderOffset, ReminderOffsetError, TaskFieldError, TaskNotes, TaskTitle,
 };
 use crate::domain::reminders::ports::ReminderService;
-use crate::inbound::http::handlers::shared::{ApiError, ApiSuccess};
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::Json;
+use crate::inbound::http::handlers::get_task::TaskResponseData;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
 use crate::inbound::http::AppState;
 
 impl From<CreateTaskError> for ApiError {
     fn from(e: CreateTaskError) -> Self {
         match e {
-            CreateTaskError::Duplicate { title } => {
-                Self::UnprocessableEntity(format!("task with title {} already exists", title))
+            CreateTaskError::Duplicate { title } => Self::UnprocessableEntity(ErrorDetail::new(
+                "task.duplicate_title",
+                format!("task with title {} already exists", title),
+            )),
+            CreateTaskError::ListNotFound { id } => Self::UnprocessableEntity(ErrorDetail::new(
+                "list.not_found",
+                format!("task list with id {} not found", id),
+            )),
+            CreateTaskError::ParentNotFound { id } => Self::UnprocessableEntity(ErrorDetail::new(
+                "task.parent_not_found",
+                format!("parent task with id {} not found", id),
+            )),
+            e @ CreateTaskError::TooDeep => {
+                Self::UnprocessableEntity(ErrorDetail::new("task.too_deep", e.to_string()))
+            }
+            e @ CreateTaskError::Forbidden { .. } => {
+                Self::Forbidden(ErrorDetail::new("list.role_forbidden", e.to_string()))
+            }
+            e @ CreateTaskError::AssigneeNotMember { .. } => Self::UnprocessableEntity(
+                ErrorDetail::new("task.assignee_not_member", e.to_string()),
+            ),
+            e @ CreateTaskError::RemindAtWithoutDueAt => {
+                Self::UnprocessableEntity(ErrorDetail::invalid_field("remind_at", e.to_string()))
             }
-            CreateTaskError::Unknown(_cause) => {
-                // tracing::error!("{:?}\n{}", cause, cause.backtrace());
+            CreateTaskError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
                 Self::InternalServerError("Internal server error".to_string())
             }
         }
@@ -28,68 +60,182 @@ impl From<CreateTaskError> for ApiError {
 
 impl From<ParseCreateTaskHttpRequestError> for ApiError {
     fn from(e: ParseCreateTaskHttpRequestError) -> Self {
-        let message = match e {
-            ParseCreateTaskHttpRequestError::Title(_) => "task title cannot be empty".to_string(),
+        let (field, message) = match e {
+            ParseCreateTaskHttpRequestError::Field(e) => (task_field_name(&e), e.to_string()),
+            ParseCreateTaskHttpRequestError::DueAt(e) => ("due_at", e.to_string()),
+            ParseCreateTaskHttpRequestError::ReminderOffset(e) => ("remind_at", e.to_string()),
+            ParseCreateTaskHttpRequestError::RemindAt(e) => ("remind_at", e.to_string()),
+            ParseCreateTaskHttpRequestError::Recurrence(e) => {
+                ("recurrence", format!("invalid recurrence rule: {}", e))
+            }
+            ParseCreateTaskHttpRequestError::Tag(e) => ("tags", e.to_string()),
+            ParseCreateTaskHttpRequestError::Tags(e) => ("tags", e.to_string()),
         };
 
-        Self::UnprocessableEntity(message)
+        Self::UnprocessableEntity(ErrorDetail::invalid_field(field, message))
     }
 }
 
-/// The body of a [Task] creation request
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
-pub struct CreateTaskRequestBody {
-    title: String,
-}
-
-/// The response body data field for successful [Task] creation.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
-pub struct CreateTaskResponseData {
-    id: String,
-}
-
-impl From<&Task> for CreateTaskResponseData {
-    fn from(task: &Task) -> Self {
-        Self {
-            id: task.id().to_string(),
-        }
+/// The name of the request body field that a [TaskFieldError] is about.
+pub fn task_field_name(e: &TaskFieldError) -> &'static str {
+    match e {
+        TaskFieldError::TitleEmpty(_) | TaskFieldError::TitleTooLong => "title",
+        TaskFieldError::PriorityInvalid(_) => "priority",
+        TaskFieldError::NotesTooLong => "notes",
     }
 }
 
-/// The body of a [Task] creation request
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+/// The body of a task creation request. `due_at` is an RFC 3339 timestamp, `remind_at` a list
+/// of offsets before it, such as `15m`, `2h`, `1d` or `1w`, and `recurrence` an RFC 5545 RRULE.
+/// Without a `list_id`, the task is created in the inbox, or in the list of its parent for a
+/// subtask. A `parent_id` creates the task as a subtask, after its siblings unless a `position`
+/// is given. `priority` is one of `none` (the default), `low`, `medium`, `high` or `urgent`,
+/// `notes` are Markdown, and `tags` are normalised to lowercase. `assignee_id` assigns the task to
+/// the owner or a member of its list, who then receives its reminders.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
 pub struct CreateTaskHttpRequestBody {
     title: String,
+    priority: Option<String>,
+    notes: Option<String>,
+    due_at: Option<String>,
+    #[serde(default)]
+    remind_at: Vec<String>,
+    recurrence: Option<String>,
+    list_id: Option<Uuid>,
+    parent_id: Option<Uuid>,
+    position: Option<i32>,
+    #[serde(default)]
+    tags: Vec<String>,
+    assignee_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Error)]
 enum ParseCreateTaskHttpRequestError {
     #[error(transparent)]
-    Title(#[from] TaskTitleEmptyError),
+    Field(#[from] TaskFieldError),
+    #[error(transparent)]
+    DueAt(#[from] DueAtInvalidError),
+    #[error(transparent)]
+    ReminderOffset(#[from] ReminderOffsetError),
+    #[error(transparent)]
+    RemindAt(#[from] RemindAtTooManyError),
+    #[error(transparent)]
+    Recurrence(#[from] RecurrenceError),
+    #[error(transparent)]
+    Tag(#[from] TagInvalidError),
+    #[error(transparent)]
+    Tags(#[from] TaskTagsTooManyError),
 }
 
 impl CreateTaskHttpRequestBody {
     /// Converts the HTTP request body into a domain request.
     fn try_into_domain(self) -> Result<CreateTaskRequest, ParseCreateTaskHttpRequestError> {
         let title = TaskTitle::new(&self.title)?;
-        Ok(CreateTaskRequest::new(title))
+        let offsets = self
+            .remind_at
+            .iter()
+            .map(|raw| ReminderOffset::parse(raw))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut req = CreateTaskRequest::new(title).with_remind_at(RemindAt::new(offsets)?);
+        if let Some(priority) = self.priority.as_deref() {
+            req = req.with_priority(Priority::parse(priority)?);
+        }
+        if let Some(notes) = self.notes.as_deref() {
+            req = req.with_notes(TaskNotes::new(notes)?);
+        }
+        if let Some(due_at) = self.due_at.as_deref() {
+            req = req.with_due_at(DueAt::parse(due_at)?);
+        }
+        if let Some(recurrence) = self.recurrence.as_deref() {
+            req = req.with_recurrence(Recurrence::parse(recurrence)?);
+        }
+        if let Some(list_id) = self.list_id {
+            req = req.with_list_id(list_id);
+        }
+        if let Some(parent_id) = self.parent_id {
+            req = req.with_parent_id(parent_id);
+        }
+        if let Some(position) = self.position {
+            req = req.with_position(position);
+        }
+        if let Some(assignee_id) = self.assignee_id {
+            req = req.with_assignee_id(assignee_id);
+        }
+        let tags = self
+            .tags
+            .iter()
+            .map(|raw| Tag::new(raw))
+            .collect::<Result<Vec<_>, _>>()?;
+        req = req.with_tags(TaskTags::new(tags)?);
+        Ok(req)
     }
 }
 
-/// Create a new [Task].
+/// Create a new [Task](crate::domain::reminders::models::task::Task).
 ///
 /// # Responses
 ///
-/// - 201 Created: the [Task] was sucessfully created.
-/// - 422 Unprocessable Entity: A [Task] with the same title already exists.
-pub async fn create_task<RS: ReminderService, RD: ReadinessService>(
-    State(state): State<AppState<RS, RD>>,
+/// - 201 Created: the task was sucessfully created.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is read-only or restricted to another task list, or the user's
+///   role in the task list does not allow the change.
+/// - 422 Unprocessable Entity: the request is invalid, a task with the same title already
+///   exists, the task list or parent task does not exist, subtasks would be nested too deeply,
+///   or the assignee is not a member of the task list.
+#[utoipa::path(
+    post,
+    path = "/api/tasks",
+    tag = "tasks",
+    request_body = CreateTaskHttpRequestBody,
+    responses(
+        (
+            status = 201,
+            description = "The task was sucessfully created",
+            body = ApiResponseBody<TaskResponseData>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is read-only or restricted to another task list, or the \
+                           user's role in the task list does not allow the change",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 422,
+            description = "The request is invalid, a task with the same title already exists, the \
+                           task list or parent task does not exist, subtasks would be nested too \
+                           deeply, or the assignee is not a member of the task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn create_task<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
     Json(body): Json<CreateTaskHttpRequestBody>,
-) -> Result<ApiSuccess<CreateTaskResponseData>, ApiError> {
+) -> Result<ApiSuccess<TaskResponseData>, ApiError> {
     let domain_req = body.try_into_domain()?;
+    match (domain_req.list_id(), domain_req.parent_id()) {
+        // Subtasks without a list of their own go in the list of their parent
+        (None, Some(parent_id)) => {
+            scope
+                .require_task(state.reminder_service.as_ref(), &user.id(), &parent_id)
+                .await?
+        }
+        (list_id, _) => scope.require_list(list_id)?,
+    }
     state
         .reminder_service
-        .create_task(&domain_req)
+        .create_task(&user.id(), &domain_req)
         .await
         .map_err(ApiError::from)
         .map(|ref task| ApiSuccess::new(StatusCode::CREATED, task.into()))
@@ -98,70 +244,40 @@ pub async fn create_task<RS: ReminderService, RD: ReadinessService>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::readiness::models::ready::ReadinessError;
-    use crate::domain::reminders::models::task::CreateTaskError;
-    use crate::domain::reminders::models::task::{CreateTaskRequest, Task};
-    use crate::domain::reminders::ports::ReminderService;
-    use anyhow::anyhow;
-    use std::mem;
-    use std::sync::Arc;
-    use uuid::Uuid;
-
-    #[derive(Clone)]
-    struct MockReminderService {
-        create_task_result: Arc<std::sync::Mutex<Result<Task, CreateTaskError>>>,
-    }
-
-    impl ReminderService for MockReminderService {
-        async fn create_task(&self, _: &CreateTaskRequest) -> Result<Task, CreateTaskError> {
-            let mut guard = self.create_task_result.lock();
-            let mut result = Err(CreateTaskError::Unknown(anyhow!("substitute error")));
-            mem::swap(guard.as_deref_mut().unwrap(), &mut result);
-            result
-        }
-    }
-
-    #[derive(Clone)]
-    struct MockReadinessService {
-        is_ready_result: Arc<std::sync::Mutex<Result<(), ReadinessError>>>,
-    }
-
-    impl ReadinessService for MockReadinessService {
-        async fn is_ready(&self) -> Result<(), ReadinessError> {
-            let mut guard = self.is_ready_result.lock();
-            let mut result = Err(ReadinessError::DatabaseNotReady);
-            mem::swap(guard.as_deref_mut().unwrap(), &mut result);
-            result
-        }
-    }
+    use crate::domain::reminders::models::task::Task;
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use std::sync::{Arc, Mutex};
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_create_task_success() {
         let task_title = TaskTitle::new("Clean apartment").unwrap();
-        let task_id = Uuid::new_v4();
-        let service = MockReminderService {
-            create_task_result: Arc::new(std::sync::Mutex::new(Ok(Task::new(
-                task_id,
-                task_title.clone(),
-            )))),
+        let task = Task {
+            due_at: Some(DueAt::parse("2025-01-01T09:00:00+01:00").unwrap()),
+            remind_at: RemindAt::new(vec![ReminderOffset::parse("15m").unwrap()]).unwrap(),
+            ..Task::new(Uuid::new_v4(), task_title.clone())
         };
-        let readiness_service = MockReadinessService {
-            is_ready_result: Arc::new(std::sync::Mutex::new(Ok(()))),
+        let service = MockReminderService {
+            create_task_result: Arc::new(Mutex::new(Ok(task.clone()))),
+            ..Default::default()
         };
-        let state = axum::extract::State(AppState {
-            reminder_service: Arc::new(service),
-            readiness_service: Arc::new(readiness_service),
-        });
-        let body = axum::extract::Json(CreateTaskHttpRequestBody {
+        let state = State(app_state(service, MockReadinessService::default()));
+        let body = Json(CreateTaskHttpRequestBody {
             title: task_title.to_string(),
+            priority: None,
+            notes: None,
+            due_at: Some("2025-01-01T09:00:00+01:00".to_string()),
+            remind_at: vec!["15m".to_string()],
+            recurrence: Some("FREQ=MONTHLY".to_string()),
+            list_id: None,
+            parent_id: None,
+            position: None,
+            tags: vec![],
+            assignee_id: None,
         });
-        let expected = ApiSuccess::new(
-            StatusCode::CREATED,
-            CreateTaskResponseData {
-                id: task_id.to_string(),
-            },
-        );
-        let actual = create_task(state, body).await;
+        let expected = ApiSuccess::new(StatusCode::CREATED, TaskResponseData::from(&task));
+        let actual = create_task(state, current_user(), full_scope(), body).await;
         assert!(
             actual.is_ok(),
             "expected create_task to succeed, but got {:?}",
@@ -174,4 +290,123 @@ mod tests {
             expected, actual
         );
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_task_invalid_due_at() {
+        let state = State(app_state(
+            MockReminderService::default(),
+            MockReadinessService::default(),
+        ));
+        let body = Json(CreateTaskHttpRequestBody {
+            title: "Clean apartment".to_string(),
+            priority: None,
+            notes: None,
+            due_at: Some("2025-01-01T09:00:00".to_string()),
+            remind_at: vec![],
+            recurrence: None,
+            list_id: None,
+            parent_id: None,
+            position: None,
+            tags: vec![],
+            assignee_id: None,
+        });
+        let actual = create_task(state, current_user(), full_scope(), body).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::UnprocessableEntity(ErrorDetail::invalid_field(
+                "due_at",
+                "due date \"2025-01-01T09:00:00\" must be an RFC 3339 timestamp with a timezone \
+                 offset"
+            )))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_task_reminders_without_due_at() {
+        let service = MockReminderService {
+            create_task_result: Arc::new(Mutex::new(Err(CreateTaskError::RemindAtWithoutDueAt))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let body = Json(CreateTaskHttpRequestBody {
+            title: "Call mom".to_string(),
+            priority: None,
+            notes: None,
+            due_at: None,
+            remind_at: vec!["15m".to_string()],
+            recurrence: None,
+            list_id: None,
+            parent_id: None,
+            position: None,
+            tags: vec![],
+            assignee_id: None,
+        });
+        let actual = create_task(state, current_user(), full_scope(), body).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::UnprocessableEntity(ErrorDetail::invalid_field(
+                "remind_at",
+                "reminders cannot be set on a task without a due date"
+            )))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_task_invalid_recurrence() {
+        let state = State(app_state(
+            MockReminderService::default(),
+            MockReadinessService::default(),
+        ));
+        let body = Json(CreateTaskHttpRequestBody {
+            title: "Pay rent".to_string(),
+            priority: None,
+            notes: None,
+            due_at: None,
+            remind_at: vec![],
+            recurrence: Some("FREQ=MONTHLY;BYDAY=MO".to_string()),
+            list_id: None,
+            parent_id: None,
+            position: None,
+            tags: vec![],
+            assignee_id: None,
+        });
+        let actual = create_task(state, current_user(), full_scope(), body).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::UnprocessableEntity(ErrorDetail::invalid_field(
+                "recurrence",
+                "invalid recurrence rule: recurrence BYDAY is only supported with FREQ=DAILY or \
+                 FREQ=WEEKLY, not FREQ=MONTHLY"
+            )))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_task_invalid_priority() {
+        let state = State(app_state(
+            MockReminderService::default(),
+            MockReadinessService::default(),
+        ));
+        let body = Json(CreateTaskHttpRequestBody {
+            title: "File taxes".to_string(),
+            priority: Some("critical".to_string()),
+            notes: Some("Receipts are in the **blue** folder".to_string()),
+            due_at: None,
+            remind_at: vec![],
+            recurrence: None,
+            list_id: None,
+            parent_id: None,
+            position: None,
+            tags: vec![],
+            assignee_id: None,
+        });
+        let actual = create_task(state, current_user(), full_scope(), body).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::UnprocessableEntity(ErrorDetail::invalid_field(
+                "priority",
+                "priority \"critical\" must be one of none, low, medium, high or urgent"
+            )))
+        );
+    }
 }
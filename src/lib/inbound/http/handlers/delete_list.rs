@@ -0,0 +1,190 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::list::{DeleteTaskListError, DeleteTaskListMode};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::{Path, Query};
+use crate::inbound::http::handlers::shared::{ApiError, ErrorDetail, ProblemDetails};
+use crate::inbound::http::AppState;
+
+impl From<DeleteTaskListError> for ApiError {
+    fn from(e: DeleteTaskListError) -> Self {
+        match e {
+            DeleteTaskListError::NotFound { id } => Self::NotFound(ErrorDetail::new(
+                "list.not_found",
+                format!("task list with id {} not found", id),
+            )),
+            DeleteTaskListError::NotEmpty { id, tasks } => Self::Conflict(ErrorDetail::new(
+                "list.not_empty",
+                format!(
+                "task list with id {} still has {} tasks; delete them with tasks=delete or keep \
+                 them with tasks=move_to_inbox",
+                    id, tasks
+                ),
+            )),
+            e @ DeleteTaskListError::Forbidden { .. } => {
+                Self::Forbidden(ErrorDetail::new("list.role_forbidden", e.to_string()))
+            }
+            DeleteTaskListError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// What to do with the tasks of a deleted list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+enum DeleteTaskListTasks {
+    Delete,
+    MoveToInbox,
+}
+
+/// The query parameters of a task list deletion. Without `tasks`, only an empty list is deleted;
+/// `tasks=delete` deletes its tasks too and `tasks=move_to_inbox` keeps them in the inbox.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeleteTaskListHttpQuery {
+    tasks: Option<DeleteTaskListTasks>,
+}
+
+impl DeleteTaskListHttpQuery {
+    fn mode(&self) -> DeleteTaskListMode {
+        match self.tasks {
+            None => DeleteTaskListMode::OnlyIfEmpty,
+            Some(DeleteTaskListTasks::Delete) => DeleteTaskListMode::Cascade,
+            Some(DeleteTaskListTasks::MoveToInbox) => DeleteTaskListMode::MoveToInbox,
+        }
+    }
+}
+
+/// Move a [TaskList](crate::domain::reminders::models::list::TaskList) to the trash, dealing
+/// with its tasks as the [query](DeleteTaskListHttpQuery) asks.
+///
+/// # Responses
+///
+/// - 204 No Content: the list was moved to the trash.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is read-only or restricted to another task list, or the user's
+///   role in the task list does not allow the change.
+/// - 404 Not Found: no list with the given id exists.
+/// - 409 Conflict: the list still has tasks and `tasks` was not given.
+#[utoipa::path(
+    delete,
+    path = "/api/lists/{id}",
+    tag = "lists",
+    params(
+        ("id" = Uuid, Path, description = "The id of the task list"),
+        DeleteTaskListHttpQuery,
+    ),
+    responses(
+        (status = 204, description = "The list was moved to the trash"),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is read-only or restricted to another task list, or the \
+                           user's role in the task list does not allow the change",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 404,
+            description = "No list with the given id exists",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 409,
+            description = "The list still has tasks and `tasks` was not given",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn delete_list<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path(id): Path<Uuid>,
+    Query(query): Query<DeleteTaskListHttpQuery>,
+) -> Result<StatusCode, ApiError> {
+    scope.require_all_lists()?;
+    state
+        .reminder_service
+        .delete_list(&user.id(), &id, query.mode())
+        .await
+        .map_err(ApiError::from)
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_delete_list_not_empty() {
+        let id = Uuid::new_v4();
+        let service = MockReminderService {
+            delete_list_result: Arc::new(Mutex::new(Err(DeleteTaskListError::NotEmpty {
+                id,
+                tasks: 3,
+            }))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let actual = delete_list(
+            state,
+            current_user(),
+            full_scope(),
+            Path(id),
+            Query(DeleteTaskListHttpQuery::default()),
+        )
+        .await;
+        assert_eq!(
+            actual,
+            Err(ApiError::Conflict(ErrorDetail::new(
+                "list.not_empty",
+                format!(
+                "task list with id {} still has 3 tasks; delete them with tasks=delete or keep \
+                 them with tasks=move_to_inbox",
+                id
+            )
+            )))
+        );
+    }
+
+    #[test]
+    fn test_delete_list_query_modes() {
+        let mode = |uri: &str| {
+            axum::extract::Query::<DeleteTaskListHttpQuery>::try_from_uri(&uri.parse().unwrap())
+                .map(|axum::extract::Query(query)| query.mode())
+        };
+        assert_eq!(mode("/lists/1").unwrap(), DeleteTaskListMode::OnlyIfEmpty);
+        assert_eq!(
+            mode("/lists/1?tasks=delete").unwrap(),
+            DeleteTaskListMode::Cascade
+        );
+        assert_eq!(
+            mode("/lists/1?tasks=move_to_inbox").unwrap(),
+            DeleteTaskListMode::MoveToInbox
+        );
+        assert!(mode("/lists/1?tasks=keep").is_err());
+    }
+}
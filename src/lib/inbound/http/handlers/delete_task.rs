@@ -0,0 +1,194 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::task::DeleteTaskError;
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::etag::IfMatch;
+use crate::inbound::http::handlers::extract::Path;
+use crate::inbound::http::handlers::shared::{ApiError, ErrorDetail, ProblemDetails};
+use crate::inbound::http::AppState;
+
+impl From<DeleteTaskError> for ApiError {
+    fn from(e: DeleteTaskError) -> Self {
+        match e {
+            DeleteTaskError::NotFound { id } => Self::NotFound(ErrorDetail::new(
+                "task.not_found",
+                format!("task with id {} not found", id),
+            )),
+            e @ DeleteTaskError::Forbidden { .. } => {
+                Self::Forbidden(ErrorDetail::new("list.role_forbidden", e.to_string()))
+            }
+            e @ DeleteTaskError::Conflict { .. } => {
+                Self::PreconditionFailed(ErrorDetail::new("task.version_conflict", e.to_string()))
+            }
+            DeleteTaskError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Move a [Task](crate::domain::reminders::models::task::Task) and its subtasks to the trash, if
+/// it is still at the version whose `ETag` is sent in `If-Match`.
+///
+/// # Responses
+///
+/// - 204 No Content: the task was moved to the trash.
+/// - 400 Bad Request: the `If-Match` header is not `*` or an `ETag`.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is read-only or restricted to another task list, or the user's
+///   role in the task list does not allow the change.
+/// - 404 Not Found: no task with the given id exists.
+/// - 412 Precondition Failed: the task was changed since the version in `If-Match`.
+/// - 428 Precondition Required: the request has no `If-Match` header.
+#[utoipa::path(
+    delete,
+    path = "/api/tasks/{id}",
+    tag = "tasks",
+    params(
+        ("id" = Uuid, Path, description = "The id of the task"),
+        (
+            "If-Match" = String,
+            Header,
+            description = "The ETag of the task the deletion is based on, or * to delete any \
+                           version",
+        ),
+    ),
+    responses(
+        (status = 204, description = "The task was moved to the trash"),
+        (
+            status = 400,
+            description = "The If-Match header is not * or an ETag",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is read-only or restricted to another task list, or the \
+                           user's role in the task list does not allow the change",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 404,
+            description = "No task with the given id exists",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 412,
+            description = "The task was changed since the version in If-Match",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 428,
+            description = "The request has no If-Match header",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn delete_task<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path(id): Path<Uuid>,
+    IfMatch(version): IfMatch,
+) -> Result<StatusCode, ApiError> {
+    scope
+        .require_task(state.reminder_service.as_ref(), &user.id(), &id)
+        .await?;
+    state
+        .reminder_service
+        .delete_task(&user.id(), &id, version)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_delete_task_success() {
+        let service = MockReminderService {
+            delete_task_result: Arc::new(Mutex::new(Ok(()))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let actual = delete_task(
+            state,
+            current_user(),
+            full_scope(),
+            Path(Uuid::new_v4()),
+            IfMatch(None),
+        )
+        .await;
+        assert_eq!(actual, Ok(StatusCode::NO_CONTENT));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_delete_task_not_found() {
+        let id = Uuid::new_v4();
+        let service = MockReminderService {
+            delete_task_result: Arc::new(Mutex::new(Err(DeleteTaskError::NotFound { id }))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let actual =
+            delete_task(state, current_user(), full_scope(), Path(id), IfMatch(None)).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::NotFound(ErrorDetail::new(
+                "task.not_found",
+                format!("task with id {} not found", id)
+            )))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_delete_task_conflict() {
+        let id = Uuid::new_v4();
+        let service = MockReminderService {
+            delete_task_result: Arc::new(Mutex::new(Err(DeleteTaskError::Conflict {
+                id,
+                version: 2,
+            }))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let actual = delete_task(
+            state,
+            current_user(),
+            full_scope(),
+            Path(id),
+            IfMatch(Some(2)),
+        )
+        .await;
+        assert_eq!(
+            actual,
+            Err(ApiError::PreconditionFailed(ErrorDetail::new(
+                "task.version_conflict",
+                format!("task with id {} was changed since version 2", id)
+            )))
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::trash::PurgeTrashError;
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::shared::{ApiError, ProblemDetails};
+use crate::inbound::http::AppState;
+
+impl From<PurgeTrashError> for ApiError {
+    fn from(e: PurgeTrashError) -> Self {
+        match e {
+            PurgeTrashError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Permanently delete the tasks and lists in the trash that the user owns.
+///
+/// # Responses
+///
+/// - 204 No Content: the trash was emptied.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is read-only or restricted to a task list.
+#[utoipa::path(
+    delete,
+    path = "/api/trash",
+    tag = "trash",
+    responses(
+        (status = 204, description = "The trash was emptied"),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is read-only or restricted to a task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn empty_trash<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+) -> Result<StatusCode, ApiError> {
+    scope.require_all_lists()?;
+    state
+        .reminder_service
+        .empty_trash(&user.id())
+        .await
+        .map_err(ApiError::from)
+        .map(|_| StatusCode::NO_CONTENT)
+}
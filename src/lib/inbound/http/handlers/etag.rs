@@ -0,0 +1,189 @@
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::header::{ETAG, IF_MATCH, IF_NONE_MATCH};
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+use crate::domain::reminders::models::task::{Task, TaskTree};
+use crate::inbound::http::handlers::shared::{ApiError, ErrorDetail};
+
+/// The entity tag of a [Task], `"<version>"`, which changes whenever the task does.
+///
+/// The tag of a [TaskTree] is followed by a digest of the versions of its subtasks, as in
+/// `"<version>.<digest>"`, so that it also changes when they do. Either form can be sent back in
+/// `If-Match`, which only compares the version of the task itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ETag(String);
+
+impl ETag {
+    pub fn task(task: &Task) -> Self {
+        Self(format!("\"{}\"", task.version()))
+    }
+
+    pub fn tree(tree: &TaskTree) -> Self {
+        if tree.subtasks().is_empty() {
+            return Self::task(tree.task());
+        }
+        let mut hasher = Sha256::new();
+        let mut pending = tree.subtasks().iter().collect::<Vec<_>>();
+        while let Some(subtree) = pending.pop() {
+            hasher.update(subtree.task().id().as_bytes());
+            hasher.update(subtree.task().version().to_be_bytes());
+            pending.extend(subtree.subtasks());
+        }
+        let digest = URL_SAFE_NO_PAD.encode(&hasher.finalize()[..12]);
+        Self(format!("\"{}.{}\"", tree.task().version(), digest))
+    }
+
+    /// The version of the task that the entity tag `raw` was made from, if any.
+    fn version(raw: &str) -> Option<i64> {
+        let opaque = raw.trim().strip_prefix('"')?.strip_suffix('"')?;
+        let version = opaque
+            .split_once('.')
+            .map_or(opaque, |(version, _)| version);
+        version.parse().ok()
+    }
+
+    /// Whether the `If-None-Match` header in `headers` lists this tag, comparing weakly, so that
+    /// the client's copy is still current.
+    pub fn is_current(&self, headers: &HeaderMap) -> bool {
+        headers
+            .get_all(IF_NONE_MATCH)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|tag| tag.trim())
+            .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == self.0)
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        HeaderValue::from_str(&self.0).expect("entity tags are visible ASCII")
+    }
+}
+
+/// A response that carries the [ETag] of the task it represents.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tagged<T> {
+    /// The task in the body of the response.
+    Body(ETag, T),
+    /// 304 Not Modified, when the client's copy of the task is current.
+    NotModified(ETag),
+}
+
+impl<T: IntoResponse> IntoResponse for Tagged<T> {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Body(etag, body) => ([(ETAG, etag.header_value())], body).into_response(),
+            Self::NotModified(etag) => {
+                (StatusCode::NOT_MODIFIED, [(ETAG, etag.header_value())]).into_response()
+            }
+        }
+    }
+}
+
+/// The version of a task that a change to it was based on, from the `If-Match` header that
+/// every change must carry, or `None` for `If-Match: *` to change the task whatever its version.
+///
+/// Requests without the header are rejected with 428 Precondition Required, and those whose
+/// header is not `*` or a single [ETag] with 400 Bad Request, as [ApiError]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IfMatch(pub Option<i64>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for IfMatch
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(IF_MATCH) else {
+            return Err(ApiError::PreconditionRequired(ErrorDetail::new(
+                "request.missing_if_match",
+                "changes to a task must send the ETag of the task in If-Match",
+            )));
+        };
+        let raw = value.to_str().unwrap_or_default();
+        if raw.trim() == "*" {
+            return Ok(Self(None));
+        }
+        ETag::version(raw)
+            .map(|version| Self(Some(version)))
+            .ok_or_else(|| {
+                ApiError::BadRequest(ErrorDetail::new(
+                    "request.invalid_if_match",
+                    "If-Match must be * or the ETag of the task",
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::reminders::models::task::TaskTitle;
+    use axum::http::Request;
+    use uuid::Uuid;
+
+    fn task(version: i64) -> Task {
+        let mut task = Task::new(Uuid::new_v4(), TaskTitle::new("Pack").unwrap());
+        task.version = version;
+        task
+    }
+
+    async fn if_match(value: Option<&str>) -> Result<IfMatch, ApiError> {
+        let mut request = Request::patch("/");
+        if let Some(value) = value {
+            request = request.header(IF_MATCH, value);
+        }
+        let (mut parts, _) = request.body(()).unwrap().into_parts();
+        IfMatch::from_request_parts(&mut parts, &()).await
+    }
+
+    #[test]
+    fn test_tree_etag_changes_with_its_subtasks() {
+        let parent = task(3);
+        let mut subtask = task(1);
+        subtask.parent_id = Some(parent.id());
+        let before = ETag::tree(&TaskTree::new(parent.clone(), vec![subtask.clone()]));
+        subtask.version = 2;
+        let after = ETag::tree(&TaskTree::new(parent.clone(), vec![subtask]));
+        assert_ne!(before, after);
+        assert_eq!(ETag::version(&before.0), Some(3));
+        assert_eq!(ETag::tree(&TaskTree::new(parent, vec![])).0, "\"3\"");
+    }
+
+    #[test]
+    fn test_if_none_match_compares_weakly() {
+        let etag = ETag::task(&task(2));
+        let headers = |value: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(IF_NONE_MATCH, HeaderValue::from_static(value));
+            headers
+        };
+        assert!(etag.is_current(&headers("\"2\"")));
+        assert!(etag.is_current(&headers("\"1\", W/\"2\"")));
+        assert!(etag.is_current(&headers("*")));
+        assert!(!etag.is_current(&headers("\"1\"")));
+        assert!(!etag.is_current(&HeaderMap::new()));
+    }
+
+    #[tokio::test]
+    async fn test_if_match_is_required() {
+        assert_eq!(if_match(Some("\"4\"")).await, Ok(IfMatch(Some(4))));
+        assert_eq!(if_match(Some("\"4.abc\"")).await, Ok(IfMatch(Some(4))));
+        assert_eq!(if_match(Some("*")).await, Ok(IfMatch(None)));
+        assert_eq!(
+            if_match(None).await.unwrap_err().status(),
+            StatusCode::PRECONDITION_REQUIRED
+        );
+        assert_eq!(
+            if_match(Some("W/\"4\"")).await.unwrap_err().status(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
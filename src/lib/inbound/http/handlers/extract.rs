@@ -0,0 +1,240 @@
+use axum::async_trait;
+use axum::extract::rejection::{JsonRejection, PathRejection, QueryRejection};
+use axum::extract::{FromRequest, FromRequestParts, Request};
+use axum::http::request::Parts;
+use serde::de::DeserializeOwned;
+
+use crate::inbound::http::handlers::shared::{ApiError, ErrorDetail};
+
+impl From<JsonRejection> for ApiError {
+    fn from(e: JsonRejection) -> Self {
+        match e {
+            JsonRejection::JsonDataError(e) => {
+                Self::UnprocessableEntity(ErrorDetail::new("request.invalid_body", e.body_text()))
+            }
+            JsonRejection::JsonSyntaxError(e) => {
+                Self::BadRequest(ErrorDetail::new("request.malformed_json", e.body_text()))
+            }
+            JsonRejection::MissingJsonContentType(e) => Self::UnsupportedMediaType(
+                ErrorDetail::new("request.unsupported_media_type", e.body_text()),
+            ),
+            e => Self::BadRequest(ErrorDetail::new("request.unreadable_body", e.body_text())),
+        }
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(e: PathRejection) -> Self {
+        match e {
+            PathRejection::FailedToDeserializePathParams(e) => {
+                Self::BadRequest(ErrorDetail::new("request.invalid_path", e.body_text()))
+            }
+            // The route does not match the extractor, which is a bug rather than a bad request
+            e => Self::InternalServerError(e.body_text()),
+        }
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(e: QueryRejection) -> Self {
+        Self::BadRequest(ErrorDetail::new("request.invalid_query", e.body_text()))
+    }
+}
+
+/// A JSON request body, deserialized into `T`, like [axum::Json].
+///
+/// Requests without a JSON `Content-Type` are rejected with 415 Unsupported Media Type, those
+/// whose body is not JSON with 400 Bad Request, and those whose JSON does not match `T` with 422
+/// Unprocessable Entity, as [ApiError]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Json<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::Json(value) = axum::Json::<T>::from_request(req, state).await?;
+        Ok(Self(value))
+    }
+}
+
+/// The parameters in the path of a request, deserialized into `T`, like [axum::extract::Path].
+///
+/// Requests with parameters that do not parse, such as an id that is not a UUID, are rejected
+/// with 400 Bad Request as an [ApiError].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Path<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::extract::Path(value) =
+            axum::extract::Path::<T>::from_request_parts(parts, state).await?;
+        Ok(Self(value))
+    }
+}
+
+/// The query string of a request, deserialized into `T`, like [axum::extract::Query].
+///
+/// Requests with a query string that does not parse are rejected with 400 Bad Request as an
+/// [ApiError].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Query<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::extract::Query(value) =
+            axum::extract::Query::<T>::from_request_parts(parts, state).await?;
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inbound::http::handlers::shared::PROBLEM_JSON;
+    use axum::body::{to_bytes, Body};
+    use axum::http::header::CONTENT_TYPE;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::Router;
+    use serde::Deserialize;
+    use serde_json::Value;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    #[derive(Debug, Deserialize)]
+    struct NewTask {
+        #[allow(dead_code)]
+        title: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Params {
+        #[allow(dead_code)]
+        limit: u32,
+    }
+
+    async fn handler(
+        Path(_): Path<Uuid>,
+        Query(_): Query<Params>,
+        Json(_): Json<NewTask>,
+    ) -> &'static str {
+        "ok"
+    }
+
+    /// Send a request to a route using every extractor, and return the status and problem.
+    async fn send(path: &str, content_type: &str, body: &'static str) -> (StatusCode, Value) {
+        let router = Router::new().route("/:id", post(handler));
+        let request = Request::post(path)
+            .header(CONTENT_TYPE, content_type)
+            .body(Body::from(body))
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        if status.is_success() {
+            return (status, Value::Null);
+        }
+        assert_eq!(response.headers()[CONTENT_TYPE], PROBLEM_JSON);
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    fn path(id: &str, query: &str) -> String {
+        format!("/{}?{}", id, query)
+    }
+
+    #[tokio::test]
+    async fn test_valid_request_passes() {
+        let (status, _) = send(
+            &path(&Uuid::new_v4().to_string(), "limit=5"),
+            "application/json",
+            r#"{"title":"Buy milk"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_json_is_bad_request() {
+        let (status, problem) = send(
+            &path(&Uuid::new_v4().to_string(), "limit=5"),
+            "application/json",
+            r#"{"title":"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(problem["code"], "request.malformed_json");
+        assert_eq!(problem["status"], 400);
+        assert_eq!(problem["title"], "Bad Request");
+        assert_eq!(problem["type"], "about:blank");
+    }
+
+    #[tokio::test]
+    async fn test_missing_content_type_is_unsupported_media_type() {
+        let (status, problem) = send(
+            &path(&Uuid::new_v4().to_string(), "limit=5"),
+            "text/plain",
+            r#"{"title":"Buy milk"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(problem["code"], "request.unsupported_media_type");
+        assert_eq!(problem["status"], 415);
+        assert_eq!(problem["title"], "Unsupported Media Type");
+    }
+
+    #[tokio::test]
+    async fn test_mistyped_json_is_unprocessable() {
+        let (status, problem) = send(
+            &path(&Uuid::new_v4().to_string(), "limit=5"),
+            "application/json",
+            r#"{"title":5}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(problem["code"], "request.invalid_body");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_path_is_bad_request() {
+        let (status, problem) = send(
+            &path("not-a-uuid", "limit=5"),
+            "application/json",
+            r#"{"title":"Buy milk"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(problem["code"], "request.invalid_path");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_query_is_bad_request() {
+        let (status, problem) = send(
+            &path(&Uuid::new_v4().to_string(), "limit=many"),
+            "application/json",
+            r#"{"title":"Buy milk"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(problem["code"], "request.invalid_query");
+    }
+}
@@ -0,0 +1,39 @@
+use axum::http::StatusCode;
+
+use crate::inbound::http::handlers::auth::CurrentUser;
+use crate::inbound::http::handlers::register_user::UserResponseData;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ProblemDetails,
+};
+
+/// Retrieve the [User](crate::domain::users::models::user::User) signed in with the bearer token
+/// of the request.
+///
+/// # Responses
+///
+/// - 200 OK: the request has a valid bearer token.
+/// - 401 Unauthorized: the request has no valid bearer token.
+#[utoipa::path(
+    get,
+    path = "/api/users/me",
+    tag = "users",
+    responses(
+        (
+            status = 200,
+            description = "The request has a valid bearer token",
+            body = ApiResponseBody<UserResponseData>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_current_user(
+    CurrentUser(user): CurrentUser,
+) -> Result<ApiSuccess<UserResponseData>, ApiError> {
+    Ok(ApiSuccess::new(StatusCode::OK, (&user).into()))
+}
@@ -0,0 +1,142 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::list::{GetTaskListError, TaskList};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::Path;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<GetTaskListError> for ApiError {
+    fn from(e: GetTaskListError) -> Self {
+        match e {
+            GetTaskListError::NotFound { id } => Self::NotFound(ErrorDetail::new(
+                "list.not_found",
+                format!("task list with id {} not found", id),
+            )),
+            GetTaskListError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// The response body data field for a single [TaskList].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TaskListResponseData {
+    id: String,
+    title: String,
+    colour: Option<String>,
+    position: i32,
+    archived: bool,
+    created_at: String,
+    owner_id: String,
+    /// The role of the requesting user in the list.
+    role: String,
+}
+
+impl From<&TaskList> for TaskListResponseData {
+    fn from(list: &TaskList) -> Self {
+        Self {
+            id: list.id().to_string(),
+            title: list.title().to_string(),
+            colour: list.colour().map(ToString::to_string),
+            position: list.position(),
+            archived: list.archived(),
+            created_at: list.created_at().to_rfc3339(),
+            owner_id: list.owner_id().to_string(),
+            role: list.role().to_string(),
+        }
+    }
+}
+
+/// Retrieve a [TaskList] by its id.
+///
+/// # Responses
+///
+/// - 200 OK: the [TaskList] was found.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is restricted to another task list.
+/// - 404 Not Found: no [TaskList] with the given id exists.
+#[utoipa::path(
+    get,
+    path = "/api/lists/{id}",
+    tag = "lists",
+    params(("id" = Uuid, Path, description = "The id of the task list")),
+    responses(
+        (
+            status = 200,
+            description = "The task list was found",
+            body = ApiResponseBody<TaskListResponseData>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is restricted to another task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 404,
+            description = "No task list with the given id exists",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_list<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path(id): Path<Uuid>,
+) -> Result<ApiSuccess<TaskListResponseData>, ApiError> {
+    scope.require_list(Some(id))?;
+    state
+        .reminder_service
+        .get_list(&user.id(), &id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref list| ApiSuccess::new(StatusCode::OK, list.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_list_not_found() {
+        let id = Uuid::new_v4();
+        let service = MockReminderService {
+            get_list_result: Arc::new(Mutex::new(Err(GetTaskListError::NotFound { id }))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let actual = get_list(state, current_user(), full_scope(), Path(id)).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::NotFound(ErrorDetail::new(
+                "list.not_found",
+                format!("task list with id {} not found", id)
+            )))
+        );
+    }
+}
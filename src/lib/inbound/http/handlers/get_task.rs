@@ -0,0 +1,288 @@
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::task::{GetTaskError, Task, TaskTree};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::etag::{ETag, Tagged};
+use crate::inbound::http::handlers::extract::Path;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<GetTaskError> for ApiError {
+    fn from(e: GetTaskError) -> Self {
+        match e {
+            GetTaskError::NotFound { id } => Self::NotFound(ErrorDetail::new(
+                "task.not_found",
+                format!("task with id {} not found", id),
+            )),
+            GetTaskError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// The response body data field for a single [Task].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TaskResponseData {
+    id: String,
+    title: String,
+    completed: bool,
+    priority: String,
+    notes: Option<String>,
+    due_at: Option<String>,
+    remind_at: Vec<String>,
+    recurrence: Option<String>,
+    list_id: Option<String>,
+    parent_id: Option<String>,
+    position: i32,
+    tags: Vec<String>,
+    assignee_id: Option<String>,
+    created_at: String,
+    updated_by: Option<String>,
+    completed_by: Option<String>,
+    /// Incremented on every change to the task, and sent as its `ETag`.
+    version: i64,
+}
+
+impl From<&Task> for TaskResponseData {
+    fn from(task: &Task) -> Self {
+        Self {
+            id: task.id().to_string(),
+            title: task.title().to_string(),
+            completed: task.completed(),
+            priority: task.priority().to_string(),
+            notes: task.notes().map(ToString::to_string),
+            due_at: task.due_at().map(ToString::to_string),
+            remind_at: task
+                .remind_at()
+                .offsets()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            recurrence: task.recurrence().map(ToString::to_string),
+            list_id: task.list_id().map(|id| id.to_string()),
+            parent_id: task.parent_id().map(|id| id.to_string()),
+            position: task.position(),
+            tags: task.tags().tags().iter().map(ToString::to_string).collect(),
+            assignee_id: task.assignee_id().map(|id| id.to_string()),
+            created_at: task.created_at().to_rfc3339(),
+            updated_by: task.updated_by().map(|id| id.to_string()),
+            completed_by: task.completed_by().map(|id| id.to_string()),
+            version: task.version(),
+        }
+    }
+}
+
+/// The response body data field for a [Task] with its subtasks, nested in sibling order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TaskDetailResponseData {
+    #[serde(flatten)]
+    task: TaskResponseData,
+    #[schema(no_recursion)]
+    subtasks: Vec<TaskDetailResponseData>,
+}
+
+impl From<&TaskTree> for TaskDetailResponseData {
+    fn from(tree: &TaskTree) -> Self {
+        Self {
+            task: tree.task().into(),
+            subtasks: tree.subtasks().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Retrieve a [Task] by its id, with its subtasks, tagged with an `ETag` that changes whenever
+/// they do.
+///
+/// # Responses
+///
+/// - 200 OK: the [Task] was found.
+/// - 304 Not Modified: the `If-None-Match` header lists the current `ETag` of the [Task].
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is restricted to another task list.
+/// - 404 Not Found: no [Task] with the given id exists.
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}",
+    tag = "tasks",
+    params(
+        ("id" = Uuid, Path, description = "The id of the task"),
+        (
+            "If-None-Match" = Option<String>,
+            Header,
+            description = "The ETag of the client's copy of the task, to skip sending it again",
+        ),
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The task was found",
+            body = ApiResponseBody<TaskDetailResponseData>,
+            headers(("ETag" = String, description = "The version of the task and its subtasks")),
+        ),
+        (
+            status = 304,
+            description = "The client's copy of the task is current",
+            headers(("ETag" = String, description = "The version of the task and its subtasks")),
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is restricted to another task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 404,
+            description = "No task with the given id exists",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn get_task<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Tagged<ApiSuccess<TaskDetailResponseData>>, ApiError> {
+    let tree = state
+        .reminder_service
+        .get_task_tree(&user.id(), &id)
+        .await?;
+    scope.require_list(tree.task().list_id())?;
+    let etag = ETag::tree(&tree);
+    if etag.is_current(&headers) {
+        return Ok(Tagged::NotModified(etag));
+    }
+    Ok(Tagged::Body(
+        etag,
+        ApiSuccess::new(StatusCode::OK, (&tree).into()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::reminders::models::task::TaskTitle;
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use axum::http::header::IF_NONE_MATCH;
+    use axum::http::HeaderValue;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_task_success() {
+        let task = Task::new(Uuid::new_v4(), TaskTitle::new("Prepare trip").unwrap());
+        let mut subtask = Task::new(Uuid::new_v4(), TaskTitle::new("Book hotel").unwrap());
+        subtask.parent_id = Some(task.id());
+        let tree = TaskTree::new(task.clone(), vec![subtask.clone()]);
+        let service = MockReminderService {
+            get_task_tree_result: Arc::new(Mutex::new(Ok(tree.clone()))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let expected = ApiSuccess::new(
+            StatusCode::OK,
+            TaskDetailResponseData {
+                task: TaskResponseData {
+                    id: task.id().to_string(),
+                    title: "Prepare trip".to_string(),
+                    completed: false,
+                    priority: "none".to_string(),
+                    notes: None,
+                    due_at: None,
+                    remind_at: vec![],
+                    recurrence: None,
+                    list_id: None,
+                    parent_id: None,
+                    position: 0,
+                    tags: vec![],
+                    assignee_id: None,
+                    created_at: task.created_at().to_rfc3339(),
+                    updated_by: None,
+                    completed_by: None,
+                    version: 1,
+                },
+                subtasks: vec![TaskDetailResponseData {
+                    task: (&subtask).into(),
+                    subtasks: vec![],
+                }],
+            },
+        );
+        let actual = get_task(
+            state,
+            current_user(),
+            full_scope(),
+            Path(task.id()),
+            HeaderMap::new(),
+        )
+        .await;
+        assert_eq!(actual, Ok(Tagged::Body(ETag::tree(&tree), expected)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_task_not_modified() {
+        let task = Task::new(Uuid::new_v4(), TaskTitle::new("Prepare trip").unwrap());
+        let tree = TaskTree::new(task.clone(), vec![]);
+        let service = MockReminderService {
+            get_task_tree_result: Arc::new(Mutex::new(Ok(tree))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_NONE_MATCH, HeaderValue::from_static("\"1\""));
+        let actual = get_task(
+            state,
+            current_user(),
+            full_scope(),
+            Path(task.id()),
+            headers,
+        )
+        .await;
+        assert_eq!(actual, Ok(Tagged::NotModified(ETag::task(&task))));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_task_not_found() {
+        let id = Uuid::new_v4();
+        let service = MockReminderService {
+            get_task_tree_result: Arc::new(Mutex::new(Err(GetTaskError::NotFound { id }))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let actual = get_task(
+            state,
+            current_user(),
+            full_scope(),
+            Path(id),
+            HeaderMap::new(),
+        )
+        .await;
+        assert_eq!(
+            actual,
+            Err(ApiError::NotFound(ErrorDetail::new(
+                "task.not_found",
+                format!("task with id {} not found", id)
+            )))
+        );
+    }
+}
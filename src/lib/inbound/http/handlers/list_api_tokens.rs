@@ -0,0 +1,143 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::models::api_token::{ApiToken, ListApiTokensError};
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<ListApiTokensError> for ApiError {
+    fn from(e: ListApiTokensError) -> Self {
+        match e {
+            ListApiTokensError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// The response body data field for a single [ApiToken]. The secret is never returned after
+/// the token is created.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ApiTokenResponseData {
+    id: String,
+    name: String,
+    access: String,
+    list_id: Option<String>,
+    created_at: String,
+    last_used_at: Option<String>,
+}
+
+impl From<&ApiToken> for ApiTokenResponseData {
+    fn from(token: &ApiToken) -> Self {
+        Self {
+            id: token.id().to_string(),
+            name: token.name().to_string(),
+            access: token.scope().access().to_string(),
+            list_id: token.scope().list_id().map(|id| id.to_string()),
+            created_at: token.created_at().to_rfc3339(),
+            last_used_at: token.last_used_at().map(|at| at.to_rfc3339()),
+        }
+    }
+}
+
+/// List the [ApiToken]s of the current user, oldest first.
+///
+/// # Responses
+///
+/// - 200 OK: the tokens were listed.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is restricted to a task list.
+#[utoipa::path(
+    get,
+    path = "/api/tokens",
+    tag = "tokens",
+    responses(
+        (
+            status = 200,
+            description = "The tokens were listed",
+            body = ApiResponseBody<Vec<ApiTokenResponseData>>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is restricted to a task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn list_api_tokens<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+) -> Result<ApiSuccess<Vec<ApiTokenResponseData>>, ApiError> {
+    scope.require_all_lists()?;
+    state
+        .user_service
+        .list_api_tokens(&user.id())
+        .await
+        .map_err(ApiError::from)
+        .map(|tokens| ApiSuccess::new(StatusCode::OK, tokens.iter().map(Into::into).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::users::models::api_token::{ApiTokenName, TokenScope};
+    use crate::inbound::http::handlers::shared::ErrorDetail;
+    use crate::inbound::http::handlers::test_utils::{
+        current_user, full_scope, user_app_state, MockUserService,
+    };
+    use chrono::Utc;
+    use std::sync::{Arc, Mutex};
+    use uuid::Uuid;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_list_api_tokens_success() {
+        let tokens = vec![ApiToken {
+            id: Uuid::new_v4(),
+            name: ApiTokenName::new("cron").unwrap(),
+            scope: TokenScope::full(),
+            created_at: Utc::now(),
+            last_used_at: Some(Utc::now()),
+        }];
+        let service = MockUserService {
+            list_api_tokens_result: Arc::new(Mutex::new(Ok(tokens.clone()))),
+            ..Default::default()
+        };
+        let state = State(user_app_state(service));
+        let expected = ApiSuccess::new(StatusCode::OK, tokens.iter().map(Into::into).collect());
+        let actual = list_api_tokens(state, current_user(), full_scope()).await;
+        assert_eq!(actual, Ok(expected));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_list_api_tokens_with_list_token() {
+        let state = State(user_app_state(MockUserService::default()));
+        let list_id = Uuid::new_v4();
+        let scope = Scope(TokenScope::full().with_list_id(list_id));
+        let actual = list_api_tokens(state, current_user(), scope).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::Forbidden(ErrorDetail::new(
+                "token.list_restricted",
+                format!("this token is restricted to task list {}", list_id)
+            )))
+        );
+    }
+}
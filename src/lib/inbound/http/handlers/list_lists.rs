@@ -0,0 +1,89 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::list::ListTaskListsError;
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::Query;
+use crate::inbound::http::handlers::get_list::TaskListResponseData;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<ListTaskListsError> for ApiError {
+    fn from(e: ListTaskListsError) -> Self {
+        match e {
+            ListTaskListsError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// The query parameters of a task list listing. `archived=true` includes archived lists.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListTaskListsHttpQuery {
+    #[serde(default)]
+    archived: bool,
+}
+
+/// List all [TaskList](crate::domain::reminders::models::list::TaskList)s in display order.
+///
+/// # Responses
+///
+/// - 200 OK: the lists were listed.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is restricted to another task list.
+#[utoipa::path(
+    get,
+    path = "/api/lists",
+    tag = "lists",
+    params(ListTaskListsHttpQuery),
+    responses(
+        (
+            status = 200,
+            description = "The lists were listed",
+            body = ApiResponseBody<Vec<TaskListResponseData>>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is restricted to another task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn list_lists<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Query(query): Query<ListTaskListsHttpQuery>,
+) -> Result<ApiSuccess<Vec<TaskListResponseData>>, ApiError> {
+    state
+        .reminder_service
+        .list_lists(&user.id(), query.archived)
+        .await
+        .map_err(ApiError::from)
+        .map(|lists| {
+            let lists = lists
+                .iter()
+                .filter(|list| scope.0.allows_list(Some(list.id())))
+                .map(Into::into)
+                .collect();
+            ApiSuccess::new(StatusCode::OK, lists)
+        })
+}
@@ -0,0 +1,139 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::member::{ListTaskListMembersError, TaskListMember};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::Path;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<ListTaskListMembersError> for ApiError {
+    fn from(e: ListTaskListMembersError) -> Self {
+        match e {
+            ListTaskListMembersError::NotFound { id } => Self::NotFound(ErrorDetail::new(
+                "list.not_found",
+                format!("task list with id {} not found", id),
+            )),
+            ListTaskListMembersError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// The response body data field for a single [TaskListMember].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TaskListMemberResponseData {
+    user_id: String,
+    email: String,
+    role: String,
+    added_at: String,
+}
+
+impl From<&TaskListMember> for TaskListMemberResponseData {
+    fn from(member: &TaskListMember) -> Self {
+        Self {
+            user_id: member.user_id().to_string(),
+            email: member.email().to_string(),
+            role: member.role().to_string(),
+            added_at: member.added_at().to_rfc3339(),
+        }
+    }
+}
+
+/// List the users who may access a
+/// [TaskList](crate::domain::reminders::models::list::TaskList): its creator first, then
+/// everyone it is shared with.
+///
+/// # Responses
+///
+/// - 200 OK: the members of the list.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is restricted to another task list.
+/// - 404 Not Found: no list with the given id exists.
+#[utoipa::path(
+    get,
+    path = "/api/lists/{id}/members",
+    tag = "lists",
+    params(("id" = Uuid, Path, description = "The id of the task list")),
+    responses(
+        (
+            status = 200,
+            description = "The members of the list",
+            body = ApiResponseBody<Vec<TaskListMemberResponseData>>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is restricted to another task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 404,
+            description = "No list with the given id exists",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn list_members<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path(id): Path<Uuid>,
+) -> Result<ApiSuccess<Vec<TaskListMemberResponseData>>, ApiError> {
+    scope.require_list(Some(id))?;
+    state
+        .reminder_service
+        .list_members(&user.id(), &id)
+        .await
+        .map_err(ApiError::from)
+        .map(|members| ApiSuccess::new(StatusCode::OK, members.iter().map(Into::into).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::reminders::models::member::ListRole;
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use chrono::Utc;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_list_members_success() {
+        let CurrentUser(user) = current_user();
+        let member = TaskListMember {
+            user_id: user.id(),
+            email: user.email().clone(),
+            role: ListRole::Editor,
+            added_at: Utc::now(),
+        };
+        let service = MockReminderService {
+            list_members_result: Arc::new(Mutex::new(Ok(vec![member.clone()]))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let expected = ApiSuccess::new(StatusCode::OK, vec![(&member).into()]);
+        let actual =
+            list_members(state, CurrentUser(user), full_scope(), Path(Uuid::new_v4())).await;
+        assert_eq!(actual, Ok(expected));
+    }
+}
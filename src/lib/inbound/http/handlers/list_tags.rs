@@ -0,0 +1,88 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::tag::{ListTagsError, TagUsage};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<ListTagsError> for ApiError {
+    fn from(e: ListTagsError) -> Self {
+        match e {
+            ListTagsError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// The response body data field for a [Tag](crate::domain::reminders::models::tag::Tag) with
+/// the number of tasks that carry it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TagResponseData {
+    tag: String,
+    tasks: u64,
+}
+
+impl From<&TagUsage> for TagResponseData {
+    fn from(usage: &TagUsage) -> Self {
+        Self {
+            tag: usage.tag().to_string(),
+            tasks: usage.tasks(),
+        }
+    }
+}
+
+/// List every tag in use, in alphabetical order, with its number of tasks.
+///
+/// # Responses
+///
+/// - 200 OK: the tags were listed.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is restricted to another task list.
+#[utoipa::path(
+    get,
+    path = "/api/tags",
+    tag = "tags",
+    responses(
+        (
+            status = 200,
+            description = "The tags were listed",
+            body = ApiResponseBody<Vec<TagResponseData>>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is restricted to another task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn list_tags<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+) -> Result<ApiSuccess<Vec<TagResponseData>>, ApiError> {
+    scope.require_all_lists()?;
+    state
+        .reminder_service
+        .list_tags(&user.id())
+        .await
+        .map_err(ApiError::from)
+        .map(|tags| ApiSuccess::new(StatusCode::OK, tags.iter().map(Into::into).collect()))
+}
@@ -0,0 +1,470 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::query::{
+    ListTasksRequest, PageSize, PageSizeInvalidError, TagMatch, TaskCursor,
+    TaskCursorSortMismatchError, TaskFilter, TaskListFilter, TaskPage, TaskSearch, TaskSort,
+    TaskSortInvalidError, TaskTagFilter, TaskTagFilterEmptyError,
+};
+use crate::domain::reminders::models::tag::{Tag, TagInvalidError};
+use crate::domain::reminders::models::task::{DueAt, DueAtInvalidError, ListTasksError, Priority};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::Query;
+use crate::inbound::http::handlers::get_task::TaskResponseData;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<ListTasksError> for ApiError {
+    fn from(e: ListTasksError) -> Self {
+        match e {
+            ListTasksError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<ParseListTasksHttpQueryError> for ApiError {
+    fn from(e: ParseListTasksHttpQueryError) -> Self {
+        let field = match e {
+            ParseListTasksHttpQueryError::Completed(_) => "completed",
+            ParseListTasksHttpQueryError::DueBefore(_) => "due_before",
+            ParseListTasksHttpQueryError::DueAfter(_) => "due_after",
+            ParseListTasksHttpQueryError::Search => "q",
+            ParseListTasksHttpQueryError::List(_) => "list",
+            ParseListTasksHttpQueryError::Tag(_) | ParseListTasksHttpQueryError::Tags(_) => "tag",
+            ParseListTasksHttpQueryError::TagMatch(_) => "tag_match",
+            ParseListTasksHttpQueryError::Assignee(_) => "assignee",
+            ParseListTasksHttpQueryError::Sort(_) => "sort",
+            ParseListTasksHttpQueryError::Limit(_) => "limit",
+            ParseListTasksHttpQueryError::Cursor | ParseListTasksHttpQueryError::CursorSort(_) => {
+                "cursor"
+            }
+        };
+        Self::UnprocessableEntity(ErrorDetail::invalid_field(field, e.to_string()))
+    }
+}
+
+/// The query parameters of a task listing. All are optional:
+///
+/// - `completed`: `true` or `false` to list only completed or incomplete tasks.
+/// - `due_before`, `due_after`: RFC 3339 timestamps bounding the due date, exclusively.
+/// - `q`: text the title must contain, ignoring case.
+/// - `list`: `inbox` for tasks in no list, or the id of a task list.
+/// - `tag`: comma-separated tags the task must carry.
+/// - `tag_match`: `all` (the default) to require every tag, or `any` to require at least one.
+/// - `assignee`: `me` for tasks assigned to the current user, or the id of another user.
+/// - `sort`: `created` (the default), `due` or `priority`, or any of them prefixed with `-` for
+///   descending order, so `-priority` lists urgent tasks first.
+/// - `limit`: the page size, from 1 to 100, 50 by default.
+/// - `cursor`: the `next_cursor` of the previous page, to continue listing after it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListTasksHttpQuery {
+    completed: Option<String>,
+    due_before: Option<String>,
+    due_after: Option<String>,
+    q: Option<String>,
+    list: Option<String>,
+    tag: Option<String>,
+    tag_match: Option<String>,
+    assignee: Option<String>,
+    /// `created` (the default), `due` or `priority`, prefixed with `-` for descending order.
+    #[param(example = "-priority")]
+    sort: Option<String>,
+    limit: Option<String>,
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Error)]
+enum ParseListTasksHttpQueryError {
+    #[error("completed must be true or false, not {0:?}")]
+    Completed(String),
+    #[error(transparent)]
+    DueBefore(DueAtInvalidError),
+    #[error(transparent)]
+    DueAfter(DueAtInvalidError),
+    #[error("search text cannot be empty")]
+    Search,
+    #[error("list must be inbox or a task list id, not {0:?}")]
+    List(String),
+    #[error(transparent)]
+    Tag(#[from] TagInvalidError),
+    #[error(transparent)]
+    Tags(#[from] TaskTagFilterEmptyError),
+    #[error("tag_match must be all or any, not {0:?}")]
+    TagMatch(String),
+    #[error("assignee must be me or a user id, not {0:?}")]
+    Assignee(String),
+    #[error(transparent)]
+    Sort(#[from] TaskSortInvalidError),
+    #[error(transparent)]
+    Limit(#[from] PageSizeInvalidError),
+    #[error("invalid cursor")]
+    Cursor,
+    #[error(transparent)]
+    CursorSort(#[from] TaskCursorSortMismatchError),
+}
+
+impl ListTasksHttpQuery {
+    /// Converts the HTTP query of the `user` into a domain request. Without an explicit `sort`, a
+    /// `cursor` continues in the order it was created for.
+    fn try_into_domain(
+        self,
+        user: &Uuid,
+    ) -> Result<ListTasksRequest, ParseListTasksHttpQueryError> {
+        let mut filter = TaskFilter::new();
+        if let Some(completed) = self.completed.as_deref() {
+            let completed = completed
+                .parse()
+                .map_err(|_| ParseListTasksHttpQueryError::Completed(completed.to_string()))?;
+            filter = filter.with_completed(completed);
+        }
+        if let Some(before) = self.due_before.as_deref() {
+            filter = filter.with_due_before(
+                *DueAt::parse(before)
+                    .map_err(ParseListTasksHttpQueryError::DueBefore)?
+                    .as_datetime(),
+            );
+        }
+        if let Some(after) = self.due_after.as_deref() {
+            filter = filter.with_due_after(
+                *DueAt::parse(after)
+                    .map_err(ParseListTasksHttpQueryError::DueAfter)?
+                    .as_datetime(),
+            );
+        }
+        if let Some(q) = self.q.as_deref() {
+            let search = TaskSearch::new(q).map_err(|_| ParseListTasksHttpQueryError::Search)?;
+            filter = filter.with_search(search);
+        }
+        if let Some(list) = self.list.as_deref() {
+            let list = match list.trim() {
+                "inbox" => TaskListFilter::Inbox,
+                id => Uuid::parse_str(id)
+                    .map(TaskListFilter::List)
+                    .map_err(|_| ParseListTasksHttpQueryError::List(list.to_string()))?,
+            };
+            filter = filter.with_list(list);
+        }
+        let mode = match self.tag_match.as_deref().map(str::trim) {
+            None | Some("all") => TagMatch::All,
+            Some("any") => TagMatch::Any,
+            Some(mode) => return Err(ParseListTasksHttpQueryError::TagMatch(mode.to_string())),
+        };
+        if let Some(tags) = self.tag.as_deref() {
+            let tags = tags
+                .split(',')
+                .map(Tag::new)
+                .collect::<Result<Vec<_>, _>>()?;
+            filter = filter.with_tags(TaskTagFilter::new(tags, mode)?);
+        }
+        if let Some(assignee) = self.assignee.as_deref() {
+            let assignee = match assignee.trim() {
+                "me" => *user,
+                id => Uuid::parse_str(id)
+                    .map_err(|_| ParseListTasksHttpQueryError::Assignee(assignee.to_string()))?,
+            };
+            filter = filter.with_assignee(assignee);
+        }
+
+        let cursor = self.cursor.as_deref().map(decode_cursor).transpose()?;
+        let sort = match (self.sort.as_deref(), &cursor) {
+            (Some(sort), _) => TaskSort::parse(sort)?,
+            (None, Some(cursor)) => cursor.sort(),
+            (None, None) => TaskSort::default(),
+        };
+        let mut req = ListTasksRequest::new().with_filter(filter).with_sort(sort);
+        if let Some(limit) = self.limit.as_deref() {
+            let size = limit
+                .trim()
+                .parse()
+                .map_err(|_| PageSizeInvalidError)
+                .and_then(PageSize::new)?;
+            req = req.with_size(size);
+        }
+        if let Some(cursor) = cursor {
+            req = req.with_cursor(cursor)?;
+        }
+        Ok(req)
+    }
+}
+
+/// The contents of an encoded [TaskCursor]. Clients treat cursors as opaque strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CursorData {
+    sort: String,
+    due_at: Option<String>,
+    #[serde(default)]
+    priority: Option<String>,
+    created_at: String,
+    id: Uuid,
+}
+
+fn encode_cursor(cursor: &TaskCursor) -> String {
+    let data = CursorData {
+        sort: cursor.sort().to_string(),
+        due_at: cursor.due_at().map(DateTime::to_rfc3339),
+        priority: Some(cursor.priority().to_string()),
+        created_at: cursor.created_at().to_rfc3339(),
+        id: cursor.id(),
+    };
+    let json = serde_json::to_vec(&data).expect("cursor data is always serializable");
+    URL_SAFE_NO_PAD.encode(json)
+}
+
+fn decode_cursor(raw: &str) -> Result<TaskCursor, ParseListTasksHttpQueryError> {
+    let data = URL_SAFE_NO_PAD
+        .decode(raw.trim())
+        .ok()
+        .and_then(|json| serde_json::from_slice::<CursorData>(&json).ok())
+        .ok_or(ParseListTasksHttpQueryError::Cursor)?;
+    let timestamp = |raw: &str| {
+        DateTime::parse_from_rfc3339(raw)
+            .map(|at| at.with_timezone(&Utc))
+            .map_err(|_| ParseListTasksHttpQueryError::Cursor)
+    };
+    let sort = TaskSort::parse(&data.sort).map_err(|_| ParseListTasksHttpQueryError::Cursor)?;
+    let due_at = data.due_at.as_deref().map(timestamp).transpose()?;
+    // Cursors created before tasks could be sorted by priority have none
+    let priority = data
+        .priority
+        .as_deref()
+        .map(Priority::parse)
+        .transpose()
+        .map_err(|_| ParseListTasksHttpQueryError::Cursor)?
+        .unwrap_or_default();
+    let created_at = timestamp(&data.created_at)?;
+    Ok(TaskCursor::new(sort, due_at, priority, created_at, data.id))
+}
+
+/// The response body data field for a page of [Task](crate::domain::reminders::models::task::Task)s.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ListTasksResponseData {
+    tasks: Vec<TaskResponseData>,
+    /// Pass as `cursor` to fetch the next page; `null` on the last page.
+    next_cursor: Option<String>,
+}
+
+impl From<&TaskPage> for ListTasksResponseData {
+    fn from(page: &TaskPage) -> Self {
+        Self {
+            tasks: page.tasks.iter().map(Into::into).collect(),
+            next_cursor: page.next_cursor.as_ref().map(encode_cursor),
+        }
+    }
+}
+
+/// List a page of [Task](crate::domain::reminders::models::task::Task)s matching the
+/// [query](ListTasksHttpQuery).
+///
+/// # Responses
+///
+/// - 200 OK: the tasks were listed.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is restricted to another task list.
+/// - 422 Unprocessable Entity: a query parameter is invalid.
+#[utoipa::path(
+    get,
+    path = "/api/tasks",
+    tag = "tasks",
+    params(ListTasksHttpQuery),
+    responses(
+        (
+            status = 200,
+            description = "The tasks were listed",
+            body = ApiResponseBody<ListTasksResponseData>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is restricted to another task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 422,
+            description = "A query parameter is invalid",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn list_tasks<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Query(query): Query<ListTasksHttpQuery>,
+) -> Result<ApiSuccess<ListTasksResponseData>, ApiError> {
+    let domain_req = query.try_into_domain(&user.id())?;
+    // Tokens restricted to a list may only list the tasks of that list
+    scope.require_list(domain_req.filter().list().and_then(|list| list.list_id()))?;
+    state
+        .reminder_service
+        .list_tasks(&user.id(), &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref page| ApiSuccess::new(StatusCode::OK, page.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::reminders::models::task::{Task, TaskTitle};
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_list_tasks_success() {
+        let tasks = vec![
+            Task::new(Uuid::new_v4(), TaskTitle::new("Clean apartment").unwrap()),
+            Task::new(Uuid::new_v4(), TaskTitle::new("Buy groceries").unwrap()),
+        ];
+        let page = TaskPage {
+            next_cursor: Some(TaskCursor::after(TaskSort::default(), &tasks[1])),
+            tasks,
+        };
+        let service = MockReminderService {
+            list_tasks_result: Arc::new(Mutex::new(Ok(page.clone()))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let expected = ApiSuccess::new(StatusCode::OK, (&page).into());
+        let actual = list_tasks(
+            state,
+            current_user(),
+            full_scope(),
+            Query(ListTasksHttpQuery::default()),
+        )
+        .await;
+        assert_eq!(actual, Ok(expected));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_list_tasks_invalid_limit() {
+        let state = State(app_state(
+            MockReminderService::default(),
+            MockReadinessService::default(),
+        ));
+        let query = ListTasksHttpQuery {
+            limit: Some("500".to_string()),
+            ..Default::default()
+        };
+        let actual = list_tasks(state, current_user(), full_scope(), Query(query)).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::UnprocessableEntity(ErrorDetail::invalid_field(
+                "limit",
+                "page size must be between 1 and 100"
+            )))
+        );
+    }
+
+    #[test]
+    fn test_tags_are_matched_all_by_default_or_any() {
+        let query = ListTasksHttpQuery {
+            tag: Some("Errands, garden".to_string()),
+            ..Default::default()
+        };
+        let req = query.try_into_domain(&Uuid::new_v4()).unwrap();
+        let tags = req.filter().tags().unwrap();
+        assert_eq!(tags.mode(), TagMatch::All);
+        assert_eq!(
+            tags.tags(),
+            [Tag::new("errands").unwrap(), Tag::new("garden").unwrap()]
+        );
+
+        let query = ListTasksHttpQuery {
+            tag: Some("errands".to_string()),
+            tag_match: Some("any".to_string()),
+            ..Default::default()
+        };
+        let req = query.try_into_domain(&Uuid::new_v4()).unwrap();
+        assert_eq!(req.filter().tags().unwrap().mode(), TagMatch::Any);
+
+        let query = ListTasksHttpQuery {
+            tag: Some("errands,,garden".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            query.try_into_domain(&Uuid::new_v4()),
+            Err(ParseListTasksHttpQueryError::Tag(_))
+        ));
+    }
+
+    #[test]
+    fn test_cursor_round_trip_continues_in_its_sort() {
+        let task = Task::new(Uuid::new_v4(), TaskTitle::new("Water plants").unwrap());
+        let cursor = TaskCursor::after(TaskSort::parse("-due").unwrap(), &task);
+        let query = ListTasksHttpQuery {
+            cursor: Some(encode_cursor(&cursor)),
+            ..Default::default()
+        };
+        let req = query.try_into_domain(&Uuid::new_v4()).unwrap();
+        assert_eq!(req.sort(), cursor.sort());
+        assert_eq!(req.cursor(), Some(&cursor));
+
+        let task = Task {
+            priority: Priority::High,
+            ..task
+        };
+        let cursor = TaskCursor::after(TaskSort::parse("-priority").unwrap(), &task);
+        let query = ListTasksHttpQuery {
+            cursor: Some(encode_cursor(&cursor)),
+            ..Default::default()
+        };
+        let req = query.try_into_domain(&Uuid::new_v4()).unwrap();
+        assert_eq!(req.sort().to_string(), "-priority");
+        assert_eq!(req.cursor().unwrap().priority(), Priority::High);
+
+        let query = ListTasksHttpQuery {
+            cursor: Some("not-a-cursor".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            query.try_into_domain(&Uuid::new_v4()),
+            Err(ParseListTasksHttpQueryError::Cursor)
+        ));
+    }
+
+    #[test]
+    fn test_assignee_me_is_the_current_user() {
+        let user = Uuid::new_v4();
+        let query = ListTasksHttpQuery {
+            assignee: Some("me".to_string()),
+            ..Default::default()
+        };
+        let req = query.try_into_domain(&user).unwrap();
+        assert_eq!(req.filter().assignee(), Some(user));
+
+        let query = ListTasksHttpQuery {
+            assignee: Some("someone".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            query.try_into_domain(&user),
+            Err(ParseListTasksHttpQueryError::Assignee(_))
+        ));
+    }
+}
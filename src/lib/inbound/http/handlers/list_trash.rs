@@ -0,0 +1,166 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::list::TaskList;
+use crate::domain::reminders::models::task::Task;
+use crate::domain::reminders::models::trash::{ListTrashError, Trash, Trashed};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::get_list::TaskListResponseData;
+use crate::inbound::http::handlers::get_task::TaskResponseData;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<ListTrashError> for ApiError {
+    fn from(e: ListTrashError) -> Self {
+        match e {
+            ListTrashError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// A [Task] in the trash, with the time it was deleted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TrashedTaskResponseData {
+    #[serde(flatten)]
+    task: TaskResponseData,
+    deleted_at: String,
+}
+
+impl From<&Trashed<Task>> for TrashedTaskResponseData {
+    fn from(trashed: &Trashed<Task>) -> Self {
+        Self {
+            task: trashed.item().into(),
+            deleted_at: trashed.deleted_at().to_rfc3339(),
+        }
+    }
+}
+
+/// A [TaskList] in the trash, with the time it was deleted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TrashedTaskListResponseData {
+    #[serde(flatten)]
+    list: TaskListResponseData,
+    deleted_at: String,
+}
+
+impl From<&Trashed<TaskList>> for TrashedTaskListResponseData {
+    fn from(trashed: &Trashed<TaskList>) -> Self {
+        Self {
+            list: trashed.item().into(),
+            deleted_at: trashed.deleted_at().to_rfc3339(),
+        }
+    }
+}
+
+/// The response body data field for the [Trash].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TrashResponseData {
+    lists: Vec<TrashedTaskListResponseData>,
+    tasks: Vec<TrashedTaskResponseData>,
+}
+
+impl From<&Trash> for TrashResponseData {
+    fn from(trash: &Trash) -> Self {
+        Self {
+            lists: trash.lists().iter().map(Into::into).collect(),
+            tasks: trash.tasks().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// List the deleted tasks and lists that can still be restored, most recently deleted first.
+///
+/// # Responses
+///
+/// - 200 OK: the trash was listed.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is restricted to a task list.
+#[utoipa::path(
+    get,
+    path = "/api/trash",
+    tag = "trash",
+    responses(
+        (
+            status = 200,
+            description = "The trash was listed",
+            body = ApiResponseBody<TrashResponseData>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is restricted to a task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn list_trash<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+) -> Result<ApiSuccess<TrashResponseData>, ApiError> {
+    scope.require_all_lists()?;
+    state
+        .reminder_service
+        .list_trash(&user.id())
+        .await
+        .map_err(ApiError::from)
+        .map(|ref trash| ApiSuccess::new(StatusCode::OK, trash.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::reminders::models::task::TaskTitle;
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use chrono::Utc;
+    use std::sync::{Arc, Mutex};
+    use uuid::Uuid;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_list_trash_success() {
+        let task = Task::new(Uuid::new_v4(), TaskTitle::new("Pack").unwrap());
+        let trash = Trash {
+            lists: vec![],
+            tasks: vec![Trashed {
+                item: task.clone(),
+                deleted_at: Utc::now(),
+            }],
+        };
+        let service = MockReminderService {
+            list_trash_result: Arc::new(Mutex::new(Ok(trash.clone()))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let actual = list_trash(state, current_user(), full_scope()).await;
+        let expected = ApiSuccess::new(
+            StatusCode::OK,
+            TrashResponseData {
+                lists: vec![],
+                tasks: vec![TrashedTaskResponseData {
+                    task: (&task).into(),
+                    deleted_at: trash.tasks[0].deleted_at.to_rfc3339(),
+                }],
+            },
+        );
+        assert_eq!(actual, Ok(expected));
+    }
+}
@@ -6,6 +6,14 @@ use axum::http::StatusCode;
 /// # Responses
 ///
 /// - 200 OK: the server is alive.
+#[utoipa::path(
+    get,
+    path = "/api/liveness",
+    tag = "health",
+    responses(
+        (status = 200, description = "The server is alive"),
+    ),
+)]
 pub async fn liveness() -> Result<ApiSuccess<()>, ApiError> {
     Ok(ApiSuccess::new(StatusCode::OK, ()))
 }
@@ -0,0 +1,154 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::models::session::{LogInError, LogInRequest, Session};
+use crate::domain::users::models::user::{EmailAddress, Password};
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::extract::Json;
+use crate::inbound::http::handlers::register_user::UserResponseData;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<LogInError> for ApiError {
+    fn from(e: LogInError) -> Self {
+        match e {
+            LogInError::InvalidCredentials => {
+                Self::Unauthorized(ErrorDetail::new("auth.invalid_credentials", e.to_string()))
+            }
+            LogInError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// The response body data field for a new [Session]. This is the only time the token is
+/// returned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct SessionResponseData {
+    token: String,
+    expires_at: String,
+    user: UserResponseData,
+}
+
+impl From<&Session> for SessionResponseData {
+    fn from(session: &Session) -> Self {
+        Self {
+            token: session.token().as_str().to_string(),
+            expires_at: session.expires_at().to_rfc3339(),
+            user: session.user().into(),
+        }
+    }
+}
+
+/// The body of a sign in request.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct LogInHttpRequestBody {
+    email: String,
+    password: String,
+}
+
+impl LogInHttpRequestBody {
+    /// Converts the HTTP request body into a domain request. Credentials that could never have
+    /// been registered are reported like any other wrong credentials.
+    fn try_into_domain(self) -> Result<LogInRequest, LogInError> {
+        let email = EmailAddress::new(&self.email).map_err(|_| LogInError::InvalidCredentials)?;
+        let password = Password::new(&self.password).map_err(|_| LogInError::InvalidCredentials)?;
+        Ok(LogInRequest::new(email, password))
+    }
+}
+
+/// Sign a [User](crate::domain::users::models::user::User) in, starting a [Session] whose token
+/// is sent as `Authorization: Bearer <token>` with later requests.
+///
+/// # Responses
+///
+/// - 201 Created: the [Session] was started.
+/// - 401 Unauthorized: the email address or password is wrong.
+#[utoipa::path(
+    post,
+    path = "/api/sessions",
+    tag = "sessions",
+    request_body = LogInHttpRequestBody,
+    responses(
+        (
+            status = 201,
+            description = "The session was started",
+            body = ApiResponseBody<SessionResponseData>,
+        ),
+        (
+            status = 401,
+            description = "The email address or password is wrong",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+)]
+pub async fn log_in<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    Json(body): Json<LogInHttpRequestBody>,
+) -> Result<ApiSuccess<SessionResponseData>, ApiError> {
+    let domain_req = body.try_into_domain()?;
+    state
+        .user_service
+        .log_in(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref session| ApiSuccess::new(StatusCode::CREATED, session.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inbound::http::handlers::test_utils::{
+        current_user, user_app_state, MockUserService,
+    };
+    use chrono::Utc;
+    use std::sync::{Arc, Mutex};
+
+    fn body(email: &str, password: &str) -> Json<LogInHttpRequestBody> {
+        Json(LogInHttpRequestBody {
+            email: email.to_string(),
+            password: password.to_string(),
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_log_in_success() {
+        let session = Session::start(current_user().0, Utc::now());
+        let service = MockUserService {
+            log_in_result: Arc::new(Mutex::new(Ok(session.clone()))),
+            ..Default::default()
+        };
+        let state = State(user_app_state(service));
+        let expected = ApiSuccess::new(StatusCode::CREATED, (&session).into());
+        let actual = log_in(state, body("ada@example.com", "correct horse")).await;
+        assert_eq!(actual, Ok(expected));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_log_in_invalid_credentials() {
+        let service = MockUserService {
+            log_in_result: Arc::new(Mutex::new(Err(LogInError::InvalidCredentials))),
+            ..Default::default()
+        };
+        let state = State(user_app_state(service));
+        let expected = Err(ApiError::Unauthorized(ErrorDetail::new(
+            "auth.invalid_credentials",
+            "invalid email address or password".to_string(),
+        )));
+        let actual = log_in(state, body("ada@example.com", "battery staple")).await;
+        assert_eq!(actual, expected);
+
+        let state = State(user_app_state(MockUserService::default()));
+        let actual = log_in(state, body("ada@example.com", "short")).await;
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,55 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::models::session::LogOutError;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::BearerToken;
+use crate::inbound::http::handlers::shared::{ApiError, ProblemDetails};
+use crate::inbound::http::AppState;
+
+impl From<LogOutError> for ApiError {
+    fn from(e: LogOutError) -> Self {
+        match e {
+            LogOutError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// End the [Session](crate::domain::users::models::session::Session) of the bearer token of the
+/// request. Ending a session that already ended or expired succeeds.
+///
+/// # Responses
+///
+/// - 204 No Content: the session has ended.
+/// - 401 Unauthorized: the request has no bearer token.
+#[utoipa::path(
+    delete,
+    path = "/api/sessions/current",
+    tag = "sessions",
+    responses(
+        (status = 204, description = "The session has ended"),
+        (
+            status = 401,
+            description = "The request has no bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn log_out<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    BearerToken(token): BearerToken,
+) -> Result<StatusCode, ApiError> {
+    state
+        .user_service
+        .log_out(&token)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,130 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Deserialize;
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::tag::{MergeTagsError, Tag, TagInvalidError};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::{Json, Path};
+use crate::inbound::http::handlers::list_tags::TagResponseData;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<MergeTagsError> for ApiError {
+    fn from(e: MergeTagsError) -> Self {
+        match e {
+            MergeTagsError::NotFound { tag } => Self::UnprocessableEntity(ErrorDetail::new(
+                "tag.not_found",
+                format!("tag {} not found", tag),
+            )),
+            MergeTagsError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<ParseMergeTagsHttpRequestError> for ApiError {
+    fn from(e: ParseMergeTagsHttpRequestError) -> Self {
+        Self::UnprocessableEntity(ErrorDetail::invalid_field("tags", e.to_string()))
+    }
+}
+
+/// The body of a tag merge request, holding the tags to merge into the tag of the path.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct MergeTagsHttpRequestBody {
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Error)]
+enum ParseMergeTagsHttpRequestError {
+    #[error(transparent)]
+    Tag(#[from] TagInvalidError),
+    #[error("at least one tag to merge is required")]
+    Empty,
+}
+
+impl MergeTagsHttpRequestBody {
+    /// Converts the HTTP request body into the [Tag]s to merge.
+    fn try_into_domain(self) -> Result<Vec<Tag>, ParseMergeTagsHttpRequestError> {
+        let tags = self
+            .tags
+            .iter()
+            .map(|raw| Tag::new(raw))
+            .collect::<Result<Vec<_>, _>>()?;
+        if tags.is_empty() {
+            return Err(ParseMergeTagsHttpRequestError::Empty);
+        }
+        Ok(tags)
+    }
+}
+
+/// Merge the tags of the request body into the tag of the path, which may be new, on every task
+/// that carries them.
+///
+/// # Responses
+///
+/// - 200 OK: the tags were merged.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is read-only or restricted to another task list.
+/// - 422 Unprocessable Entity: the request is invalid or no task carries one of the tags to
+///   merge.
+#[utoipa::path(
+    post,
+    path = "/api/tags/{tag}/merge",
+    tag = "tags",
+    params(
+        ("tag" = String, Path, description = "The tag to merge the others into"),
+    ),
+    request_body = MergeTagsHttpRequestBody,
+    responses(
+        (
+            status = 200,
+            description = "The tags were merged",
+            body = ApiResponseBody<TagResponseData>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is read-only or restricted to another task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 422,
+            description = "The request is invalid or no task carries one of the tags to merge",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn merge_tags<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path(tag): Path<String>,
+    Json(body): Json<MergeTagsHttpRequestBody>,
+) -> Result<ApiSuccess<TagResponseData>, ApiError> {
+    scope.require_all_lists()?;
+    let into = Tag::new(&tag).map_err(ParseMergeTagsHttpRequestError::from)?;
+    let tags = body.try_into_domain()?;
+    state
+        .reminder_service
+        .merge_tags(&user.id(), &tags, &into)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref usage| ApiSuccess::new(StatusCode::OK, usage.into()))
+}
@@ -0,0 +1,52 @@
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::domain::metrics::ports::Metrics;
+use crate::inbound::http::handlers::shared::ApiError;
+
+/// The route recorded for requests that match no route, so that unknown paths cannot grow the
+/// number of recorded routes without bound.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Middleware that records every request with the route it matched, the status of its response
+/// and how long it took to answer.
+pub async fn record_request<M: Metrics>(
+    State(metrics): State<Arc<M>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let started = Instant::now();
+    let method = req.method().clone();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+    let response = next.run(req).await;
+    metrics.record_http_request(
+        method.as_str(),
+        &route,
+        response.status().as_u16(),
+        started.elapsed(),
+    );
+    response
+}
+
+/// Render the metrics for Prometheus to scrape. No bearer token is needed.
+///
+/// # Responses
+///
+/// - 200 OK: the metrics in the Prometheus text exposition format.
+pub async fn render_metrics<M: Metrics>(
+    State(metrics): State<Arc<M>>,
+) -> Result<Response, ApiError> {
+    let rendered = metrics.render().map_err(|cause| {
+        tracing::error!("{:?}\n{}", cause, cause.backtrace());
+        ApiError::InternalServerError("Internal server error".to_string())
+    })?;
+    Ok(([(CONTENT_TYPE, "text/plain; version=0.0.4")], rendered).into_response())
+}
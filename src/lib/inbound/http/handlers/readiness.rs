@@ -1,25 +1,42 @@
 use crate::{
-    domain::{readiness::ports::ReadinessService, reminders::ports::ReminderService},
+    domain::{
+        readiness::ports::ReadinessService, reminders::ports::ReminderService,
+        users::ports::UserService,
+    },
     inbound::http::{
-        handlers::shared::{ApiError, ApiSuccess},
+        handlers::shared::{ApiError, ApiSuccess, ErrorDetail, ProblemDetails},
         AppState,
     },
 };
 use axum::{extract::State, http::StatusCode};
 
-/// Check if the server is ready to accept requests.
+/// Check if the server is ready to accept requests. No bearer token is needed, so that probes
+/// can call it.
 ///
 /// # Responses
 ///
 /// - 200 OK: the server is ready.
-pub async fn readiness<RS: ReminderService, RD: ReadinessService>(
-    State(state): State<AppState<RS, RD>>,
+/// - 503 Service Unavailable: the database cannot be reached.
+#[utoipa::path(
+    get,
+    path = "/api/readiness",
+    tag = "health",
+    responses(
+        (status = 200, description = "The server is ready"),
+        (
+            status = 503,
+            description = "The database cannot be reached",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+)]
+pub async fn readiness<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
 ) -> Result<ApiSuccess<()>, ApiError> {
-    state
-        .readiness_service
-        .is_ready()
-        .await
-        .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+    state.readiness_service.is_ready().await.map_err(|e| {
+        ApiError::ServiceUnavailable(ErrorDetail::new("service.unavailable", e.to_string()))
+    })?;
 
     Ok(ApiSuccess::new(StatusCode::OK, ()))
 }
@@ -28,57 +45,34 @@ pub async fn readiness<RS: ReminderService, RD: ReadinessService>(
 mod tests {
     use super::*;
     use crate::domain::readiness::models::ready::ReadinessError;
-    use crate::domain::reminders::models::task::CreateTaskError;
-    use crate::domain::reminders::models::task::{CreateTaskRequest, Task, TaskTitle};
-    use crate::domain::reminders::ports::ReminderService;
-    use anyhow::anyhow;
-    use std::mem;
-    use std::sync::Arc;
-    use uuid::Uuid;
-
-    #[derive(Clone)]
-    struct MockReminderService {
-        create_task_result: Arc<std::sync::Mutex<Result<Task, CreateTaskError>>>,
-    }
-
-    impl ReminderService for MockReminderService {
-        async fn create_task(&self, _: &CreateTaskRequest) -> Result<Task, CreateTaskError> {
-            let mut guard = self.create_task_result.lock();
-            let mut result = Err(CreateTaskError::Unknown(anyhow!("substitute error")));
-            mem::swap(guard.as_deref_mut().unwrap(), &mut result);
-            result
-        }
-    }
-
-    #[derive(Clone)]
-    struct MockReadinessService {
-        is_ready_result: Arc<std::sync::Mutex<Result<(), ReadinessError>>>,
-    }
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, MockReadinessService, MockReminderService,
+    };
+    use std::sync::{Arc, Mutex};
 
-    impl ReadinessService for MockReadinessService {
-        async fn is_ready(&self) -> Result<(), ReadinessError> {
-            let mut guard = self.is_ready_result.lock();
-            let mut result = Err(ReadinessError::DatabaseNotReady);
-            mem::swap(guard.as_deref_mut().unwrap(), &mut result);
-            result
-        }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_readiness_success() {
+        let state = State(app_state(
+            MockReminderService::default(),
+            MockReadinessService::default(),
+        ));
+        let actual = readiness(state).await;
+        assert!(actual.is_ok());
     }
 
     #[tokio::test(flavor = "multi_thread")]
-    async fn test_readiness_success() {
+    async fn test_readiness_database_not_ready() {
         let readiness_service = MockReadinessService {
-            is_ready_result: Arc::new(std::sync::Mutex::new(Ok(()))),
+            is_ready_result: Arc::new(Mutex::new(Err(ReadinessError::DatabaseNotReady))),
         };
-        let state = axum::extract::State(AppState {
-            reminder_service: Arc::new(MockReminderService {
-                create_task_result: Arc::new(std::sync::Mutex::new(Ok(Task::new(
-                    Uuid::new_v4(),
-                    TaskTitle::new("Clean apartment").unwrap(),
-                )))),
-            }),
-            readiness_service: Arc::new(readiness_service),
-        });
+        let state = State(app_state(MockReminderService::default(), readiness_service));
         let actual = readiness(state).await;
-        assert!(actual.is_ok());
+        assert_eq!(
+            actual,
+            Err(ApiError::ServiceUnavailable(ErrorDetail::new(
+                "service.unavailable",
+                ReadinessError::DatabaseNotReady.to_string(),
+            )))
+        );
     }
 }
@@ -0,0 +1,193 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::models::user::{
+    EmailAddress, EmailAddressInvalidError, Password, PasswordInvalidError, RegisterUserError,
+    RegisterUserRequest, User,
+};
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::extract::Json;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<RegisterUserError> for ApiError {
+    fn from(e: RegisterUserError) -> Self {
+        match e {
+            RegisterUserError::Duplicate { .. } => {
+                Self::UnprocessableEntity(ErrorDetail::new("user.duplicate_email", e.to_string()))
+            }
+            RegisterUserError::Closed => {
+                Self::Forbidden(ErrorDetail::new("user.registration_closed", e.to_string()))
+            }
+            RegisterUserError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<ParseRegisterUserHttpRequestError> for ApiError {
+    fn from(e: ParseRegisterUserHttpRequestError) -> Self {
+        let field = match e {
+            ParseRegisterUserHttpRequestError::Email(_) => "email",
+            ParseRegisterUserHttpRequestError::Password(_) => "password",
+        };
+        Self::UnprocessableEntity(ErrorDetail::invalid_field(field, e.to_string()))
+    }
+}
+
+/// The response body data field for a single [User]. The password hash is never returned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct UserResponseData {
+    id: String,
+    email: String,
+    created_at: String,
+}
+
+impl From<&User> for UserResponseData {
+    fn from(user: &User) -> Self {
+        Self {
+            id: user.id().to_string(),
+            email: user.email().to_string(),
+            created_at: user.created_at().to_rfc3339(),
+        }
+    }
+}
+
+/// The body of a [User] registration request.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct RegisterUserHttpRequestBody {
+    email: String,
+    password: String,
+}
+
+#[derive(Debug, Clone, Error)]
+enum ParseRegisterUserHttpRequestError {
+    #[error(transparent)]
+    Email(#[from] EmailAddressInvalidError),
+    #[error(transparent)]
+    Password(#[from] PasswordInvalidError),
+}
+
+impl RegisterUserHttpRequestBody {
+    /// Converts the HTTP request body into a domain request.
+    fn try_into_domain(self) -> Result<RegisterUserRequest, ParseRegisterUserHttpRequestError> {
+        let email = EmailAddress::new(&self.email)?;
+        let password = Password::new(&self.password)?;
+        Ok(RegisterUserRequest::new(email, password))
+    }
+}
+
+/// Register a new [User], who can then sign in with their email address and password.
+///
+/// # Responses
+///
+/// - 201 Created: the [User] was successfully registered.
+/// - 403 Forbidden: registration is closed.
+/// - 422 Unprocessable Entity: the request is invalid or a [User] with the same email address
+///   already exists.
+#[utoipa::path(
+    post,
+    path = "/api/users",
+    tag = "users",
+    request_body = RegisterUserHttpRequestBody,
+    responses(
+        (
+            status = 201,
+            description = "The user was successfully registered",
+            body = ApiResponseBody<UserResponseData>,
+        ),
+        (
+            status = 403,
+            description = "Registration is closed",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 422,
+            description = "The request is invalid or a user with the same email address already \
+                           exists",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+)]
+pub async fn register_user<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    Json(body): Json<RegisterUserHttpRequestBody>,
+) -> Result<ApiSuccess<UserResponseData>, ApiError> {
+    let domain_req = body.try_into_domain()?;
+    state
+        .user_service
+        .register_user(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref user| ApiSuccess::new(StatusCode::CREATED, user.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inbound::http::handlers::test_utils::{
+        current_user, user_app_state, MockUserService,
+    };
+    use std::sync::{Arc, Mutex};
+
+    fn body(email: &str, password: &str) -> Json<RegisterUserHttpRequestBody> {
+        Json(RegisterUserHttpRequestBody {
+            email: email.to_string(),
+            password: password.to_string(),
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_register_user_success() {
+        let user = current_user().0;
+        let service = MockUserService {
+            register_user_result: Arc::new(Mutex::new(Ok(user.clone()))),
+            ..Default::default()
+        };
+        let state = State(user_app_state(service));
+        let expected = ApiSuccess::new(StatusCode::CREATED, (&user).into());
+        let actual = register_user(state, body("Ada@Example.com", "correct horse")).await;
+        assert_eq!(actual, Ok(expected));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_register_user_short_password() {
+        let state = State(user_app_state(MockUserService::default()));
+        let actual = register_user(state, body("ada@example.com", "short")).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::UnprocessableEntity(ErrorDetail::invalid_field(
+                "password",
+                "password must be between 8 and 128 characters long"
+            )))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_register_user_closed() {
+        let service = MockUserService {
+            register_user_result: Arc::new(Mutex::new(Err(RegisterUserError::Closed))),
+            ..Default::default()
+        };
+        let state = State(user_app_state(service));
+        let actual = register_user(state, body("ada@example.com", "correct horse")).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::Forbidden(ErrorDetail::new(
+                "user.registration_closed",
+                "registration is closed"
+            )))
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Deserialize;
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::tag::{RenameTagError, Tag, TagInvalidError};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::{Json, Path};
+use crate::inbound::http::handlers::list_tags::TagResponseData;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<RenameTagError> for ApiError {
+    fn from(e: RenameTagError) -> Self {
+        match e {
+            RenameTagError::NotFound { tag } => Self::NotFound(ErrorDetail::new(
+                "tag.not_found",
+                format!("tag {} not found", tag),
+            )),
+            e @ RenameTagError::Duplicate { .. } => {
+                Self::UnprocessableEntity(ErrorDetail::new("tag.duplicate", e.to_string()))
+            }
+            RenameTagError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<ParseRenameTagHttpRequestError> for ApiError {
+    fn from(e: ParseRenameTagHttpRequestError) -> Self {
+        Self::UnprocessableEntity(ErrorDetail::invalid_field("tag", e.to_string()))
+    }
+}
+
+/// The body of a tag rename request, holding the new name of the tag.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct RenameTagHttpRequestBody {
+    tag: String,
+}
+
+#[derive(Debug, Clone, Error)]
+enum ParseRenameTagHttpRequestError {
+    #[error(transparent)]
+    Tag(#[from] TagInvalidError),
+}
+
+impl RenameTagHttpRequestBody {
+    /// Converts the HTTP request body into the new [Tag].
+    fn try_into_domain(self) -> Result<Tag, ParseRenameTagHttpRequestError> {
+        Ok(Tag::new(&self.tag)?)
+    }
+}
+
+/// Rename a tag on every task that carries it.
+///
+/// # Responses
+///
+/// - 200 OK: the tag was renamed.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is read-only or restricted to another task list.
+/// - 404 Not Found: no task carries the tag.
+/// - 422 Unprocessable Entity: the request is invalid or the new name is already in use, in
+///   which case the tags should be merged instead.
+#[utoipa::path(
+    patch,
+    path = "/api/tags/{tag}",
+    tag = "tags",
+    params(("tag" = String, Path, description = "The tag to rename")),
+    request_body = RenameTagHttpRequestBody,
+    responses(
+        (
+            status = 200,
+            description = "The tag was renamed",
+            body = ApiResponseBody<TagResponseData>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is read-only or restricted to another task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 404,
+            description = "No task carries the tag",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 422,
+            description = "The request is invalid or the new name is already in use, in which case \
+                           the tags should be merged instead",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn rename_tag<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path(tag): Path<String>,
+    Json(body): Json<RenameTagHttpRequestBody>,
+) -> Result<ApiSuccess<TagResponseData>, ApiError> {
+    scope.require_all_lists()?;
+    let from = Tag::new(&tag).map_err(ParseRenameTagHttpRequestError::from)?;
+    let to = body.try_into_domain()?;
+    state
+        .reminder_service
+        .rename_tag(&user.id(), &from, &to)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref usage| ApiSuccess::new(StatusCode::OK, usage.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_rename_tag_to_tag_in_use() {
+        let service = MockReminderService {
+            rename_tag_result: Arc::new(Mutex::new(Err(RenameTagError::Duplicate {
+                tag: Tag::new("home").unwrap(),
+            }))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let body = Json(RenameTagHttpRequestBody {
+            tag: "Home".to_string(),
+        });
+        let actual = rename_tag(
+            state,
+            current_user(),
+            full_scope(),
+            Path("house".to_string()),
+            body,
+        )
+        .await;
+        assert_eq!(
+            actual,
+            Err(ApiError::UnprocessableEntity(ErrorDetail::new(
+                "tag.duplicate",
+                "tag home already exists, merge the tags instead"
+            )))
+        );
+    }
+}
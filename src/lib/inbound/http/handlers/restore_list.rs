@@ -0,0 +1,100 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::list::RestoreTaskListError;
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::Path;
+use crate::inbound::http::handlers::get_list::TaskListResponseData;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<RestoreTaskListError> for ApiError {
+    fn from(e: RestoreTaskListError) -> Self {
+        match e {
+            e @ RestoreTaskListError::NotFound { .. } => {
+                Self::NotFound(ErrorDetail::new("list.not_found", e.to_string()))
+            }
+            e @ RestoreTaskListError::Duplicate { .. } => {
+                Self::UnprocessableEntity(ErrorDetail::new("list.duplicate_title", e.to_string()))
+            }
+            e @ RestoreTaskListError::TaskDuplicate { .. } => {
+                Self::UnprocessableEntity(ErrorDetail::new("task.duplicate_title", e.to_string()))
+            }
+            RestoreTaskListError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Restore a [TaskList](crate::domain::reminders::models::list::TaskList) from the trash, along
+/// with the tasks deleted with it.
+///
+/// # Responses
+///
+/// - 200 OK: the list was restored.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is read-only or restricted to a task list.
+/// - 404 Not Found: no list with the given id is in the trash.
+/// - 422 Unprocessable Entity: an unarchived list with the same title, or an incomplete task
+///   with the title of one of its tasks, already exists.
+#[utoipa::path(
+    post,
+    path = "/api/lists/{id}/restore",
+    tag = "trash",
+    params(("id" = Uuid, Path, description = "The id of the task list")),
+    responses(
+        (
+            status = 200,
+            description = "The list was restored",
+            body = ApiResponseBody<TaskListResponseData>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is read-only or restricted to a task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 404,
+            description = "No list with the given id is in the trash",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 422,
+            description = "An unarchived list with the same title, or an incomplete task with \
+                           the title of one of its tasks, already exists",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn restore_list<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path(id): Path<Uuid>,
+) -> Result<ApiSuccess<TaskListResponseData>, ApiError> {
+    scope.require_all_lists()?;
+    state
+        .reminder_service
+        .restore_list(&user.id(), &id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref list| ApiSuccess::new(StatusCode::OK, list.into()))
+}
@@ -0,0 +1,167 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::task::RestoreTaskError;
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::etag::{ETag, Tagged};
+use crate::inbound::http::handlers::extract::Path;
+use crate::inbound::http::handlers::get_task::TaskResponseData;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<RestoreTaskError> for ApiError {
+    fn from(e: RestoreTaskError) -> Self {
+        match e {
+            e @ RestoreTaskError::NotFound { .. } => {
+                Self::NotFound(ErrorDetail::new("task.not_found", e.to_string()))
+            }
+            e @ RestoreTaskError::Duplicate { .. } => {
+                Self::UnprocessableEntity(ErrorDetail::new("task.duplicate_title", e.to_string()))
+            }
+            e @ RestoreTaskError::ParentDeleted { .. } => {
+                Self::Conflict(ErrorDetail::new("task.parent_deleted", e.to_string()))
+            }
+            e @ RestoreTaskError::ListDeleted { .. } => {
+                Self::Conflict(ErrorDetail::new("task.list_deleted", e.to_string()))
+            }
+            e @ RestoreTaskError::Forbidden { .. } => {
+                Self::Forbidden(ErrorDetail::new("list.role_forbidden", e.to_string()))
+            }
+            RestoreTaskError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Restore a [Task](crate::domain::reminders::models::task::Task) from the trash, along with the
+/// subtasks deleted with it.
+///
+/// # Responses
+///
+/// - 200 OK: the task was restored.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is read-only or restricted to a task list, or the user's role in
+///   the task list does not allow the change.
+/// - 404 Not Found: no task with the given id is in the trash.
+/// - 409 Conflict: the parent or list of the task is in the trash and must be restored first.
+/// - 422 Unprocessable Entity: an incomplete task with the same title already exists.
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{id}/restore",
+    tag = "trash",
+    params(("id" = Uuid, Path, description = "The id of the task")),
+    responses(
+        (
+            status = 200,
+            description = "The task was restored",
+            body = ApiResponseBody<TaskResponseData>,
+            headers(("ETag" = String, description = "The version of the task")),
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is read-only or restricted to a task list, or the user's \
+                           role in the task list does not allow the change",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 404,
+            description = "No task with the given id is in the trash",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 409,
+            description = "The parent or list of the task is in the trash",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 422,
+            description = "An incomplete task with the same title already exists",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn restore_task<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path(id): Path<Uuid>,
+) -> Result<Tagged<ApiSuccess<TaskResponseData>>, ApiError> {
+    scope.require_all_lists()?;
+    state
+        .reminder_service
+        .restore_task(&user.id(), &id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref task| {
+            Tagged::Body(
+                ETag::task(task),
+                ApiSuccess::new(StatusCode::OK, task.into()),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::reminders::models::task::{Task, TaskTitle};
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_restore_task_success() {
+        let mut task = Task::new(Uuid::new_v4(), TaskTitle::new("Pack").unwrap());
+        task.version = 3;
+        let service = MockReminderService {
+            restore_task_result: Arc::new(Mutex::new(Ok(task.clone()))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let actual = restore_task(state, current_user(), full_scope(), Path(task.id())).await;
+        assert_eq!(
+            actual,
+            Ok(Tagged::Body(
+                ETag::task(&task),
+                ApiSuccess::new(StatusCode::OK, (&task).into())
+            ))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_restore_task_with_parent_in_trash() {
+        let id = Uuid::new_v4();
+        let service = MockReminderService {
+            restore_task_result: Arc::new(Mutex::new(Err(RestoreTaskError::ParentDeleted { id }))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let actual = restore_task(state, current_user(), full_scope(), Path(Uuid::new_v4())).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::Conflict(ErrorDetail::new(
+                "task.parent_deleted",
+                format!("parent task with id {} is in the trash", id)
+            )))
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::models::api_token::RevokeApiTokenError;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::Path;
+use crate::inbound::http::handlers::shared::{ApiError, ErrorDetail, ProblemDetails};
+use crate::inbound::http::AppState;
+
+impl From<RevokeApiTokenError> for ApiError {
+    fn from(e: RevokeApiTokenError) -> Self {
+        match e {
+            RevokeApiTokenError::NotFound { id } => Self::NotFound(ErrorDetail::new(
+                "api_token.not_found",
+                format!("API token with id {} not found", id),
+            )),
+            RevokeApiTokenError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Revoke an [ApiToken](crate::domain::users::models::api_token::ApiToken) of the current user,
+/// so that its secret no longer authenticates requests.
+///
+/// # Responses
+///
+/// - 204 No Content: the token was revoked.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token of the request is read-only or restricted to a task list.
+/// - 404 Not Found: the user has no token with the given id.
+#[utoipa::path(
+    delete,
+    path = "/api/tokens/{id}",
+    tag = "tokens",
+    params(("id" = Uuid, Path, description = "The id of the API token")),
+    responses(
+        (status = 204, description = "The token was revoked"),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token of the request is read-only or restricted to a task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 404,
+            description = "The user has no token with the given id",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn revoke_api_token<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    scope.require_all_lists()?;
+    state
+        .user_service
+        .revoke_api_token(&user.id(), &id)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inbound::http::handlers::test_utils::{
+        current_user, full_scope, user_app_state, MockUserService,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_revoke_api_token_success() {
+        let service = MockUserService {
+            revoke_api_token_result: Arc::new(Mutex::new(Ok(()))),
+            ..Default::default()
+        };
+        let state = State(user_app_state(service));
+        let actual =
+            revoke_api_token(state, current_user(), full_scope(), Path(Uuid::new_v4())).await;
+        assert_eq!(actual, Ok(StatusCode::NO_CONTENT));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_revoke_api_token_not_found() {
+        let id = Uuid::new_v4();
+        let service = MockUserService {
+            revoke_api_token_result: Arc::new(Mutex::new(Err(RevokeApiTokenError::NotFound {
+                id,
+            }))),
+            ..Default::default()
+        };
+        let state = State(user_app_state(service));
+        let actual = revoke_api_token(state, current_user(), full_scope(), Path(id)).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::NotFound(ErrorDetail::new(
+                "api_token.not_found",
+                format!("API token with id {} not found", id)
+            )))
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Deserialize;
+use thiserror::Error;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::member::{
+    ListRole, ListRoleInvalidError, ShareTaskListError, ShareTaskListRequest,
+};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::models::user::{EmailAddress, EmailAddressInvalidError};
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::{Json, Path};
+use crate::inbound::http::handlers::list_members::TaskListMemberResponseData;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<ShareTaskListError> for ApiError {
+    fn from(e: ShareTaskListError) -> Self {
+        match e {
+            ShareTaskListError::NotFound { id } => Self::NotFound(ErrorDetail::new(
+                "list.not_found",
+                format!("task list with id {} not found", id),
+            )),
+            e @ ShareTaskListError::Forbidden { .. } => {
+                Self::Forbidden(ErrorDetail::new("list.role_forbidden", e.to_string()))
+            }
+            e @ ShareTaskListError::UserNotFound { .. } => {
+                Self::UnprocessableEntity(ErrorDetail::new("user.not_found", e.to_string()))
+            }
+            e @ ShareTaskListError::Creator { .. } => {
+                Self::UnprocessableEntity(ErrorDetail::new("list.member_is_creator", e.to_string()))
+            }
+            ShareTaskListError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<ParseShareTaskListHttpRequestError> for ApiError {
+    fn from(e: ParseShareTaskListHttpRequestError) -> Self {
+        let field = match e {
+            ParseShareTaskListHttpRequestError::Email(_) => "email",
+            ParseShareTaskListHttpRequestError::Role(_) => "role",
+        };
+        Self::UnprocessableEntity(ErrorDetail::invalid_field(field, e.to_string()))
+    }
+}
+
+/// The body of a request to share a task list with the user registered under `email`. `role` is
+/// one of `viewer` (the default), `editor` or `owner`. Sharing with a user the list is already
+/// shared with changes their role.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct ShareTaskListHttpRequestBody {
+    email: String,
+    role: Option<String>,
+}
+
+#[derive(Debug, Clone, Error)]
+enum ParseShareTaskListHttpRequestError {
+    #[error(transparent)]
+    Email(#[from] EmailAddressInvalidError),
+    #[error(transparent)]
+    Role(#[from] ListRoleInvalidError),
+}
+
+impl ShareTaskListHttpRequestBody {
+    /// Converts the HTTP request body into a domain request.
+    fn try_into_domain(self) -> Result<ShareTaskListRequest, ParseShareTaskListHttpRequestError> {
+        let email = EmailAddress::new(&self.email)?;
+        let role = self
+            .role
+            .as_deref()
+            .map(ListRole::parse)
+            .transpose()?
+            .unwrap_or_default();
+        Ok(ShareTaskListRequest::new(email, role))
+    }
+}
+
+/// Share a [TaskList](crate::domain::reminders::models::list::TaskList) with another user, or
+/// change their role in it. Only owners of the list may share it.
+///
+/// # Responses
+///
+/// - 201 Created: the list was shared with the user, or their role changed.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is read-only or restricted to another task list, or the user is
+///   not an owner of the list.
+/// - 404 Not Found: no list with the given id exists.
+/// - 422 Unprocessable Entity: the email address or role is invalid, no user is registered with
+///   the email address, or it is that of the creator of the list.
+#[utoipa::path(
+    post,
+    path = "/api/lists/{id}/members",
+    tag = "lists",
+    params(("id" = Uuid, Path, description = "The id of the task list")),
+    request_body = ShareTaskListHttpRequestBody,
+    responses(
+        (
+            status = 201,
+            description = "The list was shared with the user, or their role changed",
+            body = ApiResponseBody<TaskListMemberResponseData>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is read-only or restricted to another task list, or the user \
+                           is not an owner of the list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 404,
+            description = "No list with the given id exists",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 422,
+            description = "The email address or role is invalid, no user is registered with the \
+                           email address, or it is that of the creator of the list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn share_list<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path(id): Path<Uuid>,
+    Json(body): Json<ShareTaskListHttpRequestBody>,
+) -> Result<ApiSuccess<TaskListMemberResponseData>, ApiError> {
+    scope.require_list(Some(id))?;
+    let req = body.try_into_domain()?;
+    state
+        .reminder_service
+        .share_list(&user.id(), &id, &req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref member| ApiSuccess::new(StatusCode::CREATED, member.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::reminders::models::member::TaskListMember;
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use chrono::Utc;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_share_list_success() {
+        let member = TaskListMember {
+            user_id: Uuid::new_v4(),
+            email: EmailAddress::new("bob@example.com").unwrap(),
+            role: ListRole::Viewer,
+            added_at: Utc::now(),
+        };
+        let service = MockReminderService {
+            share_list_result: Arc::new(Mutex::new(Ok(member.clone()))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let body = Json(ShareTaskListHttpRequestBody {
+            email: "bob@example.com".to_string(),
+            role: None,
+        });
+        let expected = ApiSuccess::new(StatusCode::CREATED, (&member).into());
+        let actual = share_list(
+            state,
+            current_user(),
+            full_scope(),
+            Path(Uuid::new_v4()),
+            body,
+        )
+        .await;
+        assert_eq!(actual, Ok(expected));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_share_list_forbidden() {
+        let id = Uuid::new_v4();
+        let service = MockReminderService {
+            share_list_result: Arc::new(Mutex::new(Err(ShareTaskListError::Forbidden {
+                id,
+                role: ListRole::Editor,
+            }))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let body = Json(ShareTaskListHttpRequestBody {
+            email: "bob@example.com".to_string(),
+            role: Some("owner".to_string()),
+        });
+        let actual = share_list(state, current_user(), full_scope(), Path(id), body).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::Forbidden(ErrorDetail::new(
+                "list.role_forbidden",
+                format!(
+                    "your role in task list {}, editor, does not let you share it",
+                    id
+                )
+            )))
+        );
+    }
+}
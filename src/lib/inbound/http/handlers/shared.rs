@@ -1,6 +1,9 @@
+use axum::http::header::{CONTENT_TYPE, WWW_AUTHENTICATE};
+use axum::http::HeaderValue;
 use axum::response::{IntoResponse, Response};
 use axum::{http::StatusCode, Json};
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::ToSchema;
 
 #[derive(Debug, Clone)]
 pub struct ApiSuccess<T: Serialize + PartialEq>(StatusCode, Json<ApiResponseBody<T>>);
@@ -26,10 +29,34 @@ impl<T: Serialize + PartialEq> IntoResponse for ApiSuccess<T> {
     }
 }
 
+/// An error response of the API, rendered as an RFC 9457 `application/problem+json` document.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ApiError {
+    /// The request cannot be read: its body is not JSON, or a path or query parameter has the
+    /// wrong type.
+    BadRequest(ErrorDetail),
+    /// The request carries no valid credentials. Responses ask for a bearer token.
+    Unauthorized(ErrorDetail),
+    /// The credentials are valid but do not allow the request.
+    Forbidden(ErrorDetail),
+    NotFound(ErrorDetail),
+    Conflict(ErrorDetail),
+    /// A precondition of a conditional request, such as `If-Match`, does not hold.
+    PreconditionFailed(ErrorDetail),
+    /// The request must be conditional, with an `If-Match` header, and is not.
+    PreconditionRequired(ErrorDetail),
+    /// The request is well-formed but its content is invalid.
+    UnprocessableEntity(ErrorDetail),
+    /// The request body is not in a format the route accepts, such as JSON sent without an
+    /// `application/json` `Content-Type`.
+    UnsupportedMediaType(ErrorDetail),
+    /// The client sent too many requests and should retry later.
+    #[allow(dead_code)]
+    TooManyRequests(ErrorDetail),
+    /// A dependency of the server, such as the database, is unavailable.
+    ServiceUnavailable(ErrorDetail),
+    /// An unexpected failure. The message is logged, never sent to the client.
     InternalServerError(String),
-    UnprocessableEntity(String),
 }
 
 impl From<anyhow::Error> for ApiError {
@@ -38,8 +65,53 @@ impl From<anyhow::Error> for ApiError {
     }
 }
 
-/// Generic response structure shared by all API responses.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+/// What went wrong with a request: a stable, machine-readable `code` such as
+/// `task.duplicate_title`, a human-readable message and the request fields at fault, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorDetail {
+    code: &'static str,
+    message: String,
+    fields: Vec<InvalidField>,
+}
+
+impl ErrorDetail {
+    /// The code of [ErrorDetail::invalid_field].
+    pub const INVALID_FIELD: &'static str = "request.invalid_field";
+
+    pub fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// The request `field` failed validation, for the reason in `message`.
+    pub fn invalid_field(field: &str, message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self::new(Self::INVALID_FIELD, message.clone()).with_field(field, message)
+    }
+
+    /// Report that the request `field` is at fault, for the reason in `message`.
+    pub fn with_field(mut self, field: &str, message: impl Into<String>) -> Self {
+        self.fields.push(InvalidField {
+            field: field.to_string(),
+            message: message.into(),
+        });
+        self
+    }
+}
+
+/// A request field that failed validation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct InvalidField {
+    /// The name of the field, as sent in the request body or query string.
+    pub field: String,
+    pub message: String,
+}
+
+/// Generic response structure shared by all successful API responses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
 pub struct ApiResponseBody<T: Serialize + PartialEq> {
     status_code: u16,
     data: T,
@@ -54,45 +126,182 @@ impl<T: Serialize + PartialEq> ApiResponseBody<T> {
     }
 }
 
-impl ApiResponseBody<ApiErrorData> {
-    pub fn new_error(status_code: StatusCode, message: String) -> Self {
+/// The media type of [ProblemDetails] documents.
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// The body of all error responses, an RFC 9457 problem details document. The `type` is always
+/// `about:blank`, so clients should tell problems apart by their `code`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    #[schema(example = "about:blank")]
+    pub problem_type: String,
+    /// The reason phrase of the status code.
+    #[schema(example = "Unprocessable Entity")]
+    pub title: String,
+    #[schema(example = 422)]
+    pub status: u16,
+    /// A human-readable explanation of this occurrence of the problem.
+    #[schema(example = "task with title Buy milk already exists")]
+    pub detail: String,
+    /// A stable, machine-readable code for the problem.
+    #[schema(example = "task.duplicate_title")]
+    pub code: String,
+    /// The request fields that failed validation, for the `request.invalid_field` code.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<InvalidField>,
+}
+
+impl ProblemDetails {
+    pub fn new(status: StatusCode, detail: ErrorDetail) -> Self {
         Self {
-            status_code: status_code.as_u16(),
-            data: ApiErrorData { message },
+            problem_type: "about:blank".to_string(),
+            title: status.canonical_reason().unwrap_or_default().to_string(),
+            status: status.as_u16(),
+            detail: detail.message,
+            code: detail.code.to_string(),
+            errors: detail.fields,
         }
     }
 }
 
-/// The response data format for all error responses.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
-pub struct ApiErrorData {
-    pub message: String,
+impl ApiError {
+    /// The code of [ApiError::InternalServerError] responses.
+    pub const INTERNAL_ERROR: &'static str = "internal_error";
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            Self::PreconditionRequired(_) => StatusCode::PRECONDITION_REQUIRED,
+            Self::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            Self::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         use crate::inbound::http::handlers::shared::ApiError::*;
 
-        match self {
-            InternalServerError(_e) => {
-                // tracing::error!("{:?}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiResponseBody::new_error(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        "Internal server error".to_string(),
-                    )),
-                )
-                    .into_response()
+        let status = self.status();
+        let detail = match self {
+            InternalServerError(e) => {
+                tracing::error!("{:?}", e);
+                ErrorDetail::new(Self::INTERNAL_ERROR, "Internal server error")
             }
-            UnprocessableEntity(message) => (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                Json(ApiResponseBody::new_error(
-                    StatusCode::UNPROCESSABLE_ENTITY,
-                    message,
-                )),
-            )
-                .into_response(),
+            BadRequest(detail)
+            | Unauthorized(detail)
+            | Forbidden(detail)
+            | NotFound(detail)
+            | Conflict(detail)
+            | PreconditionFailed(detail)
+            | PreconditionRequired(detail)
+            | UnprocessableEntity(detail)
+            | UnsupportedMediaType(detail)
+            | TooManyRequests(detail)
+            | ServiceUnavailable(detail) => detail,
+        };
+        let mut response = (
+            status,
+            [(CONTENT_TYPE, PROBLEM_JSON)],
+            Json(ProblemDetails::new(status, detail)),
+        )
+            .into_response();
+        if status == StatusCode::UNAUTHORIZED {
+            response
+                .headers_mut()
+                .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
         }
+        response
+    }
+}
+
+/// Deserialize a field that may be present but `null`, distinguishing it from an absent field
+/// when combined with `#[serde(default)]`: absent is `None`, `null` is `Some(None)`.
+pub fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::to_bytes;
+    use serde_json::{json, Value};
+
+    async fn render(error: ApiError) -> (Response, Value) {
+        let response = error.into_response();
+        let (parts, body) = response.into_parts();
+        let bytes = to_bytes(body, usize::MAX).await.unwrap();
+        (
+            Response::from_parts(parts, axum::body::Body::empty()),
+            serde_json::from_slice(&bytes).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_error_is_rendered_as_problem_details() {
+        let error = ApiError::UnprocessableEntity(ErrorDetail::new(
+            "task.duplicate_title",
+            "task with title Buy milk already exists",
+        ));
+        let (response, problem) = render(error).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(response.headers()[CONTENT_TYPE], PROBLEM_JSON);
+        assert_eq!(
+            problem,
+            json!({
+                "type": "about:blank",
+                "title": "Unprocessable Entity",
+                "status": 422,
+                "detail": "task with title Buy milk already exists",
+                "code": "task.duplicate_title",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_field_is_listed_in_errors() {
+        let error = ApiError::UnprocessableEntity(ErrorDetail::invalid_field(
+            "due_at",
+            "due date is invalid",
+        ));
+        let (_, problem) = render(error).await;
+        assert_eq!(problem["code"], ErrorDetail::INVALID_FIELD);
+        assert_eq!(
+            problem["errors"],
+            json!([{ "field": "due_at", "message": "due date is invalid" }])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_asks_for_a_bearer_token() {
+        let error = ApiError::Unauthorized(ErrorDetail::new(
+            "auth.missing_token",
+            "missing bearer token",
+        ));
+        let (response, _) = render(error).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[WWW_AUTHENTICATE], "Bearer");
+    }
+
+    #[tokio::test]
+    async fn test_internal_server_error_hides_its_cause() {
+        let error = ApiError::InternalServerError("connection refused".to_string());
+        let (response, problem) = render(error).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(problem["code"], ApiError::INTERNAL_ERROR);
+        assert_eq!(problem["detail"], "Internal server error");
     }
 }
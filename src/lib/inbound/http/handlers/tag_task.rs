@@ -0,0 +1,226 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Deserialize;
+use thiserror::Error;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::tag::{Tag, TagInvalidError, TagTaskError};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::{Json, Path};
+use crate::inbound::http::handlers::get_task::TaskResponseData;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<TagTaskError> for ApiError {
+    fn from(e: TagTaskError) -> Self {
+        match e {
+            TagTaskError::NotFound { id } => Self::NotFound(ErrorDetail::new(
+                "task.not_found",
+                format!("task with id {} not found", id),
+            )),
+            TagTaskError::TooMany(e) => {
+                Self::UnprocessableEntity(ErrorDetail::new("task.too_many_tags", e.to_string()))
+            }
+            e @ TagTaskError::Forbidden { .. } => {
+                Self::Forbidden(ErrorDetail::new("list.role_forbidden", e.to_string()))
+            }
+            e @ TagTaskError::Conflict { .. } => {
+                Self::Conflict(ErrorDetail::new("task.version_conflict", e.to_string()))
+            }
+            TagTaskError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<ParseTagTaskHttpRequestError> for ApiError {
+    fn from(e: ParseTagTaskHttpRequestError) -> Self {
+        Self::UnprocessableEntity(ErrorDetail::invalid_field("tags", e.to_string()))
+    }
+}
+
+/// The body of a request to add tags to a task. Tags the task already carries are ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct TagTaskHttpRequestBody {
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Error)]
+enum ParseTagTaskHttpRequestError {
+    #[error(transparent)]
+    Tag(#[from] TagInvalidError),
+}
+
+impl TagTaskHttpRequestBody {
+    /// Converts the HTTP request body into the [Tag]s to add.
+    fn try_into_domain(self) -> Result<Vec<Tag>, ParseTagTaskHttpRequestError> {
+        Ok(self
+            .tags
+            .iter()
+            .map(|raw| Tag::new(raw))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+}
+
+/// Add tags to a [Task](crate::domain::reminders::models::task::Task).
+///
+/// # Responses
+///
+/// - 200 OK: the tags were added.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is read-only or restricted to another task list, or the user's
+///   role in the task list does not allow the change.
+/// - 404 Not Found: no task with the given id exists.
+/// - 409 Conflict: the task kept changing while its tags were being changed.
+/// - 422 Unprocessable Entity: a tag is invalid or the task would carry too many tags.
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{id}/tags",
+    tag = "tasks",
+    params(("id" = Uuid, Path, description = "The id of the task")),
+    request_body = TagTaskHttpRequestBody,
+    responses(
+        (
+            status = 200,
+            description = "The tags were added",
+            body = ApiResponseBody<TaskResponseData>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is read-only or restricted to another task list, or the \
+                           user's role in the task list does not allow the change",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 404,
+            description = "No task with the given id exists",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 409,
+            description = "The task kept changing while its tags were being changed",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 422,
+            description = "A tag is invalid or the task would carry too many tags",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn tag_task<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path(id): Path<Uuid>,
+    Json(body): Json<TagTaskHttpRequestBody>,
+) -> Result<ApiSuccess<TaskResponseData>, ApiError> {
+    scope
+        .require_task(state.reminder_service.as_ref(), &user.id(), &id)
+        .await?;
+    let tags = body.try_into_domain()?;
+    state
+        .reminder_service
+        .tag_task(&user.id(), &id, &tags)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref task| ApiSuccess::new(StatusCode::OK, task.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::reminders::models::tag::TaskTags;
+    use crate::domain::reminders::models::task::{Task, TaskTitle};
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tag_task_success() {
+        let mut task = Task::new(Uuid::new_v4(), TaskTitle::new("Buy stamps").unwrap());
+        task.tags = TaskTags::new(vec![Tag::new("errands").unwrap()]).unwrap();
+        let service = MockReminderService {
+            tag_task_result: Arc::new(Mutex::new(Ok(task.clone()))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let body = Json(TagTaskHttpRequestBody {
+            tags: vec!["Errands".to_string()],
+        });
+        let expected = ApiSuccess::new(StatusCode::OK, (&task).into());
+        let actual = tag_task(state, current_user(), full_scope(), Path(task.id()), body).await;
+        assert_eq!(actual, Ok(expected));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tag_task_conflict() {
+        let id = Uuid::new_v4();
+        let service = MockReminderService {
+            tag_task_result: Arc::new(Mutex::new(Err(TagTaskError::Conflict { id }))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let body = Json(TagTaskHttpRequestBody {
+            tags: vec!["errands".to_string()],
+        });
+        let actual = tag_task(state, current_user(), full_scope(), Path(id), body).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::Conflict(ErrorDetail::new(
+                "task.version_conflict",
+                format!(
+                    "task with id {} kept changing while its tags were being changed",
+                    id
+                )
+            )))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tag_task_invalid_tag() {
+        let state = State(app_state(
+            MockReminderService::default(),
+            MockReadinessService::default(),
+        ));
+        let body = Json(TagTaskHttpRequestBody {
+            tags: vec!["home,garden".to_string()],
+        });
+        let actual = tag_task(
+            state,
+            current_user(),
+            full_scope(),
+            Path(Uuid::new_v4()),
+            body,
+        )
+        .await;
+        assert_eq!(
+            actual,
+            Err(ApiError::UnprocessableEntity(ErrorDetail::invalid_field(
+                "tags",
+                "tag \"home,garden\" must be between 1 and 32 characters long and cannot contain \
+                 commas"
+            )))
+        );
+    }
+}
@@ -0,0 +1,472 @@
+use crate::domain::readiness::models::ready::ReadinessError;
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::list::{
+    CreateTaskListError, DeleteTaskListError, GetTaskListError, ListTaskListsError,
+    RestoreTaskListError, UpdateTaskListError,
+};
+use crate::domain::reminders::models::list::{
+    CreateTaskListRequest, DeleteTaskListMode, TaskList, UpdateTaskListRequest,
+};
+use crate::domain::reminders::models::member::{
+    ListTaskListMembersError, ShareTaskListError, ShareTaskListRequest, TaskListMember,
+    UnshareTaskListError,
+};
+use crate::domain::reminders::models::query::{ListTasksRequest, TaskPage};
+use crate::domain::reminders::models::reminder::{FireRemindersError, FiredReminders};
+use crate::domain::reminders::models::tag::{
+    ListTagsError, MergeTagsError, RenameTagError, TagTaskError, UntagTaskError,
+};
+use crate::domain::reminders::models::tag::{Tag, TagUsage};
+use crate::domain::reminders::models::task::{
+    CreateTaskError, DeleteTaskError, GetTaskError, ListTasksError, RestoreTaskError,
+    UpdateTaskError,
+};
+use crate::domain::reminders::models::task::{
+    CreateTaskRequest, Task, TaskTree, UpdateTaskRequest,
+};
+use crate::domain::reminders::models::trash::{
+    ListTrashError, PurgeTrashError, PurgedTrash, Trash,
+};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::models::api_token::{
+    ApiToken, ApiTokenSecret, CreateApiTokenError, CreateApiTokenRequest, ListApiTokensError,
+    NewApiToken, Principal, RevokeApiTokenError, TokenScope,
+};
+use crate::domain::users::models::session::{
+    AuthenticateError, LogInError, LogInRequest, LogOutError, Session, SessionToken,
+};
+use crate::domain::users::models::user::{
+    EmailAddress, RegisterUserError, RegisterUserRequest, User,
+};
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::AppState;
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use std::mem;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Swap the stored result out of `slot`, leaving `substitute` in its place.
+fn take<T>(slot: &Mutex<T>, mut substitute: T) -> T {
+    let mut guard = slot.lock().unwrap();
+    mem::swap(&mut *guard, &mut substitute);
+    substitute
+}
+
+/// A [ReminderService] that returns preconfigured results. Each result can be returned once;
+/// subsequent calls return an unknown error.
+#[derive(Clone)]
+pub struct MockReminderService {
+    pub create_task_result: Arc<Mutex<Result<Task, CreateTaskError>>>,
+    pub get_task_result: Arc<Mutex<Result<Task, GetTaskError>>>,
+    pub get_task_tree_result: Arc<Mutex<Result<TaskTree, GetTaskError>>>,
+    pub list_tasks_result: Arc<Mutex<Result<TaskPage, ListTasksError>>>,
+    pub update_task_result: Arc<Mutex<Result<Task, UpdateTaskError>>>,
+    pub delete_task_result: Arc<Mutex<Result<(), DeleteTaskError>>>,
+    pub create_list_result: Arc<Mutex<Result<TaskList, CreateTaskListError>>>,
+    pub get_list_result: Arc<Mutex<Result<TaskList, GetTaskListError>>>,
+    pub list_lists_result: Arc<Mutex<Result<Vec<TaskList>, ListTaskListsError>>>,
+    pub update_list_result: Arc<Mutex<Result<TaskList, UpdateTaskListError>>>,
+    pub delete_list_result: Arc<Mutex<Result<(), DeleteTaskListError>>>,
+    pub list_members_result: Arc<Mutex<Result<Vec<TaskListMember>, ListTaskListMembersError>>>,
+    pub share_list_result: Arc<Mutex<Result<TaskListMember, ShareTaskListError>>>,
+    pub unshare_list_result: Arc<Mutex<Result<(), UnshareTaskListError>>>,
+    pub tag_task_result: Arc<Mutex<Result<Task, TagTaskError>>>,
+    pub untag_task_result: Arc<Mutex<Result<Task, UntagTaskError>>>,
+    pub list_tags_result: Arc<Mutex<Result<Vec<TagUsage>, ListTagsError>>>,
+    pub rename_tag_result: Arc<Mutex<Result<TagUsage, RenameTagError>>>,
+    pub merge_tags_result: Arc<Mutex<Result<TagUsage, MergeTagsError>>>,
+    pub list_trash_result: Arc<Mutex<Result<Trash, ListTrashError>>>,
+    pub restore_task_result: Arc<Mutex<Result<Task, RestoreTaskError>>>,
+    pub restore_list_result: Arc<Mutex<Result<TaskList, RestoreTaskListError>>>,
+    pub empty_trash_result: Arc<Mutex<Result<PurgedTrash, PurgeTrashError>>>,
+}
+
+impl Default for MockReminderService {
+    fn default() -> Self {
+        Self {
+            create_task_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            get_task_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            get_task_tree_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            list_tasks_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            update_task_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            delete_task_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            create_list_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            get_list_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            list_lists_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            update_list_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            delete_list_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            list_members_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            share_list_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            unshare_list_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            tag_task_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            untag_task_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            list_tags_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            rename_tag_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            merge_tags_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            list_trash_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            restore_task_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            restore_list_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            empty_trash_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+        }
+    }
+}
+
+impl ReminderService for MockReminderService {
+    async fn create_task(&self, _: &Uuid, _: &CreateTaskRequest) -> Result<Task, CreateTaskError> {
+        take(
+            &self.create_task_result,
+            Err(CreateTaskError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn get_task(&self, _: &Uuid, _: &Uuid) -> Result<Task, GetTaskError> {
+        take(
+            &self.get_task_result,
+            Err(GetTaskError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn get_task_tree(&self, _: &Uuid, _: &Uuid) -> Result<TaskTree, GetTaskError> {
+        take(
+            &self.get_task_tree_result,
+            Err(GetTaskError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn list_tasks(&self, _: &Uuid, _: &ListTasksRequest) -> Result<TaskPage, ListTasksError> {
+        take(
+            &self.list_tasks_result,
+            Err(ListTasksError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn update_task(
+        &self,
+        _: &Uuid,
+        _: &Uuid,
+        _: &UpdateTaskRequest,
+    ) -> Result<Task, UpdateTaskError> {
+        take(
+            &self.update_task_result,
+            Err(UpdateTaskError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn delete_task(&self, _: &Uuid, _: &Uuid, _: Option<i64>) -> Result<(), DeleteTaskError> {
+        take(
+            &self.delete_task_result,
+            Err(DeleteTaskError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn create_list(
+        &self,
+        _: &Uuid,
+        _: &CreateTaskListRequest,
+    ) -> Result<TaskList, CreateTaskListError> {
+        take(
+            &self.create_list_result,
+            Err(CreateTaskListError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn get_list(&self, _: &Uuid, _: &Uuid) -> Result<TaskList, GetTaskListError> {
+        take(
+            &self.get_list_result,
+            Err(GetTaskListError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn list_lists(&self, _: &Uuid, _: bool) -> Result<Vec<TaskList>, ListTaskListsError> {
+        take(
+            &self.list_lists_result,
+            Err(ListTaskListsError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn update_list(
+        &self,
+        _: &Uuid,
+        _: &Uuid,
+        _: &UpdateTaskListRequest,
+    ) -> Result<TaskList, UpdateTaskListError> {
+        take(
+            &self.update_list_result,
+            Err(UpdateTaskListError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn delete_list(
+        &self,
+        _: &Uuid,
+        _: &Uuid,
+        _: DeleteTaskListMode,
+    ) -> Result<(), DeleteTaskListError> {
+        take(
+            &self.delete_list_result,
+            Err(DeleteTaskListError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn list_members(
+        &self,
+        _: &Uuid,
+        _: &Uuid,
+    ) -> Result<Vec<TaskListMember>, ListTaskListMembersError> {
+        take(
+            &self.list_members_result,
+            Err(ListTaskListMembersError::Unknown(anyhow!(
+                "substitute error"
+            ))),
+        )
+    }
+
+    async fn share_list(
+        &self,
+        _: &Uuid,
+        _: &Uuid,
+        _: &ShareTaskListRequest,
+    ) -> Result<TaskListMember, ShareTaskListError> {
+        take(
+            &self.share_list_result,
+            Err(ShareTaskListError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn unshare_list(&self, _: &Uuid, _: &Uuid, _: &Uuid) -> Result<(), UnshareTaskListError> {
+        take(
+            &self.unshare_list_result,
+            Err(UnshareTaskListError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn tag_task(&self, _: &Uuid, _: &Uuid, _: &[Tag]) -> Result<Task, TagTaskError> {
+        take(
+            &self.tag_task_result,
+            Err(TagTaskError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn untag_task(&self, _: &Uuid, _: &Uuid, _: &[Tag]) -> Result<Task, UntagTaskError> {
+        take(
+            &self.untag_task_result,
+            Err(UntagTaskError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn list_tags(&self, _: &Uuid) -> Result<Vec<TagUsage>, ListTagsError> {
+        take(
+            &self.list_tags_result,
+            Err(ListTagsError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn rename_tag(&self, _: &Uuid, _: &Tag, _: &Tag) -> Result<TagUsage, RenameTagError> {
+        take(
+            &self.rename_tag_result,
+            Err(RenameTagError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn merge_tags(&self, _: &Uuid, _: &[Tag], _: &Tag) -> Result<TagUsage, MergeTagsError> {
+        take(
+            &self.merge_tags_result,
+            Err(MergeTagsError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn list_trash(&self, _: &Uuid) -> Result<Trash, ListTrashError> {
+        take(
+            &self.list_trash_result,
+            Err(ListTrashError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn restore_task(&self, _: &Uuid, _: &Uuid) -> Result<Task, RestoreTaskError> {
+        take(
+            &self.restore_task_result,
+            Err(RestoreTaskError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn restore_list(&self, _: &Uuid, _: &Uuid) -> Result<TaskList, RestoreTaskListError> {
+        take(
+            &self.restore_list_result,
+            Err(RestoreTaskListError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn empty_trash(&self, _: &Uuid) -> Result<PurgedTrash, PurgeTrashError> {
+        take(
+            &self.empty_trash_result,
+            Err(PurgeTrashError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn purge_trash(&self, _: DateTime<Utc>) -> Result<PurgedTrash, PurgeTrashError> {
+        Ok(PurgedTrash::default())
+    }
+
+    async fn fire_due_reminders(
+        &self,
+        _: DateTime<Utc>,
+    ) -> Result<FiredReminders, FireRemindersError> {
+        Ok(FiredReminders::default())
+    }
+}
+
+/// A [ReadinessService] that returns a preconfigured result once.
+#[derive(Clone)]
+pub struct MockReadinessService {
+    pub is_ready_result: Arc<Mutex<Result<(), ReadinessError>>>,
+}
+
+impl Default for MockReadinessService {
+    fn default() -> Self {
+        Self {
+            is_ready_result: Arc::new(Mutex::new(Ok(()))),
+        }
+    }
+}
+
+impl ReadinessService for MockReadinessService {
+    async fn is_ready(&self) -> Result<(), ReadinessError> {
+        take(&self.is_ready_result, Err(ReadinessError::DatabaseNotReady))
+    }
+}
+
+/// A [UserService] that returns preconfigured results. Each result can be returned once;
+/// subsequent calls return an unknown error.
+#[derive(Clone)]
+pub struct MockUserService {
+    pub register_user_result: Arc<Mutex<Result<User, RegisterUserError>>>,
+    pub log_in_result: Arc<Mutex<Result<Session, LogInError>>>,
+    pub authenticate_result: Arc<Mutex<Result<User, AuthenticateError>>>,
+    pub log_out_result: Arc<Mutex<Result<(), LogOutError>>>,
+    pub create_api_token_result: Arc<Mutex<Result<NewApiToken, CreateApiTokenError>>>,
+    pub list_api_tokens_result: Arc<Mutex<Result<Vec<ApiToken>, ListApiTokensError>>>,
+    pub revoke_api_token_result: Arc<Mutex<Result<(), RevokeApiTokenError>>>,
+    pub authenticate_api_token_result: Arc<Mutex<Result<Principal, AuthenticateError>>>,
+}
+
+impl Default for MockUserService {
+    fn default() -> Self {
+        Self {
+            register_user_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            log_in_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            authenticate_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            log_out_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            create_api_token_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            list_api_tokens_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            revoke_api_token_result: Arc::new(Mutex::new(Err(anyhow!("unexpected call").into()))),
+            authenticate_api_token_result: Arc::new(Mutex::new(Err(
+                anyhow!("unexpected call").into()
+            ))),
+        }
+    }
+}
+
+impl UserService for MockUserService {
+    async fn register_user(&self, _: &RegisterUserRequest) -> Result<User, RegisterUserError> {
+        take(
+            &self.register_user_result,
+            Err(RegisterUserError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn log_in(&self, _: &LogInRequest) -> Result<Session, LogInError> {
+        take(
+            &self.log_in_result,
+            Err(LogInError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn authenticate(&self, _: &SessionToken) -> Result<User, AuthenticateError> {
+        take(
+            &self.authenticate_result,
+            Err(AuthenticateError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn log_out(&self, _: &SessionToken) -> Result<(), LogOutError> {
+        take(
+            &self.log_out_result,
+            Err(LogOutError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn create_api_token(
+        &self,
+        _: &Uuid,
+        _: &CreateApiTokenRequest,
+    ) -> Result<NewApiToken, CreateApiTokenError> {
+        take(
+            &self.create_api_token_result,
+            Err(CreateApiTokenError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn list_api_tokens(&self, _: &Uuid) -> Result<Vec<ApiToken>, ListApiTokensError> {
+        take(
+            &self.list_api_tokens_result,
+            Err(ListApiTokensError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn revoke_api_token(&self, _: &Uuid, _: &Uuid) -> Result<(), RevokeApiTokenError> {
+        take(
+            &self.revoke_api_token_result,
+            Err(RevokeApiTokenError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+
+    async fn authenticate_api_token(
+        &self,
+        _: &ApiTokenSecret,
+    ) -> Result<Principal, AuthenticateError> {
+        take(
+            &self.authenticate_api_token_result,
+            Err(AuthenticateError::Unknown(anyhow!("substitute error"))),
+        )
+    }
+}
+
+/// The signed in user that handlers under test act on behalf of.
+pub fn current_user() -> CurrentUser {
+    CurrentUser(User {
+        id: Uuid::new_v4(),
+        email: EmailAddress::new("ada@example.com").unwrap(),
+        created_at: Utc::now(),
+    })
+}
+
+/// The scope of a session, which may access all of the user's data.
+pub fn full_scope() -> Scope {
+    Scope(TokenScope::full())
+}
+
+/// Build the [AppState] shared by handlers from the given mocks.
+pub fn app_state(
+    reminder_service: MockReminderService,
+    readiness_service: MockReadinessService,
+) -> AppState<MockReminderService, MockReadinessService, MockUserService> {
+    AppState {
+        reminder_service: Arc::new(reminder_service),
+        readiness_service: Arc::new(readiness_service),
+        user_service: Arc::new(MockUserService::default()),
+    }
+}
+
+/// Build the [AppState] shared by handlers from the given [MockUserService], for the handlers of
+/// the users domain.
+pub fn user_app_state(
+    user_service: MockUserService,
+) -> AppState<MockReminderService, MockReadinessService, MockUserService> {
+    AppState {
+        reminder_service: Arc::new(MockReminderService::default()),
+        readiness_service: Arc::new(MockReadinessService::default()),
+        user_service: Arc::new(user_service),
+    }
+}
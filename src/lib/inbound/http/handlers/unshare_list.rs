@@ -0,0 +1,123 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::member::UnshareTaskListError;
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::Path;
+use crate::inbound::http::handlers::shared::{ApiError, ErrorDetail, ProblemDetails};
+use crate::inbound::http::AppState;
+
+impl From<UnshareTaskListError> for ApiError {
+    fn from(e: UnshareTaskListError) -> Self {
+        match e {
+            UnshareTaskListError::NotFound { id } => Self::NotFound(ErrorDetail::new(
+                "list.not_found",
+                format!("task list with id {} not found", id),
+            )),
+            e @ UnshareTaskListError::MemberNotFound { .. } => {
+                Self::NotFound(ErrorDetail::new("list.member_not_found", e.to_string()))
+            }
+            e @ UnshareTaskListError::Forbidden { .. } => {
+                Self::Forbidden(ErrorDetail::new("list.role_forbidden", e.to_string()))
+            }
+            UnshareTaskListError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Stop sharing a [TaskList](crate::domain::reminders::models::list::TaskList) with a user.
+/// Owners of the list may remove anyone it is shared with; other members may only leave it.
+///
+/// # Responses
+///
+/// - 204 No Content: the list is no longer shared with the user.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is read-only or restricted to another task list, or the user is
+///   not an owner of the list and tried to remove someone else.
+/// - 404 Not Found: no list with the given id exists, or it is not shared with the user.
+#[utoipa::path(
+    delete,
+    path = "/api/lists/{id}/members/{user_id}",
+    tag = "lists",
+    params(
+        ("id" = Uuid, Path, description = "The id of the task list"),
+        ("user_id" = Uuid, Path, description = "The id of the member to remove"),
+    ),
+    responses(
+        (status = 204, description = "The list is no longer shared with the user"),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is read-only or restricted to another task list, or the user \
+                           is not an owner of the list and tried to remove someone else",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 404,
+            description = "No list with the given id exists, or it is not shared with the user",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn unshare_list<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path((id, member)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    scope.require_list(Some(id))?;
+    state
+        .reminder_service
+        .unshare_list(&user.id(), &id, &member)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_unshare_list_member_not_found() {
+        let (id, user_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let service = MockReminderService {
+            unshare_list_result: Arc::new(Mutex::new(Err(UnshareTaskListError::MemberNotFound {
+                id,
+                user_id,
+            }))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let actual = unshare_list(state, current_user(), full_scope(), Path((id, user_id))).await;
+        assert_eq!(
+            actual,
+            Err(ApiError::NotFound(ErrorDetail::new(
+                "list.member_not_found",
+                format!(
+                    "user with id {} is not a member of task list {}",
+                    user_id, id
+                )
+            )))
+        );
+    }
+}
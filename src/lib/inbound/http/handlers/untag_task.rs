@@ -0,0 +1,116 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::tag::{Tag, UntagTaskError};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::Path;
+use crate::inbound::http::handlers::get_task::TaskResponseData;
+use crate::inbound::http::handlers::shared::{
+    ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<UntagTaskError> for ApiError {
+    fn from(e: UntagTaskError) -> Self {
+        match e {
+            UntagTaskError::NotFound { id } => Self::NotFound(ErrorDetail::new(
+                "task.not_found",
+                format!("task with id {} not found", id),
+            )),
+            e @ UntagTaskError::Forbidden { .. } => {
+                Self::Forbidden(ErrorDetail::new("list.role_forbidden", e.to_string()))
+            }
+            e @ UntagTaskError::Conflict { .. } => {
+                Self::Conflict(ErrorDetail::new("task.version_conflict", e.to_string()))
+            }
+            UntagTaskError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Remove a tag from a [Task](crate::domain::reminders::models::task::Task). Removing a tag the
+/// task does not carry succeeds without changing it.
+///
+/// # Responses
+///
+/// - 200 OK: the tag was removed.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is read-only or restricted to another task list, or the user's
+///   role in the task list does not allow the change.
+/// - 404 Not Found: no task with the given id exists.
+/// - 409 Conflict: the task kept changing while its tags were being changed.
+/// - 422 Unprocessable Entity: the tag is invalid.
+#[utoipa::path(
+    delete,
+    path = "/api/tasks/{id}/tags/{tag}",
+    tag = "tasks",
+    params(
+        ("id" = Uuid, Path, description = "The id of the task"),
+        ("tag" = String, Path, description = "The tag to remove"),
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The tag was removed",
+            body = ApiResponseBody<TaskResponseData>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is read-only or restricted to another task list, or the \
+                           user's role in the task list does not allow the change",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 404,
+            description = "No task with the given id exists",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 409,
+            description = "The task kept changing while its tags were being changed",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 422,
+            description = "The tag is invalid",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn untag_task<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path((id, tag)): Path<(Uuid, String)>,
+) -> Result<ApiSuccess<TaskResponseData>, ApiError> {
+    scope
+        .require_task(state.reminder_service.as_ref(), &user.id(), &id)
+        .await?;
+    let tag = Tag::new(&tag).map_err(|e| {
+        ApiError::UnprocessableEntity(ErrorDetail::invalid_field("tag", e.to_string()))
+    })?;
+    state
+        .reminder_service
+        .untag_task(&user.id(), &id, &[tag])
+        .await
+        .map_err(ApiError::from)
+        .map(|ref task| ApiSuccess::new(StatusCode::OK, task.into()))
+}
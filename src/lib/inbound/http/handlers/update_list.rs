@@ -0,0 +1,207 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Deserialize;
+use thiserror::Error;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::list::UpdateTaskListError;
+use crate::domain::reminders::models::list::{
+    TaskListColour, TaskListColourInvalidError, TaskListTitle, TaskListTitleEmptyError,
+    UpdateTaskListRequest,
+};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::extract::{Json, Path};
+use crate::inbound::http::handlers::get_list::TaskListResponseData;
+use crate::inbound::http::handlers::shared::{
+    deserialize_some, ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<UpdateTaskListError> for ApiError {
+    fn from(e: UpdateTaskListError) -> Self {
+        match e {
+            UpdateTaskListError::NotFound { id } => Self::NotFound(ErrorDetail::new(
+                "list.not_found",
+                format!("task list with id {} not found", id),
+            )),
+            UpdateTaskListError::Duplicate { title } => {
+                Self::UnprocessableEntity(ErrorDetail::new(
+                    "list.duplicate_title",
+                    format!("task list with title {} already exists", title),
+                ))
+            }
+            e @ UpdateTaskListError::Forbidden { .. } => {
+                Self::Forbidden(ErrorDetail::new("list.role_forbidden", e.to_string()))
+            }
+            UpdateTaskListError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<ParseUpdateTaskListHttpRequestError> for ApiError {
+    fn from(e: ParseUpdateTaskListHttpRequestError) -> Self {
+        let field = match e {
+            ParseUpdateTaskListHttpRequestError::Title(_) => "title",
+            ParseUpdateTaskListHttpRequestError::Colour(_) => "colour",
+        };
+        Self::UnprocessableEntity(ErrorDetail::invalid_field(field, e.to_string()))
+    }
+}
+
+/// The body of a [TaskList](crate::domain::reminders::models::list::TaskList) update request,
+/// used to rename, recolour, reorder, archive and unarchive lists. Omitted fields are left
+/// unchanged; a `null` `colour` clears it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct UpdateTaskListHttpRequestBody {
+    title: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    colour: Option<Option<String>>,
+    position: Option<i32>,
+    archived: Option<bool>,
+}
+
+#[derive(Debug, Clone, Error)]
+enum ParseUpdateTaskListHttpRequestError {
+    #[error(transparent)]
+    Title(#[from] TaskListTitleEmptyError),
+    #[error(transparent)]
+    Colour(#[from] TaskListColourInvalidError),
+}
+
+impl UpdateTaskListHttpRequestBody {
+    /// Converts the HTTP request body into a domain request.
+    fn try_into_domain(self) -> Result<UpdateTaskListRequest, ParseUpdateTaskListHttpRequestError> {
+        let mut req = UpdateTaskListRequest::new();
+        if let Some(title) = self.title.as_deref() {
+            req = req.with_title(TaskListTitle::new(title)?);
+        }
+        if let Some(colour) = self.colour {
+            req = req.with_colour(colour.as_deref().map(TaskListColour::new).transpose()?);
+        }
+        if let Some(position) = self.position {
+            req = req.with_position(position);
+        }
+        if let Some(archived) = self.archived {
+            req = req.with_archived(archived);
+        }
+        Ok(req)
+    }
+}
+
+/// Update a [TaskList](crate::domain::reminders::models::list::TaskList).
+///
+/// # Responses
+///
+/// - 200 OK: the list was successfully updated.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is read-only or restricted to another task list, or the user's
+///   role in the task list does not allow the change.
+/// - 404 Not Found: no list with the given id exists.
+/// - 422 Unprocessable Entity: the request is invalid or the new title is already in use.
+#[utoipa::path(
+    patch,
+    path = "/api/lists/{id}",
+    tag = "lists",
+    params(("id" = Uuid, Path, description = "The id of the task list")),
+    request_body = UpdateTaskListHttpRequestBody,
+    responses(
+        (
+            status = 200,
+            description = "The list was successfully updated",
+            body = ApiResponseBody<TaskListResponseData>,
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is read-only or restricted to another task list, or the \
+                           user's role in the task list does not allow the change",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 404,
+            description = "No list with the given id exists",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 422,
+            description = "The request is invalid or the new title is already in use",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn update_list<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateTaskListHttpRequestBody>,
+) -> Result<ApiSuccess<TaskListResponseData>, ApiError> {
+    scope.require_list(Some(id))?;
+    let domain_req = body.try_into_domain()?;
+    state
+        .reminder_service
+        .update_list(&user.id(), &id, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref list| ApiSuccess::new(StatusCode::OK, list.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::reminders::models::list::TaskList;
+    use crate::domain::reminders::models::member::ListRole;
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use chrono::Utc;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_update_list_archives() {
+        let list = TaskList {
+            id: Uuid::new_v4(),
+            title: TaskListTitle::new("Work").unwrap(),
+            colour: None,
+            position: 1,
+            archived: true,
+            created_at: Utc::now(),
+            owner_id: Uuid::new_v4(),
+            role: ListRole::Owner,
+        };
+        let service = MockReminderService {
+            update_list_result: Arc::new(Mutex::new(Ok(list.clone()))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let body: UpdateTaskListHttpRequestBody =
+            serde_json::from_str(r#"{"archived": true, "colour": null}"#).unwrap();
+        assert_eq!(body.colour, Some(None));
+        let expected = ApiSuccess::new(StatusCode::OK, (&list).into());
+        let actual = update_list(
+            state,
+            current_user(),
+            full_scope(),
+            Path(list.id()),
+            Json(body),
+        )
+        .await;
+        assert_eq!(actual, Ok(expected));
+    }
+}
@@ -0,0 +1,457 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Deserialize;
+use thiserror::Error;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::readiness::ports::ReadinessService;
+use crate::domain::reminders::models::recurrence::{Recurrence, RecurrenceError};
+use crate::domain::reminders::models::tag::{Tag, TagInvalidError, TaskTags, TaskTagsTooManyError};
+use crate::domain::reminders::models::task::UpdateTaskError;
+use crate::domain::reminders::models::task::{
+    DueAt, DueAtInvalidError, Priority, RemindAt, RemindAtTooManyError, ReminderOffset,
+    ReminderOffsetError, TaskFieldError, TaskNotes, TaskTitle, UpdateTaskRequest,
+};
+use crate::domain::reminders::ports::ReminderService;
+use crate::domain::users::ports::UserService;
+use crate::inbound::http::handlers::auth::{CurrentUser, Scope};
+use crate::inbound::http::handlers::create_task::task_field_name;
+use crate::inbound::http::handlers::etag::{ETag, IfMatch, Tagged};
+use crate::inbound::http::handlers::extract::{Json, Path};
+use crate::inbound::http::handlers::get_task::TaskResponseData;
+use crate::inbound::http::handlers::shared::{
+    deserialize_some, ApiError, ApiResponseBody, ApiSuccess, ErrorDetail, ProblemDetails,
+};
+use crate::inbound::http::AppState;
+
+impl From<UpdateTaskError> for ApiError {
+    fn from(e: UpdateTaskError) -> Self {
+        match e {
+            UpdateTaskError::NotFound { id } => Self::NotFound(ErrorDetail::new(
+                "task.not_found",
+                format!("task with id {} not found", id),
+            )),
+            UpdateTaskError::Duplicate { title } => Self::UnprocessableEntity(ErrorDetail::new(
+                "task.duplicate_title",
+                format!("task with title {} already exists", title),
+            )),
+            UpdateTaskError::ListNotFound { id } => Self::UnprocessableEntity(ErrorDetail::new(
+                "list.not_found",
+                format!("task list with id {} not found", id),
+            )),
+            UpdateTaskError::ParentNotFound { id } => Self::UnprocessableEntity(ErrorDetail::new(
+                "task.parent_not_found",
+                format!("parent task with id {} not found", id),
+            )),
+            e @ UpdateTaskError::TooDeep => {
+                Self::UnprocessableEntity(ErrorDetail::new("task.too_deep", e.to_string()))
+            }
+            e @ UpdateTaskError::ParentCycle { .. } => {
+                Self::UnprocessableEntity(ErrorDetail::new("task.parent_cycle", e.to_string()))
+            }
+            e @ UpdateTaskError::Forbidden { .. } => {
+                Self::Forbidden(ErrorDetail::new("list.role_forbidden", e.to_string()))
+            }
+            e @ UpdateTaskError::AssigneeNotMember { .. } => Self::UnprocessableEntity(
+                ErrorDetail::new("task.assignee_not_member", e.to_string()),
+            ),
+            e @ UpdateTaskError::RemindAtWithoutDueAt => {
+                Self::UnprocessableEntity(ErrorDetail::invalid_field("remind_at", e.to_string()))
+            }
+            e @ UpdateTaskError::Conflict { .. } => {
+                Self::PreconditionFailed(ErrorDetail::new("task.version_conflict", e.to_string()))
+            }
+            UpdateTaskError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<ParseUpdateTaskHttpRequestError> for ApiError {
+    fn from(e: ParseUpdateTaskHttpRequestError) -> Self {
+        let (field, message) = match e {
+            ParseUpdateTaskHttpRequestError::Field(e) => (task_field_name(&e), e.to_string()),
+            ParseUpdateTaskHttpRequestError::DueAt(e) => ("due_at", e.to_string()),
+            ParseUpdateTaskHttpRequestError::ReminderOffset(e) => ("remind_at", e.to_string()),
+            ParseUpdateTaskHttpRequestError::RemindAt(e) => ("remind_at", e.to_string()),
+            ParseUpdateTaskHttpRequestError::Recurrence(e) => {
+                ("recurrence", format!("invalid recurrence rule: {}", e))
+            }
+            ParseUpdateTaskHttpRequestError::Tag(e) => ("tags", e.to_string()),
+            ParseUpdateTaskHttpRequestError::Tags(e) => ("tags", e.to_string()),
+        };
+
+        Self::UnprocessableEntity(ErrorDetail::invalid_field(field, message))
+    }
+}
+
+/// The body of a [Task](crate::domain::reminders::models::task::Task) update request. Omitted
+/// fields are left unchanged; a `null` `notes`, `due_at` or `recurrence` clears it, a `null`
+/// `list_id` moves the task to the inbox, and a `null` `parent_id` makes it a top-level task.
+/// `tags` replaces all tags of the task, and a `null` `assignee_id` unassigns it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, ToSchema)]
+pub struct UpdateTaskHttpRequestBody {
+    title: Option<String>,
+    completed: Option<bool>,
+    priority: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    notes: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    due_at: Option<Option<String>>,
+    remind_at: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    recurrence: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    list_id: Option<Option<Uuid>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    parent_id: Option<Option<Uuid>>,
+    position: Option<i32>,
+    tags: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    assignee_id: Option<Option<Uuid>>,
+}
+
+#[derive(Debug, Clone, Error)]
+enum ParseUpdateTaskHttpRequestError {
+    #[error(transparent)]
+    Field(#[from] TaskFieldError),
+    #[error(transparent)]
+    DueAt(#[from] DueAtInvalidError),
+    #[error(transparent)]
+    ReminderOffset(#[from] ReminderOffsetError),
+    #[error(transparent)]
+    RemindAt(#[from] RemindAtTooManyError),
+    #[error(transparent)]
+    Recurrence(#[from] RecurrenceError),
+    #[error(transparent)]
+    Tag(#[from] TagInvalidError),
+    #[error(transparent)]
+    Tags(#[from] TaskTagsTooManyError),
+}
+
+impl UpdateTaskHttpRequestBody {
+    /// Converts the HTTP request body into a domain request.
+    fn try_into_domain(self) -> Result<UpdateTaskRequest, ParseUpdateTaskHttpRequestError> {
+        let mut req = UpdateTaskRequest::new();
+        if let Some(title) = self.title.as_deref() {
+            req = req.with_title(TaskTitle::new(title)?);
+        }
+        if let Some(completed) = self.completed {
+            req = req.with_completed(completed);
+        }
+        if let Some(priority) = self.priority.as_deref() {
+            req = req.with_priority(Priority::parse(priority)?);
+        }
+        if let Some(notes) = self.notes {
+            req = req.with_notes(notes.as_deref().map(TaskNotes::new).transpose()?);
+        }
+        if let Some(due_at) = self.due_at {
+            req = req.with_due_at(due_at.as_deref().map(DueAt::parse).transpose()?);
+        }
+        if let Some(remind_at) = self.remind_at {
+            let offsets = remind_at
+                .iter()
+                .map(|raw| ReminderOffset::parse(raw))
+                .collect::<Result<Vec<_>, _>>()?;
+            req = req.with_remind_at(RemindAt::new(offsets)?);
+        }
+        if let Some(recurrence) = self.recurrence {
+            req = req.with_recurrence(recurrence.as_deref().map(Recurrence::parse).transpose()?);
+        }
+        if let Some(list_id) = self.list_id {
+            req = req.with_list_id(list_id);
+        }
+        if let Some(parent_id) = self.parent_id {
+            req = req.with_parent_id(parent_id);
+        }
+        if let Some(position) = self.position {
+            req = req.with_position(position);
+        }
+        if let Some(tags) = self.tags {
+            let tags = tags
+                .iter()
+                .map(|raw| Tag::new(raw))
+                .collect::<Result<Vec<_>, _>>()?;
+            req = req.with_tags(TaskTags::new(tags)?);
+        }
+        if let Some(assignee_id) = self.assignee_id {
+            req = req.with_assignee_id(assignee_id);
+        }
+        Ok(req)
+    }
+}
+
+/// Update a [Task](crate::domain::reminders::models::task::Task), if it is still at the version
+/// whose `ETag` is sent in `If-Match`.
+///
+/// # Responses
+///
+/// - 200 OK: the task was successfully updated.
+/// - 400 Bad Request: the `If-Match` header is not `*` or an `ETag`.
+/// - 401 Unauthorized: the request has no valid bearer token.
+/// - 403 Forbidden: the token is read-only or restricted to another task list, or the user's
+///   role in the task list does not allow the change.
+/// - 404 Not Found: no task with the given id exists.
+/// - 422 Unprocessable Entity: the request is invalid, the new title is already in use, the task
+///   list or parent task does not exist, the task cannot be moved below the parent, or the
+///   assignee is not a member of the task list.
+/// - 412 Precondition Failed: the task was changed since the version in `If-Match`.
+/// - 428 Precondition Required: the request has no `If-Match` header.
+#[utoipa::path(
+    patch,
+    path = "/api/tasks/{id}",
+    tag = "tasks",
+    params(
+        ("id" = Uuid, Path, description = "The id of the task"),
+        (
+            "If-Match" = String,
+            Header,
+            description = "The ETag of the task the change is based on, or * to overwrite any \
+                           version",
+        ),
+    ),
+    request_body = UpdateTaskHttpRequestBody,
+    responses(
+        (
+            status = 200,
+            description = "The task was successfully updated",
+            body = ApiResponseBody<TaskResponseData>,
+            headers(("ETag" = String, description = "The new version of the task")),
+        ),
+        (
+            status = 400,
+            description = "The If-Match header is not * or an ETag",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 401,
+            description = "The request has no valid bearer token",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 403,
+            description = "The token is read-only or restricted to another task list, or the \
+                           user's role in the task list does not allow the change",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 404,
+            description = "No task with the given id exists",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 422,
+            description = "The request is invalid, the new title is already in use, the task list \
+                           or parent task does not exist, the task cannot be moved below the \
+                           parent, or the assignee is not a member of the task list",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 412,
+            description = "The task was changed since the version in If-Match",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+        (
+            status = 428,
+            description = "The request has no If-Match header",
+            body = ProblemDetails,
+            content_type = "application/problem+json",
+        ),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn update_task<RS: ReminderService, RD: ReadinessService, US: UserService>(
+    State(state): State<AppState<RS, RD, US>>,
+    CurrentUser(user): CurrentUser,
+    scope: Scope,
+    Path(id): Path<Uuid>,
+    IfMatch(version): IfMatch,
+    Json(body): Json<UpdateTaskHttpRequestBody>,
+) -> Result<Tagged<ApiSuccess<TaskResponseData>>, ApiError> {
+    scope
+        .require_task(state.reminder_service.as_ref(), &user.id(), &id)
+        .await?;
+    let mut domain_req = body.try_into_domain()?;
+    if let Some(version) = version {
+        domain_req = domain_req.with_version(version);
+    }
+    if let Some(list_id) = domain_req.list_id() {
+        scope.require_list(list_id)?;
+    }
+    state
+        .reminder_service
+        .update_task(&user.id(), &id, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref task| {
+            Tagged::Body(
+                ETag::task(task),
+                ApiSuccess::new(StatusCode::OK, task.into()),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::reminders::models::task::Task;
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, current_user, full_scope, MockReadinessService, MockReminderService,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_update_task_success() {
+        let mut task = Task::new(Uuid::new_v4(), TaskTitle::new("Clean apartment").unwrap());
+        task.completed = true;
+        let service = MockReminderService {
+            update_task_result: Arc::new(Mutex::new(Ok(task.clone()))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let body = Json(UpdateTaskHttpRequestBody {
+            completed: Some(true),
+            ..Default::default()
+        });
+        let expected = Tagged::Body(
+            ETag::task(&task),
+            ApiSuccess::new(StatusCode::OK, (&task).into()),
+        );
+        let actual = update_task(
+            state,
+            current_user(),
+            full_scope(),
+            Path(task.id()),
+            IfMatch(Some(1)),
+            body,
+        )
+        .await;
+        assert_eq!(actual, Ok(expected));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_update_task_conflict() {
+        let id = Uuid::new_v4();
+        let service = MockReminderService {
+            update_task_result: Arc::new(Mutex::new(Err(UpdateTaskError::Conflict {
+                id,
+                version: 1,
+            }))),
+            ..Default::default()
+        };
+        let state = State(app_state(service, MockReadinessService::default()));
+        let body = Json(UpdateTaskHttpRequestBody {
+            title: Some("Clean kitchen".to_string()),
+            ..Default::default()
+        });
+        let actual = update_task(
+            state,
+            current_user(),
+            full_scope(),
+            Path(id),
+            IfMatch(Some(1)),
+            body,
+        )
+        .await;
+        assert_eq!(
+            actual,
+            Err(ApiError::PreconditionFailed(ErrorDetail::new(
+                "task.version_conflict",
+                format!("task with id {} was changed since version 1", id)
+            )))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_update_task_title_too_long() {
+        let state = State(app_state(
+            MockReminderService::default(),
+            MockReadinessService::default(),
+        ));
+        let body = Json(UpdateTaskHttpRequestBody {
+            title: Some("x".repeat(TaskTitle::MAX_LENGTH + 1)),
+            ..Default::default()
+        });
+        let actual = update_task(
+            state,
+            current_user(),
+            full_scope(),
+            Path(Uuid::new_v4()),
+            IfMatch(None),
+            body,
+        )
+        .await;
+        assert_eq!(
+            actual,
+            Err(ApiError::UnprocessableEntity(ErrorDetail::invalid_field(
+                "title",
+                "task title cannot be longer than 200 characters"
+            )))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_update_task_empty_title() {
+        let state = State(app_state(
+            MockReminderService::default(),
+            MockReadinessService::default(),
+        ));
+        let body = Json(UpdateTaskHttpRequestBody {
+            title: Some("  ".to_string()),
+            ..Default::default()
+        });
+        let actual = update_task(
+            state,
+            current_user(),
+            full_scope(),
+            Path(Uuid::new_v4()),
+            IfMatch(None),
+            body,
+        )
+        .await;
+        assert_eq!(
+            actual,
+            Err(ApiError::UnprocessableEntity(ErrorDetail::invalid_field(
+                "title",
+                "task title cannot be empty"
+            )))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_update_task_too_many_reminders() {
+        let state = State(app_state(
+            MockReminderService::default(),
+            MockReadinessService::default(),
+        ));
+        let body = Json(UpdateTaskHttpRequestBody {
+            remind_at: Some((1..=11).map(|h| format!("{}h", h)).collect()),
+            ..Default::default()
+        });
+        let actual = update_task(
+            state,
+            current_user(),
+            full_scope(),
+            Path(Uuid::new_v4()),
+            IfMatch(None),
+            body,
+        )
+        .await;
+        assert_eq!(
+            actual,
+            Err(ApiError::UnprocessableEntity(ErrorDetail::invalid_field(
+                "remind_at",
+                "a task cannot have more than 10 reminders"
+            )))
+        );
+    }
+}
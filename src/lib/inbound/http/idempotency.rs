@@ -0,0 +1,396 @@
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use axum::body::{to_bytes, Body, Bytes};
+use axum::extract::Request;
+use axum::http::header::{AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE};
+use axum::http::{HeaderName, HeaderValue, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use tower::{Layer, Service};
+
+use crate::domain::idempotency::models::key::{
+    Claim, ClaimIdempotencyKeyError, IdempotencyKey, IdempotentRequest, StoredResponse,
+};
+use crate::domain::idempotency::ports::IdempotencyService;
+use crate::inbound::http::handlers::shared::{ApiError, ErrorDetail};
+
+/// The header in which clients send the key of a request they may retry.
+pub const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+
+/// The header set on responses replayed from an earlier request with the same key.
+pub const IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// The largest request body that is read to fingerprint a request, the same as the default
+/// limit of [axum::Json].
+const MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+impl From<ClaimIdempotencyKeyError> for ApiError {
+    fn from(e: ClaimIdempotencyKeyError) -> Self {
+        match e {
+            ClaimIdempotencyKeyError::InProgress => {
+                Self::Conflict(ErrorDetail::new("idempotency.in_progress", e.to_string()))
+            }
+            ClaimIdempotencyKeyError::Mismatch => {
+                Self::UnprocessableEntity(ErrorDetail::new("idempotency.key_reused", e.to_string()))
+            }
+            ClaimIdempotencyKeyError::Unknown(cause) => {
+                tracing::error!("{:?}\n{}", cause, cause.backtrace());
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// A [Layer] that makes mutating requests sent with an `Idempotency-Key` header safe to retry.
+///
+/// The first request with a key is handled, and its response is stored under the key. Retries
+/// with the same key and the same method, URI and body get the stored response back, with an
+/// `Idempotent-Replayed: true` header, instead of being handled again. Reusing a key for a
+/// different request is rejected with 422 Unprocessable Entity, and retrying before the first
+/// request has completed with 409 Conflict.
+///
+/// Keys are scoped to the `Authorization` header, so that clients cannot replay each other's
+/// responses. Responses with a 5xx status are not stored, so that the request can be retried.
+#[derive(Debug, Clone)]
+pub struct IdempotencyLayer<IS: IdempotencyService> {
+    idempotency_service: IS,
+}
+
+impl<IS: IdempotencyService> IdempotencyLayer<IS> {
+    pub fn new(idempotency_service: IS) -> Self {
+        Self {
+            idempotency_service,
+        }
+    }
+}
+
+impl<S, IS: IdempotencyService> Layer<S> for IdempotencyLayer<IS> {
+    type Service = Idempotency<S, IS>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Idempotency {
+            inner,
+            idempotency_service: self.idempotency_service.clone(),
+        }
+    }
+}
+
+/// The [Service] applied by [IdempotencyLayer].
+#[derive(Debug, Clone)]
+pub struct Idempotency<S, IS: IdempotencyService> {
+    inner: S,
+    idempotency_service: IS,
+}
+
+impl<S, IS> Service<Request> for Idempotency<S, IS>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    IS: IdempotencyService,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        // The service that was polled ready is taken to handle this request, leaving a clone in
+        // its place
+        let clone = self.inner.clone();
+        let inner = std::mem::replace(&mut self.inner, clone);
+        let idempotency_service = self.idempotency_service.clone();
+        Box::pin(async move {
+            let response = handle(inner, &idempotency_service, req)
+                .await
+                .unwrap_or_else(IntoResponse::into_response);
+            Ok(response)
+        })
+    }
+}
+
+/// Handle `req` with `inner`, unless it has an idempotency key that was used before.
+async fn handle<S, IS>(
+    mut inner: S,
+    idempotency_service: &IS,
+    req: Request,
+) -> Result<Response, ApiError>
+where
+    S: Service<Request, Response = Response, Error = Infallible>,
+    IS: IdempotencyService,
+{
+    let Some(key) = idempotency_key(&req)? else {
+        let Ok(response) = inner.call(req).await;
+        return Ok(response);
+    };
+
+    let (parts, body) = req.into_parts();
+    let body = to_bytes(body, MAX_BODY_SIZE).await.map_err(|e| {
+        ApiError::BadRequest(ErrorDetail::new("request.unreadable_body", e.to_string()))
+    })?;
+    let owner = URL_SAFE_NO_PAD.encode(Sha256::digest(
+        parts
+            .headers
+            .get(AUTHORIZATION)
+            .map(HeaderValue::as_bytes)
+            .unwrap_or_default(),
+    ));
+    let fingerprint = fingerprint(&parts.method, &parts.uri.to_string(), &body);
+    let idempotent_req = IdempotentRequest::new(owner, key, fingerprint);
+
+    if let Claim::Replay(stored) = idempotency_service.claim(&idempotent_req).await? {
+        return Ok(replay(stored));
+    }
+
+    let Ok(response) = inner
+        .call(Request::from_parts(parts, Body::from(body)))
+        .await;
+    if response.status().is_server_error() {
+        abandon(idempotency_service, &idempotent_req).await;
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            abandon(idempotency_service, &idempotent_req).await;
+            return Err(ApiError::InternalServerError(e.to_string()));
+        }
+    };
+    let content_type = parts
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    // Headers such as ETag and Location are replayed too, as clients rely on them to continue
+    let headers = parts
+        .headers
+        .iter()
+        .filter(|(name, _)| ![CONTENT_TYPE, CONTENT_LENGTH].contains(name))
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    let stored = StoredResponse::new(parts.status.as_u16(), content_type, body.to_vec())
+        .with_headers(headers);
+    if let Err(e) = idempotency_service.complete(&idempotent_req, &stored).await {
+        // The request has taken effect, so its response is sent even though retries will not
+        // get it back
+        tracing::error!("failed to store idempotent response: {:?}", e);
+    }
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+/// The idempotency key of `req`, if it may change data and has one.
+///
+/// # Errors
+///
+/// - [ApiError::BadRequest] if the key is not valid.
+fn idempotency_key(req: &Request) -> Result<Option<IdempotencyKey>, ApiError> {
+    let mutating = matches!(
+        *req.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    );
+    let Some(value) = req.headers().get(IDEMPOTENCY_KEY).filter(|_| mutating) else {
+        return Ok(None);
+    };
+    let invalid_key = |message: String| {
+        ApiError::BadRequest(ErrorDetail::new("idempotency.invalid_key", message))
+    };
+    let raw = value
+        .to_str()
+        .map_err(|e| invalid_key(format!("idempotency key is not valid: {}", e)))?;
+    let key = IdempotencyKey::new(raw).map_err(|e| invalid_key(e.to_string()))?;
+    Ok(Some(key))
+}
+
+/// A digest of what makes two requests the same: their method, URI and body.
+fn fingerprint(method: &Method, uri: &str, body: &Bytes) -> String {
+    let mut hasher = Sha256::new();
+    for part in [method.as_str().as_bytes(), uri.as_bytes(), body] {
+        hasher.update((part.len() as u64).to_be_bytes());
+        hasher.update(part);
+    }
+    URL_SAFE_NO_PAD.encode(hasher.finalize())
+}
+
+/// Release the key of `req`, so that a retry is handled rather than reported as in progress.
+async fn abandon<IS: IdempotencyService>(idempotency_service: &IS, req: &IdempotentRequest) {
+    if let Err(e) = idempotency_service.abandon(req).await {
+        tracing::error!("failed to release idempotency key: {:?}", e);
+    }
+}
+
+/// The response to send again for a retried request.
+fn replay(stored: StoredResponse) -> Response {
+    let status = StatusCode::from_u16(stored.status()).unwrap_or(StatusCode::OK);
+    let mut response = (status, Body::from(stored.body().to_vec())).into_response();
+    let headers = response.headers_mut();
+    if let Some(content_type) = stored
+        .content_type()
+        .and_then(|value| HeaderValue::from_str(value).ok())
+    {
+        headers.insert(CONTENT_TYPE, content_type);
+    }
+    for (name, value) in stored.headers() {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    headers.insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::idempotency::service::Service as IdempotencyDomainService;
+    use crate::outbound::memory::Memory;
+    use axum::http::header::{ETAG, LOCATION};
+    use axum::routing::post;
+    use axum::Router;
+    use serde_json::Value;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    /// A router whose only route counts the requests it handles, and answers with the count, tagged
+    /// with it as the ETag, or with 500 Internal Server Error if the body is `fail`.
+    fn router() -> (Router, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let router = Router::new()
+            .route(
+                "/tasks",
+                post(move |body: String| async move {
+                    let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                    if body == "fail" {
+                        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                    }
+                    (
+                        StatusCode::CREATED,
+                        [
+                            (ETAG, format!("\"{}\"", n)),
+                            (LOCATION, format!("/tasks/{}", n)),
+                        ],
+                        axum::Json(serde_json::json!({ "n": n })),
+                    )
+                        .into_response()
+                }),
+            )
+            .layer(IdempotencyLayer::new(IdempotencyDomainService::new(
+                Memory::new(),
+            )));
+        (router, calls)
+    }
+
+    async fn send(
+        router: &Router,
+        key: Option<&str>,
+        token: &str,
+        body: &'static str,
+    ) -> (StatusCode, Option<HeaderValue>, Value) {
+        let mut request = Request::post("/tasks").header(AUTHORIZATION, token);
+        if let Some(key) = key {
+            request = request.header(IDEMPOTENCY_KEY, key);
+        }
+        let response = router
+            .clone()
+            .oneshot(request.body(Body::from(body)).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let replayed = response.headers().get(IDEMPOTENT_REPLAYED).cloned();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+        (status, replayed, json)
+    }
+
+    #[tokio::test]
+    async fn test_retry_replays_the_stored_response() {
+        let (router, calls) = router();
+        let first = send(&router, Some("k1"), "Bearer a", "milk").await;
+        assert_eq!(first.0, StatusCode::CREATED);
+        assert_eq!(first.1, None);
+        let retry = send(&router, Some("k1"), "Bearer a", "milk").await;
+        assert_eq!(retry.0, StatusCode::CREATED);
+        assert_eq!(retry.1, Some(HeaderValue::from_static("true")));
+        assert_eq!(retry.2, first.2);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_replays_the_stored_headers() {
+        let (router, _) = router();
+        let request = || {
+            Request::post("/tasks")
+                .header(AUTHORIZATION, "Bearer a")
+                .header(IDEMPOTENCY_KEY, "k1")
+                .body(Body::from("milk"))
+                .unwrap()
+        };
+        router.clone().oneshot(request()).await.unwrap();
+        let retry = router.clone().oneshot(request()).await.unwrap();
+        let headers = retry.headers();
+        assert_eq!(headers[IDEMPOTENT_REPLAYED], "true");
+        assert_eq!(headers[ETAG], "\"1\"");
+        assert_eq!(headers[LOCATION], "/tasks/1");
+        assert_eq!(headers[CONTENT_TYPE], "application/json");
+    }
+
+    #[tokio::test]
+    async fn test_requests_without_a_key_are_always_handled() {
+        let (router, calls) = router();
+        send(&router, None, "Bearer a", "milk").await;
+        send(&router, None, "Bearer a", "milk").await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_key_reused_with_another_body_is_unprocessable() {
+        let (router, calls) = router();
+        send(&router, Some("k1"), "Bearer a", "milk").await;
+        let (status, _, problem) = send(&router, Some("k1"), "Bearer a", "eggs").await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(problem["code"], "idempotency.key_reused");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_keys_are_scoped_to_the_credentials() {
+        let (router, calls) = router();
+        send(&router, Some("k1"), "Bearer a", "milk").await;
+        let (status, replayed, _) = send(&router, Some("k1"), "Bearer b", "milk").await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(replayed, None);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_not_replayed() {
+        let (router, calls) = router();
+        send(&router, Some("k1"), "Bearer a", "fail").await;
+        let (status, replayed, _) = send(&router, Some("k1"), "Bearer a", "fail").await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(replayed, None);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_key_is_bad_request() {
+        let (router, calls) = router();
+        let (status, _, problem) = send(&router, Some(""), "Bearer a", "milk").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(problem["code"], "idempotency.invalid_key");
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,250 @@
+use axum::Json;
+use utoipa::openapi::path::{ParameterBuilder, ParameterIn};
+use utoipa::openapi::schema::{ObjectBuilder, Type};
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::openapi::Required;
+use utoipa::{Modify, OpenApi};
+
+use crate::domain::idempotency::models::key::IdempotencyKey;
+use crate::inbound::http::handlers;
+
+/// The OpenAPI document of the API, derived from its handlers and their request and response
+/// types.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "modus", description = "Tasks, task lists and reminders"),
+    paths(
+        handlers::create_task::create_task,
+        handlers::list_tasks::list_tasks,
+        handlers::get_task::get_task,
+        handlers::update_task::update_task,
+        handlers::delete_task::delete_task,
+        handlers::tag_task::tag_task,
+        handlers::untag_task::untag_task,
+        handlers::create_list::create_list,
+        handlers::list_lists::list_lists,
+        handlers::get_list::get_list,
+        handlers::update_list::update_list,
+        handlers::delete_list::delete_list,
+        handlers::list_members::list_members,
+        handlers::share_list::share_list,
+        handlers::unshare_list::unshare_list,
+        handlers::list_tags::list_tags,
+        handlers::rename_tag::rename_tag,
+        handlers::merge_tags::merge_tags,
+        handlers::list_trash::list_trash,
+        handlers::empty_trash::empty_trash,
+        handlers::restore_task::restore_task,
+        handlers::restore_list::restore_list,
+        handlers::register_user::register_user,
+        handlers::get_current_user::get_current_user,
+        handlers::log_in::log_in,
+        handlers::log_out::log_out,
+        handlers::create_api_token::create_api_token,
+        handlers::list_api_tokens::list_api_tokens,
+        handlers::revoke_api_token::revoke_api_token,
+        handlers::liveness::liveness,
+        handlers::readiness::readiness,
+    ),
+    tags(
+        (name = "tasks", description = "Tasks, their subtasks and tags"),
+        (name = "lists", description = "Task lists and the users they are shared with"),
+        (name = "tags", description = "The tags carried by tasks"),
+        (name = "trash", description = "Deleted tasks and lists, until restored or purged"),
+        (name = "users", description = "User accounts"),
+        (name = "sessions", description = "Signing in and out"),
+        (name = "tokens", description = "Personal API tokens"),
+        (name = "health", description = "Liveness and readiness probes"),
+    ),
+    modifiers(&BearerAuth, &IdempotencyKeyHeader),
+)]
+pub struct ApiDoc;
+
+/// Declares the `bearer` security scheme that authenticated operations require.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+/// Documents the optional `Idempotency-Key` header that every mutating operation accepts.
+struct IdempotencyKeyHeader;
+
+impl Modify for IdempotencyKeyHeader {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let parameter = ParameterBuilder::new()
+            .name("Idempotency-Key")
+            .parameter_in(ParameterIn::Header)
+            .required(Required::False)
+            .description(Some(
+                "A key that makes the request safe to retry. Retries with the same key and body \
+                 get the first response again, with an `Idempotent-Replayed: true` header.",
+            ))
+            .schema(Some(
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .min_length(Some(1))
+                    .max_length(Some(IdempotencyKey::MAX_LENGTH)),
+            ))
+            .build();
+        for item in openapi.paths.paths.values_mut() {
+            let operations = [
+                &mut item.post,
+                &mut item.put,
+                &mut item.patch,
+                &mut item.delete,
+            ];
+            for operation in operations.into_iter().flatten() {
+                operation
+                    .parameters
+                    .get_or_insert_with(Vec::new)
+                    .push(parameter.clone());
+            }
+        }
+    }
+}
+
+/// Serve the [ApiDoc], from which client SDKs are generated. No bearer token is needed.
+///
+/// # Responses
+///
+/// - 200 OK: the OpenAPI document of the API.
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inbound::http::api_routes;
+    use crate::inbound::http::handlers::test_utils::{
+        app_state, MockReadinessService, MockReminderService,
+    };
+    use axum::body::Body;
+    use axum::extract::{MatchedPath, Request};
+    use axum::http::{Method, StatusCode};
+    use axum::middleware::{self, Next};
+    use axum::response::Response;
+    use axum::Router;
+    use std::collections::BTreeSet;
+    use tower::ServiceExt;
+
+    const METHODS: [Method; 5] = [
+        Method::GET,
+        Method::POST,
+        Method::PUT,
+        Method::PATCH,
+        Method::DELETE,
+    ];
+
+    /// Echo the route a request matched in the response, so that tests can tell it apart from a
+    /// handler that answers 404 Not Found.
+    async fn matched_path(req: Request, next: Next) -> Response {
+        let path = req.extensions().get::<MatchedPath>().cloned();
+        let mut response = next.run(req).await;
+        if let Some(path) = path {
+            response.extensions_mut().insert(path);
+        }
+        response
+    }
+
+    fn router() -> Router {
+        Router::new()
+            .nest("/api", api_routes())
+            .layer(middleware::from_fn(matched_path))
+            .with_state(app_state(
+                MockReminderService::default(),
+                MockReadinessService::default(),
+            ))
+    }
+
+    /// The route, in the syntax of the router, that serves `method` at the documented `path`, if
+    /// any.
+    async fn served_route(router: &Router, method: &Method, path: &str) -> Option<String> {
+        let uri = path
+            .replace("{tag}", "house")
+            .replace("{id}", "6f5e1a52-8a4b-4e0d-9d9e-0b9a1f3c2d10")
+            .replace("{user_id}", "0d0c8f38-52b3-4f4b-a0e1-7b9a3b2c1d0e");
+        let req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        let response = router.clone().oneshot(req).await.unwrap();
+        if response.status() == StatusCode::METHOD_NOT_ALLOWED {
+            return None;
+        }
+        response
+            .extensions()
+            .get::<MatchedPath>()
+            .map(|path| path.as_str().to_string())
+    }
+
+    /// The paths of the routes declared in [api_routes], read from its source because a router
+    /// cannot list its routes.
+    fn declared_paths() -> BTreeSet<String> {
+        let source = include_str!("../http.rs");
+        let body = &source[source.find("fn api_routes").unwrap()..];
+        body.split(".route(")
+            .skip(1)
+            .filter_map(|route| route.trim_start().strip_prefix('"'))
+            .map(|route| format!("/api{}", &route[..route.find('"').unwrap()]))
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_spec_documents_exactly_the_served_routes() {
+        let spec = ApiDoc::openapi();
+        let router = router();
+        let mut documented_paths = BTreeSet::new();
+        for (path, item) in &spec.paths.paths {
+            // The router writes path parameters as `:id` rather than `{id}`
+            let route = path.replace('{', ":").replace('}', "");
+            documented_paths.insert(route.clone());
+            for method in METHODS {
+                let documented = match method {
+                    Method::GET => item.get.is_some(),
+                    Method::POST => item.post.is_some(),
+                    Method::PUT => item.put.is_some(),
+                    Method::PATCH => item.patch.is_some(),
+                    _ => item.delete.is_some(),
+                };
+                let served = served_route(&router, &method, path).await;
+                if documented {
+                    assert_eq!(
+                        served.as_deref(),
+                        Some(route.as_str()),
+                        "{} {} is documented but not served",
+                        method,
+                        path
+                    );
+                } else {
+                    assert_ne!(
+                        served.as_deref(),
+                        Some(route.as_str()),
+                        "{} {} is served but not documented",
+                        method,
+                        path
+                    );
+                }
+            }
+        }
+        assert_eq!(documented_paths, declared_paths());
+    }
+
+    #[test]
+    fn test_spec_is_openapi_3_1_with_bearer_auth() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        assert!(spec["openapi"].as_str().unwrap().starts_with("3.1"));
+        assert_eq!(
+            spec["components"]["securitySchemes"]["bearer"]["scheme"],
+            "bearer"
+        );
+    }
+}
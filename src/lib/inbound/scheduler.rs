@@ -0,0 +1,102 @@
+use crate::domain::reminders::ports::ReminderService;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::{self, MissedTickBehavior};
+
+/// Configure the reminder scheduler
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchedulerConfig {
+    /// How often to look for reminders that are due.
+    pub interval: Duration,
+    /// How long deleted tasks and lists stay in the trash before they are purged.
+    pub trash_retention: Duration,
+}
+
+/// Periodically fires the reminders that are due and purges the trash. Any number of schedulers
+/// may run against the same store; each reminder is delivered by exactly one of them.
+pub struct Scheduler<RS: ReminderService> {
+    reminder_service: RS,
+    interval: Duration,
+    trash_retention: TimeDelta,
+}
+
+impl<RS: ReminderService> Scheduler<RS> {
+    /// Returns a new scheduler that fires reminders through `reminder_service`.
+    pub fn new(reminder_service: RS, config: SchedulerConfig) -> Self {
+        Self {
+            reminder_service,
+            interval: config.interval,
+            trash_retention: TimeDelta::from_std(config.trash_retention).unwrap_or(TimeDelta::MAX),
+        }
+    }
+
+    /// Run the scheduler until `shutdown` completes. A pass that is in progress when `shutdown`
+    /// completes is allowed to finish.
+    pub async fn run(self, shutdown: impl Future<Output = ()>) -> anyhow::Result<()> {
+        let mut ticker = time::interval(self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        tokio::pin!(shutdown);
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => return Ok(()),
+                _ = ticker.tick() => self.tick().await,
+            }
+        }
+    }
+
+    async fn tick(&self) {
+        let now = Utc::now();
+        match self.reminder_service.fire_due_reminders(now).await {
+            Ok(fired) if fired.delivered + fired.failed > 0 => {
+                tracing::info!(
+                    delivered = fired.delivered,
+                    failed = fired.failed,
+                    "Fired reminders"
+                );
+            }
+            Ok(_) => {}
+            Err(e) => tracing::error!(error = ?e, "Failed to fire due reminders"),
+        }
+        let deleted_before = now
+            .checked_sub_signed(self.trash_retention)
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        match self.reminder_service.purge_trash(deleted_before).await {
+            Ok(purged) if purged.tasks + purged.lists > 0 => {
+                tracing::info!(tasks = purged.tasks, lists = purged.lists, "Purged trash");
+            }
+            Ok(_) => {}
+            Err(e) => tracing::error!(error = ?e, "Failed to purge trash"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inbound::http::MockReminderService;
+    use tokio::sync::oneshot;
+
+    #[tokio::test]
+    async fn test_run_returns_when_shutdown_completes() {
+        let config = SchedulerConfig {
+            interval: Duration::from_millis(10),
+            trash_retention: Duration::from_secs(60),
+        };
+        let scheduler = Scheduler::new(MockReminderService::default(), config);
+        let (stop, stopped) = oneshot::channel::<()>();
+        let run = tokio::spawn(scheduler.run(async {
+            let _ = stopped.await;
+        }));
+
+        // Let the scheduler make a few passes before shutting it down
+        time::sleep(Duration::from_millis(50)).await;
+        stop.send(()).unwrap();
+        let result = time::timeout(Duration::from_secs(1), run)
+            .await
+            .expect("expected the scheduler to stop after shutdown")
+            .unwrap();
+        assert!(result.is_ok(), "expected Ok, got {:?}", result);
+    }
+}
@@ -1 +1,6 @@
+pub mod memory;
+pub mod prometheus;
 pub mod sql;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod stdout;
@@ -0,0 +1,1850 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::idempotency::models::key::{
+    ClaimIdempotencyKeyError, CompleteIdempotentRequestError, IdempotencyRecord, IdempotentRequest,
+    StoredResponse,
+};
+use crate::domain::idempotency::ports::IdempotencyRepository;
+use crate::domain::readiness::models::ready::ReadinessError;
+use crate::domain::readiness::ports::ReadinessRepository;
+use crate::domain::reminders::models::list::{
+    CreateTaskListError, DeleteTaskListError, GetTaskListError, ListTaskListsError,
+    RestoreTaskListError, UpdateTaskListError,
+};
+use crate::domain::reminders::models::list::{
+    CreateTaskListRequest, DeleteTaskListMode, TaskList, TaskListTitle, UpdateTaskListRequest,
+};
+use crate::domain::reminders::models::member::{ListRole, ShareTaskListRequest, TaskListMember};
+use crate::domain::reminders::models::member::{
+    ListTaskListMembersError, ShareTaskListError, UnshareTaskListError,
+};
+use crate::domain::reminders::models::query::{ListTasksRequest, TaskPage};
+use crate::domain::reminders::models::reminder::{DueReminder, FireRemindersError};
+use crate::domain::reminders::models::tag::{
+    ListTagsError, MergeTagsError, RenameTagError, Tag, TagUsage,
+};
+use crate::domain::reminders::models::task::{
+    CreateTaskError, DeleteTaskError, GetTaskError, ListTasksError, RestoreTaskError,
+    UpdateTaskError,
+};
+use crate::domain::reminders::models::task::{
+    CreateTaskRequest, Task, TaskTitle, UpdateTaskRequest,
+};
+use crate::domain::reminders::models::trash::{
+    ListTrashError, PurgeTrashError, PurgedTrash, Trash, Trashed,
+};
+use crate::domain::reminders::ports::ReminderRepository;
+use crate::domain::users::models::api_token::{
+    ApiToken, ApiTokenSecret, CreateApiTokenError, ListApiTokensError, NewApiToken, Principal,
+    RevokeApiTokenError,
+};
+use crate::domain::users::models::session::{
+    AuthenticateError, LogInError, LogOutError, Session, SessionToken,
+};
+use crate::domain::users::models::user::{
+    EmailAddress, PasswordHash, RegisterUserError, User, UserCredentials,
+};
+use crate::domain::users::ports::UserRepository;
+
+/// The delivery state of a single reminder of a task.
+#[derive(Debug, Clone, Default)]
+struct ReminderState {
+    fired: bool,
+    attempts: u32,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Tasks in the order they were created.
+    tasks: Vec<Task>,
+    reminders: BTreeMap<(Uuid, DateTime<Utc>), ReminderState>,
+    lists: Vec<TaskList>,
+    /// The members of each list other than its owner, by list id, in the order they were added.
+    members: Vec<(Uuid, TaskListMember)>,
+    /// The id of the user who owns each task and list.
+    owners: HashMap<Uuid, Uuid>,
+    /// When each task and list in the trash was moved there, by id.
+    trashed: HashMap<Uuid, DateTime<Utc>>,
+    users: Vec<UserCredentials>,
+    /// The user id and expiry of each session, by the digest of its token.
+    sessions: HashMap<String, (Uuid, DateTime<Utc>)>,
+    /// The user id, secret digest and details of each API token, oldest first.
+    api_tokens: Vec<(Uuid, String, ApiToken)>,
+    /// The record and expiry of each idempotency key, by owner digest and key.
+    idempotency_keys: HashMap<(String, String), (IdempotencyRecord, DateTime<Utc>)>,
+}
+
+impl State {
+    /// Whether the task or list with `id` belongs to `owner`.
+    fn owns(&self, owner: &Uuid, id: &Uuid) -> bool {
+        self.owners.get(id) == Some(owner)
+    }
+
+    /// Whether the task or list with `id` is in the trash.
+    fn is_trashed(&self, id: &Uuid) -> bool {
+        self.trashed.contains_key(id)
+    }
+
+    /// The tasks that belong to `owner`, other than those in the trash.
+    fn owned_tasks<'a>(&'a self, owner: &'a Uuid) -> impl Iterator<Item = &'a Task> + 'a {
+        self.tasks
+            .iter()
+            .filter(move |task| self.owns(owner, &task.id) && !self.is_trashed(&task.id))
+    }
+
+    fn find(&self, owner: &Uuid, id: &Uuid) -> Option<usize> {
+        self.tasks
+            .iter()
+            .position(|task| task.id == *id && self.owns(owner, id) && !self.is_trashed(id))
+    }
+
+    /// The ids of `id` and of every task nested below it.
+    fn subtree(&self, id: &Uuid) -> Vec<Uuid> {
+        let mut ids = vec![*id];
+        let mut index = 0;
+        while let Some(parent_id) = ids.get(index).copied() {
+            ids.extend(
+                self.tasks
+                    .iter()
+                    .filter(|task| task.parent_id == Some(parent_id))
+                    .map(|task| task.id),
+            );
+            index += 1;
+        }
+        ids
+    }
+
+    /// Remove the tasks with the given ids along with their reminders.
+    fn remove_tasks(&mut self, ids: &[Uuid]) {
+        self.tasks.retain(|task| !ids.contains(&task.id));
+        self.owners.retain(|id, _| !ids.contains(id));
+        self.trashed.retain(|id, _| !ids.contains(id));
+        self.reminders
+            .retain(|(task_id, _), _| !ids.contains(task_id));
+    }
+
+    /// Move the tasks with the given ids to the trash at `now`.
+    fn trash_tasks(&mut self, ids: &[Uuid], now: DateTime<Utc>) {
+        for task in self.tasks.iter_mut().filter(|task| ids.contains(&task.id)) {
+            task.version += 1;
+            self.trashed.insert(task.id, now);
+        }
+    }
+
+    /// The ids of the tasks in `roots` and the subtasks of each that were moved to the trash at
+    /// the same time as it.
+    fn trashed_together(&self, roots: &[Uuid]) -> Vec<Uuid> {
+        roots
+            .iter()
+            .flat_map(|root| {
+                let deleted_at = self.trashed.get(root);
+                self.subtree(root)
+                    .into_iter()
+                    .filter(move |id| deleted_at.is_some() && self.trashed.get(id) == deleted_at)
+            })
+            .collect()
+    }
+
+    /// The first of the tasks with the given ids that cannot be taken out of the trash because
+    /// it is incomplete while another incomplete task of `owner` has the same title.
+    fn restore_clash(&self, owner: &Uuid, ids: &[Uuid]) -> Option<TaskTitle> {
+        self.tasks
+            .iter()
+            .filter(|task| ids.contains(&task.id) && !task.completed)
+            .find(|task| self.is_duplicate(owner, &task.title, Some(&task.id)))
+            .map(|task| task.title.clone())
+    }
+
+    /// Take the tasks with the given ids out of the trash.
+    fn untrash_tasks(&mut self, ids: &[Uuid]) {
+        for task in self.tasks.iter_mut().filter(|task| ids.contains(&task.id)) {
+            task.version += 1;
+            self.trashed.remove(&task.id);
+        }
+    }
+
+    /// Remove the tasks and lists in the trash of `owner`, or of every user if it is `None`, that
+    /// were moved there before `deleted_before`, if given.
+    fn purge(
+        &mut self,
+        owner: Option<&Uuid>,
+        deleted_before: Option<DateTime<Utc>>,
+    ) -> PurgedTrash {
+        let expired = |id: &Uuid| {
+            self.trashed
+                .get(id)
+                .is_some_and(|at| deleted_before.is_none_or(|before| *at < before))
+                && owner.is_none_or(|owner| self.owns(owner, id))
+        };
+        let lists = self
+            .lists
+            .iter()
+            .map(|list| list.id)
+            .filter(|id| expired(id))
+            .collect::<Vec<_>>();
+        // Tasks left in a list are removed with it, whenever they were moved to the trash
+        let mut tasks = self
+            .tasks
+            .iter()
+            .filter(|task| {
+                expired(&task.id) || task.list_id.is_some_and(|list_id| lists.contains(&list_id))
+            })
+            .flat_map(|task| self.subtree(&task.id))
+            .collect::<Vec<_>>();
+        tasks.sort();
+        tasks.dedup();
+
+        self.remove_tasks(&tasks);
+        self.lists.retain(|list| !lists.contains(&list.id));
+        self.owners.retain(|id, _| !lists.contains(id));
+        self.trashed.retain(|id, _| !lists.contains(id));
+        self.members.retain(|(list_id, _)| !lists.contains(list_id));
+        PurgedTrash {
+            tasks: tasks.len() as u64,
+            lists: lists.len() as u64,
+        }
+    }
+
+    /// The number of tasks of `owner` that carry `tag`.
+    fn tag_usage(&self, owner: &Uuid, tag: &Tag) -> TagUsage {
+        TagUsage {
+            tag: tag.clone(),
+            tasks: self
+                .owned_tasks(owner)
+                .filter(|task| task.tags.contains(tag))
+                .count() as u64,
+        }
+    }
+
+    fn find_list(&self, owner: &Uuid, id: &Uuid) -> Option<usize> {
+        self.lists
+            .iter()
+            .position(|list| list.id == *id && self.owns(owner, id) && !self.is_trashed(id))
+    }
+
+    /// The role of `user` in the list with `id`, or `None` if they may not access it.
+    fn role(&self, user: &Uuid, id: &Uuid) -> Option<ListRole> {
+        if self.owns(user, id) {
+            return Some(ListRole::Owner);
+        }
+        self.members
+            .iter()
+            .find(|(list_id, member)| list_id == id && member.user_id == *user)
+            .map(|(_, member)| member.role)
+    }
+
+    /// The list with `id` as seen by `user`, if they may access it and it is not in the trash.
+    fn visible_list(&self, user: &Uuid, id: &Uuid) -> Option<TaskList> {
+        if self.is_trashed(id) {
+            return None;
+        }
+        let role = self.role(user, id)?;
+        let list = self.lists.iter().find(|list| list.id == *id)?;
+        Some(TaskList {
+            role,
+            ..list.clone()
+        })
+    }
+
+    /// Whether `user` owns the task or may access the list it is in, ignoring the trash.
+    fn may_access(&self, user: &Uuid, task: &Task) -> bool {
+        self.owns(user, &task.id)
+            || task
+                .list_id
+                .is_some_and(|list_id| self.role(user, &list_id).is_some())
+    }
+
+    /// Whether the task is not in the trash and `user` may access it.
+    fn can_see(&self, user: &Uuid, task: &Task) -> bool {
+        !self.is_trashed(&task.id) && self.may_access(user, task)
+    }
+
+    /// Whether another unarchived list of `owner` already has `title`, ignoring case.
+    fn is_duplicate_list(
+        &self,
+        owner: &Uuid,
+        title: &TaskListTitle,
+        except: Option<&Uuid>,
+    ) -> bool {
+        let title = title.to_string().to_lowercase();
+        self.lists.iter().any(|list| {
+            self.owns(owner, &list.id)
+                && !self.is_trashed(&list.id)
+                && !list.archived
+                && Some(&list.id) != except
+                && list.title.to_string().to_lowercase() == title
+        })
+    }
+
+    /// Whether another incomplete task of `owner` already has `title`, ignoring case.
+    fn is_duplicate(&self, owner: &Uuid, title: &TaskTitle, except: Option<&Uuid>) -> bool {
+        let title = title.to_string().to_lowercase();
+        self.owned_tasks(owner).any(|task| {
+            !task.completed
+                && Some(&task.id) != except
+                && task.title.to_string().to_lowercase() == title
+        })
+    }
+
+    /// Add a task of `owner` created from `req`, placed after its siblings unless `req` gives a
+    /// position. Its reminders are left to the caller.
+    fn insert_task(&mut self, owner: &Uuid, req: &CreateTaskRequest) -> Task {
+        let position = req.position().unwrap_or_else(|| {
+            self.owned_tasks(owner)
+                .filter(|task| task.parent_id == req.parent_id())
+                .map(|task| task.position + 1)
+                .max()
+                .unwrap_or_default()
+        });
+        let task = Task {
+            id: Uuid::new_v4(),
+            title: req.title().clone(),
+            completed: false,
+            priority: req.priority(),
+            notes: req.notes().cloned(),
+            due_at: req.due_at().copied(),
+            remind_at: req.remind_at().clone(),
+            recurrence: req.recurrence().cloned(),
+            list_id: req.list_id(),
+            parent_id: req.parent_id(),
+            position,
+            tags: req.tags().clone(),
+            assignee_id: req.assignee_id(),
+            created_at: Utc::now(),
+            updated_by: req.updated_by(),
+            completed_by: None,
+            version: 1,
+        };
+        self.owners.insert(task.id, *owner);
+        self.tasks.push(task.clone());
+        task
+    }
+
+    /// Complete every incomplete task nested below the task with `id` on behalf of `user`.
+    fn complete_subtasks(&mut self, id: &Uuid, user: Option<Uuid>) {
+        let subtree = self
+            .subtree(id)
+            .into_iter()
+            .filter(|subtask| subtask != id && !self.is_trashed(subtask))
+            .collect::<Vec<_>>();
+        for task in self.tasks.iter_mut() {
+            if subtree.contains(&task.id) && !task.completed {
+                task.completed = true;
+                task.completed_by = user;
+                task.updated_by = user.or(task.updated_by);
+                task.version += 1;
+            }
+        }
+    }
+
+    /// Complete or reopen the ancestors of a task whose parent is `parent_id` on behalf of
+    /// `user`, so that a task with subtasks is completed exactly when all of its subtasks are.
+    /// Fails with the title of an ancestor that cannot be reopened because another incomplete
+    /// task has the same title, leaving the ancestors below it changed.
+    fn sync_ancestors(
+        &mut self,
+        owner: &Uuid,
+        parent_id: Option<Uuid>,
+        user: Option<Uuid>,
+    ) -> Result<(), TaskTitle> {
+        let mut parent_id = parent_id;
+        while let Some(index) = parent_id.and_then(|id| self.find(owner, &id)) {
+            let id = self.tasks[index].id;
+            let subtasks = self
+                .owned_tasks(owner)
+                .filter(|task| task.parent_id == Some(id))
+                .map(|task| task.completed)
+                .collect::<Vec<_>>();
+            if subtasks.is_empty() {
+                return Ok(());
+            }
+            let completed = subtasks.iter().all(|&completed| completed);
+            let parent = &self.tasks[index];
+            if parent.completed == completed {
+                return Ok(());
+            }
+            if !completed && self.is_duplicate(owner, &parent.title, Some(&id)) {
+                return Err(parent.title.clone());
+            }
+            let parent = &mut self.tasks[index];
+            parent.completed = completed;
+            parent.completed_by = user.filter(|_| completed);
+            parent.updated_by = user.or(parent.updated_by);
+            parent.version += 1;
+            parent_id = parent.parent_id;
+        }
+        Ok(())
+    }
+
+    /// Replace the pending reminders of a task with those derived from its current due date and
+    /// reminder offsets. Reminders that already fired are kept so they never fire twice.
+    fn sync_reminders(&mut self, index: usize) {
+        let task = &self.tasks[index];
+        let id = task.id;
+        self.reminders
+            .retain(|(task_id, _), reminder| *task_id != id || reminder.fired);
+        if let Some(due_at) = task.due_at {
+            for offset in task.remind_at.offsets() {
+                self.reminders
+                    .entry((id, offset.remind_at(&due_at)))
+                    .or_default();
+            }
+        }
+    }
+}
+
+/// A [ReminderRepository] and [UserRepository] that keeps everything in process memory, for
+/// running without a database and for tests. Clones share the same data, which is lost when the
+/// last clone is dropped.
+#[derive(Debug, Clone, Default)]
+pub struct Memory {
+    state: Arc<Mutex<State>>,
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        // No operation panics while holding the lock, so the state is never left inconsistent
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ReminderRepository for Memory {
+    async fn create_task(
+        &self,
+        owner: &Uuid,
+        req: &CreateTaskRequest,
+    ) -> Result<Task, CreateTaskError> {
+        let mut state = self.state();
+        if state.is_duplicate(owner, req.title(), None) {
+            return Err(CreateTaskError::Duplicate {
+                title: req.title().clone(),
+            });
+        }
+        if let Some(id) = req
+            .list_id()
+            .filter(|id| state.find_list(owner, id).is_none())
+        {
+            return Err(CreateTaskError::ListNotFound { id });
+        }
+        if let Some(id) = req.parent_id().filter(|id| state.find(owner, id).is_none()) {
+            return Err(CreateTaskError::ParentNotFound { id });
+        }
+
+        let tasks = state.tasks.clone();
+        let task = state.insert_task(owner, req);
+        if let Err(title) = state.sync_ancestors(owner, task.parent_id, task.updated_by) {
+            state.tasks = tasks;
+            state.owners.remove(&task.id);
+            return Err(CreateTaskError::Duplicate { title });
+        }
+        let index = state.tasks.len() - 1;
+        state.sync_reminders(index);
+        Ok(task)
+    }
+
+    async fn get_task(&self, user: &Uuid, id: &Uuid) -> Result<Task, GetTaskError> {
+        let state = self.state();
+        state
+            .tasks
+            .iter()
+            .find(|task| task.id == *id && state.can_see(user, task))
+            .cloned()
+            .ok_or(GetTaskError::NotFound { id: *id })
+    }
+
+    async fn list_tasks(
+        &self,
+        user: &Uuid,
+        req: &ListTasksRequest,
+    ) -> Result<TaskPage, ListTasksError> {
+        let state = self.state();
+        let mut tasks = state
+            .tasks
+            .iter()
+            .filter(|task| state.can_see(user, task))
+            .filter(|task| req.filter().matches(task))
+            .filter(|task| req.cursor().is_none_or(|cursor| cursor.precedes(task)))
+            .collect::<Vec<_>>();
+        tasks.sort_by(|a, b| req.sort().compare(a, b));
+        let tasks = tasks
+            .into_iter()
+            .take(req.size().get() as usize + 1)
+            .cloned()
+            .collect();
+        Ok(req.page(tasks))
+    }
+
+    async fn list_subtasks(&self, owner: &Uuid, id: &Uuid) -> Result<Vec<Task>, ListTasksError> {
+        let state = self.state();
+        let subtree = state.subtree(id);
+        let mut subtasks = state
+            .owned_tasks(owner)
+            .filter(|task| task.id != *id && subtree.contains(&task.id))
+            .cloned()
+            .collect::<Vec<_>>();
+        subtasks.sort_by_key(|task| (task.position, task.created_at, task.id));
+        Ok(subtasks)
+    }
+
+    async fn update_task(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &UpdateTaskRequest,
+    ) -> Result<Task, UpdateTaskError> {
+        let mut state = self.state();
+        let index = state
+            .find(owner, id)
+            .ok_or(UpdateTaskError::NotFound { id: *id })?;
+        if let Some(version) = req.version().filter(|v| *v != state.tasks[index].version) {
+            return Err(UpdateTaskError::Conflict { id: *id, version });
+        }
+
+        let mut task = state.tasks[index].clone();
+        task.version += 1;
+        if let Some(title) = req.title() {
+            task.title = title.clone();
+        }
+        if let Some(completed) = req.completed().filter(|c| *c != task.completed) {
+            task.completed = completed;
+            task.completed_by = req.updated_by().filter(|_| completed);
+        }
+        if let Some(user) = req.updated_by() {
+            task.updated_by = Some(user);
+        }
+        if let Some(priority) = req.priority() {
+            task.priority = priority;
+        }
+        if let Some(notes) = req.notes() {
+            task.notes = notes.cloned();
+        }
+        if let Some(due_at) = req.due_at() {
+            task.due_at = due_at.copied();
+        }
+        if let Some(remind_at) = req.remind_at() {
+            task.remind_at = remind_at.clone();
+        }
+        if let Some(recurrence) = req.recurrence() {
+            task.recurrence = recurrence.cloned();
+        }
+        if let Some(list_id) = req.list_id() {
+            task.list_id = list_id;
+        }
+        if let Some(parent_id) = req.parent_id().filter(|id| *id != task.parent_id) {
+            task.parent_id = parent_id;
+            task.position = state
+                .owned_tasks(owner)
+                .filter(|task| task.parent_id == parent_id)
+                .map(|task| task.position + 1)
+                .max()
+                .unwrap_or_default();
+        }
+        if let Some(position) = req.position() {
+            task.position = position;
+        }
+        if let Some(tags) = req.tags() {
+            task.tags = tags.clone();
+        }
+        if let Some(assignee_id) = req.assignee_id() {
+            task.assignee_id = assignee_id;
+        }
+        if !task.completed && state.is_duplicate(owner, &task.title, Some(id)) {
+            return Err(UpdateTaskError::Duplicate { title: task.title });
+        }
+        if let Some(id) = req
+            .list_id()
+            .flatten()
+            .filter(|id| state.find_list(owner, id).is_none())
+        {
+            return Err(UpdateTaskError::ListNotFound { id });
+        }
+        if let Some(id) = req
+            .parent_id()
+            .flatten()
+            .filter(|id| state.find(owner, id).is_none())
+        {
+            return Err(UpdateTaskError::ParentNotFound { id });
+        }
+
+        let tasks = state.tasks.clone();
+        let before = std::mem::replace(&mut state.tasks[index], task.clone());
+        let mut next = None;
+        if !before.completed && task.completed {
+            state.complete_subtasks(id, req.updated_by());
+            if let Some(mut occurrence) = task.next_occurrence(Utc::now()) {
+                if let Some(user) = req.updated_by() {
+                    occurrence = occurrence.with_updated_by(user);
+                }
+                if state.is_duplicate(owner, occurrence.title(), None) {
+                    state.tasks = tasks;
+                    return Err(UpdateTaskError::Duplicate {
+                        title: occurrence.title().clone(),
+                    });
+                }
+                next = Some(state.insert_task(owner, &occurrence));
+            }
+        }
+        if req.completed().is_some() || req.parent_id().is_some() {
+            let mut synced = Ok(());
+            if before.parent_id != task.parent_id {
+                synced = state.sync_ancestors(owner, before.parent_id, req.updated_by());
+            }
+            if let Err(title) =
+                synced.and_then(|_| state.sync_ancestors(owner, task.parent_id, req.updated_by()))
+            {
+                state.tasks = tasks;
+                if let Some(next) = next {
+                    state.owners.remove(&next.id);
+                }
+                return Err(UpdateTaskError::Duplicate { title });
+            }
+        }
+        state.sync_reminders(index);
+        if next.is_some() {
+            let last = state.tasks.len() - 1;
+            state.sync_reminders(last);
+        }
+        Ok(task)
+    }
+
+    async fn delete_task(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        version: Option<i64>,
+        updated_by: &Uuid,
+    ) -> Result<(), DeleteTaskError> {
+        let mut state = self.state();
+        let index = state
+            .find(owner, id)
+            .ok_or(DeleteTaskError::NotFound { id: *id })?;
+        if let Some(version) = version.filter(|v| *v != state.tasks[index].version) {
+            return Err(DeleteTaskError::Conflict { id: *id, version });
+        }
+        let subtree = state
+            .subtree(id)
+            .into_iter()
+            .filter(|id| !state.is_trashed(id))
+            .collect::<Vec<_>>();
+        let parent_id = state.tasks[index].parent_id;
+        state.trash_tasks(&subtree, Utc::now());
+        // Deleting a subtask can only complete its ancestors, which never clashes
+        let _ = state.sync_ancestors(owner, parent_id, Some(*updated_by));
+        Ok(())
+    }
+
+    async fn create_list(
+        &self,
+        owner: &Uuid,
+        req: &CreateTaskListRequest,
+    ) -> Result<TaskList, CreateTaskListError> {
+        let mut state = self.state();
+        if state.is_duplicate_list(owner, req.title(), None) {
+            return Err(CreateTaskListError::Duplicate {
+                title: req.title().clone(),
+            });
+        }
+
+        let position = req.position().unwrap_or_else(|| {
+            state
+                .lists
+                .iter()
+                .filter(|list| state.owns(owner, &list.id))
+                .map(|list| list.position + 1)
+                .max()
+                .unwrap_or_default()
+        });
+        let list = TaskList {
+            id: Uuid::new_v4(),
+            title: req.title().clone(),
+            colour: req.colour().cloned(),
+            position,
+            archived: false,
+            created_at: Utc::now(),
+            owner_id: *owner,
+            role: ListRole::Owner,
+        };
+        state.owners.insert(list.id, *owner);
+        state.lists.push(list.clone());
+        Ok(list)
+    }
+
+    async fn get_list(&self, user: &Uuid, id: &Uuid) -> Result<TaskList, GetTaskListError> {
+        self.state()
+            .visible_list(user, id)
+            .ok_or(GetTaskListError::NotFound { id: *id })
+    }
+
+    async fn list_lists(
+        &self,
+        user: &Uuid,
+        include_archived: bool,
+    ) -> Result<Vec<TaskList>, ListTaskListsError> {
+        let state = self.state();
+        let mut lists = state
+            .lists
+            .iter()
+            .filter_map(|list| state.visible_list(user, &list.id))
+            .filter(|list| include_archived || !list.archived)
+            .collect::<Vec<_>>();
+        lists.sort_by_key(|list| (list.position, list.created_at, list.id));
+        Ok(lists)
+    }
+
+    async fn update_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &UpdateTaskListRequest,
+    ) -> Result<TaskList, UpdateTaskListError> {
+        let mut state = self.state();
+        let index = state
+            .find_list(owner, id)
+            .ok_or(UpdateTaskListError::NotFound { id: *id })?;
+
+        let mut list = state.lists[index].clone();
+        if let Some(title) = req.title() {
+            list.title = title.clone();
+        }
+        if let Some(colour) = req.colour() {
+            list.colour = colour.cloned();
+        }
+        if let Some(position) = req.position() {
+            list.position = position;
+        }
+        if let Some(archived) = req.archived() {
+            list.archived = archived;
+        }
+        if !list.archived && state.is_duplicate_list(owner, &list.title, Some(id)) {
+            return Err(UpdateTaskListError::Duplicate { title: list.title });
+        }
+
+        state.lists[index] = list.clone();
+        Ok(list)
+    }
+
+    async fn delete_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        mode: DeleteTaskListMode,
+    ) -> Result<(), DeleteTaskListError> {
+        let mut state = self.state();
+        if state.find_list(owner, id).is_none() {
+            return Err(DeleteTaskListError::NotFound { id: *id });
+        }
+
+        let now = Utc::now();
+        let in_list = |task: &Task| task.list_id == Some(*id) && !state.is_trashed(&task.id);
+        match mode {
+            DeleteTaskListMode::OnlyIfEmpty => {
+                let tasks = state.tasks.iter().filter(|task| in_list(task)).count();
+                if tasks > 0 {
+                    return Err(DeleteTaskListError::NotEmpty {
+                        id: *id,
+                        tasks: tasks as u64,
+                    });
+                }
+            }
+            DeleteTaskListMode::Cascade => {
+                let mut removed = state
+                    .tasks
+                    .iter()
+                    .filter(|task| in_list(task))
+                    .flat_map(|task| state.subtree(&task.id))
+                    .filter(|id| !state.is_trashed(id))
+                    .collect::<Vec<_>>();
+                removed.sort();
+                removed.dedup();
+                state.trash_tasks(&removed, now);
+            }
+            DeleteTaskListMode::MoveToInbox => {
+                let moved = state
+                    .tasks
+                    .iter()
+                    .filter(|task| in_list(task))
+                    .map(|task| task.id)
+                    .collect::<Vec<_>>();
+                for task in state
+                    .tasks
+                    .iter_mut()
+                    .filter(|task| moved.contains(&task.id))
+                {
+                    task.list_id = None;
+                    // Only the owner can be assigned tasks in their inbox
+                    task.assignee_id = task.assignee_id.filter(|assignee| assignee == owner);
+                    task.version += 1;
+                }
+            }
+        }
+        state.trashed.insert(*id, now);
+        // Tokens restricted to the list are revoked with it
+        state
+            .api_tokens
+            .retain(|(_, _, token)| token.scope.list_id != Some(*id));
+        Ok(())
+    }
+
+    async fn list_members(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> Result<Vec<TaskListMember>, ListTaskListMembersError> {
+        let state = self.state();
+        let index = state
+            .find_list(owner, id)
+            .ok_or(ListTaskListMembersError::NotFound { id: *id })?;
+        let creator = state
+            .users
+            .iter()
+            .find(|stored| stored.user.id == *owner)
+            .map(|stored| TaskListMember {
+                user_id: *owner,
+                email: stored.user.email.clone(),
+                role: ListRole::Owner,
+                added_at: state.lists[index].created_at,
+            });
+        Ok(creator
+            .into_iter()
+            .chain(
+                state
+                    .members
+                    .iter()
+                    .filter(|(list_id, _)| list_id == id)
+                    .map(|(_, member)| member.clone()),
+            )
+            .collect())
+    }
+
+    async fn share_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &ShareTaskListRequest,
+    ) -> Result<TaskListMember, ShareTaskListError> {
+        let mut state = self.state();
+        if state.find_list(owner, id).is_none() {
+            return Err(ShareTaskListError::NotFound { id: *id });
+        }
+        let user = state
+            .users
+            .iter()
+            .find(|stored| stored.user.email == *req.email())
+            .map(|stored| stored.user.clone())
+            .ok_or_else(|| ShareTaskListError::UserNotFound {
+                email: req.email().clone(),
+            })?;
+        if user.id == *owner {
+            return Err(ShareTaskListError::Creator {
+                id: *id,
+                email: user.email,
+            });
+        }
+        if let Some((_, member)) = state
+            .members
+            .iter_mut()
+            .find(|(list_id, member)| list_id == id && member.user_id == user.id)
+        {
+            member.role = req.role();
+            return Ok(member.clone());
+        }
+        let member = TaskListMember {
+            user_id: user.id,
+            email: user.email,
+            role: req.role(),
+            added_at: Utc::now(),
+        };
+        state.members.push((*id, member.clone()));
+        Ok(member)
+    }
+
+    async fn unshare_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        member: &Uuid,
+    ) -> Result<(), UnshareTaskListError> {
+        let mut state = self.state();
+        if state.find_list(owner, id).is_none() {
+            return Err(UnshareTaskListError::NotFound { id: *id });
+        }
+        let index = state
+            .members
+            .iter()
+            .position(|(list_id, stored)| list_id == id && stored.user_id == *member)
+            .ok_or(UnshareTaskListError::MemberNotFound {
+                id: *id,
+                user_id: *member,
+            })?;
+        state.members.remove(index);
+        for task in state
+            .tasks
+            .iter_mut()
+            .filter(|task| task.list_id == Some(*id) && task.assignee_id == Some(*member))
+        {
+            task.assignee_id = None;
+            task.version += 1;
+        }
+        Ok(())
+    }
+
+    async fn list_tags(&self, owner: &Uuid) -> Result<Vec<TagUsage>, ListTagsError> {
+        let state = self.state();
+        let mut usage = BTreeMap::<Tag, u64>::new();
+        for tag in state.owned_tasks(owner).flat_map(|task| task.tags.tags()) {
+            *usage.entry(tag.clone()).or_default() += 1;
+        }
+        Ok(usage
+            .into_iter()
+            .map(|(tag, tasks)| TagUsage { tag, tasks })
+            .collect())
+    }
+
+    async fn rename_tag(
+        &self,
+        owner: &Uuid,
+        from: &Tag,
+        to: &Tag,
+    ) -> Result<TagUsage, RenameTagError> {
+        let mut state = self.state();
+        if state.tag_usage(owner, from).tasks == 0 {
+            return Err(RenameTagError::NotFound { tag: from.clone() });
+        }
+        if from != to && state.tag_usage(owner, to).tasks > 0 {
+            return Err(RenameTagError::Duplicate { tag: to.clone() });
+        }
+        let State {
+            tasks,
+            owners,
+            trashed,
+            ..
+        } = &mut *state;
+        for task in tasks
+            .iter_mut()
+            .filter(|task| owners.get(&task.id) == Some(owner) && !trashed.contains_key(&task.id))
+        {
+            let tags = task.tags.replace(std::slice::from_ref(from), to);
+            if tags != task.tags {
+                task.tags = tags;
+                task.version += 1;
+            }
+        }
+        Ok(state.tag_usage(owner, to))
+    }
+
+    async fn merge_tags(
+        &self,
+        owner: &Uuid,
+        tags: &[Tag],
+        into: &Tag,
+    ) -> Result<TagUsage, MergeTagsError> {
+        let mut state = self.state();
+        if let Some(tag) = tags
+            .iter()
+            .find(|tag| state.tag_usage(owner, tag).tasks == 0)
+        {
+            return Err(MergeTagsError::NotFound { tag: tag.clone() });
+        }
+        let State {
+            tasks,
+            owners,
+            trashed,
+            ..
+        } = &mut *state;
+        for task in tasks
+            .iter_mut()
+            .filter(|task| owners.get(&task.id) == Some(owner) && !trashed.contains_key(&task.id))
+        {
+            let merged = task.tags.replace(tags, into);
+            if merged != task.tags {
+                task.tags = merged;
+                task.version += 1;
+            }
+        }
+        Ok(state.tag_usage(owner, into))
+    }
+
+    async fn claim_due_reminders(
+        &self,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<DueReminder>, FireRemindersError> {
+        let mut state = self.state();
+        let State {
+            tasks,
+            reminders,
+            owners,
+            trashed,
+            ..
+        } = &mut *state;
+
+        let mut due = reminders
+            .iter_mut()
+            .filter(|((_, remind_at), reminder)| !reminder.fired && *remind_at <= now)
+            .filter_map(|((task_id, remind_at), reminder)| {
+                let task = tasks.iter().find(|task| task.id == *task_id)?;
+                if trashed.contains_key(task_id) {
+                    return None;
+                }
+                let recipient = task.assignee_id.or_else(|| owners.get(task_id).copied())?;
+                (!task.completed).then_some((*remind_at, task, recipient, reminder))
+            })
+            .collect::<Vec<_>>();
+        due.sort_by_key(|(remind_at, _, _, _)| *remind_at);
+
+        let claimed = due
+            .into_iter()
+            .take(limit as usize)
+            .map(|(remind_at, task, recipient, reminder)| {
+                reminder.fired = true;
+                DueReminder {
+                    task: task.clone(),
+                    remind_at,
+                    recipient,
+                    attempts: reminder.attempts,
+                }
+            })
+            .collect();
+        Ok(claimed)
+    }
+
+    /// Only the attempt is recorded, since nothing reads the error back from memory.
+    async fn record_reminder_failure(
+        &self,
+        reminder: &DueReminder,
+        _: &str,
+        retry: bool,
+    ) -> Result<(), FireRemindersError> {
+        let mut state = self.state();
+        if let Some(stored) = state
+            .reminders
+            .get_mut(&(reminder.task().id(), *reminder.remind_at()))
+        {
+            stored.attempts += 1;
+            if retry {
+                stored.fired = false;
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_trash(&self, user: &Uuid) -> Result<Trash, ListTrashError> {
+        let state = self.state();
+        let mut lists = state
+            .lists
+            .iter()
+            .filter(|list| state.owns(user, &list.id))
+            .filter_map(|list| {
+                Some(Trashed {
+                    item: list.clone(),
+                    deleted_at: *state.trashed.get(&list.id)?,
+                })
+            })
+            .collect::<Vec<_>>();
+        lists.sort_by_key(|trashed| {
+            let list = &trashed.item;
+            (
+                std::cmp::Reverse(trashed.deleted_at),
+                list.position,
+                list.id,
+            )
+        });
+        let mut tasks = state
+            .tasks
+            .iter()
+            .filter(|task| {
+                state.owns(user, &task.id)
+                    || task
+                        .list_id
+                        .is_some_and(|list_id| state.visible_list(user, &list_id).is_some())
+            })
+            .filter_map(|task| {
+                Some(Trashed {
+                    item: task.clone(),
+                    deleted_at: *state.trashed.get(&task.id)?,
+                })
+            })
+            .collect::<Vec<_>>();
+        tasks.sort_by_key(|trashed| {
+            let task = &trashed.item;
+            (
+                std::cmp::Reverse(trashed.deleted_at),
+                task.position,
+                task.id,
+            )
+        });
+        Ok(Trash { lists, tasks })
+    }
+
+    async fn get_trashed_task(&self, user: &Uuid, id: &Uuid) -> Result<Task, GetTaskError> {
+        let state = self.state();
+        state
+            .tasks
+            .iter()
+            .find(|task| task.id == *id && state.is_trashed(id) && state.may_access(user, task))
+            .cloned()
+            .ok_or(GetTaskError::NotFound { id: *id })
+    }
+
+    async fn restore_task(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        updated_by: &Uuid,
+    ) -> Result<Task, RestoreTaskError> {
+        let mut state = self.state();
+        let task = state
+            .tasks
+            .iter()
+            .find(|task| task.id == *id && state.owns(owner, id) && state.is_trashed(id))
+            .cloned()
+            .ok_or(RestoreTaskError::NotFound { id: *id })?;
+        if let Some(id) = task.parent_id.filter(|id| state.is_trashed(id)) {
+            return Err(RestoreTaskError::ParentDeleted { id });
+        }
+        if let Some(id) = task.list_id.filter(|id| state.is_trashed(id)) {
+            return Err(RestoreTaskError::ListDeleted { id });
+        }
+        let restored = state.trashed_together(&[*id]);
+        if let Some(title) = state.restore_clash(owner, &restored) {
+            return Err(RestoreTaskError::Duplicate { title });
+        }
+
+        let (tasks, trashed) = (state.tasks.clone(), state.trashed.clone());
+        state.untrash_tasks(&restored);
+        if let Err(title) = state.sync_ancestors(owner, task.parent_id, Some(*updated_by)) {
+            state.tasks = tasks;
+            state.trashed = trashed;
+            return Err(RestoreTaskError::Duplicate { title });
+        }
+        Ok(Task {
+            version: task.version + 1,
+            ..task
+        })
+    }
+
+    async fn restore_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> Result<TaskList, RestoreTaskListError> {
+        let mut state = self.state();
+        let list = state
+            .lists
+            .iter()
+            .find(|list| list.id == *id && state.owns(owner, id) && state.is_trashed(id))
+            .cloned()
+            .ok_or(RestoreTaskListError::NotFound { id: *id })?;
+        if !list.archived && state.is_duplicate_list(owner, &list.title, Some(id)) {
+            return Err(RestoreTaskListError::Duplicate { title: list.title });
+        }
+        let deleted_at = state.trashed.get(id).copied();
+        let roots = state
+            .tasks
+            .iter()
+            .filter(|task| {
+                task.list_id == Some(*id) && state.trashed.get(&task.id) == deleted_at.as_ref()
+            })
+            .map(|task| task.id)
+            .collect::<Vec<_>>();
+        let mut restored = state.trashed_together(&roots);
+        restored.sort();
+        restored.dedup();
+        if let Some(title) = state.restore_clash(owner, &restored) {
+            return Err(RestoreTaskListError::TaskDuplicate { title });
+        }
+
+        state.untrash_tasks(&restored);
+        state.trashed.remove(id);
+        Ok(list)
+    }
+
+    async fn empty_trash(&self, owner: &Uuid) -> Result<PurgedTrash, PurgeTrashError> {
+        Ok(self.state().purge(Some(owner), None))
+    }
+
+    async fn purge_trash(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<PurgedTrash, PurgeTrashError> {
+        Ok(self.state().purge(None, Some(deleted_before)))
+    }
+}
+
+impl UserRepository for Memory {
+    async fn create_user(
+        &self,
+        email: &EmailAddress,
+        password_hash: &PasswordHash,
+    ) -> Result<User, RegisterUserError> {
+        let mut state = self.state();
+        if state.users.iter().any(|stored| stored.user.email == *email) {
+            return Err(RegisterUserError::Duplicate {
+                email: email.clone(),
+            });
+        }
+        let user = User {
+            id: Uuid::new_v4(),
+            email: email.clone(),
+            created_at: Utc::now(),
+        };
+        state.users.push(UserCredentials {
+            user: user.clone(),
+            password_hash: password_hash.clone(),
+        });
+        Ok(user)
+    }
+
+    async fn get_credentials(
+        &self,
+        email: &EmailAddress,
+    ) -> Result<Option<UserCredentials>, LogInError> {
+        let state = self.state();
+        Ok(state
+            .users
+            .iter()
+            .find(|stored| stored.user.email == *email)
+            .cloned())
+    }
+
+    async fn create_session(&self, session: &Session) -> Result<(), LogInError> {
+        let mut state = self.state();
+        state.sessions.insert(
+            session.token().digest(),
+            (session.user().id(), session.expires_at()),
+        );
+        Ok(())
+    }
+
+    async fn get_session_user(
+        &self,
+        token: &SessionToken,
+        now: DateTime<Utc>,
+    ) -> Result<User, AuthenticateError> {
+        let state = self.state();
+        let (user_id, _) = state
+            .sessions
+            .get(&token.digest())
+            .filter(|(_, expires_at)| *expires_at > now)
+            .ok_or(AuthenticateError::InvalidToken)?;
+        state
+            .users
+            .iter()
+            .find(|stored| stored.user.id == *user_id)
+            .map(|stored| stored.user.clone())
+            .ok_or(AuthenticateError::InvalidToken)
+    }
+
+    async fn delete_session(&self, token: &SessionToken) -> Result<(), LogOutError> {
+        self.state().sessions.remove(&token.digest());
+        Ok(())
+    }
+
+    async fn create_api_token(
+        &self,
+        user: &Uuid,
+        token: &NewApiToken,
+    ) -> Result<(), CreateApiTokenError> {
+        let mut state = self.state();
+        if let Some(list_id) = token.token().scope().list_id() {
+            if state.find_list(user, &list_id).is_none() {
+                return Err(CreateApiTokenError::ListNotFound { id: list_id });
+            }
+        }
+        state
+            .api_tokens
+            .push((*user, token.secret().digest(), token.token().clone()));
+        Ok(())
+    }
+
+    async fn list_api_tokens(&self, user: &Uuid) -> Result<Vec<ApiToken>, ListApiTokensError> {
+        let state = self.state();
+        Ok(state
+            .api_tokens
+            .iter()
+            .filter(|(owner, _, _)| owner == user)
+            .map(|(_, _, token)| token.clone())
+            .collect())
+    }
+
+    async fn delete_api_token(&self, user: &Uuid, id: &Uuid) -> Result<(), RevokeApiTokenError> {
+        let mut state = self.state();
+        let index = state
+            .api_tokens
+            .iter()
+            .position(|(owner, _, token)| owner == user && token.id == *id)
+            .ok_or(RevokeApiTokenError::NotFound { id: *id })?;
+        state.api_tokens.remove(index);
+        Ok(())
+    }
+
+    async fn use_api_token(
+        &self,
+        secret: &ApiTokenSecret,
+        now: DateTime<Utc>,
+    ) -> Result<Principal, AuthenticateError> {
+        let mut state = self.state();
+        let digest = secret.digest();
+        let (user_id, _, token) = state
+            .api_tokens
+            .iter_mut()
+            .find(|(_, stored, _)| *stored == digest)
+            .ok_or(AuthenticateError::InvalidApiToken)?;
+        token.last_used_at = Some(now);
+        let (user_id, scope) = (*user_id, token.scope);
+        state
+            .users
+            .iter()
+            .find(|stored| stored.user.id == user_id)
+            .map(|stored| Principal {
+                user: stored.user.clone(),
+                scope,
+            })
+            .ok_or(AuthenticateError::InvalidApiToken)
+    }
+}
+
+/// The map key under which the idempotency key of `req` is stored.
+fn idempotency_key(req: &IdempotentRequest) -> (String, String) {
+    (req.owner().to_string(), req.key().as_str().to_string())
+}
+
+impl IdempotencyRepository for Memory {
+    async fn claim_idempotency_key(
+        &self,
+        req: &IdempotentRequest,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<IdempotencyRecord>, ClaimIdempotencyKeyError> {
+        let mut state = self.state();
+        state
+            .idempotency_keys
+            .retain(|_, (_, expires_at)| *expires_at > now);
+        if let Some((record, _)) = state.idempotency_keys.get(&idempotency_key(req)) {
+            return Ok(Some(record.clone()));
+        }
+        let record = IdempotencyRecord {
+            fingerprint: req.fingerprint().to_string(),
+            response: None,
+        };
+        state
+            .idempotency_keys
+            .insert(idempotency_key(req), (record, expires_at));
+        Ok(None)
+    }
+
+    async fn save_idempotent_response(
+        &self,
+        req: &IdempotentRequest,
+        response: &StoredResponse,
+    ) -> Result<(), CompleteIdempotentRequestError> {
+        if let Some((record, _)) = self.state().idempotency_keys.get_mut(&idempotency_key(req)) {
+            record.response = Some(response.clone());
+        }
+        Ok(())
+    }
+
+    async fn delete_idempotency_key(
+        &self,
+        req: &IdempotentRequest,
+    ) -> Result<(), CompleteIdempotentRequestError> {
+        self.state().idempotency_keys.remove(&idempotency_key(req));
+        Ok(())
+    }
+}
+
+impl ReadinessRepository for Memory {
+    async fn is_ready(&self) -> Result<(), ReadinessError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::idempotency::models::key::IdempotencyKey;
+    use crate::domain::reminders::models::query::{
+        PageSize, TagMatch, TaskFilter, TaskSearch, TaskSort, TaskTagFilter,
+    };
+    use crate::domain::reminders::models::tag::TaskTags;
+    use crate::domain::reminders::models::task::{DueAt, Priority, RemindAt, ReminderOffset};
+    use chrono::Duration;
+
+    fn title(raw: &str) -> TaskTitle {
+        TaskTitle::new(raw).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_titles_are_unique_among_incomplete_tasks_ignoring_case() {
+        let memory = Memory::new();
+        let owner = Uuid::new_v4();
+        let task = memory
+            .create_task(&owner, &CreateTaskRequest::new(title("Water plants")))
+            .await
+            .unwrap();
+
+        let result = memory
+            .create_task(&owner, &CreateTaskRequest::new(title("water PLANTS")))
+            .await;
+        assert!(matches!(result, Err(CreateTaskError::Duplicate { .. })));
+
+        memory
+            .update_task(
+                &owner,
+                &task.id(),
+                &UpdateTaskRequest::new().with_completed(true),
+            )
+            .await
+            .unwrap();
+        memory
+            .create_task(&owner, &CreateTaskRequest::new(title("water PLANTS")))
+            .await
+            .unwrap();
+
+        let result = memory
+            .update_task(
+                &owner,
+                &task.id(),
+                &UpdateTaskRequest::new().with_completed(false),
+            )
+            .await;
+        assert!(matches!(result, Err(UpdateTaskError::Duplicate { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_changes_to_an_outdated_version_conflict() {
+        let memory = Memory::new();
+        let owner = Uuid::new_v4();
+        let task = memory
+            .create_task(&owner, &CreateTaskRequest::new(title("Renew passport")))
+            .await
+            .unwrap();
+        assert_eq!(task.version(), 1);
+
+        let req = UpdateTaskRequest::new()
+            .with_completed(true)
+            .with_version(1);
+        let updated = memory.update_task(&owner, &task.id(), &req).await.unwrap();
+        assert_eq!(updated.version(), 2);
+        let result = memory.update_task(&owner, &task.id(), &req).await;
+        assert!(matches!(
+            result,
+            Err(UpdateTaskError::Conflict { version: 1, .. })
+        ));
+
+        let result = memory
+            .delete_task(&owner, &task.id(), Some(1), &owner)
+            .await;
+        assert!(matches!(
+            result,
+            Err(DeleteTaskError::Conflict { version: 1, .. })
+        ));
+        memory
+            .delete_task(&owner, &task.id(), Some(2), &owner)
+            .await
+            .unwrap();
+        let result = memory
+            .delete_task(&owner, &task.id(), Some(2), &owner)
+            .await;
+        assert!(matches!(result, Err(DeleteTaskError::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_list_tasks_pages_through_matching_tasks() {
+        let memory = Memory::new();
+        let owner = Uuid::new_v4();
+        for name in ["Buy milk", "Walk dog", "Buy bread", "Buy eggs"] {
+            memory
+                .create_task(&owner, &CreateTaskRequest::new(title(name)))
+                .await
+                .unwrap();
+        }
+
+        let filter = TaskFilter::new().with_search(TaskSearch::new("buy").unwrap());
+        let req = ListTasksRequest::new()
+            .with_filter(filter)
+            .with_sort(TaskSort::parse("-created").unwrap())
+            .with_size(PageSize::new(2).unwrap());
+        let first = memory.list_tasks(&owner, &req).await.unwrap();
+        let cursor = first.next_cursor.clone().unwrap();
+        let second = memory
+            .list_tasks(&owner, &req.with_cursor(cursor).unwrap())
+            .await
+            .unwrap();
+
+        let titles = first
+            .tasks
+            .iter()
+            .chain(&second.tasks)
+            .map(|task| task.title().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(titles, vec!["Buy eggs", "Buy bread", "Buy milk"]);
+        assert_eq!(second.next_cursor, None);
+    }
+
+    #[tokio::test]
+    async fn test_tasks_sorted_by_priority_page_across_equal_priorities() {
+        let memory = Memory::new();
+        let owner = Uuid::new_v4();
+        for (name, priority) in [
+            ("Buy milk", Priority::High),
+            ("Walk dog", Priority::None),
+            ("Pay rent", Priority::High),
+            ("Call mom", Priority::High),
+            ("File taxes", Priority::Urgent),
+        ] {
+            let req = CreateTaskRequest::new(title(name)).with_priority(priority);
+            memory.create_task(&owner, &req).await.unwrap();
+        }
+
+        let mut req = ListTasksRequest::new()
+            .with_sort(TaskSort::parse("-priority").unwrap())
+            .with_size(PageSize::new(2).unwrap());
+        let mut titles = vec![];
+        loop {
+            let page = memory.list_tasks(&owner, &req).await.unwrap();
+            titles.extend(page.tasks.iter().map(|task| task.title().to_string()));
+            match page.next_cursor {
+                Some(cursor) => req = req.with_cursor(cursor).unwrap(),
+                None => break,
+            }
+        }
+        assert_eq!(
+            titles,
+            vec!["File taxes", "Call mom", "Pay rent", "Buy milk", "Walk dog"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_due_reminders_are_claimed_once_oldest_first() {
+        let memory = Memory::new();
+        let owner = Uuid::new_v4();
+        let now = Utc::now();
+        let offsets = RemindAt::new(vec![
+            ReminderOffset::from_minutes(10).unwrap(),
+            ReminderOffset::from_minutes(60).unwrap(),
+        ])
+        .unwrap();
+        let req = CreateTaskRequest::new(title("Call the dentist"))
+            .with_due_at(DueAt::new(now + Duration::minutes(5)))
+            .with_remind_at(offsets);
+        memory.create_task(&owner, &req).await.unwrap();
+
+        let claimed = memory.claim_due_reminders(now, 10).await.unwrap();
+        let remind_at = claimed.iter().map(|r| *r.remind_at()).collect::<Vec<_>>();
+        assert_eq!(
+            remind_at,
+            vec![now - Duration::minutes(55), now - Duration::minutes(5)]
+        );
+        assert!(memory
+            .claim_due_reminders(now, 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        memory
+            .record_reminder_failure(&claimed[0], "unreachable", true)
+            .await
+            .unwrap();
+        let retried = memory.claim_due_reminders(now, 10).await.unwrap();
+        assert_eq!(retried.len(), 1);
+        assert_eq!(retried[0].attempts(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_delete_list_keeps_moves_or_deletes_its_tasks() {
+        let memory = Memory::new();
+        let owner = Uuid::new_v4();
+        let list_title = |raw| TaskListTitle::new(raw).unwrap();
+        let list = memory
+            .create_list(&owner, &CreateTaskListRequest::new(list_title("Garden")))
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Water plants")).with_list_id(list.id());
+        let task = memory.create_task(&owner, &req).await.unwrap();
+
+        let result = memory
+            .delete_list(&owner, &list.id(), DeleteTaskListMode::OnlyIfEmpty)
+            .await;
+        assert!(matches!(
+            result,
+            Err(DeleteTaskListError::NotEmpty { tasks: 1, .. })
+        ));
+        memory
+            .delete_list(&owner, &list.id(), DeleteTaskListMode::MoveToInbox)
+            .await
+            .unwrap();
+        assert_eq!(
+            memory.get_task(&owner, &task.id()).await.unwrap().list_id(),
+            None
+        );
+
+        let list = memory
+            .create_list(&owner, &CreateTaskListRequest::new(list_title("Garden")))
+            .await
+            .unwrap();
+        let req = UpdateTaskRequest::new().with_list_id(Some(list.id()));
+        memory.update_task(&owner, &task.id(), &req).await.unwrap();
+        memory
+            .delete_list(&owner, &list.id(), DeleteTaskListMode::Cascade)
+            .await
+            .unwrap();
+        let result = memory.get_task(&owner, &task.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+
+        let req = CreateTaskRequest::new(title("Mow lawn")).with_list_id(list.id());
+        let result = memory.create_task(&owner, &req).await;
+        assert!(matches!(result, Err(CreateTaskError::ListNotFound { .. })));
+    }
+    #[tokio::test]
+    async fn test_completion_spreads_to_subtasks_and_parents_or_not_at_all() {
+        let memory = Memory::new();
+        let owner = Uuid::new_v4();
+        let trip = memory
+            .create_task(&owner, &CreateTaskRequest::new(title("Plan trip")))
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Pack")).with_parent_id(trip.id());
+        let pack = memory.create_task(&owner, &req).await.unwrap();
+        let req = CreateTaskRequest::new(title("Socks")).with_parent_id(pack.id());
+        let socks = memory.create_task(&owner, &req).await.unwrap();
+
+        let done = UpdateTaskRequest::new()
+            .with_completed(true)
+            .with_updated_by(owner);
+        memory.update_task(&owner, &pack.id(), &done).await.unwrap();
+        let socks = memory.get_task(&owner, &socks.id()).await.unwrap();
+        assert!(socks.completed());
+        assert_eq!(socks.completed_by(), Some(owner));
+        assert!(memory
+            .get_task(&owner, &trip.id())
+            .await
+            .unwrap()
+            .completed());
+
+        // Reopening the subtask would reopen both parents, but the top one clashes
+        memory
+            .create_task(&owner, &CreateTaskRequest::new(title("Plan TRIP")))
+            .await
+            .unwrap();
+        let reopen = UpdateTaskRequest::new().with_completed(false);
+        match memory.update_task(&owner, &socks.id(), &reopen).await {
+            Err(UpdateTaskError::Duplicate { title }) => assert_eq!(title.to_string(), "Plan trip"),
+            other => panic!("expected a duplicate title error, got {:?}", other),
+        }
+        assert!(memory
+            .get_task(&owner, &socks.id())
+            .await
+            .unwrap()
+            .completed());
+        assert!(memory
+            .get_task(&owner, &pack.id())
+            .await
+            .unwrap()
+            .completed());
+    }
+
+    #[tokio::test]
+    async fn test_deleted_tasks_are_trashed_restored_and_purged() {
+        let memory = Memory::new();
+        let owner = Uuid::new_v4();
+        let trip = memory
+            .create_task(&owner, &CreateTaskRequest::new(title("Prepare trip")))
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Pack")).with_parent_id(trip.id());
+        let pack = memory.create_task(&owner, &req).await.unwrap();
+
+        memory
+            .delete_task(&owner, &trip.id(), None, &owner)
+            .await
+            .unwrap();
+        let result = memory.get_task(&owner, &pack.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+        let trash = memory.list_trash(&owner).await.unwrap();
+        assert_eq!(trash.tasks().len(), 2);
+        let result = memory.restore_task(&owner, &pack.id(), &owner).await;
+        assert!(matches!(result, Err(RestoreTaskError::ParentDeleted { id }) if id == trip.id()));
+
+        let again = memory
+            .create_task(&owner, &CreateTaskRequest::new(title("Prepare trip")))
+            .await
+            .unwrap();
+        let result = memory.restore_task(&owner, &trip.id(), &owner).await;
+        assert!(matches!(result, Err(RestoreTaskError::Duplicate { .. })));
+        let req = UpdateTaskRequest::new().with_completed(true);
+        memory.update_task(&owner, &again.id(), &req).await.unwrap();
+        memory
+            .restore_task(&owner, &trip.id(), &owner)
+            .await
+            .unwrap();
+        memory.get_task(&owner, &pack.id()).await.unwrap();
+        assert!(memory.list_trash(&owner).await.unwrap().tasks().is_empty());
+
+        let list_title = TaskListTitle::new("Errands").unwrap();
+        let list = memory
+            .create_list(&owner, &CreateTaskListRequest::new(list_title))
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Post letter")).with_list_id(list.id());
+        let letter = memory.create_task(&owner, &req).await.unwrap();
+        memory
+            .delete_list(&owner, &list.id(), DeleteTaskListMode::Cascade)
+            .await
+            .unwrap();
+        let result = memory.restore_task(&owner, &letter.id(), &owner).await;
+        assert!(matches!(result, Err(RestoreTaskError::ListDeleted { id }) if id == list.id()));
+        memory.restore_list(&owner, &list.id()).await.unwrap();
+        memory.get_task(&owner, &letter.id()).await.unwrap();
+
+        memory
+            .delete_task(&owner, &letter.id(), None, &owner)
+            .await
+            .unwrap();
+        let purged = memory
+            .purge_trash(Utc::now() - Duration::days(1))
+            .await
+            .unwrap();
+        assert_eq!(purged, PurgedTrash::default());
+        let purged = memory
+            .purge_trash(Utc::now() + Duration::seconds(1))
+            .await
+            .unwrap();
+        assert_eq!(purged, PurgedTrash { tasks: 1, lists: 0 });
+        let result = memory.get_trashed_task(&owner, &letter.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+
+        memory
+            .delete_list(&owner, &list.id(), DeleteTaskListMode::OnlyIfEmpty)
+            .await
+            .unwrap();
+        memory
+            .delete_task(&owner, &trip.id(), None, &owner)
+            .await
+            .unwrap();
+        let purged = memory.empty_trash(&owner).await.unwrap();
+        assert_eq!(purged, PurgedTrash { tasks: 2, lists: 1 });
+        assert_eq!(memory.list_trash(&owner).await.unwrap(), Trash::default());
+    }
+
+    #[tokio::test]
+    async fn test_tags_are_renamed_merged_and_filtered() {
+        let memory = Memory::new();
+        let owner = Uuid::new_v4();
+        let tag = |raw: &str| Tag::new(raw).unwrap();
+        let tags = |raw: &[&str]| TaskTags::new(raw.iter().map(|raw| tag(raw)).collect()).unwrap();
+        let stamps = CreateTaskRequest::new(title("Buy stamps")).with_tags(tags(&["errands"]));
+        let stamps = memory.create_task(&owner, &stamps).await.unwrap();
+        let bulbs = CreateTaskRequest::new(title("Plant bulbs")).with_tags(tags(&["garden"]));
+        let bulbs = memory.create_task(&owner, &bulbs).await.unwrap();
+        let fence =
+            CreateTaskRequest::new(title("Paint fence")).with_tags(tags(&["garden", "diy"]));
+        memory.create_task(&owner, &fence).await.unwrap();
+
+        let result = memory
+            .rename_tag(&owner, &tag("errands"), &tag("garden"))
+            .await;
+        assert!(matches!(result, Err(RenameTagError::Duplicate { .. })));
+        let usage = memory
+            .rename_tag(&owner, &tag("diy"), &tag("home"))
+            .await
+            .unwrap();
+        assert_eq!(usage.tasks(), 1);
+        let usage = memory
+            .merge_tags(&owner, &[tag("garden"), tag("home")], &tag("outside"))
+            .await
+            .unwrap();
+        assert_eq!(usage.tasks(), 2);
+        let result = memory
+            .merge_tags(&owner, &[tag("garden")], &tag("home"))
+            .await;
+        assert!(matches!(result, Err(MergeTagsError::NotFound { .. })));
+
+        let usage = memory.list_tags(&owner).await.unwrap();
+        let usage = usage
+            .iter()
+            .map(|usage| (usage.tag().as_str(), usage.tasks()))
+            .collect::<Vec<_>>();
+        assert_eq!(usage, vec![("errands", 1), ("outside", 2)]);
+
+        let filter = |mode| {
+            let tags = TaskTagFilter::new(vec![tag("errands"), tag("outside")], mode).unwrap();
+            ListTasksRequest::new().with_filter(TaskFilter::new().with_tags(tags))
+        };
+        let page = memory
+            .list_tasks(&owner, &filter(TagMatch::All))
+            .await
+            .unwrap();
+        assert!(page.tasks.is_empty());
+        let page = memory
+            .list_tasks(&owner, &filter(TagMatch::Any))
+            .await
+            .unwrap();
+        let ids = page.tasks.iter().map(Task::id).collect::<Vec<_>>();
+        assert!(ids.contains(&stamps.id()) && ids.contains(&bulbs.id()));
+        assert_eq!(ids.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_keys_are_claimed_replayed_and_expire() {
+        let memory = Memory::new();
+        let key = IdempotencyKey::new("retry-1").unwrap();
+        let req = IdempotentRequest::new("owner".to_string(), key.clone(), "a".to_string());
+        let now = Utc::now();
+        let expires_at = now + Duration::hours(1);
+        let claimed = memory
+            .claim_idempotency_key(&req, now, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(claimed, None);
+        let pending = memory
+            .claim_idempotency_key(&req, now, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(
+            pending,
+            Some(IdempotencyRecord {
+                fingerprint: "a".to_string(),
+                response: None,
+            })
+        );
+        let other = IdempotentRequest::new("other".to_string(), key, "b".to_string());
+        let claimed = memory
+            .claim_idempotency_key(&other, now, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(claimed, None);
+
+        let response = StoredResponse::new(201, Some("application/json".to_string()), vec![7])
+            .with_headers(vec![("etag".to_string(), "\"1\"".to_string())]);
+        memory
+            .save_idempotent_response(&req, &response)
+            .await
+            .unwrap();
+        let replayed = memory
+            .claim_idempotency_key(&req, now, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(replayed.unwrap().response, Some(response));
+
+        let claimed = memory
+            .claim_idempotency_key(&req, expires_at, expires_at + Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(claimed, None);
+        memory.delete_idempotency_key(&req).await.unwrap();
+        let claimed = memory
+            .claim_idempotency_key(&req, now, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(claimed, None);
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::Context;
+use prometheus::{
+    exponential_buckets, Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter,
+    IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use sqlx::PgPool;
+use std::time::Duration;
+
+use crate::domain::metrics::ports::Metrics;
+
+/// [Metrics] kept in a Prometheus registry of their own and rendered for scraping.
+#[derive(Debug, Clone)]
+pub struct Prometheus {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_request_duration: HistogramVec,
+    tasks_created: IntCounter,
+    task_creation_failures: IntCounter,
+    tasks_completed: IntCounter,
+    tasks_deleted: IntCounter,
+    reminders_delivered: IntCounter,
+    reminders_failed: IntCounter,
+    scheduler_lag: Histogram,
+    db_pool_connections: IntGaugeVec,
+    /// The pool whose usage is sampled whenever the metrics are rendered, if any.
+    pool: Option<PgPool>,
+}
+
+impl Prometheus {
+    /// The prefix of the name of every metric.
+    const NAMESPACE: &'static str = "modus";
+
+    pub fn new() -> anyhow::Result<Self> {
+        let registry = Registry::new();
+        let http_requests = IntCounterVec::new(
+            Self::opts("http_requests_total", "HTTP requests by route and status"),
+            &["method", "route", "status"],
+        )?;
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::from(Self::opts(
+                "http_request_duration_seconds",
+                "Time taken to answer HTTP requests, by route and status",
+            )),
+            &["method", "route", "status"],
+        )?;
+        let tasks_created =
+            IntCounter::with_opts(Self::opts("tasks_created_total", "Tasks created"))?;
+        let task_creation_failures = IntCounter::with_opts(Self::opts(
+            "task_creation_failures_total",
+            "Tasks that could not be created",
+        ))?;
+        let tasks_completed = IntCounter::with_opts(Self::opts(
+            "tasks_completed_total",
+            "Tasks completed by users",
+        ))?;
+        let tasks_deleted =
+            IntCounter::with_opts(Self::opts("tasks_deleted_total", "Tasks deleted by users"))?;
+        let reminders_delivered = IntCounter::with_opts(Self::opts(
+            "reminders_delivered_total",
+            "Reminders delivered",
+        ))?;
+        let reminders_failed = IntCounter::with_opts(Self::opts(
+            "reminders_failed_total",
+            "Reminder deliveries that failed",
+        ))?;
+        // From one second up to about four and a half hours late
+        let scheduler_lag = Histogram::with_opts(
+            HistogramOpts::from(Self::opts(
+                "scheduler_lag_seconds",
+                "How long after their time reminders are fired",
+            ))
+            .buckets(exponential_buckets(1.0, 2.0, 15)?),
+        )?;
+        let db_pool_connections = IntGaugeVec::new(
+            Self::opts("db_pool_connections", "Database connections by state"),
+            &["state"],
+        )?;
+
+        registry.register(Box::new(http_requests.clone()))?;
+        registry.register(Box::new(http_request_duration.clone()))?;
+        registry.register(Box::new(tasks_created.clone()))?;
+        registry.register(Box::new(task_creation_failures.clone()))?;
+        registry.register(Box::new(tasks_completed.clone()))?;
+        registry.register(Box::new(tasks_deleted.clone()))?;
+        registry.register(Box::new(reminders_delivered.clone()))?;
+        registry.register(Box::new(reminders_failed.clone()))?;
+        registry.register(Box::new(scheduler_lag.clone()))?;
+        registry.register(Box::new(db_pool_connections.clone()))?;
+
+        Ok(Self {
+            registry,
+            http_requests,
+            http_request_duration,
+            tasks_created,
+            task_creation_failures,
+            tasks_completed,
+            tasks_deleted,
+            reminders_delivered,
+            reminders_failed,
+            scheduler_lag,
+            db_pool_connections,
+            pool: None,
+        })
+    }
+
+    /// Report the usage of the `pool` along with the other metrics.
+    pub fn with_pool(mut self, pool: PgPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    fn opts(name: &str, help: &str) -> Opts {
+        Opts::new(name, help).namespace(Self::NAMESPACE)
+    }
+}
+
+impl Metrics for Prometheus {
+    fn record_http_request(&self, method: &str, route: &str, status: u16, latency: Duration) {
+        let status = status.to_string();
+        let labels = [method, route, status.as_str()];
+        self.http_requests.with_label_values(&labels).inc();
+        self.http_request_duration
+            .with_label_values(&labels)
+            .observe(latency.as_secs_f64());
+    }
+
+    fn record_task_creation_success(&self) {
+        self.tasks_created.inc();
+    }
+
+    fn record_task_creation_failure(&self) {
+        self.task_creation_failures.inc();
+    }
+
+    fn record_task_completion(&self) {
+        self.tasks_completed.inc();
+    }
+
+    fn record_task_deletion(&self) {
+        self.tasks_deleted.inc();
+    }
+
+    fn record_reminder_delivery(&self) {
+        self.reminders_delivered.inc();
+    }
+
+    fn record_reminder_failure(&self) {
+        self.reminders_failed.inc();
+    }
+
+    fn record_scheduler_lag(&self, lag: Duration) {
+        self.scheduler_lag.observe(lag.as_secs_f64());
+    }
+
+    fn render(&self) -> anyhow::Result<String> {
+        if let Some(pool) = &self.pool {
+            let idle = pool.num_idle() as i64;
+            let in_use = i64::from(pool.size()) - idle;
+            self.db_pool_connections
+                .with_label_values(&["idle"])
+                .set(idle);
+            self.db_pool_connections
+                .with_label_values(&["in_use"])
+                .set(in_use);
+        }
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .context("failed to encode metrics")?;
+        String::from_utf8(buffer).context("encoded metrics are not UTF-8")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorded_metrics_are_rendered() {
+        let metrics = Prometheus::new().unwrap();
+        metrics.record_http_request("GET", "/api/tasks/:id", 404, Duration::from_millis(3));
+        metrics.record_task_creation_success();
+        metrics.record_reminder_delivery();
+        metrics.record_scheduler_lag(Duration::from_secs(5));
+
+        let rendered = metrics.render().unwrap();
+        assert!(rendered.contains(
+            r#"modus_http_requests_total{method="GET",route="/api/tasks/:id",status="404"} 1"#
+        ));
+        assert!(rendered.contains("modus_tasks_created_total 1"));
+        assert!(rendered.contains("modus_tasks_deleted_total 0"));
+        assert!(rendered.contains("modus_reminders_delivered_total 1"));
+        assert!(rendered.contains("modus_scheduler_lag_seconds_count 1"));
+    }
+}
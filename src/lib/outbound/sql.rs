@@ -1,15 +1,586 @@
 use std::str::FromStr;
 
 use anyhow::{anyhow, Context};
+use chrono::{DateTime, Utc};
 use sqlx::postgres::PgConnectOptions;
-use sqlx::{Executor, PgPool, Transaction};
+use sqlx::{Executor, FromRow, PgPool, Postgres, QueryBuilder, Transaction};
+use tracing::instrument;
 use uuid::Uuid;
 
+use crate::domain::idempotency::models::key::{
+    ClaimIdempotencyKeyError, CompleteIdempotentRequestError, IdempotencyRecord, IdempotentRequest,
+    StoredResponse,
+};
+use crate::domain::idempotency::ports::IdempotencyRepository;
 use crate::domain::readiness::models::ready::ReadinessError;
 use crate::domain::readiness::ports::ReadinessRepository;
-use crate::domain::reminders::models::task::CreateTaskError;
-use crate::domain::reminders::models::task::{CreateTaskRequest, Task, TaskTitle};
+use crate::domain::reminders::models::list::{
+    CreateTaskListError, DeleteTaskListError, GetTaskListError, ListTaskListsError,
+    RestoreTaskListError, UpdateTaskListError,
+};
+use crate::domain::reminders::models::list::{
+    CreateTaskListRequest, DeleteTaskListMode, TaskList, TaskListColour, TaskListTitle,
+    UpdateTaskListRequest,
+};
+use crate::domain::reminders::models::member::{
+    ListRole, ListTaskListMembersError, ShareTaskListError, ShareTaskListRequest, TaskListMember,
+    UnshareTaskListError,
+};
+use crate::domain::reminders::models::query::{
+    ListTasksRequest, SortDirection, TagMatch, TaskListFilter, TaskPage, TaskSortField,
+};
+use crate::domain::reminders::models::recurrence::Recurrence;
+use crate::domain::reminders::models::reminder::{DueReminder, FireRemindersError};
+use crate::domain::reminders::models::tag::{
+    ListTagsError, MergeTagsError, RenameTagError, Tag, TagUsage, TaskTags,
+};
+use crate::domain::reminders::models::task::{
+    CreateTaskError, DeleteTaskError, GetTaskError, ListTasksError, RestoreTaskError,
+    UpdateTaskError,
+};
+use crate::domain::reminders::models::task::{
+    CreateTaskRequest, DueAt, Priority, RemindAt, ReminderOffset, Task, TaskNotes, TaskTitle,
+    UpdateTaskRequest,
+};
+use crate::domain::reminders::models::trash::{
+    ListTrashError, PurgeTrashError, PurgedTrash, Trash, Trashed,
+};
 use crate::domain::reminders::ports::ReminderRepository;
+use crate::domain::users::models::api_token::{
+    ApiToken, ApiTokenName, ApiTokenSecret, CreateApiTokenError, ListApiTokensError, NewApiToken,
+    Principal, RevokeApiTokenError, TokenAccess, TokenScope,
+};
+use crate::domain::users::models::session::{
+    AuthenticateError, LogInError, LogOutError, Session, SessionToken,
+};
+use crate::domain::users::models::user::{
+    EmailAddress, PasswordHash, RegisterUserError, User, UserCredentials,
+};
+use crate::domain::users::ports::UserRepository;
+
+pub mod admin;
+
+/// A row of the `tasks` table.
+#[derive(FromRow)]
+struct TaskRow {
+    id: Uuid,
+    title: String,
+    completed: bool,
+    priority: String,
+    notes: Option<String>,
+    due_at: Option<DateTime<Utc>>,
+    reminder_offsets: Vec<i32>,
+    recurrence: Option<String>,
+    list_id: Option<Uuid>,
+    parent_id: Option<Uuid>,
+    position: i32,
+    tags: Vec<String>,
+    created_at: DateTime<Utc>,
+    updated_by: Option<Uuid>,
+    completed_by: Option<Uuid>,
+    assignee_id: Option<Uuid>,
+    version: i64,
+}
+
+impl TryFrom<TaskRow> for Task {
+    type Error = anyhow::Error;
+
+    fn try_from(row: TaskRow) -> Result<Self, Self::Error> {
+        let title = TaskTitle::new(&row.title)
+            .with_context(|| format!("invalid title stored for task {}", row.id))?;
+        let priority = Priority::parse(&row.priority)
+            .with_context(|| format!("invalid priority stored for task {}", row.id))?;
+        let notes = row
+            .notes
+            .as_deref()
+            .map(TaskNotes::new)
+            .transpose()
+            .with_context(|| format!("invalid notes stored for task {}", row.id))?;
+        let offsets = row
+            .reminder_offsets
+            .into_iter()
+            .map(|minutes| {
+                let minutes = u32::try_from(minutes)?;
+                Ok(ReminderOffset::from_minutes(minutes)?)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+            .with_context(|| format!("invalid reminder offsets stored for task {}", row.id))?;
+        let remind_at = RemindAt::new(offsets)
+            .with_context(|| format!("invalid reminder offsets stored for task {}", row.id))?;
+        let recurrence = row
+            .recurrence
+            .as_deref()
+            .map(Recurrence::parse)
+            .transpose()
+            .with_context(|| format!("invalid recurrence stored for task {}", row.id))?;
+        let tags = row
+            .tags
+            .iter()
+            .map(|tag| Tag::new(tag))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(anyhow::Error::from)
+            .and_then(|tags| Ok(TaskTags::new(tags)?))
+            .with_context(|| format!("invalid tags stored for task {}", row.id))?;
+        Ok(Task {
+            id: row.id,
+            title,
+            completed: row.completed,
+            priority,
+            notes,
+            due_at: row.due_at.map(DueAt::new),
+            remind_at,
+            recurrence,
+            list_id: row.list_id,
+            parent_id: row.parent_id,
+            position: row.position,
+            tags,
+            assignee_id: row.assignee_id,
+            created_at: row.created_at,
+            updated_by: row.updated_by,
+            completed_by: row.completed_by,
+            version: row.version,
+        })
+    }
+}
+
+/// Encode [RemindAt] for the `reminder_offsets` column.
+fn reminder_offsets(remind_at: &RemindAt) -> Vec<i32> {
+    remind_at
+        .offsets()
+        .iter()
+        .map(|offset| offset.minutes() as i32)
+        .collect()
+}
+
+/// Encode [TaskTags] for the `tag` column of `task_tags`.
+fn tag_names(tags: &[Tag]) -> Vec<String> {
+    tags.iter().map(Tag::to_string).collect()
+}
+
+/// A row of the `task_reminders` table joined with the task it belongs to.
+struct DueReminderRow {
+    remind_at: DateTime<Utc>,
+    attempts: i32,
+    id: Uuid,
+    title: String,
+    completed: bool,
+    priority: String,
+    notes: Option<String>,
+    due_at: Option<DateTime<Utc>>,
+    reminder_offsets: Vec<i32>,
+    recurrence: Option<String>,
+    list_id: Option<Uuid>,
+    parent_id: Option<Uuid>,
+    position: i32,
+    tags: Vec<String>,
+    created_at: DateTime<Utc>,
+    updated_by: Option<Uuid>,
+    completed_by: Option<Uuid>,
+    assignee_id: Option<Uuid>,
+    version: i64,
+    recipient: Uuid,
+}
+
+impl TryFrom<DueReminderRow> for DueReminder {
+    type Error = anyhow::Error;
+
+    fn try_from(row: DueReminderRow) -> Result<Self, Self::Error> {
+        let task = Task::try_from(TaskRow {
+            id: row.id,
+            title: row.title,
+            completed: row.completed,
+            priority: row.priority,
+            notes: row.notes,
+            due_at: row.due_at,
+            reminder_offsets: row.reminder_offsets,
+            recurrence: row.recurrence,
+            list_id: row.list_id,
+            parent_id: row.parent_id,
+            position: row.position,
+            tags: row.tags,
+            created_at: row.created_at,
+            updated_by: row.updated_by,
+            completed_by: row.completed_by,
+            assignee_id: row.assignee_id,
+            version: row.version,
+        })?;
+        Ok(DueReminder {
+            task,
+            remind_at: row.remind_at,
+            recipient: row.recipient,
+            attempts: u32::try_from(row.attempts)?,
+        })
+    }
+}
+
+/// A row of the `task_lists` table.
+struct TaskListRow {
+    id: Uuid,
+    title: String,
+    colour: Option<String>,
+    position: i32,
+    archived: bool,
+    created_at: DateTime<Utc>,
+    owner_id: Uuid,
+    /// The role of the user the list was fetched for.
+    role: String,
+}
+
+impl TryFrom<TaskListRow> for TaskList {
+    type Error = anyhow::Error;
+
+    fn try_from(row: TaskListRow) -> Result<Self, Self::Error> {
+        let title = TaskListTitle::new(&row.title)
+            .with_context(|| format!("invalid title stored for task list {}", row.id))?;
+        let colour = row
+            .colour
+            .as_deref()
+            .map(TaskListColour::new)
+            .transpose()
+            .with_context(|| format!("invalid colour stored for task list {}", row.id))?;
+        let role = ListRole::parse(&row.role)
+            .with_context(|| format!("invalid role stored for task list {}", row.id))?;
+        Ok(TaskList {
+            id: row.id,
+            title,
+            colour,
+            position: row.position,
+            archived: row.archived,
+            created_at: row.created_at,
+            owner_id: row.owner_id,
+            role,
+        })
+    }
+}
+
+/// A row of the `tasks` table in the trash.
+struct TrashedTaskRow {
+    id: Uuid,
+    title: String,
+    completed: bool,
+    priority: String,
+    notes: Option<String>,
+    due_at: Option<DateTime<Utc>>,
+    reminder_offsets: Vec<i32>,
+    recurrence: Option<String>,
+    list_id: Option<Uuid>,
+    parent_id: Option<Uuid>,
+    position: i32,
+    tags: Vec<String>,
+    created_at: DateTime<Utc>,
+    updated_by: Option<Uuid>,
+    completed_by: Option<Uuid>,
+    assignee_id: Option<Uuid>,
+    version: i64,
+    deleted_at: DateTime<Utc>,
+}
+
+impl TryFrom<TrashedTaskRow> for Trashed<Task> {
+    type Error = anyhow::Error;
+
+    fn try_from(row: TrashedTaskRow) -> Result<Self, Self::Error> {
+        let task = Task::try_from(TaskRow {
+            id: row.id,
+            title: row.title,
+            completed: row.completed,
+            priority: row.priority,
+            notes: row.notes,
+            due_at: row.due_at,
+            reminder_offsets: row.reminder_offsets,
+            recurrence: row.recurrence,
+            list_id: row.list_id,
+            parent_id: row.parent_id,
+            position: row.position,
+            tags: row.tags,
+            created_at: row.created_at,
+            updated_by: row.updated_by,
+            completed_by: row.completed_by,
+            assignee_id: row.assignee_id,
+            version: row.version,
+        })?;
+        Ok(Trashed {
+            item: task,
+            deleted_at: row.deleted_at,
+        })
+    }
+}
+
+/// A row of the `task_lists` table in the trash.
+struct TrashedTaskListRow {
+    id: Uuid,
+    title: String,
+    colour: Option<String>,
+    position: i32,
+    archived: bool,
+    created_at: DateTime<Utc>,
+    owner_id: Uuid,
+    role: String,
+    deleted_at: DateTime<Utc>,
+}
+
+impl TryFrom<TrashedTaskListRow> for Trashed<TaskList> {
+    type Error = anyhow::Error;
+
+    fn try_from(row: TrashedTaskListRow) -> Result<Self, Self::Error> {
+        let list = TaskList::try_from(TaskListRow {
+            id: row.id,
+            title: row.title,
+            colour: row.colour,
+            position: row.position,
+            archived: row.archived,
+            created_at: row.created_at,
+            owner_id: row.owner_id,
+            role: row.role,
+        })?;
+        Ok(Trashed {
+            item: list,
+            deleted_at: row.deleted_at,
+        })
+    }
+}
+
+/// A tag of the `task_tags` table with the number of tasks that carry it.
+struct TagUsageRow {
+    tag: String,
+    tasks: i64,
+}
+
+impl TryFrom<TagUsageRow> for TagUsage {
+    type Error = anyhow::Error;
+
+    fn try_from(row: TagUsageRow) -> Result<Self, Self::Error> {
+        let tag =
+            Tag::new(&row.tag).with_context(|| format!("invalid tag stored: {:?}", row.tag))?;
+        Ok(TagUsage {
+            tag,
+            tasks: u64::try_from(row.tasks)?,
+        })
+    }
+}
+
+/// A row of the `users` table.
+struct UserRow {
+    id: Uuid,
+    email: String,
+    password_hash: String,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<UserRow> for UserCredentials {
+    type Error = anyhow::Error;
+
+    fn try_from(row: UserRow) -> Result<Self, Self::Error> {
+        let email = EmailAddress::new(&row.email)
+            .with_context(|| format!("invalid email address stored for user {}", row.id))?;
+        Ok(UserCredentials {
+            user: User {
+                id: row.id,
+                email,
+                created_at: row.created_at,
+            },
+            password_hash: PasswordHash::from_stored(&row.password_hash),
+        })
+    }
+}
+
+/// A row of the `api_tokens` table.
+struct ApiTokenRow {
+    id: Uuid,
+    name: String,
+    access: String,
+    list_id: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Parse the `access` and `list_id` columns of an `api_tokens` row.
+fn token_scope(access: &str, list_id: Option<Uuid>) -> anyhow::Result<TokenScope> {
+    let scope = TokenScope::full().with_access(TokenAccess::parse(access)?);
+    Ok(match list_id {
+        Some(list_id) => scope.with_list_id(list_id),
+        None => scope,
+    })
+}
+
+impl TryFrom<ApiTokenRow> for ApiToken {
+    type Error = anyhow::Error;
+
+    fn try_from(row: ApiTokenRow) -> Result<Self, Self::Error> {
+        let name = ApiTokenName::new(&row.name)
+            .with_context(|| format!("invalid name stored for API token {}", row.id))?;
+        Ok(ApiToken {
+            id: row.id,
+            name,
+            scope: token_scope(&row.access, row.list_id)?,
+            created_at: row.created_at,
+            last_used_at: row.last_used_at,
+        })
+    }
+}
+
+/// The user of an API token that was just used, with the scope of the token.
+struct ApiTokenUserRow {
+    id: Uuid,
+    email: String,
+    password_hash: String,
+    created_at: DateTime<Utc>,
+    access: String,
+    list_id: Option<Uuid>,
+}
+
+impl TryFrom<ApiTokenUserRow> for Principal {
+    type Error = anyhow::Error;
+
+    fn try_from(row: ApiTokenUserRow) -> Result<Self, Self::Error> {
+        let scope = token_scope(&row.access, row.list_id)?;
+        let credentials = UserCredentials::try_from(UserRow {
+            id: row.id,
+            email: row.email,
+            password_hash: row.password_hash,
+            created_at: row.created_at,
+        })?;
+        Ok(Principal {
+            user: credentials.user,
+            scope,
+        })
+    }
+}
+
+/// A user who may access a task list, with their email address from the `users` table.
+struct MemberRow {
+    user_id: Uuid,
+    email: String,
+    role: String,
+    added_at: DateTime<Utc>,
+}
+
+impl TryFrom<MemberRow> for TaskListMember {
+    type Error = anyhow::Error;
+
+    fn try_from(row: MemberRow) -> Result<Self, Self::Error> {
+        let email = EmailAddress::new(&row.email)
+            .with_context(|| format!("invalid email address stored for user {}", row.user_id))?;
+        let role = ListRole::parse(&row.role)
+            .with_context(|| format!("invalid role stored for member {}", row.user_id))?;
+        Ok(TaskListMember {
+            user_id: row.user_id,
+            email,
+            role,
+            added_at: row.added_at,
+        })
+    }
+}
+
+/// A row of the `idempotency_keys` table.
+#[derive(FromRow)]
+struct IdempotencyKeyRow {
+    fingerprint: String,
+    response_status: Option<i16>,
+    response_content_type: Option<String>,
+    response_headers: Vec<String>,
+    response_body: Option<Vec<u8>>,
+}
+
+impl TryFrom<IdempotencyKeyRow> for IdempotencyRecord {
+    type Error = anyhow::Error;
+
+    fn try_from(row: IdempotencyKeyRow) -> Result<Self, Self::Error> {
+        let response = match (row.response_status, row.response_body) {
+            (Some(status), Some(body)) => Some(
+                StoredResponse::new(
+                    u16::try_from(status).context("invalid response status stored")?,
+                    row.response_content_type,
+                    body,
+                )
+                .with_headers(parse_header_lines(&row.response_headers)?),
+            ),
+            _ => None,
+        };
+        Ok(IdempotencyRecord {
+            fingerprint: row.fingerprint,
+            response,
+        })
+    }
+}
+
+/// The headers of `response` as `name: value` lines, as they are stored.
+fn header_lines(response: &StoredResponse) -> Vec<String> {
+    response
+        .headers()
+        .iter()
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect()
+}
+
+/// The headers stored as `name: value` lines.
+fn parse_header_lines(lines: &[String]) -> anyhow::Result<Vec<(String, String)>> {
+    lines
+        .iter()
+        .map(|line| {
+            line.split_once(": ")
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .with_context(|| format!("invalid response header stored: {:?}", line))
+        })
+        .collect()
+}
+
+/// The outcome of sharing a task list.
+enum MemberSave {
+    Saved(MemberRow),
+    ListNotFound,
+    UserNotFound,
+    /// The list was not shared because the user created it.
+    Creator,
+}
+
+/// The outcome of deleting a task list.
+enum ListRemoval {
+    Removed,
+    NotFound,
+    /// The list was kept because it has this many tasks.
+    NotEmpty(u64),
+}
+
+/// The outcome of restoring a task from the trash.
+enum TaskRestore {
+    /// The task was restored along with these subtasks.
+    Restored(Vec<TaskRow>),
+    NotFound,
+    /// The task was kept in the trash because its parent with this id is there.
+    ParentDeleted(Uuid),
+    /// The task was kept in the trash because its list with this id is there.
+    ListDeleted(Uuid),
+    /// The task was kept in the trash because an incomplete task has this title.
+    Duplicate(String),
+}
+
+/// The outcome of restoring a task list from the trash.
+enum ListRestore {
+    Restored(TaskListRow),
+    NotFound,
+    /// The list was kept in the trash because an unarchived list has this title.
+    Duplicate(String),
+    /// The list was kept in the trash because an incomplete task has the title of one of its
+    /// tasks.
+    TaskDuplicate(String),
+}
+
+/// The outcome of renaming a tag.
+enum TagRename {
+    /// The tag was renamed on this many tasks.
+    Renamed(u64),
+    NotFound,
+    /// The tag was kept because the new name is already in use.
+    Duplicate,
+}
+
+/// The outcome of merging tags.
+enum TagMerge {
+    /// The tags were merged into one carried by this many tasks.
+    Merged(u64),
+    /// Nothing was merged because no task carries this tag.
+    NotFound(Tag),
+}
 
 #[derive(Debug, Clone)]
 pub struct Sql {
@@ -28,76 +599,3385 @@ impl Sql {
         Ok(Sql { pool })
     }
 
+    /// The pool of connections to the database, for reporting how busy it is.
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
     async fn save_task(
         &self,
         tx: &mut Transaction<'_, sqlx::Postgres>,
-        title: &TaskTitle,
-    ) -> Result<Uuid, sqlx::Error> {
+        owner: &Uuid,
+        req: &CreateTaskRequest,
+    ) -> Result<(Uuid, i32, DateTime<Utc>), sqlx::Error> {
         let id = Uuid::new_v4();
-        let title = &title.to_string();
-        let query = sqlx::query!("INSERT INTO tasks (id, title) VALUES ($1, $2)", id, title);
+        let title = &req.title().to_string();
+        let due_at = req.due_at().map(DueAt::as_datetime);
+        let recurrence = req.recurrence().map(Recurrence::to_string);
+        let notes = req.notes().map(TaskNotes::as_str);
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO tasks (
+                id, title, due_at, reminder_offsets, recurrence, list_id, parent_id, position,
+                priority, notes, owner_id, updated_by, assignee_id
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7,
+                COALESCE($8, (
+                    SELECT COALESCE(max(position) + 1, 0)
+                    FROM tasks
+                    WHERE parent_id IS NOT DISTINCT FROM $7 AND owner_id = $11
+                )),
+                $9, $10, $11, $12, $13
+            )
+            RETURNING position, created_at
+            "#,
+            id,
+            title,
+            due_at,
+            &reminder_offsets(req.remind_at()),
+            recurrence,
+            req.list_id(),
+            req.parent_id(),
+            req.position(),
+            req.priority().as_str(),
+            notes,
+            owner,
+            req.updated_by(),
+            req.assignee_id()
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok((id, row.position, row.created_at))
+    }
+
+    /// Replace the pending reminders of a task with those derived from its current due date and
+    /// reminder offsets. Reminders that already fired are kept so they never fire twice.
+    async fn sync_reminders(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        task_id: &Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let query = sqlx::query!(
+            "DELETE FROM task_reminders WHERE task_id = $1 AND fired_at IS NULL",
+            task_id
+        );
+        tx.execute(query).await?;
+        let query = sqlx::query!(
+            r#"
+            INSERT INTO task_reminders (task_id, remind_at)
+            SELECT id, due_at - make_interval(mins => offset_minutes)
+            FROM tasks, unnest(reminder_offsets) AS offset_minutes
+            WHERE id = $1 AND due_at IS NOT NULL
+            ON CONFLICT DO NOTHING
+            "#,
+            task_id
+        );
         tx.execute(query).await?;
-        Ok(id)
+        Ok(())
     }
 
-    async fn ready(&self) -> Result<(), ReadinessError> {
-        let query = sqlx::query!("SELECT 1 as health_check");
-        let _ = query.fetch_one(&self.pool).await;
+    async fn claim_reminders(
+        &self,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<DueReminderRow>, sqlx::Error> {
+        sqlx::query_as!(
+            DueReminderRow,
+            r#"
+            WITH due AS (
+                SELECT r.task_id, r.remind_at
+                FROM task_reminders r
+                JOIN tasks t ON t.id = r.task_id
+                WHERE r.fired_at IS NULL
+                    AND r.remind_at <= $1
+                    AND NOT t.completed
+                    AND t.deleted_at IS NULL
+                ORDER BY r.remind_at
+                LIMIT $2
+                FOR UPDATE OF r SKIP LOCKED
+            )
+            UPDATE task_reminders r
+            SET fired_at = now()
+            FROM due, tasks t
+            WHERE r.task_id = due.task_id AND r.remind_at = due.remind_at AND t.id = r.task_id
+            RETURNING
+                r.remind_at,
+                r.attempts,
+                t.id AS "id!",
+                t.title AS "title!",
+                t.completed AS "completed!",
+                t.priority AS "priority!",
+                t.notes,
+                t.due_at,
+                t.reminder_offsets AS "reminder_offsets!",
+                t.recurrence,
+                t.list_id,
+                t.parent_id,
+                t.position AS "position!",
+                ARRAY(SELECT tag FROM task_tags WHERE task_id = t.id ORDER BY tag) AS "tags!",
+                t.created_at AS "created_at!",
+                t.updated_by,
+                t.completed_by,
+                t.assignee_id,
+                t.version AS "version!",
+                COALESCE(t.assignee_id, t.owner_id) AS "recipient!"
+            "#,
+            now,
+            i64::from(limit)
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn release_reminder(
+        &self,
+        reminder: &DueReminder,
+        error: &str,
+        retry: bool,
+    ) -> Result<(), sqlx::Error> {
+        let query = sqlx::query!(
+            r#"
+            UPDATE task_reminders
+            SET attempts = attempts + 1,
+                last_error = $3,
+                fired_at = CASE WHEN $4 THEN NULL ELSE fired_at END
+            WHERE task_id = $1 AND remind_at = $2
+            "#,
+            reminder.task().id(),
+            reminder.remind_at(),
+            error,
+            retry
+        );
+        self.pool.execute(query).await?;
         Ok(())
     }
-}
 
-impl ReminderRepository for Sql {
-    async fn create_task(&self, req: &CreateTaskRequest) -> Result<Task, CreateTaskError> {
-        let mut tx = self
-            .pool
-            .begin()
-            .await
-            .context("failed to start PostgreSQL transaction")?;
+    /// The task with `id` if it belongs to `user` or is in a list shared with them.
+    async fn find_task(&self, user: &Uuid, id: &Uuid) -> Result<Option<TaskRow>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskRow,
+            r#"
+            SELECT
+                id, title, completed, priority, notes, due_at, reminder_offsets, recurrence,
+                list_id, parent_id, position,
+                ARRAY(SELECT tag FROM task_tags WHERE task_id = tasks.id ORDER BY tag) AS "tags!",
+                created_at, updated_by, completed_by, assignee_id, version
+            FROM tasks
+            WHERE id = $1 AND deleted_at IS NULL AND (
+                owner_id = $2
+                OR list_id IN (SELECT list_id FROM task_list_members WHERE user_id = $2)
+            )
+            "#,
+            id,
+            user
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
 
-        let task_id = self.save_task(&mut tx, req.title()).await.map_err(|e| {
-            if is_unique_constraint_violation(&e) {
-                CreateTaskError::Duplicate {
-                    title: req.title().clone(),
-                }
-            } else {
-                anyhow!(e)
-                    .context(format!("failed to save task with title: {:?}", req.title()))
-                    .into()
+    /// The task in the trash with `id` if it belongs to `user` or is in a list shared with them.
+    async fn find_trashed_task(
+        &self,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> Result<Option<TaskRow>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskRow,
+            r#"
+            SELECT
+                id, title, completed, priority, notes, due_at, reminder_offsets, recurrence,
+                list_id, parent_id, position,
+                ARRAY(SELECT tag FROM task_tags WHERE task_id = tasks.id ORDER BY tag) AS "tags!",
+                created_at, updated_by, completed_by, assignee_id, version
+            FROM tasks
+            WHERE id = $1 AND deleted_at IS NOT NULL AND (
+                owner_id = $2
+                OR list_id IN (SELECT list_id FROM task_list_members WHERE user_id = $2)
+            )
+            "#,
+            id,
+            user
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// The tasks in the trash that belong to `user` or are in lists shared with them, most
+    /// recently deleted first.
+    async fn find_trashed_tasks(&self, user: &Uuid) -> Result<Vec<TrashedTaskRow>, sqlx::Error> {
+        sqlx::query_as!(
+            TrashedTaskRow,
+            r#"
+            SELECT
+                id, title, completed, priority, notes, due_at, reminder_offsets, recurrence,
+                list_id, parent_id, position,
+                ARRAY(SELECT tag FROM task_tags WHERE task_id = tasks.id ORDER BY tag) AS "tags!",
+                created_at, updated_by, completed_by, assignee_id, version,
+                deleted_at AS "deleted_at!"
+            FROM tasks
+            WHERE deleted_at IS NOT NULL AND (
+                owner_id = $1
+                OR list_id IN (
+                    SELECT m.list_id
+                    FROM task_list_members m
+                    JOIN task_lists l ON l.id = m.list_id
+                    WHERE m.user_id = $1 AND l.deleted_at IS NULL
+                )
+            )
+            ORDER BY deleted_at DESC, position, id
+            "#,
+            user
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn find_subtasks(&self, owner: &Uuid, id: &Uuid) -> Result<Vec<TaskRow>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskRow,
+            r#"
+            WITH RECURSIVE subtasks AS (
+                SELECT id FROM tasks WHERE parent_id = $1 AND owner_id = $2 AND deleted_at IS NULL
+                UNION
+                SELECT t.id
+                FROM tasks t
+                JOIN subtasks s ON t.parent_id = s.id
+                WHERE t.deleted_at IS NULL
+            )
+            SELECT
+                id, title, completed, priority, notes, due_at, reminder_offsets, recurrence,
+                list_id, parent_id, position,
+                ARRAY(SELECT tag FROM task_tags WHERE task_id = tasks.id ORDER BY tag) AS "tags!",
+                created_at, updated_by, completed_by, assignee_id, version
+            FROM tasks
+            WHERE id IN (SELECT id FROM subtasks)
+            ORDER BY position, created_at, id
+            "#,
+            id,
+            owner
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// The tasks matching the `req` that belong to `user` or are in lists shared with them.
+    async fn find_tasks(
+        &self,
+        user: &Uuid,
+        req: &ListTasksRequest,
+    ) -> Result<Vec<TaskRow>, sqlx::Error> {
+        let sort = req.sort();
+        let (comparison, order, missing_due) = match sort.direction() {
+            SortDirection::Ascending => (">", "ASC", "infinity"),
+            SortDirection::Descending => ("<", "DESC", "-infinity"),
+        };
+        // Tasks without a due date sort last in either direction, as in TaskSort::compare
+        let due_key = format!("COALESCE(due_at, '{}')", missing_due);
+        let key = match sort.field() {
+            TaskSortField::Created => vec!["created_at", "id"],
+            TaskSortField::Due => vec![due_key.as_str(), "created_at", "id"],
+            TaskSortField::Priority => vec![PRIORITY_RANK, "created_at", "id"],
+        };
+
+        let mut query = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT
+                id, title, completed, priority, notes, due_at, reminder_offsets, recurrence,
+                list_id, parent_id, position,
+                ARRAY(SELECT tag FROM task_tags WHERE task_id = tasks.id ORDER BY tag) AS tags,
+                created_at, updated_by, completed_by, assignee_id, version
+            FROM tasks
+            WHERE deleted_at IS NULL AND (owner_id =
+            "#,
+        );
+        query
+            .push_bind(*user)
+            .push(" OR list_id IN (SELECT list_id FROM task_list_members WHERE user_id = ")
+            .push_bind(*user)
+            .push("))");
+        let filter = req.filter();
+        if let Some(completed) = filter.completed() {
+            query.push(" AND completed = ").push_bind(completed);
+        }
+        if let Some(before) = filter.due_before() {
+            query.push(" AND due_at < ").push_bind(*before);
+        }
+        if let Some(after) = filter.due_after() {
+            query.push(" AND due_at > ").push_bind(*after);
+        }
+        if let Some(search) = filter.search() {
+            query
+                .push(" AND title ILIKE ")
+                .push_bind(search.like_pattern());
+        }
+        match filter.list() {
+            Some(TaskListFilter::Inbox) => {
+                query.push(" AND list_id IS NULL");
             }
-        })?;
+            Some(TaskListFilter::List(list_id)) => {
+                query.push(" AND list_id = ").push_bind(list_id);
+            }
+            None => {}
+        }
+        if let Some(assignee) = filter.assignee() {
+            query.push(" AND assignee_id = ").push_bind(assignee);
+        }
+        if let Some(tags) = filter.tags() {
+            let names = tag_names(tags.tags());
+            match tags.mode() {
+                TagMatch::All => query
+                    .push(" AND (SELECT count(*) FROM task_tags WHERE task_id = tasks.id")
+                    .push(" AND tag = ANY(")
+                    .push_bind(names)
+                    .push(")) = ")
+                    .push_bind(tags.tags().len() as i64),
+                TagMatch::Any => query
+                    .push(" AND EXISTS (SELECT 1 FROM task_tags WHERE task_id = tasks.id")
+                    .push(" AND tag = ANY(")
+                    .push_bind(names)
+                    .push("))"),
+            };
+        }
+        if let Some(cursor) = req.cursor() {
+            query.push(format_args!(" AND ({}) {} (", key.join(", "), comparison));
+            if sort.field() == TaskSortField::Due {
+                query
+                    .push("COALESCE(")
+                    .push_bind(cursor.due_at().copied())
+                    .push(format_args!(", '{}'), ", missing_due));
+            }
+            if sort.field() == TaskSortField::Priority {
+                query.push_bind(cursor.priority() as i32).push(", ");
+            }
+            query
+                .push_bind(*cursor.created_at())
+                .push(", ")
+                .push_bind(cursor.id())
+                .push(")");
+        }
+        let order_by = key
+            .iter()
+            .map(|column| format!("{} {}", column, order))
+            .collect::<Vec<_>>();
+        // Fetch one more than requested to learn whether another page follows
+        query
+            .push(format_args!(" ORDER BY {} LIMIT ", order_by.join(", ")))
+            .push_bind(i64::from(req.size().get()) + 1);
 
-        tx.commit()
-            .await
-            .context("failed to commit PostgreSQL transaction")?;
+        query.build_query_as().fetch_all(&self.pool).await
+    }
 
-        Ok(Task {
-            id: task_id,
-            title: req.title().clone(),
-            completed: false,
-        })
+    async fn modify_task(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &UpdateTaskRequest,
+    ) -> Result<Option<TaskRow>, sqlx::Error> {
+        let title = req.title().map(|title| title.to_string());
+        let due_at = req.due_at().flatten().map(DueAt::as_datetime);
+        let offsets = req.remind_at().map(reminder_offsets);
+        let recurrence = req.recurrence().flatten().map(Recurrence::to_string);
+        let notes = req.notes().flatten().map(TaskNotes::as_str);
+        let query = sqlx::query_as!(
+            TaskRow,
+            r#"
+            UPDATE tasks
+            SET title = COALESCE($2, title),
+                completed = COALESCE($3, completed),
+                completed_at = CASE
+                    WHEN $3 IS NULL OR $3 = completed THEN completed_at
+                    WHEN $3 THEN now()
+                    ELSE NULL
+                END,
+                completed_by = CASE
+                    WHEN $3 IS NULL OR $3 = completed THEN completed_by
+                    WHEN $3 THEN $18
+                    ELSE NULL
+                END,
+                updated_by = COALESCE($18, updated_by),
+                due_at = CASE WHEN $4 THEN $5 ELSE due_at END,
+                reminder_offsets = COALESCE($6, reminder_offsets),
+                recurrence = CASE WHEN $7 THEN $8 ELSE recurrence END,
+                list_id = CASE WHEN $9 THEN $10 ELSE list_id END,
+                parent_id = CASE WHEN $11 THEN $12 ELSE parent_id END,
+                position = COALESCE($13, CASE
+                    WHEN $11 AND parent_id IS DISTINCT FROM $12 THEN (
+                        SELECT COALESCE(max(position) + 1, 0)
+                        FROM tasks
+                        WHERE parent_id IS NOT DISTINCT FROM $12 AND owner_id = $17
+                    )
+                    ELSE position
+                END),
+                priority = COALESCE($14, priority),
+                notes = CASE WHEN $15 THEN $16 ELSE notes END,
+                assignee_id = CASE WHEN $19 THEN $20 ELSE assignee_id END,
+                updated_at = now(),
+                version = version + 1
+            WHERE id = $1
+                AND owner_id = $17
+                AND deleted_at IS NULL
+                AND ($21::bigint IS NULL OR version = $21)
+            RETURNING
+                id, title, completed, priority, notes, due_at, reminder_offsets, recurrence,
+                list_id, parent_id, position,
+                ARRAY(SELECT tag FROM task_tags WHERE task_id = tasks.id ORDER BY tag) AS "tags!",
+                created_at, updated_by, completed_by, assignee_id, version
+            "#,
+            id,
+            title,
+            req.completed(),
+            req.due_at().is_some(),
+            due_at,
+            offsets.as_deref(),
+            req.recurrence().is_some(),
+            recurrence,
+            req.list_id().is_some(),
+            req.list_id().flatten(),
+            req.parent_id().is_some(),
+            req.parent_id().flatten(),
+            req.position(),
+            req.priority().map(|priority| priority.as_str()),
+            req.notes().is_some(),
+            notes,
+            owner,
+            req.updated_by(),
+            req.assignee_id().is_some(),
+            req.assignee_id().flatten(),
+            req.version()
+        );
+        query.fetch_optional(&mut **tx).await
     }
-}
 
-impl ReadinessRepository for Sql {
-    async fn is_ready(&self) -> Result<(), ReadinessError> {
-        self.ready().await.map_err(|e| {
-            anyhow!(e)
-                .context("failed to check if database is ready")
-                .into()
-        })
+    /// Lock the task with `id` for the rest of the transaction, returning whether it is completed
+    /// and its parent, or `None` if it does not exist.
+    async fn lock_task(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> Result<Option<(bool, Option<Uuid>)>, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"
+            SELECT completed, parent_id
+            FROM tasks
+            WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL
+            FOR UPDATE
+            "#,
+            id,
+            owner
+        )
+        .fetch_optional(&mut **tx)
+        .await?;
+        Ok(row.map(|row| (row.completed, row.parent_id)))
+    }
+
+    /// Complete every incomplete task nested below the task with `id` on behalf of `user`.
+    async fn complete_subtasks(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        id: &Uuid,
+        user: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        let query = sqlx::query!(
+            r#"
+            WITH RECURSIVE subtasks AS (
+                SELECT id FROM tasks WHERE parent_id = $1 AND deleted_at IS NULL
+                UNION
+                SELECT t.id
+                FROM tasks t
+                JOIN subtasks s ON t.parent_id = s.id
+                WHERE t.deleted_at IS NULL
+            )
+            UPDATE tasks
+            SET completed = TRUE,
+                completed_at = now(),
+                completed_by = $2,
+                updated_by = COALESCE($2, updated_by),
+                updated_at = now(),
+                version = version + 1
+            WHERE id IN (SELECT id FROM subtasks) AND NOT completed
+            "#,
+            id,
+            user
+        );
+        tx.execute(query).await?;
+        Ok(())
+    }
+
+    /// Complete or reopen the ancestors of a task whose parent is `parent_id` on behalf of
+    /// `user`, so that a task with subtasks is completed exactly when all of its subtasks are.
+    /// Returns the title of an ancestor that cannot be reopened because another incomplete task
+    /// has the same title, after which the transaction can only be rolled back.
+    async fn sync_ancestors(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        parent_id: Option<Uuid>,
+        user: Option<Uuid>,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let mut parent_id = parent_id;
+        while let Some(id) = parent_id {
+            let query = sqlx::query!(
+                r#"
+                SELECT
+                    t.title,
+                    t.completed,
+                    t.parent_id,
+                    (
+                        SELECT bool_and(s.completed)
+                        FROM tasks s
+                        WHERE s.parent_id = t.id AND s.deleted_at IS NULL
+                    ) AS subtasks_completed
+                FROM tasks t
+                WHERE t.id = $1 AND t.deleted_at IS NULL
+                FOR UPDATE
+                "#,
+                id
+            );
+            let Some(parent) = query.fetch_optional(&mut **tx).await? else {
+                return Ok(None);
+            };
+            let Some(completed) = parent
+                .subtasks_completed
+                .filter(|&completed| completed != parent.completed)
+            else {
+                return Ok(None);
+            };
+            let query = sqlx::query!(
+                r#"
+                UPDATE tasks
+                SET completed = $2,
+                    completed_at = CASE WHEN $2 THEN now() END,
+                    completed_by = CASE WHEN $2 THEN $3::uuid END,
+                    updated_by = COALESCE($3, updated_by),
+                    updated_at = now(),
+                    version = version + 1
+                WHERE id = $1
+                "#,
+                id,
+                completed,
+                user
+            );
+            match tx.execute(query).await {
+                Err(e) if is_duplicate_title_violation(&e) => return Ok(Some(parent.title)),
+                result => result?,
+            };
+            parent_id = parent.parent_id;
+        }
+        Ok(None)
+    }
+
+    /// Replace the tags of a task.
+    async fn save_tags(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        task_id: &Uuid,
+        tags: &TaskTags,
+    ) -> Result<(), sqlx::Error> {
+        let query = sqlx::query!("DELETE FROM task_tags WHERE task_id = $1", task_id);
+        tx.execute(query).await?;
+        let query = sqlx::query!(
+            "INSERT INTO task_tags (task_id, tag) SELECT $1, unnest($2::text[])",
+            task_id,
+            &tag_names(tags.tags())
+        );
+        tx.execute(query).await?;
+        Ok(())
+    }
+
+    /// Hold off changes to the tags of the tasks of `owner` until the end of the transaction.
+    /// Taken before any task row is locked, so that it is always acquired in the same order.
+    async fn lock_tags(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        owner: &Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let query = sqlx::query!(
+            "SELECT pg_advisory_xact_lock(hashtextextended($1::uuid::text, 0))",
+            owner
+        );
+        tx.execute(query).await?;
+        Ok(())
+    }
+
+    /// The number of tasks of `owner` that carry `tag`.
+    async fn count_tag(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        owner: &Uuid,
+        tag: &Tag,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT count(*) AS "count!"
+            FROM task_tags
+            WHERE tag = $1
+                AND task_id IN (SELECT id FROM tasks WHERE owner_id = $2 AND deleted_at IS NULL)
+            "#,
+            tag.as_str(),
+            owner
+        )
+        .fetch_one(&mut **tx)
+        .await
+    }
+
+    async fn find_tags(&self, owner: &Uuid) -> Result<Vec<TagUsageRow>, sqlx::Error> {
+        sqlx::query_as!(
+            TagUsageRow,
+            r#"
+            SELECT tag, count(*) AS "tasks!"
+            FROM task_tags
+            WHERE task_id IN (SELECT id FROM tasks WHERE owner_id = $1 AND deleted_at IS NULL)
+            GROUP BY tag
+            ORDER BY tag
+            "#,
+            owner
+        )
+        .fetch_all(&self.pool)
+        .await
     }
-}
 
-const UNIQUE_CONSTRAINT_VIOLATION_CODE: &str = "2067";
+    async fn modify_tag(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        owner: &Uuid,
+        from: &Tag,
+        to: &Tag,
+    ) -> Result<TagRename, sqlx::Error> {
+        // Keep concurrent tagging from slipping in between the checks and the rename
+        self.lock_tags(tx, owner).await?;
+        if self.count_tag(tx, owner, from).await? == 0 {
+            return Ok(TagRename::NotFound);
+        }
+        if from != to && self.count_tag(tx, owner, to).await? > 0 {
+            return Ok(TagRename::Duplicate);
+        }
+        let query = sqlx::query!(
+            r#"
+            UPDATE tasks
+            SET version = version + 1
+            WHERE owner_id = $2
+                AND deleted_at IS NULL
+                AND id IN (SELECT task_id FROM task_tags WHERE tag = $1)
+            "#,
+            from.as_str(),
+            owner
+        );
+        tx.execute(query).await?;
+        let query = sqlx::query!(
+            r#"
+            UPDATE task_tags
+            SET tag = $2
+            WHERE tag = $1
+                AND task_id IN (SELECT id FROM tasks WHERE owner_id = $3 AND deleted_at IS NULL)
+            "#,
+            from.as_str(),
+            to.as_str(),
+            owner
+        );
+        let result = tx.execute(query).await?;
+        Ok(TagRename::Renamed(result.rows_affected()))
+    }
 
-fn is_unique_constraint_violation(err: &sqlx::Error) -> bool {
-    if let sqlx::Error::Database(db_err) = err {
-        if let Some(code) = db_err.code() {
-            if code == UNIQUE_CONSTRAINT_VIOLATION_CODE {
-                return true;
+    async fn combine_tags(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        owner: &Uuid,
+        tags: &[Tag],
+        into: &Tag,
+    ) -> Result<TagMerge, sqlx::Error> {
+        self.lock_tags(tx, owner).await?;
+        for tag in tags {
+            if self.count_tag(tx, owner, tag).await? == 0 {
+                return Ok(TagMerge::NotFound(tag.clone()));
             }
         }
+        let names = tag_names(tags);
+        let query = sqlx::query!(
+            r#"
+            UPDATE tasks
+            SET version = version + 1
+            WHERE owner_id = $3
+                AND deleted_at IS NULL
+                AND id IN (SELECT task_id FROM task_tags WHERE tag = ANY($1) AND tag <> $2)
+            "#,
+            &names,
+            into.as_str(),
+            owner
+        );
+        tx.execute(query).await?;
+        let query = sqlx::query!(
+            r#"
+            INSERT INTO task_tags (task_id, tag)
+            SELECT DISTINCT task_id, $2
+            FROM task_tags
+            WHERE tag = ANY($1)
+                AND task_id IN (SELECT id FROM tasks WHERE owner_id = $3 AND deleted_at IS NULL)
+            ON CONFLICT DO NOTHING
+            "#,
+            &names,
+            into.as_str(),
+            owner
+        );
+        tx.execute(query).await?;
+        let query = sqlx::query!(
+            r#"
+            DELETE FROM task_tags
+            WHERE tag = ANY($1)
+                AND tag <> $2
+                AND task_id IN (SELECT id FROM tasks WHERE owner_id = $3 AND deleted_at IS NULL)
+            "#,
+            &names,
+            into.as_str(),
+            owner
+        );
+        tx.execute(query).await?;
+        let tasks = self.count_tag(tx, owner, into).await?;
+        Ok(TagMerge::Merged(tasks as u64))
+    }
+
+    /// Move the task with `id` and its subtasks to the trash, all at the same time so that they
+    /// are restored together. Returns whether the task was found.
+    async fn remove_task(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        owner: &Uuid,
+        id: &Uuid,
+        version: Option<i64>,
+    ) -> Result<bool, sqlx::Error> {
+        let query = sqlx::query!(
+            r#"
+            WITH RECURSIVE subtree AS (
+                SELECT id
+                FROM tasks
+                WHERE id = $1
+                    AND owner_id = $2
+                    AND deleted_at IS NULL
+                    AND ($3::bigint IS NULL OR version = $3)
+                UNION
+                SELECT t.id
+                FROM tasks t
+                JOIN subtree s ON t.parent_id = s.id
+                WHERE t.deleted_at IS NULL
+            )
+            UPDATE tasks
+            SET deleted_at = now(), updated_at = now(), version = version + 1
+            WHERE id IN (SELECT id FROM subtree)
+            "#,
+            id,
+            owner,
+            version
+        );
+        let result = tx.execute(query).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The ids of the tasks that were moved to the trash at `deleted_at` along with the task with
+    /// id `task_id`, or with the list with id `list_id`, including that task.
+    async fn find_trashed_together(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        task_id: Option<&Uuid>,
+        list_id: Option<&Uuid>,
+        deleted_at: &DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            WITH RECURSIVE subtree AS (
+                SELECT id FROM tasks WHERE (id = $1 OR list_id = $2) AND deleted_at = $3
+                UNION
+                SELECT t.id
+                FROM tasks t
+                JOIN subtree s ON t.parent_id = s.id
+                WHERE t.deleted_at = $3
+            )
+            SELECT id AS "id!" FROM subtree
+            "#,
+            task_id,
+            list_id,
+            deleted_at
+        )
+        .fetch_all(&mut **tx)
+        .await
+    }
+
+    /// The title of one of the tasks with `ids` that cannot be restored from the trash because it
+    /// is incomplete while another incomplete task has the same title.
+    async fn find_restore_clash(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        ids: &[Uuid],
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT t.title
+            FROM tasks t
+            WHERE t.id = ANY($1) AND NOT t.completed AND EXISTS (
+                SELECT 1
+                FROM tasks o
+                WHERE o.owner_id = t.owner_id
+                    AND lower(o.title) = lower(t.title)
+                    AND NOT o.completed
+                    AND o.deleted_at IS NULL
+            )
+            LIMIT 1
+            "#,
+            ids
+        )
+        .fetch_optional(&mut **tx)
+        .await
+    }
+
+    /// Take the tasks with `ids` out of the trash.
+    async fn undelete_tasks(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        ids: &[Uuid],
+    ) -> Result<Vec<TaskRow>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskRow,
+            r#"
+            UPDATE tasks
+            SET deleted_at = NULL, updated_at = now(), version = version + 1
+            WHERE id = ANY($1)
+            RETURNING
+                id, title, completed, priority, notes, due_at, reminder_offsets, recurrence,
+                list_id, parent_id, position,
+                ARRAY(SELECT tag FROM task_tags WHERE task_id = tasks.id ORDER BY tag) AS "tags!",
+                created_at, updated_by, completed_by, assignee_id, version
+            "#,
+            ids
+        )
+        .fetch_all(&mut **tx)
+        .await
+    }
+
+    /// Restore the task with `id` from the trash along with the subtasks deleted with it.
+    async fn undelete_task(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> Result<TaskRestore, sqlx::Error> {
+        let query = sqlx::query!(
+            r#"
+            SELECT parent_id, list_id, deleted_at AS "deleted_at!"
+            FROM tasks
+            WHERE id = $1 AND owner_id = $2 AND deleted_at IS NOT NULL
+            FOR UPDATE
+            "#,
+            id,
+            owner
+        );
+        let Some(trashed) = query.fetch_optional(&mut **tx).await? else {
+            return Ok(TaskRestore::NotFound);
+        };
+        if let Some(parent_id) = trashed.parent_id {
+            let deleted = sqlx::query_scalar!(
+                r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM tasks WHERE id = $1 AND deleted_at IS NOT NULL
+                ) AS "deleted!"
+                "#,
+                parent_id
+            )
+            .fetch_one(&mut **tx)
+            .await?;
+            if deleted {
+                return Ok(TaskRestore::ParentDeleted(parent_id));
+            }
+        }
+        if let Some(list_id) = trashed.list_id {
+            let deleted = sqlx::query_scalar!(
+                r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM task_lists WHERE id = $1 AND deleted_at IS NOT NULL
+                ) AS "deleted!"
+                "#,
+                list_id
+            )
+            .fetch_one(&mut **tx)
+            .await?;
+            if deleted {
+                return Ok(TaskRestore::ListDeleted(list_id));
+            }
+        }
+        let ids = self
+            .find_trashed_together(tx, Some(id), None, &trashed.deleted_at)
+            .await?;
+        if let Some(title) = self.find_restore_clash(tx, &ids).await? {
+            return Ok(TaskRestore::Duplicate(title));
+        }
+        let rows = self.undelete_tasks(tx, &ids).await?;
+        Ok(TaskRestore::Restored(rows))
+    }
+
+    /// Permanently delete the tasks and lists in the trash of `owner`, or of every user if it is
+    /// `None`, that were deleted before `deleted_before`, if given.
+    async fn remove_trash(
+        &self,
+        owner: Option<&Uuid>,
+        deleted_before: Option<&DateTime<Utc>>,
+    ) -> Result<PurgedTrash, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        // Tasks left in a list are deleted with it, whenever they were moved to the trash
+        let query = sqlx::query!(
+            r#"
+            DELETE FROM tasks
+            WHERE ($1::uuid IS NULL OR owner_id = $1) AND (
+                deleted_at < COALESCE($2::timestamptz, 'infinity')
+                OR list_id IN (
+                    SELECT id
+                    FROM task_lists
+                    WHERE ($1::uuid IS NULL OR owner_id = $1)
+                        AND deleted_at < COALESCE($2::timestamptz, 'infinity')
+                )
+            )
+            "#,
+            owner,
+            deleted_before
+        );
+        let tasks = tx.execute(query).await?.rows_affected();
+        let query = sqlx::query!(
+            r#"
+            DELETE FROM task_lists
+            WHERE ($1::uuid IS NULL OR owner_id = $1)
+                AND deleted_at < COALESCE($2::timestamptz, 'infinity')
+            "#,
+            owner,
+            deleted_before
+        );
+        let lists = tx.execute(query).await?.rows_affected();
+        tx.commit().await?;
+        Ok(PurgedTrash { tasks, lists })
+    }
+
+    async fn save_list(
+        &self,
+        owner: &Uuid,
+        req: &CreateTaskListRequest,
+    ) -> Result<TaskListRow, sqlx::Error> {
+        sqlx::query_as!(
+            TaskListRow,
+            r#"
+            INSERT INTO task_lists (id, title, colour, position, owner_id)
+            VALUES (
+                $1, $2, $3,
+                COALESCE($4, (
+                    SELECT COALESCE(max(position) + 1, 0) FROM task_lists WHERE owner_id = $5
+                )),
+                $5
+            )
+            RETURNING id, title, colour, position, archived, created_at, owner_id, 'owner' AS "role!"
+            "#,
+            Uuid::new_v4(),
+            req.title().to_string(),
+            req.colour().map(TaskListColour::to_string),
+            req.position(),
+            owner
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// The list with `id` and the role of `user` in it, if it belongs to or is shared with them.
+    async fn find_list(&self, user: &Uuid, id: &Uuid) -> Result<Option<TaskListRow>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskListRow,
+            r#"
+            SELECT
+                l.id, l.title, l.colour, l.position, l.archived, l.created_at, l.owner_id,
+                CASE WHEN l.owner_id = $2 THEN 'owner' ELSE m.role END AS "role!"
+            FROM task_lists l
+            LEFT JOIN task_list_members m ON m.list_id = l.id AND m.user_id = $2
+            WHERE l.id = $1 AND l.deleted_at IS NULL AND (l.owner_id = $2 OR m.user_id IS NOT NULL)
+            "#,
+            id,
+            user
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn find_lists(
+        &self,
+        user: &Uuid,
+        include_archived: bool,
+    ) -> Result<Vec<TaskListRow>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskListRow,
+            r#"
+            SELECT
+                l.id, l.title, l.colour, l.position, l.archived, l.created_at, l.owner_id,
+                CASE WHEN l.owner_id = $1 THEN 'owner' ELSE m.role END AS "role!"
+            FROM task_lists l
+            LEFT JOIN task_list_members m ON m.list_id = l.id AND m.user_id = $1
+            WHERE (l.owner_id = $1 OR m.user_id IS NOT NULL)
+                AND l.deleted_at IS NULL
+                AND ($2 OR NOT l.archived)
+            ORDER BY l.position, l.created_at, l.id
+            "#,
+            user,
+            include_archived
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn modify_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &UpdateTaskListRequest,
+    ) -> Result<Option<TaskListRow>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskListRow,
+            r#"
+            UPDATE task_lists
+            SET title = COALESCE($2, title),
+                colour = CASE WHEN $3 THEN $4 ELSE colour END,
+                position = COALESCE($5, position),
+                archived = COALESCE($6, archived),
+                updated_at = now()
+            WHERE id = $1 AND owner_id = $7 AND deleted_at IS NULL
+            RETURNING id, title, colour, position, archived, created_at, owner_id, 'owner' AS "role!"
+            "#,
+            id,
+            req.title().map(TaskListTitle::to_string),
+            req.colour().is_some(),
+            req.colour().flatten().map(TaskListColour::to_string),
+            req.position(),
+            req.archived(),
+            owner
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Move a list to the trash after dealing with its tasks according to `mode`, and revoke the
+    /// API tokens restricted to it.
+    async fn remove_list(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        owner: &Uuid,
+        id: &Uuid,
+        mode: DeleteTaskListMode,
+    ) -> Result<ListRemoval, sqlx::Error> {
+        // Locking the list blocks tasks from being added to it concurrently
+        let query = sqlx::query_scalar!(
+            r#"
+            SELECT id
+            FROM task_lists
+            WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL
+            FOR UPDATE
+            "#,
+            id,
+            owner
+        );
+        if query.fetch_optional(&mut **tx).await?.is_none() {
+            return Ok(ListRemoval::NotFound);
+        }
+
+        match mode {
+            DeleteTaskListMode::OnlyIfEmpty => {
+                let tasks = sqlx::query_scalar!(
+                    r#"
+                    SELECT count(*) AS "count!"
+                    FROM tasks
+                    WHERE list_id = $1 AND deleted_at IS NULL
+                    "#,
+                    id
+                )
+                .fetch_one(&mut **tx)
+                .await?;
+                if tasks > 0 {
+                    return Ok(ListRemoval::NotEmpty(tasks as u64));
+                }
+            }
+            DeleteTaskListMode::Cascade => {
+                // Subtasks in other lists go to the trash with their parents
+                let query = sqlx::query!(
+                    r#"
+                    WITH RECURSIVE subtree AS (
+                        SELECT id FROM tasks WHERE list_id = $1 AND deleted_at IS NULL
+                        UNION
+                        SELECT t.id
+                        FROM tasks t
+                        JOIN subtree s ON t.parent_id = s.id
+                        WHERE t.deleted_at IS NULL
+                    )
+                    UPDATE tasks
+                    SET deleted_at = now(), updated_at = now(), version = version + 1
+                    WHERE id IN (SELECT id FROM subtree)
+                    "#,
+                    id
+                );
+                tx.execute(query).await?;
+            }
+            DeleteTaskListMode::MoveToInbox => {
+                // Only the owner can be assigned tasks in their inbox
+                let query = sqlx::query!(
+                    r#"
+                    UPDATE tasks
+                    SET list_id = NULL,
+                        assignee_id = CASE WHEN assignee_id = owner_id THEN assignee_id END,
+                        updated_at = now(),
+                        version = version + 1
+                    WHERE list_id = $1 AND deleted_at IS NULL
+                    "#,
+                    id
+                );
+                tx.execute(query).await?;
+            }
+        }
+        let query = sqlx::query!(
+            "UPDATE task_lists SET deleted_at = now(), updated_at = now() WHERE id = $1",
+            id
+        );
+        tx.execute(query).await?;
+        let query = sqlx::query!("DELETE FROM api_tokens WHERE list_id = $1", id);
+        tx.execute(query).await?;
+        Ok(ListRemoval::Removed)
+    }
+
+    /// The lists in the trash that belong to `owner`, most recently deleted first.
+    async fn find_trashed_lists(
+        &self,
+        owner: &Uuid,
+    ) -> Result<Vec<TrashedTaskListRow>, sqlx::Error> {
+        sqlx::query_as!(
+            TrashedTaskListRow,
+            r#"
+            SELECT
+                id, title, colour, position, archived, created_at, owner_id, 'owner' AS "role!",
+                deleted_at AS "deleted_at!"
+            FROM task_lists
+            WHERE owner_id = $1 AND deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC, position, id
+            "#,
+            owner
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Restore the list with `id` from the trash along with the tasks deleted with it.
+    async fn undelete_list(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Postgres>,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> Result<ListRestore, sqlx::Error> {
+        let query = sqlx::query!(
+            r#"
+            SELECT title, deleted_at AS "deleted_at!"
+            FROM task_lists
+            WHERE id = $1 AND owner_id = $2 AND deleted_at IS NOT NULL
+            FOR UPDATE
+            "#,
+            id,
+            owner
+        );
+        let Some(trashed) = query.fetch_optional(&mut **tx).await? else {
+            return Ok(ListRestore::NotFound);
+        };
+        let query = sqlx::query_as!(
+            TaskListRow,
+            r#"
+            UPDATE task_lists
+            SET deleted_at = NULL, updated_at = now()
+            WHERE id = $1
+            RETURNING id, title, colour, position, archived, created_at, owner_id, 'owner' AS "role!"
+            "#,
+            id
+        );
+        let row = match query.fetch_one(&mut **tx).await {
+            Ok(row) => row,
+            Err(e) if is_duplicate_list_title_violation(&e) => {
+                return Ok(ListRestore::Duplicate(trashed.title))
+            }
+            Err(e) => return Err(e),
+        };
+        let ids = self
+            .find_trashed_together(tx, None, Some(id), &trashed.deleted_at)
+            .await?;
+        if let Some(title) = self.find_restore_clash(tx, &ids).await? {
+            return Ok(ListRestore::TaskDuplicate(title));
+        }
+        self.undelete_tasks(tx, &ids).await?;
+        Ok(ListRestore::Restored(row))
+    }
+
+    /// The creator of the list with `id` followed by the users it is shared with, or `None` if
+    /// `owner` has no such list.
+    async fn find_members(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> Result<Option<Vec<MemberRow>>, sqlx::Error> {
+        let creator = sqlx::query_as!(
+            MemberRow,
+            r#"
+            SELECT u.id AS user_id, u.email, 'owner' AS "role!", l.created_at AS added_at
+            FROM task_lists l
+            JOIN users u ON u.id = l.owner_id
+            WHERE l.id = $1 AND l.owner_id = $2 AND l.deleted_at IS NULL
+            "#,
+            id,
+            owner
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some(creator) = creator else {
+            return Ok(None);
+        };
+        let members = sqlx::query_as!(
+            MemberRow,
+            r#"
+            SELECT m.user_id, u.email, m.role, m.added_at
+            FROM task_list_members m
+            JOIN users u ON u.id = m.user_id
+            WHERE m.list_id = $1
+            ORDER BY m.added_at, u.email
+            "#,
+            id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(Some(std::iter::once(creator).chain(members).collect()))
+    }
+
+    /// Share the list with `id` with the user registered under the email address in the `req`,
+    /// or change their role if it is already shared with them.
+    async fn save_member(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &ShareTaskListRequest,
+    ) -> Result<MemberSave, sqlx::Error> {
+        let query = sqlx::query_scalar!(
+            "SELECT id FROM task_lists WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+            id,
+            owner
+        );
+        if query.fetch_optional(&self.pool).await?.is_none() {
+            return Ok(MemberSave::ListNotFound);
+        }
+        let Some(user) = self.find_user(req.email()).await? else {
+            return Ok(MemberSave::UserNotFound);
+        };
+        if user.id == *owner {
+            return Ok(MemberSave::Creator);
+        }
+        let row = sqlx::query_as!(
+            MemberRow,
+            r#"
+            INSERT INTO task_list_members (list_id, user_id, role)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (list_id, user_id) DO UPDATE SET role = EXCLUDED.role
+            RETURNING user_id, $4::text AS "email!", role, added_at
+            "#,
+            id,
+            user.id,
+            req.role().as_str(),
+            user.email
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(MemberSave::Saved(row))
+    }
+
+    /// Stop sharing the list with `id` with the user with id `member`, and unassign them from its
+    /// tasks. Returns whether the list exists, and whether it was shared with them.
+    async fn remove_member(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        member: &Uuid,
+    ) -> Result<(bool, bool), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let query = sqlx::query_scalar!(
+            "SELECT id FROM task_lists WHERE id = $1 AND owner_id = $2 AND deleted_at IS NULL",
+            id,
+            owner
+        );
+        if query.fetch_optional(&mut *tx).await?.is_none() {
+            return Ok((false, false));
+        }
+        let query = sqlx::query!(
+            "DELETE FROM task_list_members WHERE list_id = $1 AND user_id = $2",
+            id,
+            member
+        );
+        let result = tx.execute(query).await?;
+        let query = sqlx::query!(
+            r#"
+            UPDATE tasks
+            SET assignee_id = NULL, version = version + 1
+            WHERE list_id = $1 AND assignee_id = $2
+            "#,
+            id,
+            member
+        );
+        tx.execute(query).await?;
+        tx.commit().await?;
+        Ok((true, result.rows_affected() > 0))
+    }
+
+    async fn save_user(
+        &self,
+        email: &EmailAddress,
+        password_hash: &PasswordHash,
+    ) -> Result<UserRow, sqlx::Error> {
+        sqlx::query_as!(
+            UserRow,
+            r#"
+            INSERT INTO users (id, email, password_hash)
+            VALUES ($1, $2, $3)
+            RETURNING id, email, password_hash, created_at
+            "#,
+            Uuid::new_v4(),
+            email.as_str(),
+            password_hash.as_str()
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn find_user(&self, email: &EmailAddress) -> Result<Option<UserRow>, sqlx::Error> {
+        sqlx::query_as!(
+            UserRow,
+            "SELECT id, email, password_hash, created_at FROM users WHERE email = $1",
+            email.as_str()
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Store a session, clearing out the expired sessions of the same user on the way.
+    async fn save_session(&self, session: &Session) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let query = sqlx::query!(
+            "DELETE FROM user_sessions WHERE user_id = $1 AND expires_at <= now()",
+            session.user().id()
+        );
+        tx.execute(query).await?;
+        let query = sqlx::query!(
+            r#"
+            INSERT INTO user_sessions (token_digest, user_id, expires_at)
+            VALUES ($1, $2, $3)
+            "#,
+            session.token().digest(),
+            session.user().id(),
+            session.expires_at()
+        );
+        tx.execute(query).await?;
+        tx.commit().await
+    }
+
+    async fn find_session_user(
+        &self,
+        token: &SessionToken,
+        now: DateTime<Utc>,
+    ) -> Result<Option<UserRow>, sqlx::Error> {
+        sqlx::query_as!(
+            UserRow,
+            r#"
+            SELECT u.id, u.email, u.password_hash, u.created_at
+            FROM user_sessions s
+            JOIN users u ON u.id = s.user_id
+            WHERE s.token_digest = $1 AND s.expires_at > $2
+            "#,
+            token.digest(),
+            now
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn remove_session(&self, token: &SessionToken) -> Result<(), sqlx::Error> {
+        let query = sqlx::query!(
+            "DELETE FROM user_sessions WHERE token_digest = $1",
+            token.digest()
+        );
+        self.pool.execute(query).await?;
+        Ok(())
+    }
+
+    async fn save_api_token(&self, user: &Uuid, token: &NewApiToken) -> Result<(), sqlx::Error> {
+        let scope = token.token().scope();
+        let query = sqlx::query!(
+            r#"
+            INSERT INTO api_tokens (id, user_id, token_digest, name, access, list_id, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            token.token().id(),
+            user,
+            token.secret().digest(),
+            token.token().name().as_str(),
+            scope.access().as_str(),
+            scope.list_id(),
+            token.token().created_at()
+        );
+        self.pool.execute(query).await?;
+        Ok(())
+    }
+
+    async fn find_api_tokens(&self, user: &Uuid) -> Result<Vec<ApiTokenRow>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiTokenRow,
+            r#"
+            SELECT id, name, access, list_id, created_at, last_used_at
+            FROM api_tokens
+            WHERE user_id = $1
+            ORDER BY created_at, id
+            "#,
+            user
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn remove_api_token(&self, user: &Uuid, id: &Uuid) -> Result<bool, sqlx::Error> {
+        let query = sqlx::query!(
+            "DELETE FROM api_tokens WHERE id = $1 AND user_id = $2",
+            id,
+            user
+        );
+        let result = self.pool.execute(query).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the use of the API token with `secret` and fetch its user and scope.
+    async fn touch_api_token(
+        &self,
+        secret: &ApiTokenSecret,
+        now: DateTime<Utc>,
+    ) -> Result<Option<ApiTokenUserRow>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiTokenUserRow,
+            r#"
+            WITH used AS (
+                UPDATE api_tokens
+                SET last_used_at = $2
+                WHERE token_digest = $1
+                RETURNING user_id, access, list_id
+            )
+            SELECT u.id, u.email, u.password_hash, u.created_at, used.access, used.list_id
+            FROM used
+            JOIN users u ON u.id = used.user_id
+            "#,
+            secret.digest(),
+            now
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Claim the key of `req`. Returns `None` if it was claimed, or else the row of the request
+    /// that claimed it first, which is `None` if that request released the key meanwhile.
+    async fn save_idempotency_key(
+        &self,
+        req: &IdempotentRequest,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<Option<IdempotencyKeyRow>>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let query = sqlx::query!("DELETE FROM idempotency_keys WHERE expires_at <= $1", now);
+        tx.execute(query).await?;
+        let query = sqlx::query!(
+            r#"
+            INSERT INTO idempotency_keys (owner_digest, key, fingerprint, created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (owner_digest, key) DO NOTHING
+            "#,
+            req.owner(),
+            req.key().as_str(),
+            req.fingerprint(),
+            now,
+            expires_at
+        );
+        let claimed = tx.execute(query).await?.rows_affected() > 0;
+        let existing = if claimed {
+            None
+        } else {
+            let row = sqlx::query_as!(
+                IdempotencyKeyRow,
+                r#"
+                SELECT
+                    fingerprint, response_status, response_content_type, response_headers,
+                    response_body
+                FROM idempotency_keys
+                WHERE owner_digest = $1 AND key = $2
+                "#,
+                req.owner(),
+                req.key().as_str()
+            )
+            .fetch_optional(&mut *tx)
+            .await?;
+            Some(row)
+        };
+        tx.commit().await?;
+        Ok(existing)
+    }
+
+    async fn save_response(
+        &self,
+        req: &IdempotentRequest,
+        response: &StoredResponse,
+    ) -> Result<(), sqlx::Error> {
+        let status = i16::try_from(response.status()).unwrap_or(i16::MAX);
+        let query = sqlx::query!(
+            r#"
+            UPDATE idempotency_keys
+            SET
+                response_status = $3, response_content_type = $4, response_headers = $5,
+                response_body = $6
+            WHERE owner_digest = $1 AND key = $2
+            "#,
+            req.owner(),
+            req.key().as_str(),
+            status,
+            response.content_type(),
+            &header_lines(response),
+            response.body()
+        );
+        self.pool.execute(query).await?;
+        Ok(())
+    }
+
+    async fn remove_idempotency_key(&self, req: &IdempotentRequest) -> Result<(), sqlx::Error> {
+        let query = sqlx::query!(
+            "DELETE FROM idempotency_keys WHERE owner_digest = $1 AND key = $2",
+            req.owner(),
+            req.key().as_str()
+        );
+        self.pool.execute(query).await?;
+        Ok(())
+    }
+
+    async fn ready(&self) -> Result<(), sqlx::Error> {
+        let query = sqlx::query!("SELECT 1 as health_check");
+        query.fetch_one(&self.pool).await?;
+        Ok(())
+    }
+}
+
+impl ReminderRepository for Sql {
+    #[instrument(skip(self, req))]
+    async fn create_task(
+        &self,
+        owner: &Uuid,
+        req: &CreateTaskRequest,
+    ) -> Result<Task, CreateTaskError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start PostgreSQL transaction")?;
+
+        if !req.tags().is_empty() {
+            self.lock_tags(&mut tx, owner)
+                .await
+                .context("failed to lock the tags of the task owner")?;
+        }
+        let (task_id, position, created_at) =
+            self.save_task(&mut tx, owner, req).await.map_err(|e| {
+                if is_duplicate_title_violation(&e) {
+                    CreateTaskError::Duplicate {
+                        title: req.title().clone(),
+                    }
+                } else if let Some(id) = req.list_id().filter(|_| is_unknown_list_violation(&e)) {
+                    CreateTaskError::ListNotFound { id }
+                } else if let Some(id) = req.parent_id().filter(|_| is_unknown_parent_violation(&e))
+                {
+                    CreateTaskError::ParentNotFound { id }
+                } else {
+                    anyhow!(e)
+                        .context(format!("failed to save task with title: {:?}", req.title()))
+                        .into()
+                }
+            })?;
+
+        self.sync_reminders(&mut tx, &task_id)
+            .await
+            .with_context(|| format!("failed to schedule reminders for task: {}", task_id))?;
+        self.save_tags(&mut tx, &task_id, req.tags())
+            .await
+            .with_context(|| format!("failed to save tags of task: {}", task_id))?;
+        let clash = self
+            .sync_ancestors(&mut tx, req.parent_id(), req.updated_by())
+            .await
+            .with_context(|| format!("failed to reopen the parents of task: {}", task_id))?;
+        if let Some(title) = clash {
+            let title = TaskTitle::new(&title).with_context(|| {
+                format!("invalid title stored for a parent of task {}", task_id)
+            })?;
+            return Err(CreateTaskError::Duplicate { title });
+        }
+
+        tx.commit()
+            .await
+            .context("failed to commit PostgreSQL transaction")?;
+
+        Ok(Task {
+            id: task_id,
+            title: req.title().clone(),
+            completed: false,
+            priority: req.priority(),
+            notes: req.notes().cloned(),
+            due_at: req.due_at().copied(),
+            remind_at: req.remind_at().clone(),
+            recurrence: req.recurrence().cloned(),
+            list_id: req.list_id(),
+            parent_id: req.parent_id(),
+            position,
+            tags: req.tags().clone(),
+            assignee_id: req.assignee_id(),
+            created_at,
+            updated_by: req.updated_by(),
+            completed_by: None,
+            version: 1,
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_task(&self, user: &Uuid, id: &Uuid) -> Result<Task, GetTaskError> {
+        let row = self
+            .find_task(user, id)
+            .await
+            .with_context(|| format!("failed to fetch task with id: {}", id))?
+            .ok_or(GetTaskError::NotFound { id: *id })?;
+
+        Ok(Task::try_from(row)?)
+    }
+
+    #[instrument(skip(self, req))]
+    async fn list_tasks(
+        &self,
+        user: &Uuid,
+        req: &ListTasksRequest,
+    ) -> Result<TaskPage, ListTasksError> {
+        let rows = self
+            .find_tasks(user, req)
+            .await
+            .context("failed to fetch tasks")?;
+        let tasks = rows
+            .into_iter()
+            .map(Task::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(req.page(tasks))
+    }
+
+    #[instrument(skip(self))]
+    async fn list_subtasks(&self, owner: &Uuid, id: &Uuid) -> Result<Vec<Task>, ListTasksError> {
+        let rows = self
+            .find_subtasks(owner, id)
+            .await
+            .with_context(|| format!("failed to fetch subtasks of task with id: {}", id))?;
+        let subtasks = rows
+            .into_iter()
+            .map(Task::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(subtasks)
+    }
+
+    #[instrument(skip(self, req))]
+    async fn update_task(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &UpdateTaskRequest,
+    ) -> Result<Task, UpdateTaskError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start PostgreSQL transaction")?;
+
+        // Completing a recurring task saves the tags of its next occurrence
+        if req.tags().is_some() || req.completed() == Some(true) {
+            self.lock_tags(&mut tx, owner)
+                .await
+                .context("failed to lock the tags of the task owner")?;
+        }
+        let (was_completed, old_parent_id) = self
+            .lock_task(&mut tx, owner, id)
+            .await
+            .with_context(|| format!("failed to lock task with id: {}", id))?
+            .ok_or(UpdateTaskError::NotFound { id: *id })?;
+        let row = match self.modify_task(&mut tx, owner, id, req).await {
+            Ok(Some(row)) => row,
+            // The task is locked, so it was kept because it changed since the requested version
+            Ok(None) => {
+                return Err(match req.version() {
+                    Some(version) => UpdateTaskError::Conflict { id: *id, version },
+                    None => UpdateTaskError::NotFound { id: *id },
+                });
+            }
+            // Reopening a task can clash with another task even when the title is unchanged
+            Err(e) if is_duplicate_title_violation(&e) => {
+                let title = match req.title() {
+                    Some(title) => title.clone(),
+                    None => {
+                        self.get_task(owner, id)
+                            .await
+                            .map_err(|e| anyhow!(e))?
+                            .title
+                    }
+                };
+                return Err(UpdateTaskError::Duplicate { title });
+            }
+            Err(e) => {
+                let list_id = req.list_id().flatten();
+                if let Some(id) = list_id.filter(|_| is_unknown_list_violation(&e)) {
+                    return Err(UpdateTaskError::ListNotFound { id });
+                }
+                let parent_id = req.parent_id().flatten();
+                if let Some(id) = parent_id.filter(|_| is_unknown_parent_violation(&e)) {
+                    return Err(UpdateTaskError::ParentNotFound { id });
+                }
+                return Err(anyhow!(e)
+                    .context(format!("failed to update task with id: {}", id))
+                    .into());
+            }
+        };
+
+        self.sync_reminders(&mut tx, id)
+            .await
+            .with_context(|| format!("failed to schedule reminders for task: {}", id))?;
+        let mut task = Task::try_from(row)?;
+        if let Some(tags) = req.tags() {
+            self.save_tags(&mut tx, id, tags)
+                .await
+                .with_context(|| format!("failed to save tags of task: {}", id))?;
+            task.tags = tags.clone();
+        }
+        // The task is locked, so of concurrent completions only the first one gets here
+        if !was_completed && task.completed {
+            self.complete_subtasks(&mut tx, id, req.updated_by())
+                .await
+                .with_context(|| format!("failed to complete subtasks of task: {}", id))?;
+            if let Some(mut next) = task.next_occurrence(Utc::now()) {
+                if let Some(user) = req.updated_by() {
+                    next = next.with_updated_by(user);
+                }
+                let (next_id, _, _) = self.save_task(&mut tx, owner, &next).await.map_err(
+                    |e| -> UpdateTaskError {
+                        if is_duplicate_title_violation(&e) {
+                            UpdateTaskError::Duplicate {
+                                title: next.title().clone(),
+                            }
+                        } else {
+                            anyhow!(e)
+                                .context(format!("failed to create next occurrence of task {}", id))
+                                .into()
+                        }
+                    },
+                )?;
+                self.sync_reminders(&mut tx, &next_id)
+                    .await
+                    .with_context(|| {
+                        format!("failed to schedule reminders for task: {}", next_id)
+                    })?;
+                self.save_tags(&mut tx, &next_id, next.tags())
+                    .await
+                    .with_context(|| format!("failed to save tags of task: {}", next_id))?;
+            }
+        }
+        if req.completed().is_some() || req.parent_id().is_some() {
+            let mut clash = None;
+            if old_parent_id != task.parent_id {
+                clash = self
+                    .sync_ancestors(&mut tx, old_parent_id, req.updated_by())
+                    .await
+                    .with_context(|| format!("failed to update the parents of task: {}", id))?;
+            }
+            if clash.is_none() {
+                clash = self
+                    .sync_ancestors(&mut tx, task.parent_id, req.updated_by())
+                    .await
+                    .with_context(|| format!("failed to update the parents of task: {}", id))?;
+            }
+            if let Some(title) = clash {
+                let title = TaskTitle::new(&title)
+                    .with_context(|| format!("invalid title stored for a parent of task {}", id))?;
+                return Err(UpdateTaskError::Duplicate { title });
+            }
+        }
+
+        tx.commit()
+            .await
+            .context("failed to commit PostgreSQL transaction")?;
+
+        Ok(task)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_task(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        version: Option<i64>,
+        updated_by: &Uuid,
+    ) -> Result<(), DeleteTaskError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start PostgreSQL transaction")?;
+
+        let (_, parent_id) = self
+            .lock_task(&mut tx, owner, id)
+            .await
+            .with_context(|| format!("failed to lock task with id: {}", id))?
+            .ok_or(DeleteTaskError::NotFound { id: *id })?;
+        let deleted = self
+            .remove_task(&mut tx, owner, id, version)
+            .await
+            .with_context(|| format!("failed to delete task with id: {}", id))?;
+
+        if !deleted {
+            // The task exists, so it was kept because it changed since `version`
+            return Err(match version {
+                Some(version) => DeleteTaskError::Conflict { id: *id, version },
+                None => DeleteTaskError::NotFound { id: *id },
+            });
+        }
+        // Deleting a subtask can only complete its parents, which never clashes
+        self.sync_ancestors(&mut tx, parent_id, Some(*updated_by))
+            .await
+            .with_context(|| format!("failed to update the parents of task: {}", id))?;
+
+        tx.commit()
+            .await
+            .context("failed to commit PostgreSQL transaction")?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, req))]
+    async fn create_list(
+        &self,
+        owner: &Uuid,
+        req: &CreateTaskListRequest,
+    ) -> Result<TaskList, CreateTaskListError> {
+        let row = self.save_list(owner, req).await.map_err(|e| {
+            if is_duplicate_list_title_violation(&e) {
+                CreateTaskListError::Duplicate {
+                    title: req.title().clone(),
+                }
+            } else {
+                anyhow!(e)
+                    .context(format!(
+                        "failed to save task list with title: {:?}",
+                        req.title()
+                    ))
+                    .into()
+            }
+        })?;
+
+        Ok(TaskList::try_from(row)?)
+    }
+
+    #[instrument(skip(self))]
+    async fn get_list(&self, user: &Uuid, id: &Uuid) -> Result<TaskList, GetTaskListError> {
+        let row = self
+            .find_list(user, id)
+            .await
+            .with_context(|| format!("failed to fetch task list with id: {}", id))?
+            .ok_or(GetTaskListError::NotFound { id: *id })?;
+
+        Ok(TaskList::try_from(row)?)
+    }
+
+    #[instrument(skip(self))]
+    async fn list_lists(
+        &self,
+        user: &Uuid,
+        include_archived: bool,
+    ) -> Result<Vec<TaskList>, ListTaskListsError> {
+        let rows = self
+            .find_lists(user, include_archived)
+            .await
+            .context("failed to fetch task lists")?;
+        let lists = rows
+            .into_iter()
+            .map(TaskList::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(lists)
+    }
+
+    #[instrument(skip(self, req))]
+    async fn update_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &UpdateTaskListRequest,
+    ) -> Result<TaskList, UpdateTaskListError> {
+        let row = match self.modify_list(owner, id, req).await {
+            Ok(row) => row.ok_or(UpdateTaskListError::NotFound { id: *id })?,
+            // Unarchiving a list can clash with another list even when the title is unchanged
+            Err(e) if is_duplicate_list_title_violation(&e) => {
+                let title = match req.title() {
+                    Some(title) => title.clone(),
+                    None => {
+                        self.get_list(owner, id)
+                            .await
+                            .map_err(|e| anyhow!(e))?
+                            .title
+                    }
+                };
+                return Err(UpdateTaskListError::Duplicate { title });
+            }
+            Err(e) => {
+                return Err(anyhow!(e)
+                    .context(format!("failed to update task list with id: {}", id))
+                    .into())
+            }
+        };
+
+        Ok(TaskList::try_from(row)?)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        mode: DeleteTaskListMode,
+    ) -> Result<(), DeleteTaskListError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start PostgreSQL transaction")?;
+
+        match self
+            .remove_list(&mut tx, owner, id, mode)
+            .await
+            .with_context(|| format!("failed to delete task list with id: {}", id))?
+        {
+            ListRemoval::Removed => {}
+            ListRemoval::NotFound => return Err(DeleteTaskListError::NotFound { id: *id }),
+            ListRemoval::NotEmpty(tasks) => {
+                return Err(DeleteTaskListError::NotEmpty { id: *id, tasks })
+            }
+        }
+
+        tx.commit()
+            .await
+            .context("failed to commit PostgreSQL transaction")?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_members(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> Result<Vec<TaskListMember>, ListTaskListMembersError> {
+        let rows = self
+            .find_members(owner, id)
+            .await
+            .with_context(|| format!("failed to fetch members of task list with id: {}", id))?
+            .ok_or(ListTaskListMembersError::NotFound { id: *id })?;
+
+        let mut members = Vec::with_capacity(rows.len());
+        for row in rows {
+            members.push(TaskListMember::try_from(row)?);
+        }
+        Ok(members)
+    }
+
+    #[instrument(skip(self, req))]
+    async fn share_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &ShareTaskListRequest,
+    ) -> Result<TaskListMember, ShareTaskListError> {
+        match self
+            .save_member(owner, id, req)
+            .await
+            .with_context(|| format!("failed to share task list with id: {}", id))?
+        {
+            MemberSave::Saved(row) => Ok(TaskListMember::try_from(row)?),
+            MemberSave::ListNotFound => Err(ShareTaskListError::NotFound { id: *id }),
+            MemberSave::UserNotFound => Err(ShareTaskListError::UserNotFound {
+                email: req.email().clone(),
+            }),
+            MemberSave::Creator => Err(ShareTaskListError::Creator {
+                id: *id,
+                email: req.email().clone(),
+            }),
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn unshare_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        member: &Uuid,
+    ) -> Result<(), UnshareTaskListError> {
+        match self
+            .remove_member(owner, id, member)
+            .await
+            .with_context(|| format!("failed to unshare task list with id: {}", id))?
+        {
+            (false, _) => Err(UnshareTaskListError::NotFound { id: *id }),
+            (true, false) => Err(UnshareTaskListError::MemberNotFound {
+                id: *id,
+                user_id: *member,
+            }),
+            (true, true) => Ok(()),
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn list_tags(&self, owner: &Uuid) -> Result<Vec<TagUsage>, ListTagsError> {
+        let rows = self
+            .find_tags(owner)
+            .await
+            .context("failed to fetch tags")?;
+        let tags = rows
+            .into_iter()
+            .map(TagUsage::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(tags)
+    }
+
+    #[instrument(skip(self, from, to))]
+    async fn rename_tag(
+        &self,
+        owner: &Uuid,
+        from: &Tag,
+        to: &Tag,
+    ) -> Result<TagUsage, RenameTagError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start PostgreSQL transaction")?;
+
+        let tasks = match self
+            .modify_tag(&mut tx, owner, from, to)
+            .await
+            .with_context(|| format!("failed to rename tag {} to {}", from, to))?
+        {
+            TagRename::Renamed(tasks) => tasks,
+            TagRename::NotFound => return Err(RenameTagError::NotFound { tag: from.clone() }),
+            TagRename::Duplicate => return Err(RenameTagError::Duplicate { tag: to.clone() }),
+        };
+
+        tx.commit()
+            .await
+            .context("failed to commit PostgreSQL transaction")?;
+
+        Ok(TagUsage {
+            tag: to.clone(),
+            tasks,
+        })
+    }
+
+    #[instrument(skip(self, tags, into))]
+    async fn merge_tags(
+        &self,
+        owner: &Uuid,
+        tags: &[Tag],
+        into: &Tag,
+    ) -> Result<TagUsage, MergeTagsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start PostgreSQL transaction")?;
+
+        let tasks = match self
+            .combine_tags(&mut tx, owner, tags, into)
+            .await
+            .with_context(|| format!("failed to merge tags into {}", into))?
+        {
+            TagMerge::Merged(tasks) => tasks,
+            TagMerge::NotFound(tag) => return Err(MergeTagsError::NotFound { tag }),
+        };
+
+        tx.commit()
+            .await
+            .context("failed to commit PostgreSQL transaction")?;
+
+        Ok(TagUsage {
+            tag: into.clone(),
+            tasks,
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn claim_due_reminders(
+        &self,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<DueReminder>, FireRemindersError> {
+        let rows = self
+            .claim_reminders(now, limit)
+            .await
+            .context("failed to claim due reminders")?;
+        let reminders = rows
+            .into_iter()
+            .map(DueReminder::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(reminders)
+    }
+
+    #[instrument(skip(self, reminder, error))]
+    async fn record_reminder_failure(
+        &self,
+        reminder: &DueReminder,
+        error: &str,
+        retry: bool,
+    ) -> Result<(), FireRemindersError> {
+        self.release_reminder(reminder, error, retry)
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to record reminder failure for task: {}",
+                    reminder.task().id()
+                )
+            })?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_trash(&self, user: &Uuid) -> Result<Trash, ListTrashError> {
+        let lists = self
+            .find_trashed_lists(user)
+            .await
+            .context("failed to fetch task lists in the trash")?
+            .into_iter()
+            .map(Trashed::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let tasks = self
+            .find_trashed_tasks(user)
+            .await
+            .context("failed to fetch tasks in the trash")?
+            .into_iter()
+            .map(Trashed::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Trash { lists, tasks })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_trashed_task(&self, user: &Uuid, id: &Uuid) -> Result<Task, GetTaskError> {
+        let row = self
+            .find_trashed_task(user, id)
+            .await
+            .with_context(|| format!("failed to fetch task in the trash with id: {}", id))?
+            .ok_or(GetTaskError::NotFound { id: *id })?;
+
+        Ok(Task::try_from(row)?)
+    }
+
+    #[instrument(skip(self))]
+    async fn restore_task(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        updated_by: &Uuid,
+    ) -> Result<Task, RestoreTaskError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start PostgreSQL transaction")?;
+
+        let rows = match self.undelete_task(&mut tx, owner, id).await {
+            Ok(TaskRestore::Restored(rows)) => rows,
+            Ok(TaskRestore::NotFound) => return Err(RestoreTaskError::NotFound { id: *id }),
+            Ok(TaskRestore::ParentDeleted(id)) => {
+                return Err(RestoreTaskError::ParentDeleted { id })
+            }
+            Ok(TaskRestore::ListDeleted(id)) => return Err(RestoreTaskError::ListDeleted { id }),
+            Ok(TaskRestore::Duplicate(title)) => {
+                let title = TaskTitle::new(&title).with_context(|| {
+                    format!("invalid title stored for a subtask of task {}", id)
+                })?;
+                return Err(RestoreTaskError::Duplicate { title });
+            }
+            // Another task can take the title between the check and the restore
+            Err(e) if is_duplicate_title_violation(&e) => {
+                let task = self
+                    .get_trashed_task(owner, id)
+                    .await
+                    .map_err(|e| anyhow!(e))?;
+                return Err(RestoreTaskError::Duplicate { title: task.title });
+            }
+            Err(e) => {
+                return Err(anyhow!(e)
+                    .context(format!("failed to restore task with id: {}", id))
+                    .into())
+            }
+        };
+
+        let row = rows
+            .into_iter()
+            .find(|row| row.id == *id)
+            .ok_or(RestoreTaskError::NotFound { id: *id })?;
+        let clash = self
+            .sync_ancestors(&mut tx, row.parent_id, Some(*updated_by))
+            .await
+            .with_context(|| format!("failed to reopen the parents of task: {}", id))?;
+        if let Some(title) = clash {
+            let title = TaskTitle::new(&title)
+                .with_context(|| format!("invalid title stored for a parent of task {}", id))?;
+            return Err(RestoreTaskError::Duplicate { title });
+        }
+
+        tx.commit()
+            .await
+            .context("failed to commit PostgreSQL transaction")?;
+
+        Ok(Task::try_from(row)?)
+    }
+
+    #[instrument(skip(self))]
+    async fn restore_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> Result<TaskList, RestoreTaskListError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start PostgreSQL transaction")?;
+
+        let row = match self
+            .undelete_list(&mut tx, owner, id)
+            .await
+            .with_context(|| format!("failed to restore task list with id: {}", id))?
+        {
+            ListRestore::Restored(row) => row,
+            ListRestore::NotFound => return Err(RestoreTaskListError::NotFound { id: *id }),
+            ListRestore::Duplicate(title) => {
+                let title = TaskListTitle::new(&title)
+                    .with_context(|| format!("invalid title stored for task list {}", id))?;
+                return Err(RestoreTaskListError::Duplicate { title });
+            }
+            ListRestore::TaskDuplicate(title) => {
+                let title = TaskTitle::new(&title)
+                    .with_context(|| format!("invalid title stored for a task of list {}", id))?;
+                return Err(RestoreTaskListError::TaskDuplicate { title });
+            }
+        };
+
+        tx.commit()
+            .await
+            .context("failed to commit PostgreSQL transaction")?;
+
+        Ok(TaskList::try_from(row)?)
+    }
+
+    #[instrument(skip(self))]
+    async fn empty_trash(&self, owner: &Uuid) -> Result<PurgedTrash, PurgeTrashError> {
+        let purged = self
+            .remove_trash(Some(owner), None)
+            .await
+            .context("failed to empty the trash")?;
+
+        Ok(purged)
+    }
+
+    #[instrument(skip(self))]
+    async fn purge_trash(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<PurgedTrash, PurgeTrashError> {
+        let purged = self
+            .remove_trash(None, Some(&deleted_before))
+            .await
+            .with_context(|| format!("failed to purge the trash from before {}", deleted_before))?;
+
+        Ok(purged)
+    }
+}
+
+impl UserRepository for Sql {
+    #[instrument(skip(self, email, password_hash))]
+    async fn create_user(
+        &self,
+        email: &EmailAddress,
+        password_hash: &PasswordHash,
+    ) -> Result<User, RegisterUserError> {
+        let row = self.save_user(email, password_hash).await.map_err(|e| {
+            if is_duplicate_email_violation(&e) {
+                RegisterUserError::Duplicate {
+                    email: email.clone(),
+                }
+            } else {
+                anyhow!(e)
+                    .context(format!("failed to save user with email: {}", email))
+                    .into()
+            }
+        })?;
+
+        Ok(UserCredentials::try_from(row)?.user)
+    }
+
+    #[instrument(skip(self, email))]
+    async fn get_credentials(
+        &self,
+        email: &EmailAddress,
+    ) -> Result<Option<UserCredentials>, LogInError> {
+        let row = self
+            .find_user(email)
+            .await
+            .with_context(|| format!("failed to fetch user with email: {}", email))?;
+
+        Ok(row.map(UserCredentials::try_from).transpose()?)
+    }
+
+    #[instrument(skip(self, session))]
+    async fn create_session(&self, session: &Session) -> Result<(), LogInError> {
+        self.save_session(session)
+            .await
+            .with_context(|| format!("failed to save session of user: {}", session.user().id()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, token))]
+    async fn get_session_user(
+        &self,
+        token: &SessionToken,
+        now: DateTime<Utc>,
+    ) -> Result<User, AuthenticateError> {
+        let row = self
+            .find_session_user(token, now)
+            .await
+            .context("failed to fetch session")?
+            .ok_or(AuthenticateError::InvalidToken)?;
+
+        Ok(UserCredentials::try_from(row)?.user)
+    }
+
+    #[instrument(skip(self, token))]
+    async fn delete_session(&self, token: &SessionToken) -> Result<(), LogOutError> {
+        self.remove_session(token)
+            .await
+            .context("failed to delete session")?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, token))]
+    async fn create_api_token(
+        &self,
+        user: &Uuid,
+        token: &NewApiToken,
+    ) -> Result<(), CreateApiTokenError> {
+        self.save_api_token(user, token)
+            .await
+            .map_err(|e| match token.token().scope().list_id() {
+                Some(id) if is_unknown_token_list_violation(&e) => {
+                    CreateApiTokenError::ListNotFound { id }
+                }
+                _ => anyhow!(e)
+                    .context(format!("failed to save API token of user: {}", user))
+                    .into(),
+            })
+    }
+
+    #[instrument(skip(self))]
+    async fn list_api_tokens(&self, user: &Uuid) -> Result<Vec<ApiToken>, ListApiTokensError> {
+        let rows = self
+            .find_api_tokens(user)
+            .await
+            .with_context(|| format!("failed to fetch API tokens of user: {}", user))?;
+
+        Ok(rows
+            .into_iter()
+            .map(ApiToken::try_from)
+            .collect::<anyhow::Result<_>>()?)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_api_token(&self, user: &Uuid, id: &Uuid) -> Result<(), RevokeApiTokenError> {
+        let deleted = self
+            .remove_api_token(user, id)
+            .await
+            .with_context(|| format!("failed to delete API token with id: {}", id))?;
+
+        if deleted {
+            Ok(())
+        } else {
+            Err(RevokeApiTokenError::NotFound { id: *id })
+        }
+    }
+
+    #[instrument(skip(self, secret))]
+    async fn use_api_token(
+        &self,
+        secret: &ApiTokenSecret,
+        now: DateTime<Utc>,
+    ) -> Result<Principal, AuthenticateError> {
+        let row = self
+            .touch_api_token(secret, now)
+            .await
+            .context("failed to fetch API token")?
+            .ok_or(AuthenticateError::InvalidApiToken)?;
+
+        Ok(Principal::try_from(row)?)
+    }
+}
+
+impl ReadinessRepository for Sql {
+    #[instrument(skip(self))]
+    async fn is_ready(&self) -> Result<(), ReadinessError> {
+        self.ready().await.map_err(|e| {
+            anyhow!(e)
+                .context("failed to check if database is ready")
+                .into()
+        })
+    }
+}
+
+impl IdempotencyRepository for Sql {
+    #[instrument(skip(self, req))]
+    async fn claim_idempotency_key(
+        &self,
+        req: &IdempotentRequest,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<IdempotencyRecord>, ClaimIdempotencyKeyError> {
+        let existing = self
+            .save_idempotency_key(req, now, expires_at)
+            .await
+            .context("failed to claim idempotency key")?;
+        match existing {
+            None => Ok(None),
+            Some(Some(row)) => Ok(Some(IdempotencyRecord::try_from(row)?)),
+            // The request that claimed the key released it, and has not completed
+            Some(None) => Err(ClaimIdempotencyKeyError::InProgress),
+        }
+    }
+
+    #[instrument(skip(self, req, response))]
+    async fn save_idempotent_response(
+        &self,
+        req: &IdempotentRequest,
+        response: &StoredResponse,
+    ) -> Result<(), CompleteIdempotentRequestError> {
+        self.save_response(req, response)
+            .await
+            .context("failed to save idempotent response")?;
+        Ok(())
+    }
+
+    #[instrument(skip(self, req))]
+    async fn delete_idempotency_key(
+        &self,
+        req: &IdempotentRequest,
+    ) -> Result<(), CompleteIdempotentRequestError> {
+        self.remove_idempotency_key(req)
+            .await
+            .context("failed to delete idempotency key")?;
+        Ok(())
+    }
+}
+
+/// The rank of the priority of a task, in the order of [Priority], for sorting by priority.
+const PRIORITY_RANK: &str = "CASE priority \
+    WHEN 'low' THEN 1 WHEN 'medium' THEN 2 WHEN 'high' THEN 3 WHEN 'urgent' THEN 4 \
+    ELSE 0 END";
+
+const UNIQUE_VIOLATION_CODE: &str = "23505";
+const FOREIGN_KEY_VIOLATION_CODE: &str = "23503";
+
+/// The index that keeps titles unique among incomplete tasks, ignoring case.
+const TASK_TITLE_UNIQUE_INDEX: &str = "tasks_incomplete_title_idx";
+
+/// The index that keeps titles unique among unarchived task lists, ignoring case.
+const TASK_LIST_TITLE_UNIQUE_INDEX: &str = "task_lists_unarchived_title_idx";
+
+/// The constraint that keeps email addresses unique among users.
+const USER_EMAIL_UNIQUE_INDEX: &str = "users_email_key";
+
+/// The foreign key from a task to the list it belongs to.
+const TASK_LIST_FOREIGN_KEY: &str = "tasks_list_id_fkey";
+
+/// The foreign key from a subtask to its parent task.
+const TASK_PARENT_FOREIGN_KEY: &str = "tasks_parent_id_fkey";
+
+/// The foreign key from an API token to the list it is restricted to.
+const API_TOKEN_LIST_FOREIGN_KEY: &str = "api_tokens_list_id_fkey";
+
+fn is_violation(err: &sqlx::Error, code: &str, constraint: &str) -> bool {
+    match err {
+        sqlx::Error::Database(db_err) => {
+            db_err.code().as_deref() == Some(code) && db_err.constraint() == Some(constraint)
+        }
+        _ => false,
+    }
+}
+
+fn is_duplicate_title_violation(err: &sqlx::Error) -> bool {
+    is_violation(err, UNIQUE_VIOLATION_CODE, TASK_TITLE_UNIQUE_INDEX)
+}
+
+fn is_duplicate_list_title_violation(err: &sqlx::Error) -> bool {
+    is_violation(err, UNIQUE_VIOLATION_CODE, TASK_LIST_TITLE_UNIQUE_INDEX)
+}
+
+fn is_duplicate_email_violation(err: &sqlx::Error) -> bool {
+    is_violation(err, UNIQUE_VIOLATION_CODE, USER_EMAIL_UNIQUE_INDEX)
+}
+
+fn is_unknown_list_violation(err: &sqlx::Error) -> bool {
+    is_violation(err, FOREIGN_KEY_VIOLATION_CODE, TASK_LIST_FOREIGN_KEY)
+}
+
+fn is_unknown_parent_violation(err: &sqlx::Error) -> bool {
+    is_violation(err, FOREIGN_KEY_VIOLATION_CODE, TASK_PARENT_FOREIGN_KEY)
+}
+
+fn is_unknown_token_list_violation(err: &sqlx::Error) -> bool {
+    is_violation(err, FOREIGN_KEY_VIOLATION_CODE, API_TOKEN_LIST_FOREIGN_KEY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::idempotency::models::key::IdempotencyKey;
+    use crate::domain::reminders::models::query::{PageSize, TaskFilter, TaskSort, TaskTagFilter};
+    use crate::domain::users::models::api_token::CreateApiTokenRequest;
+    use chrono::Duration;
+
+    fn title(raw: &str) -> TaskTitle {
+        TaskTitle::new(raw).unwrap()
+    }
+
+    async fn user(sql: &Sql, email: &str) -> Uuid {
+        let email = EmailAddress::new(email).unwrap();
+        let user = sql
+            .create_user(&email, &PasswordHash::from_stored("!"))
+            .await
+            .unwrap();
+        user.id()
+    }
+
+    #[sqlx::test]
+    async fn test_titles_are_unique_among_incomplete_tasks_ignoring_case(pool: PgPool) {
+        let sql = Sql { pool };
+        let owner = user(&sql, "ada@example.com").await;
+        let task = sql
+            .create_task(&owner, &CreateTaskRequest::new(title("Water plants")))
+            .await
+            .unwrap();
+
+        let result = sql
+            .create_task(&owner, &CreateTaskRequest::new(title("water PLANTS")))
+            .await;
+        assert!(matches!(result, Err(CreateTaskError::Duplicate { .. })));
+
+        sql.update_task(
+            &owner,
+            &task.id(),
+            &UpdateTaskRequest::new().with_completed(true),
+        )
+        .await
+        .unwrap();
+        let other = sql
+            .create_task(&owner, &CreateTaskRequest::new(title("water PLANTS")))
+            .await
+            .unwrap();
+
+        let result = sql
+            .update_task(
+                &owner,
+                &task.id(),
+                &UpdateTaskRequest::new().with_completed(false),
+            )
+            .await;
+        match result {
+            Err(UpdateTaskError::Duplicate { title }) => {
+                assert_eq!(title.to_string(), "Water plants")
+            }
+            other => panic!("expected a duplicate title error, got {:?}", other),
+        }
+        let page = sql
+            .list_tasks(&owner, &ListTasksRequest::new())
+            .await
+            .unwrap();
+        assert_eq!(page.tasks.len(), 2);
+        assert!(!sql.get_task(&owner, &other.id()).await.unwrap().completed());
+    }
+
+    #[sqlx::test]
+    async fn test_renaming_to_existing_title_is_duplicate(pool: PgPool) {
+        let sql = Sql { pool };
+        let owner = user(&sql, "ada@example.com").await;
+        sql.create_task(&owner, &CreateTaskRequest::new(title("Water plants")))
+            .await
+            .unwrap();
+        let task = sql
+            .create_task(&owner, &CreateTaskRequest::new(title("Feed the cat")))
+            .await
+            .unwrap();
+
+        let req = UpdateTaskRequest::new().with_title(title("WATER plants"));
+        let result = sql.update_task(&owner, &task.id(), &req).await;
+        assert!(matches!(result, Err(UpdateTaskError::Duplicate { .. })));
+
+        let req = UpdateTaskRequest::new().with_title(title("Feed the CAT"));
+        let renamed = sql.update_task(&owner, &task.id(), &req).await.unwrap();
+        assert_eq!(renamed.title().to_string(), "Feed the CAT");
+    }
+
+    #[sqlx::test]
+    async fn test_priority_and_notes_are_saved_and_cleared(pool: PgPool) {
+        let sql = Sql { pool };
+        let owner = user(&sql, "ada@example.com").await;
+        let notes = TaskNotes::new("Bring the *blue* folder").unwrap();
+        let req = CreateTaskRequest::new(title("File taxes"))
+            .with_priority(Priority::Urgent)
+            .with_notes(notes.clone());
+        let task = sql.create_task(&owner, &req).await.unwrap();
+        let stored = sql.get_task(&owner, &task.id()).await.unwrap();
+        assert_eq!(stored.priority(), Priority::Urgent);
+        assert_eq!(stored.notes(), Some(&notes));
+
+        let req = UpdateTaskRequest::new()
+            .with_priority(Priority::Low)
+            .with_notes(None);
+        let updated = sql.update_task(&owner, &task.id(), &req).await.unwrap();
+        assert_eq!(updated.priority(), Priority::Low);
+        assert_eq!(updated.notes(), None);
+    }
+
+    #[sqlx::test]
+    async fn test_changes_to_an_outdated_version_conflict(pool: PgPool) {
+        let sql = Sql { pool };
+        let owner = user(&sql, "ada@example.com").await;
+        let task = sql
+            .create_task(&owner, &CreateTaskRequest::new(title("Renew passport")))
+            .await
+            .unwrap();
+        assert_eq!(task.version(), 1);
+
+        let req = UpdateTaskRequest::new()
+            .with_completed(true)
+            .with_version(1);
+        let updated = sql.update_task(&owner, &task.id(), &req).await.unwrap();
+        assert_eq!(updated.version(), 2);
+        let result = sql.update_task(&owner, &task.id(), &req).await;
+        assert!(matches!(
+            result,
+            Err(UpdateTaskError::Conflict { version: 1, .. })
+        ));
+
+        let result = sql.delete_task(&owner, &task.id(), Some(1), &owner).await;
+        assert!(matches!(
+            result,
+            Err(DeleteTaskError::Conflict { version: 1, .. })
+        ));
+        sql.delete_task(&owner, &task.id(), Some(2), &owner)
+            .await
+            .unwrap();
+        let result = sql.delete_task(&owner, &task.id(), Some(2), &owner).await;
+        assert!(matches!(result, Err(DeleteTaskError::NotFound { .. })));
+    }
+
+    #[sqlx::test]
+    async fn test_completion_spreads_to_subtasks_and_parents_or_not_at_all(pool: PgPool) {
+        let sql = Sql { pool };
+        let owner = user(&sql, "ada@example.com").await;
+        let trip = sql
+            .create_task(&owner, &CreateTaskRequest::new(title("Plan trip")))
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Pack")).with_parent_id(trip.id());
+        let pack = sql.create_task(&owner, &req).await.unwrap();
+        let req = CreateTaskRequest::new(title("Socks")).with_parent_id(pack.id());
+        let socks = sql.create_task(&owner, &req).await.unwrap();
+
+        let done = UpdateTaskRequest::new()
+            .with_completed(true)
+            .with_updated_by(owner);
+        sql.update_task(&owner, &pack.id(), &done).await.unwrap();
+        let socks = sql.get_task(&owner, &socks.id()).await.unwrap();
+        assert!(socks.completed());
+        assert_eq!(socks.completed_by(), Some(owner));
+        assert!(sql.get_task(&owner, &trip.id()).await.unwrap().completed());
+
+        // Reopening the subtask would reopen both parents, but the top one clashes
+        sql.create_task(&owner, &CreateTaskRequest::new(title("Plan TRIP")))
+            .await
+            .unwrap();
+        let reopen = UpdateTaskRequest::new().with_completed(false);
+        match sql.update_task(&owner, &socks.id(), &reopen).await {
+            Err(UpdateTaskError::Duplicate { title }) => assert_eq!(title.to_string(), "Plan trip"),
+            other => panic!("expected a duplicate title error, got {:?}", other),
+        }
+        assert!(sql.get_task(&owner, &socks.id()).await.unwrap().completed());
+        assert!(sql.get_task(&owner, &pack.id()).await.unwrap().completed());
+    }
+
+    #[sqlx::test]
+    async fn test_completion_is_undone_when_next_occurrence_cannot_be_created(pool: PgPool) {
+        let sql = Sql { pool };
+        let owner = user(&sql, "ada@example.com").await;
+        let req = CreateTaskRequest::new(title("Water plants"))
+            .with_recurrence(Recurrence::parse("FREQ=DAILY;COUNT=2").unwrap());
+        let task = sql.create_task(&owner, &req).await.unwrap();
+        sqlx::query(
+            "ALTER TABLE tasks ADD CONSTRAINT no_last_occurrence \
+             CHECK (recurrence IS DISTINCT FROM 'FREQ=DAILY;COUNT=1')",
+        )
+        .execute(&sql.pool)
+        .await
+        .unwrap();
+
+        let done = UpdateTaskRequest::new().with_completed(true);
+        let result = sql.update_task(&owner, &task.id(), &done).await;
+        assert!(matches!(result, Err(UpdateTaskError::Unknown(_))));
+        let stored = sql.get_task(&owner, &task.id()).await.unwrap();
+        assert!(!stored.completed());
+        assert_eq!(stored.version(), task.version());
+        let tasks = sql
+            .list_tasks(&owner, &ListTasksRequest::new())
+            .await
+            .unwrap()
+            .tasks;
+        assert_eq!(tasks.len(), 1);
+    }
+
+    #[sqlx::test]
+    async fn test_concurrent_completions_create_one_next_occurrence(pool: PgPool) {
+        let sql = Sql { pool };
+        let owner = user(&sql, "ada@example.com").await;
+        let req = CreateTaskRequest::new(title("Water plants"))
+            .with_recurrence(Recurrence::parse("FREQ=DAILY;COUNT=3").unwrap());
+        let task = sql.create_task(&owner, &req).await.unwrap();
+
+        let id = task.id();
+        let done = UpdateTaskRequest::new().with_completed(true);
+        let (first, second) = tokio::join!(
+            sql.update_task(&owner, &id, &done),
+            sql.update_task(&owner, &id, &done)
+        );
+        first.unwrap();
+        second.unwrap();
+
+        let tasks = sql
+            .list_tasks(&owner, &ListTasksRequest::new())
+            .await
+            .unwrap()
+            .tasks;
+        assert_eq!(tasks.len(), 2);
+        let next = tasks.iter().find(|t| t.id() != task.id()).unwrap();
+        assert!(!next.completed());
+        assert_eq!(next.recurrence().unwrap().to_string(), "FREQ=DAILY;COUNT=2");
+    }
+
+    #[sqlx::test]
+    async fn test_tasks_sorted_by_priority_page_across_equal_priorities(pool: PgPool) {
+        let sql = Sql { pool };
+        let owner = user(&sql, "ada@example.com").await;
+        for (name, priority) in [
+            ("Buy milk", Priority::High),
+            ("Walk dog", Priority::None),
+            ("Pay rent", Priority::High),
+            ("Call mom", Priority::High),
+            ("File taxes", Priority::Urgent),
+        ] {
+            let req = CreateTaskRequest::new(title(name)).with_priority(priority);
+            sql.create_task(&owner, &req).await.unwrap();
+        }
+
+        let mut req = ListTasksRequest::new()
+            .with_sort(TaskSort::parse("-priority").unwrap())
+            .with_size(PageSize::new(2).unwrap());
+        let mut titles = vec![];
+        loop {
+            let page = sql.list_tasks(&owner, &req).await.unwrap();
+            titles.extend(page.tasks.iter().map(|task| task.title().to_string()));
+            match page.next_cursor {
+                Some(cursor) => req = req.with_cursor(cursor).unwrap(),
+                None => break,
+            }
+        }
+        assert_eq!(
+            titles,
+            vec!["File taxes", "Call mom", "Pay rent", "Buy milk", "Walk dog"]
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_subtasks_are_listed_in_sibling_order_and_deleted_with_parent(pool: PgPool) {
+        let sql = Sql { pool };
+        let owner = user(&sql, "ada@example.com").await;
+        let trip = sql
+            .create_task(&owner, &CreateTaskRequest::new(title("Prepare trip")))
+            .await
+            .unwrap();
+        let subtask = |raw| CreateTaskRequest::new(title(raw)).with_parent_id(trip.id());
+        let pack = sql.create_task(&owner, &subtask("Pack")).await.unwrap();
+        let hotel = sql
+            .create_task(&owner, &subtask("Book hotel").with_position(-1))
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Buy sunscreen")).with_parent_id(pack.id());
+        let sunscreen = sql.create_task(&owner, &req).await.unwrap();
+        assert_eq!(pack.position(), 0);
+        assert_eq!(sunscreen.position(), 0);
+
+        let subtasks = sql.list_subtasks(&owner, &trip.id()).await.unwrap();
+        let ids = subtasks.iter().map(Task::id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![hotel.id(), pack.id(), sunscreen.id()]);
+
+        let missing = Uuid::new_v4();
+        let req = CreateTaskRequest::new(title("Orphan")).with_parent_id(missing);
+        let result = sql.create_task(&owner, &req).await;
+        assert!(matches!(result, Err(CreateTaskError::ParentNotFound { id }) if id == missing));
+
+        sql.delete_task(&owner, &trip.id(), None, &owner)
+            .await
+            .unwrap();
+        let result = sql.get_task(&owner, &sunscreen.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+    }
+
+    #[sqlx::test]
+    async fn test_deleted_tasks_are_trashed_restored_and_purged(pool: PgPool) {
+        let sql = Sql { pool };
+        let owner = user(&sql, "ada@example.com").await;
+        let trip = sql
+            .create_task(&owner, &CreateTaskRequest::new(title("Prepare trip")))
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Pack")).with_parent_id(trip.id());
+        let pack = sql.create_task(&owner, &req).await.unwrap();
+
+        sql.delete_task(&owner, &trip.id(), None, &owner)
+            .await
+            .unwrap();
+        let result = sql.get_task(&owner, &pack.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+        let trash = sql.list_trash(&owner).await.unwrap();
+        assert_eq!(trash.tasks().len(), 2);
+        let result = sql.restore_task(&owner, &pack.id(), &owner).await;
+        assert!(matches!(result, Err(RestoreTaskError::ParentDeleted { id }) if id == trip.id()));
+
+        let again = sql
+            .create_task(&owner, &CreateTaskRequest::new(title("Prepare trip")))
+            .await
+            .unwrap();
+        let result = sql.restore_task(&owner, &trip.id(), &owner).await;
+        assert!(matches!(result, Err(RestoreTaskError::Duplicate { .. })));
+        let req = UpdateTaskRequest::new().with_completed(true);
+        sql.update_task(&owner, &again.id(), &req).await.unwrap();
+        sql.restore_task(&owner, &trip.id(), &owner).await.unwrap();
+        sql.get_task(&owner, &pack.id()).await.unwrap();
+        assert!(sql.list_trash(&owner).await.unwrap().tasks().is_empty());
+
+        let list_title = TaskListTitle::new("Errands").unwrap();
+        let list = sql
+            .create_list(&owner, &CreateTaskListRequest::new(list_title))
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Post letter")).with_list_id(list.id());
+        let letter = sql.create_task(&owner, &req).await.unwrap();
+        sql.delete_list(&owner, &list.id(), DeleteTaskListMode::Cascade)
+            .await
+            .unwrap();
+        let result = sql.restore_task(&owner, &letter.id(), &owner).await;
+        assert!(matches!(result, Err(RestoreTaskError::ListDeleted { id }) if id == list.id()));
+        sql.restore_list(&owner, &list.id()).await.unwrap();
+        sql.get_task(&owner, &letter.id()).await.unwrap();
+
+        sql.delete_task(&owner, &letter.id(), None, &owner)
+            .await
+            .unwrap();
+        let purged = sql
+            .purge_trash(Utc::now() - Duration::days(1))
+            .await
+            .unwrap();
+        assert_eq!(purged, PurgedTrash::default());
+        let purged = sql
+            .purge_trash(Utc::now() + Duration::seconds(1))
+            .await
+            .unwrap();
+        assert_eq!(purged, PurgedTrash { tasks: 1, lists: 0 });
+        let result = sql.get_trashed_task(&owner, &letter.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+
+        sql.delete_list(&owner, &list.id(), DeleteTaskListMode::OnlyIfEmpty)
+            .await
+            .unwrap();
+        sql.delete_task(&owner, &trip.id(), None, &owner)
+            .await
+            .unwrap();
+        let purged = sql.empty_trash(&owner).await.unwrap();
+        assert_eq!(purged, PurgedTrash { tasks: 2, lists: 1 });
+        assert_eq!(sql.list_trash(&owner).await.unwrap(), Trash::default());
+    }
+
+    #[sqlx::test]
+    async fn test_tag_changes_only_wait_for_the_same_owner(pool: PgPool) {
+        let sql = Sql { pool };
+        let ada = user(&sql, "ada@example.com").await;
+        let bob = user(&sql, "bob@example.com").await;
+        let (errands, chores) = (Tag::new("errands").unwrap(), Tag::new("chores").unwrap());
+        let tags = TaskTags::new(vec![errands.clone()]).unwrap();
+        let req = CreateTaskRequest::new(title("Buy stamps")).with_tags(tags.clone());
+        sql.create_task(&bob, &req).await.unwrap();
+
+        let mut tx = sql.pool.begin().await.unwrap();
+        sql.lock_tags(&mut tx, &ada).await.unwrap();
+        let wait = std::time::Duration::from_millis(200);
+        let rename = sql.rename_tag(&bob, &errands, &chores);
+        let usage = tokio::time::timeout(wait, rename).await.unwrap().unwrap();
+        assert_eq!(usage.tasks(), 1);
+
+        let req = CreateTaskRequest::new(title("Post letter")).with_tags(tags);
+        let create = sql.create_task(&ada, &req);
+        tokio::pin!(create);
+        assert!(tokio::time::timeout(wait, &mut create).await.is_err());
+        tx.commit().await.unwrap();
+        create.await.unwrap();
+    }
+
+    #[sqlx::test]
+    async fn test_tags_are_renamed_merged_and_filtered(pool: PgPool) {
+        let sql = Sql { pool };
+        let owner = user(&sql, "ada@example.com").await;
+        let tag = |raw: &str| Tag::new(raw).unwrap();
+        let tags = |raw: &[&str]| TaskTags::new(raw.iter().map(|raw| tag(raw)).collect()).unwrap();
+        let req = CreateTaskRequest::new(title("Buy stamps")).with_tags(tags(&["errands"]));
+        let stamps = sql.create_task(&owner, &req).await.unwrap();
+        let req = CreateTaskRequest::new(title("Paint fence")).with_tags(tags(&["garden", "diy"]));
+        let fence = sql.create_task(&owner, &req).await.unwrap();
+        assert_eq!(
+            sql.get_task(&owner, &fence.id()).await.unwrap().tags(),
+            &tags(&["diy", "garden"])
+        );
+
+        let req = UpdateTaskRequest::new().with_tags(tags(&["errands", "garden"]));
+        sql.update_task(&owner, &stamps.id(), &req).await.unwrap();
+        let result = sql.rename_tag(&owner, &tag("diy"), &tag("garden")).await;
+        assert!(matches!(result, Err(RenameTagError::Duplicate { .. })));
+        let usage = sql
+            .rename_tag(&owner, &tag("diy"), &tag("home"))
+            .await
+            .unwrap();
+        assert_eq!(usage.tasks(), 1);
+        let usage = sql
+            .merge_tags(&owner, &[tag("garden"), tag("home")], &tag("outside"))
+            .await
+            .unwrap();
+        assert_eq!(usage.tasks(), 2);
+        let result = sql.merge_tags(&owner, &[tag("garden")], &tag("home")).await;
+        assert!(matches!(result, Err(MergeTagsError::NotFound { .. })));
+
+        let usage = sql.list_tags(&owner).await.unwrap();
+        let usage = usage
+            .iter()
+            .map(|usage| (usage.tag().as_str(), usage.tasks()))
+            .collect::<Vec<_>>();
+        assert_eq!(usage, vec![("errands", 1), ("outside", 2)]);
+
+        let filter = |mode| {
+            let tags = TaskTagFilter::new(vec![tag("errands"), tag("outside")], mode).unwrap();
+            ListTasksRequest::new().with_filter(TaskFilter::new().with_tags(tags))
+        };
+        let page = sql
+            .list_tasks(&owner, &filter(TagMatch::All))
+            .await
+            .unwrap();
+        let ids = page.tasks.iter().map(Task::id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![stamps.id()]);
+        let page = sql
+            .list_tasks(&owner, &filter(TagMatch::Any))
+            .await
+            .unwrap();
+        assert_eq!(page.tasks.len(), 2);
+    }
+
+    #[sqlx::test]
+    async fn test_users_only_see_their_own_tasks_and_lists(pool: PgPool) {
+        let sql = Sql { pool };
+        let ada = user(&sql, "ada@example.com").await;
+        let bob = user(&sql, "bob@example.com").await;
+        let list = sql
+            .create_list(
+                &ada,
+                &CreateTaskListRequest::new(TaskListTitle::new("Garden").unwrap()),
+            )
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Water plants")).with_list_id(list.id());
+        let task = sql.create_task(&ada, &req).await.unwrap();
+
+        let result = sql.get_task(&bob, &task.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+        let result = sql.delete_task(&bob, &task.id(), None, &bob).await;
+        assert!(matches!(result, Err(DeleteTaskError::NotFound { .. })));
+        let result = sql.create_task(&bob, &req).await;
+        assert!(matches!(result, Err(CreateTaskError::ListNotFound { .. })));
+        let req = CreateTaskRequest::new(title("Water plants")).with_parent_id(task.id());
+        let result = sql.create_task(&bob, &req).await;
+        assert!(matches!(
+            result,
+            Err(CreateTaskError::ParentNotFound { .. })
+        ));
+
+        sql.create_task(&bob, &CreateTaskRequest::new(title("Water plants")))
+            .await
+            .unwrap();
+        let page = sql
+            .list_tasks(&ada, &ListTasksRequest::new())
+            .await
+            .unwrap();
+        assert_eq!(page.tasks, vec![task]);
+        assert!(sql.list_lists(&bob, true).await.unwrap().is_empty());
+    }
+
+    #[sqlx::test]
+    async fn test_shared_lists_are_visible_to_their_members(pool: PgPool) {
+        let sql = Sql { pool };
+        let ada = user(&sql, "ada@example.com").await;
+        let bob = user(&sql, "bob@example.com").await;
+        let email = |raw| EmailAddress::new(raw).unwrap();
+        let list = sql
+            .create_list(
+                &ada,
+                &CreateTaskListRequest::new(TaskListTitle::new("Groceries").unwrap()),
+            )
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Buy milk")).with_list_id(list.id());
+        let task = sql.create_task(&ada, &req).await.unwrap();
+
+        let req = ShareTaskListRequest::new(email("bob@example.com"), ListRole::Viewer);
+        sql.share_list(&ada, &list.id(), &req).await.unwrap();
+        let req = ShareTaskListRequest::new(email("bob@example.com"), ListRole::Editor);
+        let member = sql.share_list(&ada, &list.id(), &req).await.unwrap();
+        assert_eq!((member.user_id(), member.role()), (bob, ListRole::Editor));
+        let req = ShareTaskListRequest::new(email("ada@example.com"), ListRole::Editor);
+        let result = sql.share_list(&ada, &list.id(), &req).await;
+        assert!(matches!(result, Err(ShareTaskListError::Creator { .. })));
+        let req = ShareTaskListRequest::new(email("cy@example.com"), ListRole::Editor);
+        let result = sql.share_list(&ada, &list.id(), &req).await;
+        assert!(matches!(
+            result,
+            Err(ShareTaskListError::UserNotFound { .. })
+        ));
+
+        let members = sql.list_members(&ada, &list.id()).await.unwrap();
+        let members = members
+            .iter()
+            .map(|member| (member.user_id(), member.role()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            members,
+            vec![(ada, ListRole::Owner), (bob, ListRole::Editor)]
+        );
+        let shared = sql.get_list(&bob, &list.id()).await.unwrap();
+        assert_eq!((shared.owner_id(), shared.role()), (ada, ListRole::Editor));
+        assert_eq!(sql.list_lists(&bob, false).await.unwrap(), vec![shared]);
+        assert_eq!(sql.get_task(&bob, &task.id()).await.unwrap(), task);
+
+        let req = UpdateTaskRequest::new()
+            .with_completed(true)
+            .with_updated_by(bob);
+        let completed = sql.update_task(&ada, &task.id(), &req).await.unwrap();
+        assert_eq!(completed.completed_by(), Some(bob));
+        let req = UpdateTaskRequest::new().with_title(title("Buy oat milk"));
+        let renamed = sql.update_task(&ada, &task.id(), &req).await.unwrap();
+        assert_eq!(
+            (renamed.updated_by(), renamed.completed_by()),
+            (Some(bob), Some(bob))
+        );
+        let req = UpdateTaskRequest::new()
+            .with_completed(false)
+            .with_updated_by(ada);
+        let reopened = sql.update_task(&ada, &task.id(), &req).await.unwrap();
+        assert_eq!(
+            (reopened.updated_by(), reopened.completed_by()),
+            (Some(ada), None)
+        );
+
+        sql.unshare_list(&ada, &list.id(), &bob).await.unwrap();
+        let result = sql.unshare_list(&ada, &list.id(), &bob).await;
+        assert!(matches!(
+            result,
+            Err(UnshareTaskListError::MemberNotFound { .. })
+        ));
+        let result = sql.get_task(&bob, &task.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+        assert!(sql.list_lists(&bob, true).await.unwrap().is_empty());
+    }
+
+    #[sqlx::test]
+    async fn test_assigned_tasks_are_listed_and_reminded_to_their_assignee(pool: PgPool) {
+        let sql = Sql { pool };
+        let ada = user(&sql, "ada@example.com").await;
+        let bob = user(&sql, "bob@example.com").await;
+        let list = sql
+            .create_list(
+                &ada,
+                &CreateTaskListRequest::new(TaskListTitle::new("Chores").unwrap()),
+            )
+            .await
+            .unwrap();
+        let email = EmailAddress::new("bob@example.com").unwrap();
+        let req = ShareTaskListRequest::new(email, ListRole::Editor);
+        sql.share_list(&ada, &list.id(), &req).await.unwrap();
+        let offsets = vec![ReminderOffset::from_minutes(5).unwrap()];
+        let req = CreateTaskRequest::new(title("Take out bins"))
+            .with_list_id(list.id())
+            .with_due_at(DueAt::new(Utc::now()))
+            .with_remind_at(RemindAt::new(offsets).unwrap())
+            .with_assignee_id(bob);
+        let task = sql.create_task(&ada, &req).await.unwrap();
+        let req = CreateTaskRequest::new(title("Water plants"));
+        sql.create_task(&ada, &req).await.unwrap();
+
+        let ids = |tasks: &[Task]| tasks.iter().map(Task::id).collect::<Vec<_>>();
+        let mine = ListTasksRequest::new().with_filter(TaskFilter::new().with_assignee(bob));
+        let page = sql.list_tasks(&bob, &mine).await.unwrap();
+        assert_eq!(ids(&page.tasks), vec![task.id()]);
+        let page = sql
+            .list_tasks(&bob, &ListTasksRequest::new())
+            .await
+            .unwrap();
+        assert_eq!(ids(&page.tasks), vec![task.id()]);
+
+        let claimed = sql.claim_due_reminders(Utc::now(), 10).await.unwrap();
+        let claimed = claimed
+            .iter()
+            .map(|reminder| (reminder.task().id(), reminder.recipient()))
+            .collect::<Vec<_>>();
+        assert_eq!(claimed, vec![(task.id(), bob)]);
+
+        sql.unshare_list(&ada, &list.id(), &bob).await.unwrap();
+        let task = sql.get_task(&ada, &task.id()).await.unwrap();
+        assert_eq!(task.assignee_id(), None);
+    }
+
+    /// Create `count` tasks for `owner` that are due now, each with a reminder five minutes
+    /// before.
+    async fn due_tasks(sql: &Sql, owner: &Uuid, count: usize) -> Vec<Task> {
+        let mut tasks = vec![];
+        for i in 0..count {
+            let offsets = vec![ReminderOffset::from_minutes(5).unwrap()];
+            let req = CreateTaskRequest::new(title(&format!("Water plant {}", i)))
+                .with_due_at(DueAt::new(Utc::now()))
+                .with_remind_at(RemindAt::new(offsets).unwrap());
+            tasks.push(sql.create_task(owner, &req).await.unwrap());
+        }
+        tasks
+    }
+
+    #[sqlx::test]
+    async fn test_concurrent_claims_return_disjoint_reminders(pool: PgPool) {
+        let sql = Sql { pool };
+        let owner = user(&sql, "ada@example.com").await;
+        let tasks = due_tasks(&sql, &owner, 6).await;
+
+        // Each claim runs on its own connection from the pool
+        let (first, second) = tokio::join!(
+            sql.claim_due_reminders(Utc::now(), 4),
+            sql.claim_due_reminders(Utc::now(), 4),
+        );
+        let ids = |claimed: Vec<DueReminder>| {
+            claimed
+                .iter()
+                .map(|reminder| reminder.task().id())
+                .collect::<Vec<_>>()
+        };
+        let (first, second) = (ids(first.unwrap()), ids(second.unwrap()));
+        assert!(
+            first.iter().all(|id| !second.contains(id)),
+            "expected disjoint claims, got {:?} and {:?}",
+            first,
+            second
+        );
+        let mut claimed = [first, second].concat();
+        claimed.sort();
+        let mut expected = tasks.iter().map(Task::id).collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(claimed, expected);
+    }
+
+    #[sqlx::test]
+    async fn test_claimed_reminders_are_not_claimed_again(pool: PgPool) {
+        let sql = Sql { pool };
+        let owner = user(&sql, "ada@example.com").await;
+        due_tasks(&sql, &owner, 2).await;
+
+        let claimed = sql.claim_due_reminders(Utc::now(), 10).await.unwrap();
+        assert_eq!(claimed.len(), 2);
+        let claimed = sql.claim_due_reminders(Utc::now(), 10).await.unwrap();
+        assert!(
+            claimed.is_empty(),
+            "expected no reminders, got {:?}",
+            claimed
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_released_reminders_are_claimed_again_only_when_retried(pool: PgPool) {
+        let sql = Sql { pool };
+        let owner = user(&sql, "ada@example.com").await;
+        let task = due_tasks(&sql, &owner, 1).await.remove(0);
+
+        let claimed = sql.claim_due_reminders(Utc::now(), 10).await.unwrap();
+        assert_eq!(claimed[0].attempts(), 0);
+        sql.record_reminder_failure(&claimed[0], "mailbox full", true)
+            .await
+            .unwrap();
+
+        let claimed = sql.claim_due_reminders(Utc::now(), 10).await.unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].task().id(), task.id());
+        assert_eq!(claimed[0].attempts(), 1);
+        sql.record_reminder_failure(&claimed[0], "mailbox full", false)
+            .await
+            .unwrap();
+
+        let claimed = sql.claim_due_reminders(Utc::now(), 10).await.unwrap();
+        assert!(
+            claimed.is_empty(),
+            "expected no reminders, got {:?}",
+            claimed
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_sessions_resolve_their_user_until_they_expire(pool: PgPool) {
+        let sql = Sql { pool };
+        let email = EmailAddress::new("ada@example.com").unwrap();
+        let hash = PasswordHash::from_stored("!");
+        let user = sql.create_user(&email, &hash).await.unwrap();
+        let result = sql.create_user(&email, &hash).await;
+        assert!(matches!(result, Err(RegisterUserError::Duplicate { .. })));
+        let credentials = sql.get_credentials(&email).await.unwrap().unwrap();
+        assert_eq!(credentials.user, user);
+
+        let now = Utc::now();
+        let session = Session::start(user.clone(), now);
+        sql.create_session(&session).await.unwrap();
+        let found = sql.get_session_user(session.token(), now).await.unwrap();
+        assert_eq!(found, user);
+        let result = sql
+            .get_session_user(session.token(), session.expires_at())
+            .await;
+        assert!(matches!(result, Err(AuthenticateError::InvalidToken)));
+
+        sql.delete_session(session.token()).await.unwrap();
+        let result = sql.get_session_user(session.token(), now).await;
+        assert!(matches!(result, Err(AuthenticateError::InvalidToken)));
+    }
+
+    #[sqlx::test]
+    async fn test_api_tokens_record_use_and_are_revoked_with_their_list(pool: PgPool) {
+        let sql = Sql { pool };
+        let ada = user(&sql, "ada@example.com").await;
+        let bob = user(&sql, "bob@example.com").await;
+        let list = sql
+            .create_list(
+                &ada,
+                &CreateTaskListRequest::new(TaskListTitle::new("Garden").unwrap()),
+            )
+            .await
+            .unwrap();
+        let scope = TokenScope::full()
+            .with_access(TokenAccess::ReadOnly)
+            .with_list_id(list.id());
+        let req = CreateApiTokenRequest::new(ApiTokenName::new("cron").unwrap(), scope);
+        let token = NewApiToken::issue(&req, Utc::now());
+        let result = sql.create_api_token(&bob, &token).await;
+        assert!(matches!(
+            result,
+            Err(CreateApiTokenError::ListNotFound { .. })
+        ));
+        sql.create_api_token(&ada, &token).await.unwrap();
+
+        let principal = sql.use_api_token(token.secret(), Utc::now()).await.unwrap();
+        assert_eq!(principal.user.id(), ada);
+        assert_eq!(principal.scope, scope);
+        let tokens = sql.list_api_tokens(&ada).await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert!(tokens[0].last_used_at().is_some());
+        assert!(sql.list_api_tokens(&bob).await.unwrap().is_empty());
+
+        sql.delete_list(&ada, &list.id(), DeleteTaskListMode::OnlyIfEmpty)
+            .await
+            .unwrap();
+        let result = sql.use_api_token(token.secret(), Utc::now()).await;
+        assert!(matches!(result, Err(AuthenticateError::InvalidApiToken)));
+    }
+
+    #[sqlx::test]
+    async fn test_idempotency_keys_are_claimed_replayed_and_expire(pool: PgPool) {
+        let sql = Sql { pool };
+        let key = IdempotencyKey::new("retry-1").unwrap();
+        let req = IdempotentRequest::new("owner".to_string(), key.clone(), "a".to_string());
+        let now = Utc::now();
+        let expires_at = now + Duration::hours(1);
+        let claimed = sql
+            .claim_idempotency_key(&req, now, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(claimed, None);
+        let pending = sql
+            .claim_idempotency_key(&req, now, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(
+            pending,
+            Some(IdempotencyRecord {
+                fingerprint: "a".to_string(),
+                response: None,
+            })
+        );
+        let other = IdempotentRequest::new("other".to_string(), key, "b".to_string());
+        let claimed = sql
+            .claim_idempotency_key(&other, now, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(claimed, None);
+
+        let response = StoredResponse::new(201, Some("application/json".to_string()), vec![7])
+            .with_headers(vec![("etag".to_string(), "\"1\"".to_string())]);
+        sql.save_idempotent_response(&req, &response).await.unwrap();
+        let replayed = sql
+            .claim_idempotency_key(&req, now, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(replayed.unwrap().response, Some(response));
+
+        let claimed = sql
+            .claim_idempotency_key(&req, expires_at, expires_at + Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(claimed, None);
+        sql.delete_idempotency_key(&req).await.unwrap();
+        let claimed = sql
+            .claim_idempotency_key(&req, now, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(claimed, None);
     }
-    false
 }
@@ -0,0 +1,400 @@
+//! Operational tasks on the PostgreSQL database that are not part of any domain, such as applying
+//! migrations and moving data between databases. Used by the `modus_admin` binary.
+
+use std::collections::BTreeSet;
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::migrate::{Migration, Migrator};
+use sqlx::{Executor, Row};
+
+use super::Sql;
+
+/// The migrations in `migrations/`, embedded at compile time so the binary can migrate a database
+/// without access to the source tree.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Applied migrations are tracked in the same table as `geni`, so databases migrated by either
+/// tool can be managed by the other.
+const MIGRATIONS_TABLE: &str = "schema_migrations";
+
+/// The tables holding application data, in an order where every table comes after the tables it
+/// references.
+const TABLES: &[&str] = &[
+    "users",
+    "user_sessions",
+    "task_lists",
+    "tasks",
+    "task_reminders",
+    "task_tags",
+    "api_tokens",
+    "task_list_members",
+];
+
+/// The tables holding short-lived data that is neither exported nor imported, but cleared when an
+/// import replaces the application data.
+const TRANSIENT_TABLES: &[&str] = &["idempotency_keys"];
+
+/// Whether a migration known to this build has been applied to the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    /// `None` if the migration was applied by a newer build.
+    pub description: Option<String>,
+    pub applied: bool,
+}
+
+/// A snapshot of all application data, tagged with the migrations its schema corresponds to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Export {
+    pub migrations: Vec<i64>,
+    pub tables: Vec<TableExport>,
+}
+
+/// The rows of a single table, each as a JSON object keyed by column name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableExport {
+    pub name: String,
+    pub rows: Vec<Value>,
+}
+
+impl Sql {
+    /// Check that the database accepts queries, returning its version.
+    pub async fn server_version(&self) -> anyhow::Result<String> {
+        let row = sqlx::query("SHOW server_version")
+            .fetch_one(&self.pool)
+            .await
+            .context("failed to query database")?;
+        Ok(row.try_get(0)?)
+    }
+
+    /// List every migration known to this build or applied to the database, in version order.
+    pub async fn migration_status(&self) -> anyhow::Result<Vec<MigrationStatus>> {
+        let applied = self.applied_migrations().await?;
+        let mut status = up_migrations()
+            .map(|migration| MigrationStatus {
+                version: migration.version,
+                description: Some(migration.description.to_string()),
+                applied: applied.contains(&migration.version),
+            })
+            .collect::<Vec<_>>();
+        let known = status.iter().map(|s| s.version).collect::<BTreeSet<_>>();
+        status.extend(applied.difference(&known).map(|&version| MigrationStatus {
+            version,
+            description: None,
+            applied: true,
+        }));
+        status.sort_by_key(|s| s.version);
+        Ok(status)
+    }
+
+    /// Apply all pending migrations in version order, each in its own transaction, returning the
+    /// migrations that were applied.
+    pub async fn migrate_up(&self) -> anyhow::Result<Vec<&'static Migration>> {
+        let applied = self.applied_migrations().await?;
+        ensure_known(&applied)?;
+
+        let mut migrated = Vec::new();
+        for migration in up_migrations().filter(|m| !applied.contains(&m.version)) {
+            let mut tx = self.pool.begin().await?;
+            tx.execute(sqlx::raw_sql(&migration.sql))
+                .await
+                .with_context(|| format!("failed to apply migration {}", migration.version))?;
+            let query = format!("INSERT INTO {} (id) VALUES ($1)", MIGRATIONS_TABLE);
+            sqlx::query(&query)
+                .bind(migration.version.to_string())
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            migrated.push(migration);
+        }
+        Ok(migrated)
+    }
+
+    /// Roll back the `steps` most recently applied migrations, newest first, returning the
+    /// migrations that were rolled back.
+    pub async fn migrate_down(&self, steps: usize) -> anyhow::Result<Vec<&'static Migration>> {
+        let applied = self.applied_migrations().await?;
+        ensure_known(&applied)?;
+
+        let mut rolled_back = Vec::new();
+        for &version in applied.iter().rev().take(steps) {
+            let migration = MIGRATOR
+                .iter()
+                .find(|m| m.version == version && m.migration_type.is_down_migration())
+                .with_context(|| format!("migration {} cannot be rolled back", version))?;
+            let mut tx = self.pool.begin().await?;
+            tx.execute(sqlx::raw_sql(&migration.sql))
+                .await
+                .with_context(|| format!("failed to roll back migration {}", version))?;
+            let query = format!("DELETE FROM {} WHERE id = $1", MIGRATIONS_TABLE);
+            sqlx::query(&query)
+                .bind(version.to_string())
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            rolled_back.push(migration);
+        }
+        Ok(rolled_back)
+    }
+
+    /// Count the tasks that were completed more than `days` days ago, deleting them unless
+    /// `dry_run` is set.
+    pub async fn purge_completed_tasks(&self, days: u32, dry_run: bool) -> anyhow::Result<u64> {
+        let days = i32::try_from(days).context("too many days")?;
+        if dry_run {
+            let count = sqlx::query_scalar!(
+                r#"
+                SELECT count(*) AS "count!"
+                FROM tasks
+                WHERE completed AND completed_at < now() - make_interval(days => $1)
+                "#,
+                days
+            )
+            .fetch_one(&self.pool)
+            .await
+            .context("failed to count completed tasks")?;
+            return Ok(count as u64);
+        }
+
+        let query = sqlx::query!(
+            r#"
+            DELETE FROM tasks
+            WHERE completed AND completed_at < now() - make_interval(days => $1)
+            "#,
+            days
+        );
+        let result = self
+            .pool
+            .execute(query)
+            .await
+            .context("failed to delete completed tasks")?;
+        Ok(result.rows_affected())
+    }
+
+    /// Rebuild the indexes of every application table, returning the tables that were reindexed.
+    /// With `concurrently`, writes are not blocked while the indexes are rebuilt.
+    pub async fn reindex(&self, concurrently: bool) -> anyhow::Result<Vec<&'static str>> {
+        for table in TABLES {
+            let query = if concurrently {
+                format!("REINDEX TABLE CONCURRENTLY {}", table)
+            } else {
+                format!("REINDEX TABLE {}", table)
+            };
+            self.pool
+                .execute(query.as_str())
+                .await
+                .with_context(|| format!("failed to reindex table {}", table))?;
+        }
+        Ok(TABLES.to_vec())
+    }
+
+    /// Read every row of every application table in a single consistent snapshot.
+    pub async fn export(&self) -> anyhow::Result<Export> {
+        self.create_migrations_table().await?;
+        let mut tx = self.pool.begin().await?;
+        tx.execute("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY")
+            .await?;
+
+        let tables = sqlx::query_scalar::<_, String>(
+            "SELECT tablename::text FROM pg_tables WHERE schemaname = current_schema()",
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .context("failed to list tables")?;
+        let unknown = tables
+            .iter()
+            .filter(|t| {
+                *t != MIGRATIONS_TABLE
+                    && !TABLES.contains(&t.as_str())
+                    && !TRANSIENT_TABLES.contains(&t.as_str())
+            })
+            .collect::<Vec<_>>();
+        if !unknown.is_empty() {
+            bail!("tables not covered by the export: {:?}", unknown);
+        }
+
+        let migrations = self.applied_migrations_in(&mut tx).await?;
+        let mut export = Export {
+            migrations: migrations.into_iter().collect(),
+            tables: Vec::new(),
+        };
+        for table in TABLES {
+            let query = format!("SELECT COALESCE(json_agg(t), '[]')::text FROM {} t", table);
+            let rows = sqlx::query_scalar::<_, String>(&query)
+                .fetch_one(&mut *tx)
+                .await
+                .with_context(|| format!("failed to export table {}", table))?;
+            export.tables.push(TableExport {
+                name: table.to_string(),
+                rows: serde_json::from_str(&rows)?,
+            });
+        }
+        tx.commit().await?;
+        Ok(export)
+    }
+
+    /// Load an [Export] into the database in a single transaction. The database must have the
+    /// same migrations applied as the export, and its tables must be empty unless `replace` is
+    /// set, in which case their rows are deleted first. Returns the number of rows imported per
+    /// table.
+    pub async fn import(
+        &self,
+        export: &Export,
+        replace: bool,
+    ) -> anyhow::Result<Vec<(&'static str, usize)>> {
+        self.create_migrations_table().await?;
+        let mut tx = self.pool.begin().await?;
+
+        let migrations = self.applied_migrations_in(&mut tx).await?;
+        if !migrations.iter().eq(export.migrations.iter()) {
+            bail!(
+                "the export was taken at migration {} but the database is at migration {}",
+                latest(export.migrations.iter()),
+                latest(migrations.iter())
+            );
+        }
+        if let Some(table) = export
+            .tables
+            .iter()
+            .find(|t| !TABLES.contains(&t.name.as_str()))
+        {
+            bail!("the export contains unknown table {}", table.name);
+        }
+
+        if replace {
+            let tables = TABLES.iter().chain(TRANSIENT_TABLES).copied();
+            tx.execute(format!("TRUNCATE {}", tables.collect::<Vec<_>>().join(", ")).as_str())
+                .await
+                .context("failed to clear tables")?;
+        } else {
+            for table in TABLES {
+                let query = format!("SELECT EXISTS (SELECT 1 FROM {})", table);
+                let populated = sqlx::query_scalar::<_, bool>(&query)
+                    .fetch_one(&mut *tx)
+                    .await?;
+                if populated {
+                    bail!(
+                        "table {} is not empty, import with --replace to overwrite",
+                        table
+                    );
+                }
+            }
+        }
+
+        let mut imported = Vec::new();
+        for table in TABLES {
+            let rows = export
+                .tables
+                .iter()
+                .find(|t| t.name == *table)
+                .map(|t| t.rows.as_slice())
+                .unwrap_or_default();
+            let query = format!(
+                "INSERT INTO {table} SELECT * FROM json_populate_recordset(NULL::{table}, $1::json)"
+            );
+            sqlx::query(&query)
+                .bind(serde_json::to_string(rows)?)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("failed to import table {}", table))?;
+            imported.push((*table, rows.len()));
+        }
+        tx.commit().await?;
+        Ok(imported)
+    }
+
+    async fn create_migrations_table(&self) -> anyhow::Result<()> {
+        let query = format!(
+            "CREATE TABLE IF NOT EXISTS {} (id VARCHAR(255) NOT NULL PRIMARY KEY)",
+            MIGRATIONS_TABLE
+        );
+        self.pool
+            .execute(query.as_str())
+            .await
+            .context("failed to create migrations table")?;
+        Ok(())
+    }
+
+    async fn applied_migrations(&self) -> anyhow::Result<BTreeSet<i64>> {
+        self.create_migrations_table().await?;
+        let mut conn = self.pool.acquire().await?;
+        self.applied_migrations_in(&mut conn).await
+    }
+
+    /// The versions of the applied migrations, as seen by `conn`.
+    async fn applied_migrations_in(
+        &self,
+        conn: &mut sqlx::PgConnection,
+    ) -> anyhow::Result<BTreeSet<i64>> {
+        let query = format!("SELECT id FROM {}", MIGRATIONS_TABLE);
+        let ids = sqlx::query_scalar::<_, String>(&query)
+            .fetch_all(&mut *conn)
+            .await
+            .context("failed to fetch applied migrations")?;
+        ids.iter()
+            .map(|id| {
+                id.parse()
+                    .with_context(|| format!("invalid migration version: {}", id))
+            })
+            .collect()
+    }
+}
+
+/// Describe the most recent of `versions` for error messages.
+fn latest<'a>(versions: impl Iterator<Item = &'a i64>) -> String {
+    versions
+        .max()
+        .map_or("none".to_string(), |version| version.to_string())
+}
+
+fn up_migrations() -> impl Iterator<Item = &'static Migration> {
+    MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+}
+
+/// Refuse to migrate a database that was migrated by a newer build, since this build cannot
+/// know how to roll those migrations back or what they expect.
+fn ensure_known(applied: &BTreeSet<i64>) -> anyhow::Result<()> {
+    let unknown = applied
+        .iter()
+        .filter(|&&version| !up_migrations().any(|m| m.version == version))
+        .collect::<Vec<_>>();
+    if !unknown.is_empty() {
+        bail!(
+            "the database has migrations unknown to this build: {:?}",
+            unknown
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::users::models::user::{EmailAddress, PasswordHash};
+    use crate::domain::users::ports::UserRepository;
+    use sqlx::PgPool;
+
+    #[sqlx::test(migrations = false)]
+    async fn test_export_covers_every_table_after_all_migrations(pool: PgPool) {
+        let sql = Sql { pool };
+        sql.migrate_up().await.unwrap();
+        let email = EmailAddress::new("ada@example.com").unwrap();
+        sql.create_user(&email, &PasswordHash::from_stored("!"))
+            .await
+            .unwrap();
+
+        let export = sql.export().await.unwrap();
+        let names = export
+            .tables
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, TABLES);
+        let imported = sql.import(&export, true).await.unwrap();
+        assert!(imported.contains(&("users", 1)));
+    }
+}
@@ -0,0 +1,3779 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Utc};
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool};
+use sqlx::{Executor, FromRow, QueryBuilder, SqliteConnection};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::domain::idempotency::models::key::{
+    ClaimIdempotencyKeyError, CompleteIdempotentRequestError, IdempotencyRecord, IdempotentRequest,
+    StoredResponse,
+};
+use crate::domain::idempotency::ports::IdempotencyRepository;
+use crate::domain::readiness::models::ready::ReadinessError;
+use crate::domain::readiness::ports::ReadinessRepository;
+use crate::domain::reminders::models::list::{
+    CreateTaskListError, DeleteTaskListError, GetTaskListError, ListTaskListsError,
+    RestoreTaskListError, UpdateTaskListError,
+};
+use crate::domain::reminders::models::list::{
+    CreateTaskListRequest, DeleteTaskListMode, TaskList, TaskListColour, TaskListTitle,
+    UpdateTaskListRequest,
+};
+use crate::domain::reminders::models::member::{
+    ListRole, ListTaskListMembersError, ShareTaskListError, ShareTaskListRequest, TaskListMember,
+    UnshareTaskListError,
+};
+use crate::domain::reminders::models::query::{
+    ListTasksRequest, SortDirection, TagMatch, TaskListFilter, TaskPage, TaskSortField,
+};
+use crate::domain::reminders::models::recurrence::Recurrence;
+use crate::domain::reminders::models::reminder::{DueReminder, FireRemindersError};
+use crate::domain::reminders::models::tag::{
+    ListTagsError, MergeTagsError, RenameTagError, Tag, TagUsage, TaskTags,
+};
+use crate::domain::reminders::models::task::{
+    CreateTaskError, DeleteTaskError, GetTaskError, ListTasksError, RestoreTaskError,
+    UpdateTaskError,
+};
+use crate::domain::reminders::models::task::{
+    CreateTaskRequest, DueAt, Priority, RemindAt, ReminderOffset, Task, TaskNotes, TaskTitle,
+    UpdateTaskRequest,
+};
+use crate::domain::reminders::models::trash::{
+    ListTrashError, PurgeTrashError, PurgedTrash, Trash, Trashed,
+};
+use crate::domain::reminders::ports::ReminderRepository;
+use crate::domain::users::models::api_token::{
+    ApiToken, ApiTokenName, ApiTokenSecret, CreateApiTokenError, ListApiTokensError, NewApiToken,
+    Principal, RevokeApiTokenError, TokenAccess, TokenScope,
+};
+use crate::domain::users::models::session::{
+    AuthenticateError, LogInError, LogOutError, Session, SessionToken,
+};
+use crate::domain::users::models::user::{
+    EmailAddress, PasswordHash, RegisterUserError, User, UserCredentials,
+};
+use crate::domain::users::ports::UserRepository;
+
+/// The SQLite migrations, applied when the database is opened.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+/// A row of the `tasks` table.
+#[derive(FromRow)]
+struct TaskRow {
+    id: Uuid,
+    title: String,
+    completed: bool,
+    priority: String,
+    notes: Option<String>,
+    due_at: Option<DateTime<Utc>>,
+    reminder_offsets: String,
+    recurrence: Option<String>,
+    list_id: Option<Uuid>,
+    parent_id: Option<Uuid>,
+    position: i32,
+    /// A JSON array of the tags of the task.
+    tags: String,
+    created_at: DateTime<Utc>,
+    updated_by: Option<Uuid>,
+    completed_by: Option<Uuid>,
+    assignee_id: Option<Uuid>,
+    version: i64,
+}
+
+impl TryFrom<TaskRow> for Task {
+    type Error = anyhow::Error;
+
+    fn try_from(row: TaskRow) -> Result<Self, Self::Error> {
+        let title = TaskTitle::new(&row.title)
+            .with_context(|| format!("invalid title stored for task {}", row.id))?;
+        let priority = Priority::parse(&row.priority)
+            .with_context(|| format!("invalid priority stored for task {}", row.id))?;
+        let notes = row
+            .notes
+            .as_deref()
+            .map(TaskNotes::new)
+            .transpose()
+            .with_context(|| format!("invalid notes stored for task {}", row.id))?;
+        let offsets = serde_json::from_str::<Vec<u32>>(&row.reminder_offsets)
+            .map_err(anyhow::Error::from)
+            .and_then(|offsets| {
+                offsets
+                    .into_iter()
+                    .map(|minutes| Ok(ReminderOffset::from_minutes(minutes)?))
+                    .collect::<anyhow::Result<Vec<_>>>()
+            })
+            .and_then(|offsets| Ok(RemindAt::new(offsets)?))
+            .with_context(|| format!("invalid reminder offsets stored for task {}", row.id))?;
+        let recurrence = row
+            .recurrence
+            .as_deref()
+            .map(Recurrence::parse)
+            .transpose()
+            .with_context(|| format!("invalid recurrence stored for task {}", row.id))?;
+        let tags = serde_json::from_str::<Vec<String>>(&row.tags)
+            .map_err(anyhow::Error::from)
+            .and_then(|tags| {
+                tags.iter()
+                    .map(|tag| Ok(Tag::new(tag)?))
+                    .collect::<anyhow::Result<Vec<_>>>()
+            })
+            .and_then(|tags| Ok(TaskTags::new(tags)?))
+            .with_context(|| format!("invalid tags stored for task {}", row.id))?;
+        Ok(Task {
+            id: row.id,
+            title,
+            completed: row.completed,
+            priority,
+            notes,
+            due_at: row.due_at.map(DueAt::new),
+            remind_at: offsets,
+            recurrence,
+            list_id: row.list_id,
+            parent_id: row.parent_id,
+            position: row.position,
+            tags,
+            assignee_id: row.assignee_id,
+            created_at: row.created_at,
+            updated_by: row.updated_by,
+            completed_by: row.completed_by,
+            version: row.version,
+        })
+    }
+}
+
+/// Encode [RemindAt] for the `reminder_offsets` column.
+fn reminder_offsets(remind_at: &RemindAt) -> String {
+    let minutes = remind_at
+        .offsets()
+        .iter()
+        .map(ReminderOffset::minutes)
+        .collect::<Vec<_>>();
+    serde_json::Value::from(minutes).to_string()
+}
+
+/// A row of the `task_lists` table.
+#[derive(FromRow)]
+struct TaskListRow {
+    id: Uuid,
+    title: String,
+    colour: Option<String>,
+    position: i32,
+    archived: bool,
+    created_at: DateTime<Utc>,
+    owner_id: Uuid,
+    /// The role of the user the list was fetched for.
+    role: String,
+}
+
+impl TryFrom<TaskListRow> for TaskList {
+    type Error = anyhow::Error;
+
+    fn try_from(row: TaskListRow) -> Result<Self, Self::Error> {
+        let title = TaskListTitle::new(&row.title)
+            .with_context(|| format!("invalid title stored for task list {}", row.id))?;
+        let colour = row
+            .colour
+            .as_deref()
+            .map(TaskListColour::new)
+            .transpose()
+            .with_context(|| format!("invalid colour stored for task list {}", row.id))?;
+        let role = ListRole::parse(&row.role)
+            .with_context(|| format!("invalid role stored for task list {}", row.id))?;
+        Ok(TaskList {
+            id: row.id,
+            title,
+            colour,
+            position: row.position,
+            archived: row.archived,
+            created_at: row.created_at,
+            owner_id: row.owner_id,
+            role,
+        })
+    }
+}
+
+/// A row of the `tasks` table in the trash.
+#[derive(FromRow)]
+struct TrashedTaskRow {
+    #[sqlx(flatten)]
+    task: TaskRow,
+    deleted_at: DateTime<Utc>,
+}
+
+impl TryFrom<TrashedTaskRow> for Trashed<Task> {
+    type Error = anyhow::Error;
+
+    fn try_from(row: TrashedTaskRow) -> Result<Self, Self::Error> {
+        Ok(Trashed {
+            item: Task::try_from(row.task)?,
+            deleted_at: row.deleted_at,
+        })
+    }
+}
+
+/// A row of the `task_lists` table in the trash.
+#[derive(FromRow)]
+struct TrashedTaskListRow {
+    #[sqlx(flatten)]
+    list: TaskListRow,
+    deleted_at: DateTime<Utc>,
+}
+
+impl TryFrom<TrashedTaskListRow> for Trashed<TaskList> {
+    type Error = anyhow::Error;
+
+    fn try_from(row: TrashedTaskListRow) -> Result<Self, Self::Error> {
+        Ok(Trashed {
+            item: TaskList::try_from(row.list)?,
+            deleted_at: row.deleted_at,
+        })
+    }
+}
+
+/// A row of the `task_reminders` table that was claimed for delivery.
+#[derive(FromRow)]
+struct ClaimedReminderRow {
+    task_id: Uuid,
+    remind_at: DateTime<Utc>,
+    attempts: i64,
+    /// The assignee of the task, or else its owner.
+    recipient: Uuid,
+}
+
+/// A tag of the `task_tags` table with the number of tasks that carry it.
+#[derive(FromRow)]
+struct TagUsageRow {
+    tag: String,
+    tasks: i64,
+}
+
+impl TryFrom<TagUsageRow> for TagUsage {
+    type Error = anyhow::Error;
+
+    fn try_from(row: TagUsageRow) -> Result<Self, Self::Error> {
+        let tag =
+            Tag::new(&row.tag).with_context(|| format!("invalid tag stored: {:?}", row.tag))?;
+        Ok(TagUsage {
+            tag,
+            tasks: u64::try_from(row.tasks)?,
+        })
+    }
+}
+
+/// A row of the `users` table.
+#[derive(FromRow)]
+struct UserRow {
+    id: Uuid,
+    email: String,
+    password_hash: String,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<UserRow> for UserCredentials {
+    type Error = anyhow::Error;
+
+    fn try_from(row: UserRow) -> Result<Self, Self::Error> {
+        let email = EmailAddress::new(&row.email)
+            .with_context(|| format!("invalid email address stored for user {}", row.id))?;
+        Ok(UserCredentials {
+            user: User {
+                id: row.id,
+                email,
+                created_at: row.created_at,
+            },
+            password_hash: PasswordHash::from_stored(&row.password_hash),
+        })
+    }
+}
+
+/// A row of the `api_tokens` table.
+#[derive(FromRow)]
+struct ApiTokenRow {
+    id: Uuid,
+    name: String,
+    access: String,
+    list_id: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Parse the `access` and `list_id` columns of an `api_tokens` row.
+fn token_scope(access: &str, list_id: Option<Uuid>) -> anyhow::Result<TokenScope> {
+    let scope = TokenScope::full().with_access(TokenAccess::parse(access)?);
+    Ok(match list_id {
+        Some(list_id) => scope.with_list_id(list_id),
+        None => scope,
+    })
+}
+
+impl TryFrom<ApiTokenRow> for ApiToken {
+    type Error = anyhow::Error;
+
+    fn try_from(row: ApiTokenRow) -> Result<Self, Self::Error> {
+        let name = ApiTokenName::new(&row.name)
+            .with_context(|| format!("invalid name stored for API token {}", row.id))?;
+        Ok(ApiToken {
+            id: row.id,
+            name,
+            scope: token_scope(&row.access, row.list_id)?,
+            created_at: row.created_at,
+            last_used_at: row.last_used_at,
+        })
+    }
+}
+
+/// The columns of an `api_tokens` row returned when it is used.
+#[derive(FromRow)]
+struct UsedApiTokenRow {
+    user_id: Uuid,
+    access: String,
+    list_id: Option<Uuid>,
+}
+
+/// The outcome of renaming a tag.
+enum TagRename {
+    /// The tag was renamed on this many tasks.
+    Renamed(u64),
+    NotFound,
+    /// The tag was kept because the new name is already in use.
+    Duplicate,
+}
+
+/// The outcome of merging tags.
+enum TagMerge {
+    /// The tags were merged into one carried by this many tasks.
+    Merged(u64),
+    /// Nothing was merged because no task carries this tag.
+    NotFound(Tag),
+}
+
+/// A user who may access a task list, with their email address from the `users` table.
+#[derive(FromRow)]
+struct MemberRow {
+    user_id: Uuid,
+    email: String,
+    role: String,
+    added_at: DateTime<Utc>,
+}
+
+impl TryFrom<MemberRow> for TaskListMember {
+    type Error = anyhow::Error;
+
+    fn try_from(row: MemberRow) -> Result<Self, Self::Error> {
+        let email = EmailAddress::new(&row.email)
+            .with_context(|| format!("invalid email address stored for user {}", row.user_id))?;
+        let role = ListRole::parse(&row.role)
+            .with_context(|| format!("invalid role stored for member {}", row.user_id))?;
+        Ok(TaskListMember {
+            user_id: row.user_id,
+            email,
+            role,
+            added_at: row.added_at,
+        })
+    }
+}
+
+/// A row of the `idempotency_keys` table.
+#[derive(FromRow)]
+struct IdempotencyKeyRow {
+    fingerprint: String,
+    response_status: Option<i64>,
+    response_content_type: Option<String>,
+    response_headers: String,
+    response_body: Option<Vec<u8>>,
+}
+
+impl TryFrom<IdempotencyKeyRow> for IdempotencyRecord {
+    type Error = anyhow::Error;
+
+    fn try_from(row: IdempotencyKeyRow) -> Result<Self, Self::Error> {
+        let response = match (row.response_status, row.response_body) {
+            (Some(status), Some(body)) => Some(
+                StoredResponse::new(
+                    u16::try_from(status).context("invalid response status stored")?,
+                    row.response_content_type,
+                    body,
+                )
+                .with_headers(parse_header_lines(&row.response_headers)?),
+            ),
+            _ => None,
+        };
+        Ok(IdempotencyRecord {
+            fingerprint: row.fingerprint,
+            response,
+        })
+    }
+}
+
+/// The headers of `response` as a JSON array of `name: value` lines, as they are stored.
+fn header_lines(response: &StoredResponse) -> String {
+    let lines = response
+        .headers()
+        .iter()
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect::<Vec<_>>();
+    serde_json::Value::from(lines).to_string()
+}
+
+/// The headers stored as a JSON array of `name: value` lines.
+fn parse_header_lines(raw: &str) -> anyhow::Result<Vec<(String, String)>> {
+    let lines = serde_json::from_str::<Vec<String>>(raw)
+        .with_context(|| format!("invalid response headers stored: {:?}", raw))?;
+    lines
+        .iter()
+        .map(|line| {
+            line.split_once(": ")
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .with_context(|| format!("invalid response header stored: {:?}", line))
+        })
+        .collect()
+}
+
+/// The outcome of sharing a task list.
+enum MemberSave {
+    Saved(MemberRow),
+    ListNotFound,
+    UserNotFound,
+    /// The list was not shared because the user created it.
+    Creator,
+}
+
+/// The outcome of deleting a task list.
+enum ListRemoval {
+    Removed,
+    NotFound,
+    /// The list was kept because it has this many tasks.
+    NotEmpty(u64),
+}
+
+/// The outcome of restoring a task from the trash.
+enum TaskRestore {
+    Restored(Box<TaskRow>),
+    NotFound,
+    /// The task was kept in the trash because its parent with this id is there.
+    ParentDeleted(Uuid),
+    /// The task was kept in the trash because its list with this id is there.
+    ListDeleted(Uuid),
+    /// The task was kept in the trash because an incomplete task has this title.
+    Duplicate(String),
+}
+
+/// The outcome of restoring a task list from the trash.
+enum ListRestore {
+    Restored(TaskListRow),
+    NotFound,
+    /// The list was kept in the trash because an unarchived list has its title.
+    Duplicate(String),
+    /// The list was kept in the trash because an incomplete task has the title of one of its
+    /// tasks.
+    TaskDuplicate(String),
+}
+
+/// A [ReminderRepository] and [UserRepository] backed by a SQLite database file, for single
+/// machine deployments. Timestamps are stored as RFC 3339 text in UTC, which sorts
+/// chronologically.
+#[derive(Debug, Clone)]
+pub struct Sqlite {
+    pool: SqlitePool,
+}
+
+impl Sqlite {
+    /// Open the database at `path`, such as `sqlite://modus.db`, creating it if it does not exist
+    /// and applying any pending migrations.
+    pub async fn new(path: &str) -> Result<Sqlite, anyhow::Error> {
+        let options = SqliteConnectOptions::from_str(path)
+            .with_context(|| format!("invalid database path: {}", path))?
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .foreign_keys(true);
+        let pool = SqlitePool::connect_with(options)
+            .await
+            .with_context(|| format!("failed to open database at: {}", path))?;
+        MIGRATOR
+            .run(&pool)
+            .await
+            .with_context(|| format!("failed to migrate database at: {}", path))?;
+
+        Ok(Sqlite { pool })
+    }
+
+    async fn save_task(
+        &self,
+        conn: &mut SqliteConnection,
+        owner: &Uuid,
+        task: &Task,
+    ) -> Result<(), sqlx::Error> {
+        let query = sqlx::query(
+            r#"
+            INSERT INTO tasks (
+                id, title, completed, created_at, updated_at, due_at, reminder_offsets, recurrence,
+                list_id, parent_id, position, priority, notes, owner_id, updated_by, assignee_id
+            )
+            VALUES (?1, ?2, FALSE, ?3, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+            "#,
+        )
+        .bind(task.id)
+        .bind(task.title.to_string())
+        .bind(task.created_at)
+        .bind(task.due_at.map(|due_at| *due_at.as_datetime()))
+        .bind(reminder_offsets(&task.remind_at))
+        .bind(task.recurrence.as_ref().map(Recurrence::to_string))
+        .bind(task.list_id)
+        .bind(task.parent_id)
+        .bind(task.position)
+        .bind(task.priority.as_str())
+        .bind(task.notes.as_ref().map(TaskNotes::as_str))
+        .bind(owner)
+        .bind(task.updated_by)
+        .bind(task.assignee_id);
+        conn.execute(query).await?;
+        Ok(())
+    }
+
+    /// Save a task of `owner` created from `req` along with its reminders and tags, placed after
+    /// its siblings unless `req` gives a position.
+    async fn insert_task(
+        &self,
+        conn: &mut SqliteConnection,
+        owner: &Uuid,
+        req: &CreateTaskRequest,
+    ) -> Result<Task, sqlx::Error> {
+        let position = match req.position() {
+            Some(position) => position,
+            None => self.next_position(conn, owner, req.parent_id()).await?,
+        };
+        let task = Task {
+            id: Uuid::new_v4(),
+            title: req.title().clone(),
+            completed: false,
+            priority: req.priority(),
+            notes: req.notes().cloned(),
+            due_at: req.due_at().copied(),
+            remind_at: req.remind_at().clone(),
+            recurrence: req.recurrence().cloned(),
+            list_id: req.list_id(),
+            parent_id: req.parent_id(),
+            position,
+            tags: req.tags().clone(),
+            assignee_id: req.assignee_id(),
+            created_at: Utc::now(),
+            updated_by: req.updated_by(),
+            completed_by: None,
+            version: 1,
+        };
+        self.save_task(conn, owner, &task).await?;
+        self.sync_reminders(conn, &task).await?;
+        self.save_tags(conn, &task).await?;
+        Ok(task)
+    }
+
+    /// The position after the last of the tasks of `owner` whose parent is `parent_id`.
+    async fn next_position(
+        &self,
+        conn: &mut SqliteConnection,
+        owner: &Uuid,
+        parent_id: Option<Uuid>,
+    ) -> Result<i32, sqlx::Error> {
+        sqlx::query_scalar::<_, i32>(
+            "SELECT COALESCE(max(position) + 1, 0) FROM tasks WHERE parent_id IS ?1 AND owner_id = ?2",
+        )
+        .bind(parent_id)
+        .bind(owner)
+        .fetch_one(conn)
+        .await
+    }
+
+    /// Replace the pending reminders of a task with those derived from its current due date and
+    /// reminder offsets. Reminders that already fired are kept so they never fire twice.
+    async fn sync_reminders(
+        &self,
+        conn: &mut SqliteConnection,
+        task: &Task,
+    ) -> Result<(), sqlx::Error> {
+        let query =
+            sqlx::query("DELETE FROM task_reminders WHERE task_id = ?1 AND fired_at IS NULL")
+                .bind(task.id);
+        conn.execute(query).await?;
+        let Some(due_at) = task.due_at else {
+            return Ok(());
+        };
+        for offset in task.remind_at.offsets() {
+            let query = sqlx::query(
+                "INSERT OR IGNORE INTO task_reminders (task_id, remind_at) VALUES (?1, ?2)",
+            )
+            .bind(task.id)
+            .bind(offset.remind_at(&due_at));
+            conn.execute(query).await?;
+        }
+        Ok(())
+    }
+
+    /// Mark up to `limit` due reminders as fired in a single statement, so that concurrent
+    /// writers never claim the same reminder.
+    async fn claim_reminders(
+        &self,
+        conn: &mut SqliteConnection,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<ClaimedReminderRow>, sqlx::Error> {
+        let mut rows = sqlx::query_as::<_, ClaimedReminderRow>(
+            r#"
+            UPDATE task_reminders
+            SET fired_at = ?1
+            WHERE (task_id, remind_at) IN (
+                SELECT r.task_id, r.remind_at
+                FROM task_reminders r
+                JOIN tasks t ON t.id = r.task_id
+                WHERE r.fired_at IS NULL
+                    AND r.remind_at <= ?1
+                    AND NOT t.completed
+                    AND t.deleted_at IS NULL
+                ORDER BY r.remind_at
+                LIMIT ?2
+            )
+            RETURNING
+                task_id,
+                remind_at,
+                attempts,
+                (SELECT COALESCE(assignee_id, owner_id) FROM tasks WHERE id = task_id) AS recipient
+            "#,
+        )
+        .bind(now)
+        .bind(i64::from(limit))
+        .fetch_all(&mut *conn)
+        .await?;
+        rows.sort_by_key(|row| row.remind_at);
+        Ok(rows)
+    }
+
+    async fn release_reminder(
+        &self,
+        reminder: &DueReminder,
+        error: &str,
+        retry: bool,
+    ) -> Result<(), sqlx::Error> {
+        let query = sqlx::query(
+            r#"
+            UPDATE task_reminders
+            SET attempts = attempts + 1,
+                last_error = ?3,
+                fired_at = CASE WHEN ?4 THEN NULL ELSE fired_at END
+            WHERE task_id = ?1 AND remind_at = ?2
+            "#,
+        )
+        .bind(reminder.task().id())
+        .bind(reminder.remind_at())
+        .bind(error)
+        .bind(retry);
+        self.pool.execute(query).await?;
+        Ok(())
+    }
+
+    /// Find the task with `id` among those of `owner`, or among those of every user when `owner`
+    /// is `None`.
+    async fn find_task(
+        &self,
+        conn: &mut SqliteConnection,
+        owner: Option<&Uuid>,
+        id: &Uuid,
+    ) -> Result<Option<TaskRow>, sqlx::Error> {
+        sqlx::query_as::<_, TaskRow>(
+            r#"
+            SELECT
+                id, title, completed, priority, notes, due_at, reminder_offsets, recurrence,
+                list_id, parent_id, position,
+                (SELECT json_group_array(tag) FROM task_tags WHERE task_id = tasks.id) AS tags,
+                created_at, updated_by, completed_by, assignee_id, version
+            FROM tasks
+            WHERE id = ?1 AND (?2 IS NULL OR owner_id = ?2) AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(owner)
+        .fetch_optional(conn)
+        .await
+    }
+
+    /// Find the task with `id` among those of `user` and those in lists shared with them.
+    async fn find_visible_task(
+        &self,
+        conn: &mut SqliteConnection,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> Result<Option<TaskRow>, sqlx::Error> {
+        sqlx::query_as::<_, TaskRow>(
+            r#"
+            SELECT
+                id, title, completed, priority, notes, due_at, reminder_offsets, recurrence,
+                list_id, parent_id, position,
+                (SELECT json_group_array(tag) FROM task_tags WHERE task_id = tasks.id) AS tags,
+                created_at, updated_by, completed_by, assignee_id, version
+            FROM tasks
+            WHERE id = ?1 AND deleted_at IS NULL AND (
+                owner_id = ?2
+                OR list_id IN (SELECT list_id FROM task_list_members WHERE user_id = ?2)
+            )
+            "#,
+        )
+        .bind(id)
+        .bind(user)
+        .fetch_optional(conn)
+        .await
+    }
+
+    /// Find the task in the trash with `id` among those of `user` and those in lists shared with
+    /// them.
+    async fn find_trashed_task(
+        &self,
+        conn: &mut SqliteConnection,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> Result<Option<TaskRow>, sqlx::Error> {
+        sqlx::query_as::<_, TaskRow>(
+            r#"
+            SELECT
+                id, title, completed, priority, notes, due_at, reminder_offsets, recurrence,
+                list_id, parent_id, position,
+                (SELECT json_group_array(tag) FROM task_tags WHERE task_id = tasks.id) AS tags,
+                created_at, updated_by, completed_by, assignee_id, version
+            FROM tasks
+            WHERE id = ?1 AND deleted_at IS NOT NULL AND (
+                owner_id = ?2
+                OR list_id IN (SELECT list_id FROM task_list_members WHERE user_id = ?2)
+            )
+            "#,
+        )
+        .bind(id)
+        .bind(user)
+        .fetch_optional(conn)
+        .await
+    }
+
+    /// The tasks in the trash that belong to `user` or are in lists shared with them, most
+    /// recently deleted first.
+    async fn find_trashed_tasks(&self, user: &Uuid) -> Result<Vec<TrashedTaskRow>, sqlx::Error> {
+        sqlx::query_as::<_, TrashedTaskRow>(
+            r#"
+            SELECT
+                id, title, completed, priority, notes, due_at, reminder_offsets, recurrence,
+                list_id, parent_id, position,
+                (SELECT json_group_array(tag) FROM task_tags WHERE task_id = tasks.id) AS tags,
+                created_at, updated_by, completed_by, assignee_id, version, deleted_at
+            FROM tasks
+            WHERE deleted_at IS NOT NULL AND (
+                owner_id = ?1
+                OR list_id IN (
+                    SELECT m.list_id
+                    FROM task_list_members m
+                    JOIN task_lists l ON l.id = m.list_id
+                    WHERE m.user_id = ?1 AND l.deleted_at IS NULL
+                )
+            )
+            ORDER BY deleted_at DESC, position, id
+            "#,
+        )
+        .bind(user)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn find_subtasks(&self, owner: &Uuid, id: &Uuid) -> Result<Vec<TaskRow>, sqlx::Error> {
+        sqlx::query_as::<_, TaskRow>(
+            r#"
+            WITH RECURSIVE subtasks (id) AS (
+                SELECT id FROM tasks WHERE parent_id = ?1 AND owner_id = ?2 AND deleted_at IS NULL
+                UNION
+                SELECT t.id
+                FROM tasks t
+                JOIN subtasks s ON t.parent_id = s.id
+                WHERE t.deleted_at IS NULL
+            )
+            SELECT
+                id, title, completed, priority, notes, due_at, reminder_offsets, recurrence,
+                list_id, parent_id, position,
+                (SELECT json_group_array(tag) FROM task_tags WHERE task_id = tasks.id) AS tags,
+                created_at, updated_by, completed_by, assignee_id, version
+            FROM tasks
+            WHERE id IN (SELECT id FROM subtasks)
+            ORDER BY position, created_at, id
+            "#,
+        )
+        .bind(id)
+        .bind(owner)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// The tasks matching the `req` that belong to `user` or are in lists shared with them.
+    async fn find_tasks(
+        &self,
+        user: &Uuid,
+        req: &ListTasksRequest,
+    ) -> Result<Vec<TaskRow>, sqlx::Error> {
+        let sort = req.sort();
+        // Tasks without a due date sort last in either direction, as in TaskSort::compare. '~'
+        // sorts after and '' before every timestamp.
+        let (comparison, order, missing_due) = match sort.direction() {
+            SortDirection::Ascending => (">", "ASC", "~"),
+            SortDirection::Descending => ("<", "DESC", ""),
+        };
+        let due_key = format!("COALESCE(due_at, '{}')", missing_due);
+        let key = match sort.field() {
+            TaskSortField::Created => vec!["created_at", "id"],
+            TaskSortField::Due => vec![due_key.as_str(), "created_at", "id"],
+            TaskSortField::Priority => vec![PRIORITY_RANK, "created_at", "id"],
+        };
+
+        let mut query = QueryBuilder::<sqlx::Sqlite>::new(
+            r#"
+            SELECT
+                id, title, completed, priority, notes, due_at, reminder_offsets, recurrence,
+                list_id, parent_id, position,
+                (SELECT json_group_array(tag) FROM task_tags WHERE task_id = tasks.id) AS tags,
+                created_at, updated_by, completed_by, assignee_id, version
+            FROM tasks
+            WHERE deleted_at IS NULL AND (owner_id =
+            "#,
+        );
+        query
+            .push_bind(*user)
+            .push(" OR list_id IN (SELECT list_id FROM task_list_members WHERE user_id = ")
+            .push_bind(*user)
+            .push("))");
+        let filter = req.filter();
+        if let Some(completed) = filter.completed() {
+            query.push(" AND completed = ").push_bind(completed);
+        }
+        if let Some(before) = filter.due_before() {
+            query.push(" AND due_at < ").push_bind(*before);
+        }
+        if let Some(after) = filter.due_after() {
+            query.push(" AND due_at > ").push_bind(*after);
+        }
+        if let Some(search) = filter.search() {
+            // LIKE ignores case for ASCII letters only
+            query
+                .push(" AND title LIKE ")
+                .push_bind(search.like_pattern())
+                .push(" ESCAPE '\\'");
+        }
+        match filter.list() {
+            Some(TaskListFilter::Inbox) => {
+                query.push(" AND list_id IS NULL");
+            }
+            Some(TaskListFilter::List(list_id)) => {
+                query.push(" AND list_id = ").push_bind(list_id);
+            }
+            None => {}
+        }
+        if let Some(assignee) = filter.assignee() {
+            query.push(" AND assignee_id = ").push_bind(assignee);
+        }
+        if let Some(tags) = filter.tags() {
+            match tags.mode() {
+                TagMatch::All => query.push(" AND (SELECT count(*)"),
+                TagMatch::Any => query.push(" AND EXISTS (SELECT 1"),
+            };
+            query.push(" FROM task_tags WHERE task_id = tasks.id AND tag IN (");
+            let mut names = query.separated(", ");
+            for tag in tags.tags() {
+                names.push_bind(tag.to_string());
+            }
+            query.push(")");
+            match tags.mode() {
+                TagMatch::All => query.push(") = ").push_bind(tags.tags().len() as i64),
+                TagMatch::Any => query.push(")"),
+            };
+        }
+        if let Some(cursor) = req.cursor() {
+            query.push(format_args!(" AND ({}) {} (", key.join(", "), comparison));
+            if sort.field() == TaskSortField::Due {
+                query
+                    .push("COALESCE(")
+                    .push_bind(cursor.due_at().copied())
+                    .push(format_args!(", '{}'), ", missing_due));
+            }
+            if sort.field() == TaskSortField::Priority {
+                query.push_bind(cursor.priority() as i32).push(", ");
+            }
+            query
+                .push_bind(*cursor.created_at())
+                .push(", ")
+                .push_bind(cursor.id())
+                .push(")");
+        }
+        let order_by = key
+            .iter()
+            .map(|column| format!("{} {}", column, order))
+            .collect::<Vec<_>>();
+        // Fetch one more than requested to learn whether another page follows
+        query
+            .push(format_args!(" ORDER BY {} LIMIT ", order_by.join(", ")))
+            .push_bind(i64::from(req.size().get()) + 1);
+
+        query.build_query_as().fetch_all(&self.pool).await
+    }
+
+    /// Write `task`, which was read at the version before its own. Returns `false` if the task
+    /// was changed since it was read.
+    async fn modify_task(
+        &self,
+        conn: &mut SqliteConnection,
+        task: &Task,
+        now: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let query = sqlx::query(
+            r#"
+            UPDATE tasks
+            SET title = ?2,
+                completed = ?3,
+                completed_at = CASE
+                    WHEN ?3 = completed THEN completed_at
+                    WHEN ?3 THEN ?4
+                    ELSE NULL
+                END,
+                due_at = ?5,
+                reminder_offsets = ?6,
+                recurrence = ?7,
+                list_id = ?8,
+                parent_id = ?9,
+                position = ?10,
+                priority = ?11,
+                notes = ?12,
+                updated_by = ?13,
+                completed_by = ?14,
+                assignee_id = ?15,
+                updated_at = ?4,
+                version = ?16
+            WHERE id = ?1 AND version = ?16 - 1
+            "#,
+        )
+        .bind(task.id)
+        .bind(task.title.to_string())
+        .bind(task.completed)
+        .bind(now)
+        .bind(task.due_at.map(|due_at| *due_at.as_datetime()))
+        .bind(reminder_offsets(&task.remind_at))
+        .bind(task.recurrence.as_ref().map(Recurrence::to_string))
+        .bind(task.list_id)
+        .bind(task.parent_id)
+        .bind(task.position)
+        .bind(task.priority.as_str())
+        .bind(task.notes.as_ref().map(TaskNotes::as_str))
+        .bind(task.updated_by)
+        .bind(task.completed_by)
+        .bind(task.assignee_id)
+        .bind(task.version);
+        let result = conn.execute(query).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Complete every incomplete task nested below the task with `id` at `now` on behalf of
+    /// `user`.
+    async fn complete_subtasks(
+        &self,
+        conn: &mut SqliteConnection,
+        id: &Uuid,
+        user: Option<Uuid>,
+        now: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let query = sqlx::query(
+            r#"
+            WITH RECURSIVE subtasks (id) AS (
+                SELECT id FROM tasks WHERE parent_id = ?1 AND deleted_at IS NULL
+                UNION
+                SELECT t.id
+                FROM tasks t
+                JOIN subtasks s ON t.parent_id = s.id
+                WHERE t.deleted_at IS NULL
+            )
+            UPDATE tasks
+            SET completed = TRUE,
+                completed_at = ?3,
+                completed_by = ?2,
+                updated_by = COALESCE(?2, updated_by),
+                updated_at = ?3,
+                version = version + 1
+            WHERE id IN (SELECT id FROM subtasks) AND NOT completed
+            "#,
+        )
+        .bind(id)
+        .bind(user)
+        .bind(now);
+        conn.execute(query).await?;
+        Ok(())
+    }
+
+    /// Complete or reopen the ancestors of a task whose parent is `parent_id` at `now` on behalf
+    /// of `user`, so that a task with subtasks is completed exactly when all of its subtasks are.
+    /// Returns the title of an ancestor that cannot be reopened because another incomplete task
+    /// has the same title.
+    async fn sync_ancestors(
+        &self,
+        conn: &mut SqliteConnection,
+        parent_id: Option<Uuid>,
+        user: Option<Uuid>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let mut parent_id = parent_id;
+        while let Some(id) = parent_id {
+            let parent = sqlx::query_as::<_, (String, bool, Option<Uuid>, Option<bool>)>(
+                r#"
+                SELECT
+                    t.title,
+                    t.completed,
+                    t.parent_id,
+                    (
+                        SELECT min(s.completed)
+                        FROM tasks s
+                        WHERE s.parent_id = t.id AND s.deleted_at IS NULL
+                    )
+                FROM tasks t
+                WHERE t.id = ?1 AND t.deleted_at IS NULL
+                "#,
+            )
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await?;
+            let Some((title, was_completed, next_id, subtasks_completed)) = parent else {
+                return Ok(None);
+            };
+            let Some(completed) = subtasks_completed.filter(|&c| c != was_completed) else {
+                return Ok(None);
+            };
+            let query = sqlx::query(
+                r#"
+                UPDATE tasks
+                SET completed = ?2,
+                    completed_at = CASE WHEN ?2 THEN ?4 END,
+                    completed_by = CASE WHEN ?2 THEN ?3 END,
+                    updated_by = COALESCE(?3, updated_by),
+                    updated_at = ?4,
+                    version = version + 1
+                WHERE id = ?1
+                "#,
+            )
+            .bind(id)
+            .bind(completed)
+            .bind(user)
+            .bind(now);
+            match conn.execute(query).await {
+                Err(e) if is_unique_constraint_violation(&e) => return Ok(Some(title)),
+                result => result?,
+            };
+            parent_id = next_id;
+        }
+        Ok(None)
+    }
+
+    /// Replace the tags of a task.
+    async fn save_tags(&self, conn: &mut SqliteConnection, task: &Task) -> Result<(), sqlx::Error> {
+        let query = sqlx::query("DELETE FROM task_tags WHERE task_id = ?1").bind(task.id);
+        conn.execute(query).await?;
+        for tag in task.tags.tags() {
+            let query = sqlx::query("INSERT INTO task_tags (task_id, tag) VALUES (?1, ?2)")
+                .bind(task.id)
+                .bind(tag.to_string());
+            conn.execute(query).await?;
+        }
+        Ok(())
+    }
+
+    /// The number of tasks of `owner` that carry `tag`.
+    async fn count_tag(
+        &self,
+        conn: &mut SqliteConnection,
+        owner: &Uuid,
+        tag: &Tag,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT count(*)
+            FROM task_tags
+            WHERE tag = ?1
+                AND task_id IN (SELECT id FROM tasks WHERE owner_id = ?2 AND deleted_at IS NULL)
+            "#,
+        )
+        .bind(tag.to_string())
+        .bind(owner)
+        .fetch_one(conn)
+        .await
+    }
+
+    async fn find_tags(&self, owner: &Uuid) -> Result<Vec<TagUsageRow>, sqlx::Error> {
+        sqlx::query_as::<_, TagUsageRow>(
+            r#"
+            SELECT tag, count(*) AS tasks
+            FROM task_tags
+            WHERE task_id IN (SELECT id FROM tasks WHERE owner_id = ?1 AND deleted_at IS NULL)
+            GROUP BY tag
+            ORDER BY tag
+            "#,
+        )
+        .bind(owner)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn modify_tag(
+        &self,
+        conn: &mut SqliteConnection,
+        owner: &Uuid,
+        from: &Tag,
+        to: &Tag,
+    ) -> Result<TagRename, sqlx::Error> {
+        if self.count_tag(&mut *conn, owner, from).await? == 0 {
+            return Ok(TagRename::NotFound);
+        }
+        if from != to && self.count_tag(&mut *conn, owner, to).await? > 0 {
+            return Ok(TagRename::Duplicate);
+        }
+        let query = sqlx::query(
+            r#"
+            UPDATE tasks
+            SET version = version + 1
+            WHERE owner_id = ?2
+                AND deleted_at IS NULL
+                AND id IN (SELECT task_id FROM task_tags WHERE tag = ?1)
+            "#,
+        )
+        .bind(from.to_string())
+        .bind(owner);
+        conn.execute(query).await?;
+        let query = sqlx::query(
+            r#"
+            UPDATE task_tags
+            SET tag = ?2
+            WHERE tag = ?1
+                AND task_id IN (SELECT id FROM tasks WHERE owner_id = ?3 AND deleted_at IS NULL)
+            "#,
+        )
+        .bind(from.to_string())
+        .bind(to.to_string())
+        .bind(owner);
+        let result = conn.execute(query).await?;
+        Ok(TagRename::Renamed(result.rows_affected()))
+    }
+
+    async fn combine_tags(
+        &self,
+        conn: &mut SqliteConnection,
+        owner: &Uuid,
+        tags: &[Tag],
+        into: &Tag,
+    ) -> Result<TagMerge, sqlx::Error> {
+        for tag in tags {
+            if self.count_tag(&mut *conn, owner, tag).await? == 0 {
+                return Ok(TagMerge::NotFound(tag.clone()));
+            }
+        }
+        for tag in tags.iter().filter(|tag| *tag != into) {
+            let query = sqlx::query(
+                r#"
+                UPDATE tasks
+                SET version = version + 1
+                WHERE owner_id = ?2
+                    AND deleted_at IS NULL
+                    AND id IN (SELECT task_id FROM task_tags WHERE tag = ?1)
+                "#,
+            )
+            .bind(tag.to_string())
+            .bind(owner);
+            conn.execute(query).await?;
+            let query = sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO task_tags (task_id, tag)
+                SELECT task_id, ?2
+                FROM task_tags
+                WHERE tag = ?1
+                    AND task_id IN (SELECT id FROM tasks WHERE owner_id = ?3 AND deleted_at IS NULL)
+                "#,
+            )
+            .bind(tag.to_string())
+            .bind(into.to_string())
+            .bind(owner);
+            conn.execute(query).await?;
+            let query = sqlx::query(
+                r#"
+                DELETE FROM task_tags
+                WHERE tag = ?1
+                    AND task_id IN (SELECT id FROM tasks WHERE owner_id = ?2 AND deleted_at IS NULL)
+                "#,
+            )
+            .bind(tag.to_string())
+            .bind(owner);
+            conn.execute(query).await?;
+        }
+        let tasks = self.count_tag(&mut *conn, owner, into).await?;
+        Ok(TagMerge::Merged(tasks as u64))
+    }
+
+    /// Move the task with `id` and its subtasks to the trash at `now`, so that they are restored
+    /// together. Returns whether the task was found.
+    async fn remove_task(
+        &self,
+        conn: &mut SqliteConnection,
+        owner: &Uuid,
+        id: &Uuid,
+        version: Option<i64>,
+        now: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let query = sqlx::query(
+            r#"
+            WITH RECURSIVE subtree (id) AS (
+                SELECT id
+                FROM tasks
+                WHERE id = ?1
+                    AND owner_id = ?2
+                    AND deleted_at IS NULL
+                    AND (?3 IS NULL OR version = ?3)
+                UNION
+                SELECT t.id
+                FROM tasks t
+                JOIN subtree s ON t.parent_id = s.id
+                WHERE t.deleted_at IS NULL
+            )
+            UPDATE tasks
+            SET deleted_at = ?4, updated_at = ?4, version = version + 1
+            WHERE id IN (SELECT id FROM subtree)
+            "#,
+        )
+        .bind(id)
+        .bind(owner)
+        .bind(version)
+        .bind(now);
+        let result = conn.execute(query).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The ids of the tasks that were moved to the trash at `deleted_at` along with the task with
+    /// id `task_id`, or with the list with id `list_id`, including that task.
+    async fn find_trashed_together(
+        &self,
+        conn: &mut SqliteConnection,
+        task_id: Option<&Uuid>,
+        list_id: Option<&Uuid>,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar::<_, Uuid>(
+            r#"
+            WITH RECURSIVE subtree (id) AS (
+                SELECT id FROM tasks WHERE (id = ?1 OR list_id = ?2) AND deleted_at = ?3
+                UNION
+                SELECT t.id
+                FROM tasks t
+                JOIN subtree s ON t.parent_id = s.id
+                WHERE t.deleted_at = ?3
+            )
+            SELECT id FROM subtree
+            "#,
+        )
+        .bind(task_id)
+        .bind(list_id)
+        .bind(deleted_at)
+        .fetch_all(conn)
+        .await
+    }
+
+    /// The title of one of the tasks with `ids` that cannot be restored from the trash because it
+    /// is incomplete while another incomplete task has the same title.
+    async fn find_restore_clash(
+        &self,
+        conn: &mut SqliteConnection,
+        ids: &[Uuid],
+    ) -> Result<Option<String>, sqlx::Error> {
+        let mut query = QueryBuilder::<sqlx::Sqlite>::new(
+            r#"
+            SELECT t.title
+            FROM tasks t
+            WHERE NOT t.completed AND EXISTS (
+                SELECT 1
+                FROM tasks o
+                WHERE o.owner_id = t.owner_id
+                    AND lower(o.title) = lower(t.title)
+                    AND NOT o.completed
+                    AND o.deleted_at IS NULL
+            )
+            AND t.id IN (
+            "#,
+        );
+        let mut separated = query.separated(", ");
+        for id in ids {
+            separated.push_bind(*id);
+        }
+        query.push(") LIMIT 1");
+        query.build_query_scalar().fetch_optional(conn).await
+    }
+
+    /// Take the tasks with `ids` out of the trash.
+    async fn undelete_tasks(
+        &self,
+        conn: &mut SqliteConnection,
+        ids: &[Uuid],
+        now: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let mut query = QueryBuilder::<sqlx::Sqlite>::new(
+            "UPDATE tasks SET deleted_at = NULL, version = version + 1, updated_at = ",
+        );
+        query.push_bind(now).push(" WHERE id IN (");
+        let mut separated = query.separated(", ");
+        for id in ids {
+            separated.push_bind(*id);
+        }
+        query.push(")");
+        query.build().execute(conn).await?;
+        Ok(())
+    }
+
+    /// Restore the task with `id` from the trash at `now`, along with the subtasks deleted with
+    /// it.
+    async fn undelete_task(
+        &self,
+        conn: &mut SqliteConnection,
+        owner: &Uuid,
+        id: &Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<TaskRestore, sqlx::Error> {
+        let trashed = sqlx::query_as::<_, (Option<Uuid>, Option<Uuid>, DateTime<Utc>)>(
+            r#"
+            SELECT parent_id, list_id, deleted_at
+            FROM tasks
+            WHERE id = ?1 AND owner_id = ?2 AND deleted_at IS NOT NULL
+            "#,
+        )
+        .bind(id)
+        .bind(owner)
+        .fetch_optional(&mut *conn)
+        .await?;
+        let Some((parent_id, list_id, deleted_at)) = trashed else {
+            return Ok(TaskRestore::NotFound);
+        };
+        if let Some(parent_id) = parent_id {
+            let deleted = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS (SELECT 1 FROM tasks WHERE id = ?1 AND deleted_at IS NOT NULL)",
+            )
+            .bind(parent_id)
+            .fetch_one(&mut *conn)
+            .await?;
+            if deleted {
+                return Ok(TaskRestore::ParentDeleted(parent_id));
+            }
+        }
+        if let Some(list_id) = list_id {
+            let deleted = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS (SELECT 1 FROM task_lists WHERE id = ?1 AND deleted_at IS NOT NULL)",
+            )
+            .bind(list_id)
+            .fetch_one(&mut *conn)
+            .await?;
+            if deleted {
+                return Ok(TaskRestore::ListDeleted(list_id));
+            }
+        }
+        let ids = self
+            .find_trashed_together(&mut *conn, Some(id), None, deleted_at)
+            .await?;
+        if let Some(title) = self.find_restore_clash(&mut *conn, &ids).await? {
+            return Ok(TaskRestore::Duplicate(title));
+        }
+        self.undelete_tasks(&mut *conn, &ids, now).await?;
+        let row = self.find_task(&mut *conn, Some(owner), id).await?;
+        Ok(row.map_or(TaskRestore::NotFound, |row| {
+            TaskRestore::Restored(Box::new(row))
+        }))
+    }
+
+    /// Permanently delete the tasks and lists in the trash of `owner`, or of every user if it is
+    /// `None`, that were deleted before `deleted_before`, if given.
+    async fn remove_trash(
+        &self,
+        owner: Option<&Uuid>,
+        deleted_before: Option<DateTime<Utc>>,
+    ) -> Result<PurgedTrash, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        // Tasks left in a list are deleted with it, whenever they were moved to the trash. They
+        // are counted up front, since subtasks deleted by cascade are not.
+        let purged = r#"
+            FROM tasks
+            WHERE (?1 IS NULL OR owner_id = ?1) AND (
+                deleted_at < COALESCE(?2, '~')
+                OR list_id IN (
+                    SELECT id
+                    FROM task_lists
+                    WHERE (?1 IS NULL OR owner_id = ?1) AND deleted_at < COALESCE(?2, '~')
+                )
+            )
+        "#;
+        let tasks = sqlx::query_scalar::<_, i64>(&format!("SELECT count(*) {}", purged))
+            .bind(owner)
+            .bind(deleted_before)
+            .fetch_one(&mut *tx)
+            .await?;
+        let delete = format!("DELETE {}", purged);
+        let query = sqlx::query(&delete).bind(owner).bind(deleted_before);
+        tx.execute(query).await?;
+        let query = sqlx::query(
+            r#"
+            DELETE FROM task_lists
+            WHERE (?1 IS NULL OR owner_id = ?1) AND deleted_at < COALESCE(?2, '~')
+            "#,
+        )
+        .bind(owner)
+        .bind(deleted_before);
+        let lists = tx.execute(query).await?.rows_affected();
+        tx.commit().await?;
+        Ok(PurgedTrash {
+            tasks: tasks as u64,
+            lists,
+        })
+    }
+
+    async fn save_list(
+        &self,
+        owner: &Uuid,
+        req: &CreateTaskListRequest,
+        now: DateTime<Utc>,
+    ) -> Result<TaskListRow, sqlx::Error> {
+        sqlx::query_as::<_, TaskListRow>(
+            r#"
+            INSERT INTO task_lists (
+                id, title, colour, position, archived, created_at, updated_at, owner_id
+            )
+            VALUES (
+                ?1, ?2, ?3,
+                COALESCE(?4, (
+                    SELECT COALESCE(max(position) + 1, 0) FROM task_lists WHERE owner_id = ?6
+                )),
+                FALSE, ?5, ?5, ?6
+            )
+            RETURNING id, title, colour, position, archived, created_at, owner_id, 'owner' AS role
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(req.title().to_string())
+        .bind(req.colour().map(TaskListColour::to_string))
+        .bind(req.position())
+        .bind(now)
+        .bind(owner)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn find_list(
+        &self,
+        conn: &mut SqliteConnection,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> Result<Option<TaskListRow>, sqlx::Error> {
+        sqlx::query_as::<_, TaskListRow>(
+            r#"
+            SELECT id, title, colour, position, archived, created_at, owner_id, 'owner' AS role
+            FROM task_lists
+            WHERE id = ?1 AND owner_id = ?2 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(owner)
+        .fetch_optional(conn)
+        .await
+    }
+
+    /// Find the list with `id` and the role of `user` in it, among the lists of `user` and
+    /// those shared with them.
+    async fn find_visible_list(
+        &self,
+        conn: &mut SqliteConnection,
+        user: &Uuid,
+        id: &Uuid,
+    ) -> Result<Option<TaskListRow>, sqlx::Error> {
+        sqlx::query_as::<_, TaskListRow>(
+            r#"
+            SELECT
+                l.id, l.title, l.colour, l.position, l.archived, l.created_at, l.owner_id,
+                CASE WHEN l.owner_id = ?2 THEN 'owner' ELSE m.role END AS role
+            FROM task_lists l
+            LEFT JOIN task_list_members m ON m.list_id = l.id AND m.user_id = ?2
+            WHERE l.id = ?1 AND l.deleted_at IS NULL AND (l.owner_id = ?2 OR m.user_id IS NOT NULL)
+            "#,
+        )
+        .bind(id)
+        .bind(user)
+        .fetch_optional(conn)
+        .await
+    }
+
+    async fn find_lists(
+        &self,
+        user: &Uuid,
+        include_archived: bool,
+    ) -> Result<Vec<TaskListRow>, sqlx::Error> {
+        sqlx::query_as::<_, TaskListRow>(
+            r#"
+            SELECT
+                l.id, l.title, l.colour, l.position, l.archived, l.created_at, l.owner_id,
+                CASE WHEN l.owner_id = ?1 THEN 'owner' ELSE m.role END AS role
+            FROM task_lists l
+            LEFT JOIN task_list_members m ON m.list_id = l.id AND m.user_id = ?1
+            WHERE (l.owner_id = ?1 OR m.user_id IS NOT NULL)
+                AND l.deleted_at IS NULL
+                AND (?2 OR NOT l.archived)
+            ORDER BY l.position, l.created_at, l.id
+            "#,
+        )
+        .bind(user)
+        .bind(include_archived)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn modify_list(
+        &self,
+        conn: &mut SqliteConnection,
+        list: &TaskList,
+        now: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let query = sqlx::query(
+            r#"
+            UPDATE task_lists
+            SET title = ?2, colour = ?3, position = ?4, archived = ?5, updated_at = ?6
+            WHERE id = ?1
+            "#,
+        )
+        .bind(list.id)
+        .bind(list.title.to_string())
+        .bind(list.colour.as_ref().map(TaskListColour::to_string))
+        .bind(list.position)
+        .bind(list.archived)
+        .bind(now);
+        conn.execute(query).await?;
+        Ok(())
+    }
+
+    /// Move a list to the trash at `now` after dealing with its tasks according to `mode`, and
+    /// revoke the API tokens restricted to it.
+    async fn remove_list(
+        &self,
+        conn: &mut SqliteConnection,
+        owner: &Uuid,
+        id: &Uuid,
+        mode: DeleteTaskListMode,
+        now: DateTime<Utc>,
+    ) -> Result<ListRemoval, sqlx::Error> {
+        if self.find_list(&mut *conn, owner, id).await?.is_none() {
+            return Ok(ListRemoval::NotFound);
+        }
+
+        match mode {
+            DeleteTaskListMode::OnlyIfEmpty => {
+                let tasks = sqlx::query_scalar::<_, i64>(
+                    "SELECT count(*) FROM tasks WHERE list_id = ?1 AND deleted_at IS NULL",
+                )
+                .bind(id)
+                .fetch_one(&mut *conn)
+                .await?;
+                if tasks > 0 {
+                    return Ok(ListRemoval::NotEmpty(tasks as u64));
+                }
+            }
+            DeleteTaskListMode::Cascade => {
+                // Subtasks in other lists go to the trash with their parents
+                let query = sqlx::query(
+                    r#"
+                    WITH RECURSIVE subtree (id) AS (
+                        SELECT id FROM tasks WHERE list_id = ?1 AND deleted_at IS NULL
+                        UNION
+                        SELECT t.id
+                        FROM tasks t
+                        JOIN subtree s ON t.parent_id = s.id
+                        WHERE t.deleted_at IS NULL
+                    )
+                    UPDATE tasks
+                    SET deleted_at = ?2, updated_at = ?2, version = version + 1
+                    WHERE id IN (SELECT id FROM subtree)
+                    "#,
+                )
+                .bind(id)
+                .bind(now);
+                conn.execute(query).await?;
+            }
+            DeleteTaskListMode::MoveToInbox => {
+                // Only the owner can be assigned tasks in their inbox
+                let query = sqlx::query(
+                    r#"
+                    UPDATE tasks
+                    SET list_id = NULL,
+                        assignee_id = CASE WHEN assignee_id = owner_id THEN assignee_id END,
+                        updated_at = ?2,
+                        version = version + 1
+                    WHERE list_id = ?1 AND deleted_at IS NULL
+                    "#,
+                )
+                .bind(id)
+                .bind(now);
+                conn.execute(query).await?;
+            }
+        }
+        let query =
+            sqlx::query("UPDATE task_lists SET deleted_at = ?2, updated_at = ?2 WHERE id = ?1")
+                .bind(id)
+                .bind(now);
+        conn.execute(query).await?;
+        let query = sqlx::query("DELETE FROM api_tokens WHERE list_id = ?1").bind(id);
+        conn.execute(query).await?;
+        Ok(ListRemoval::Removed)
+    }
+
+    /// The lists in the trash that belong to `owner`, most recently deleted first.
+    async fn find_trashed_lists(
+        &self,
+        owner: &Uuid,
+    ) -> Result<Vec<TrashedTaskListRow>, sqlx::Error> {
+        sqlx::query_as::<_, TrashedTaskListRow>(
+            r#"
+            SELECT
+                id, title, colour, position, archived, created_at, owner_id, 'owner' AS role,
+                deleted_at
+            FROM task_lists
+            WHERE owner_id = ?1 AND deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC, position, id
+            "#,
+        )
+        .bind(owner)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Restore the list with `id` from the trash at `now`, along with the tasks deleted with it.
+    async fn undelete_list(
+        &self,
+        conn: &mut SqliteConnection,
+        owner: &Uuid,
+        id: &Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<ListRestore, sqlx::Error> {
+        let trashed = sqlx::query_as::<_, TrashedTaskListRow>(
+            r#"
+            SELECT
+                id, title, colour, position, archived, created_at, owner_id, 'owner' AS role,
+                deleted_at
+            FROM task_lists
+            WHERE id = ?1 AND owner_id = ?2 AND deleted_at IS NOT NULL
+            "#,
+        )
+        .bind(id)
+        .bind(owner)
+        .fetch_optional(&mut *conn)
+        .await?;
+        let Some(TrashedTaskListRow { list, deleted_at }) = trashed else {
+            return Ok(ListRestore::NotFound);
+        };
+        let query =
+            sqlx::query("UPDATE task_lists SET deleted_at = NULL, updated_at = ?2 WHERE id = ?1")
+                .bind(id)
+                .bind(now);
+        match conn.execute(query).await {
+            Ok(_) => {}
+            Err(e) if is_unique_constraint_violation(&e) => {
+                return Ok(ListRestore::Duplicate(list.title));
+            }
+            Err(e) => return Err(e),
+        }
+        let ids = self
+            .find_trashed_together(&mut *conn, None, Some(id), deleted_at)
+            .await?;
+        if let Some(title) = self.find_restore_clash(&mut *conn, &ids).await? {
+            return Ok(ListRestore::TaskDuplicate(title));
+        }
+        self.undelete_tasks(&mut *conn, &ids, now).await?;
+        Ok(ListRestore::Restored(list))
+    }
+
+    /// The creator of the list with `id` followed by the users it is shared with, or `None` if
+    /// `owner` has no such list.
+    async fn find_members(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> Result<Option<Vec<MemberRow>>, sqlx::Error> {
+        let creator = sqlx::query_as::<_, MemberRow>(
+            r#"
+            SELECT u.id AS user_id, u.email, 'owner' AS role, l.created_at AS added_at
+            FROM task_lists l
+            JOIN users u ON u.id = l.owner_id
+            WHERE l.id = ?1 AND l.owner_id = ?2 AND l.deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(owner)
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some(creator) = creator else {
+            return Ok(None);
+        };
+        let members = sqlx::query_as::<_, MemberRow>(
+            r#"
+            SELECT m.user_id, u.email, m.role, m.added_at
+            FROM task_list_members m
+            JOIN users u ON u.id = m.user_id
+            WHERE m.list_id = ?1
+            ORDER BY m.added_at, u.email
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(Some(std::iter::once(creator).chain(members).collect()))
+    }
+
+    /// Share the list with `id` with the user registered under the email address in the `req`,
+    /// or change their role if it is already shared with them.
+    async fn save_member(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &ShareTaskListRequest,
+        now: DateTime<Utc>,
+    ) -> Result<MemberSave, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        if self.find_list(&mut conn, owner, id).await?.is_none() {
+            return Ok(MemberSave::ListNotFound);
+        }
+        let Some(user) = self.find_user(req.email()).await? else {
+            return Ok(MemberSave::UserNotFound);
+        };
+        if user.id == *owner {
+            return Ok(MemberSave::Creator);
+        }
+        let row = sqlx::query_as::<_, MemberRow>(
+            r#"
+            INSERT INTO task_list_members (list_id, user_id, role, added_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT (list_id, user_id) DO UPDATE SET role = excluded.role
+            RETURNING user_id, ?5 AS email, role, added_at
+            "#,
+        )
+        .bind(id)
+        .bind(user.id)
+        .bind(req.role().as_str())
+        .bind(now)
+        .bind(user.email)
+        .fetch_one(&mut *conn)
+        .await?;
+        Ok(MemberSave::Saved(row))
+    }
+
+    /// Stop sharing the list with `id` with the user with id `member`, and unassign them from its
+    /// tasks. Returns whether the list exists, and whether it was shared with them.
+    async fn remove_member(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        member: &Uuid,
+    ) -> Result<(bool, bool), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        if self.find_list(&mut tx, owner, id).await?.is_none() {
+            return Ok((false, false));
+        }
+        let query =
+            sqlx::query("DELETE FROM task_list_members WHERE list_id = ?1 AND user_id = ?2")
+                .bind(id)
+                .bind(member);
+        let result = tx.execute(query).await?;
+        let query = sqlx::query(
+            r#"
+            UPDATE tasks
+            SET assignee_id = NULL, version = version + 1
+            WHERE list_id = ?1 AND assignee_id = ?2
+            "#,
+        )
+        .bind(id)
+        .bind(member);
+        tx.execute(query).await?;
+        tx.commit().await?;
+        Ok((true, result.rows_affected() > 0))
+    }
+
+    async fn save_user(
+        &self,
+        email: &EmailAddress,
+        password_hash: &PasswordHash,
+        now: DateTime<Utc>,
+    ) -> Result<UserRow, sqlx::Error> {
+        sqlx::query_as::<_, UserRow>(
+            r#"
+            INSERT INTO users (id, email, password_hash, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?4)
+            RETURNING id, email, password_hash, created_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(email.as_str())
+        .bind(password_hash.as_str())
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn find_user(&self, email: &EmailAddress) -> Result<Option<UserRow>, sqlx::Error> {
+        sqlx::query_as::<_, UserRow>(
+            "SELECT id, email, password_hash, created_at FROM users WHERE email = ?1",
+        )
+        .bind(email.as_str())
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Store a session, clearing out the expired sessions of the same user on the way.
+    async fn save_session(&self, session: &Session, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let query =
+            sqlx::query("DELETE FROM user_sessions WHERE user_id = ?1 AND expires_at <= ?2")
+                .bind(session.user().id())
+                .bind(now);
+        tx.execute(query).await?;
+        let query = sqlx::query(
+            r#"
+            INSERT INTO user_sessions (token_digest, user_id, created_at, expires_at)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+        )
+        .bind(session.token().digest())
+        .bind(session.user().id())
+        .bind(now)
+        .bind(session.expires_at());
+        tx.execute(query).await?;
+        tx.commit().await
+    }
+
+    async fn find_session_user(
+        &self,
+        token: &SessionToken,
+        now: DateTime<Utc>,
+    ) -> Result<Option<UserRow>, sqlx::Error> {
+        sqlx::query_as::<_, UserRow>(
+            r#"
+            SELECT u.id, u.email, u.password_hash, u.created_at
+            FROM user_sessions s
+            JOIN users u ON u.id = s.user_id
+            WHERE s.token_digest = ?1 AND s.expires_at > ?2
+            "#,
+        )
+        .bind(token.digest())
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn remove_session(&self, token: &SessionToken) -> Result<(), sqlx::Error> {
+        let query =
+            sqlx::query("DELETE FROM user_sessions WHERE token_digest = ?1").bind(token.digest());
+        self.pool.execute(query).await?;
+        Ok(())
+    }
+
+    /// Store an API token, unless it is restricted to a list that `user` does not have.
+    async fn save_api_token(&self, user: &Uuid, token: &NewApiToken) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let scope = token.token().scope();
+        if let Some(list_id) = scope.list_id() {
+            if self.find_list(&mut tx, user, &list_id).await?.is_none() {
+                return Ok(false);
+            }
+        }
+        let query = sqlx::query(
+            r#"
+            INSERT INTO api_tokens (id, user_id, token_digest, name, access, list_id, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+        )
+        .bind(token.token().id())
+        .bind(user)
+        .bind(token.secret().digest())
+        .bind(token.token().name().as_str())
+        .bind(scope.access().as_str())
+        .bind(scope.list_id())
+        .bind(token.token().created_at());
+        tx.execute(query).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn find_api_tokens(&self, user: &Uuid) -> Result<Vec<ApiTokenRow>, sqlx::Error> {
+        sqlx::query_as::<_, ApiTokenRow>(
+            r#"
+            SELECT id, name, access, list_id, created_at, last_used_at
+            FROM api_tokens
+            WHERE user_id = ?1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(user)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn remove_api_token(&self, user: &Uuid, id: &Uuid) -> Result<bool, sqlx::Error> {
+        let query = sqlx::query("DELETE FROM api_tokens WHERE id = ?1 AND user_id = ?2")
+            .bind(id)
+            .bind(user);
+        let result = self.pool.execute(query).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the use of the API token with `secret` and fetch its user and scope.
+    async fn touch_api_token(
+        &self,
+        secret: &ApiTokenSecret,
+        now: DateTime<Utc>,
+    ) -> Result<Option<(UserRow, UsedApiTokenRow)>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let used = sqlx::query_as::<_, UsedApiTokenRow>(
+            r#"
+            UPDATE api_tokens
+            SET last_used_at = ?2
+            WHERE token_digest = ?1
+            RETURNING user_id, access, list_id
+            "#,
+        )
+        .bind(secret.digest())
+        .bind(now)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(used) = used else {
+            return Ok(None);
+        };
+        let user = sqlx::query_as::<_, UserRow>(
+            "SELECT id, email, password_hash, created_at FROM users WHERE id = ?1",
+        )
+        .bind(used.user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(Some((user, used)))
+    }
+
+    /// Claim the key of `req`. Returns `None` if it was claimed, or else the row of the request
+    /// that claimed it first, which is `None` if that request released the key meanwhile.
+    async fn save_idempotency_key(
+        &self,
+        req: &IdempotentRequest,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<Option<IdempotencyKeyRow>>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let query = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at <= ?1").bind(now);
+        tx.execute(query).await?;
+        let query = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (owner_digest, key, fingerprint, created_at, expires_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT (owner_digest, key) DO NOTHING
+            "#,
+        )
+        .bind(req.owner())
+        .bind(req.key().as_str())
+        .bind(req.fingerprint())
+        .bind(now)
+        .bind(expires_at);
+        let claimed = tx.execute(query).await?.rows_affected() > 0;
+        let existing = if claimed {
+            None
+        } else {
+            let row = sqlx::query_as::<_, IdempotencyKeyRow>(
+                r#"
+                SELECT
+                    fingerprint, response_status, response_content_type, response_headers,
+                    response_body
+                FROM idempotency_keys
+                WHERE owner_digest = ?1 AND key = ?2
+                "#,
+            )
+            .bind(req.owner())
+            .bind(req.key().as_str())
+            .fetch_optional(&mut *tx)
+            .await?;
+            Some(row)
+        };
+        tx.commit().await?;
+        Ok(existing)
+    }
+
+    async fn save_response(
+        &self,
+        req: &IdempotentRequest,
+        response: &StoredResponse,
+    ) -> Result<(), sqlx::Error> {
+        let query = sqlx::query(
+            r#"
+            UPDATE idempotency_keys
+            SET
+                response_status = ?3, response_content_type = ?4, response_headers = ?5,
+                response_body = ?6
+            WHERE owner_digest = ?1 AND key = ?2
+            "#,
+        )
+        .bind(req.owner())
+        .bind(req.key().as_str())
+        .bind(i64::from(response.status()))
+        .bind(response.content_type())
+        .bind(header_lines(response))
+        .bind(response.body());
+        self.pool.execute(query).await?;
+        Ok(())
+    }
+
+    async fn remove_idempotency_key(&self, req: &IdempotentRequest) -> Result<(), sqlx::Error> {
+        let query =
+            sqlx::query("DELETE FROM idempotency_keys WHERE owner_digest = ?1 AND key = ?2")
+                .bind(req.owner())
+                .bind(req.key().as_str());
+        self.pool.execute(query).await?;
+        Ok(())
+    }
+
+    async fn ready(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT 1").fetch_one(&self.pool).await?;
+        Ok(())
+    }
+}
+
+impl ReminderRepository for Sqlite {
+    #[instrument(skip(self, req))]
+    async fn create_task(
+        &self,
+        owner: &Uuid,
+        req: &CreateTaskRequest,
+    ) -> Result<Task, CreateTaskError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start SQLite transaction")?;
+
+        // Foreign keys cannot check the owner, so look the list and parent up front
+        if let Some(list_id) = req.list_id() {
+            self.find_list(&mut tx, owner, &list_id)
+                .await
+                .with_context(|| format!("failed to fetch task list with id: {}", list_id))?
+                .ok_or(CreateTaskError::ListNotFound { id: list_id })?;
+        }
+        if let Some(parent_id) = req.parent_id() {
+            self.find_task(&mut tx, Some(owner), &parent_id)
+                .await
+                .with_context(|| format!("failed to fetch task with id: {}", parent_id))?
+                .ok_or(CreateTaskError::ParentNotFound { id: parent_id })?;
+        }
+        let task = self.insert_task(&mut tx, owner, req).await.map_err(|e| {
+            if is_unique_constraint_violation(&e) {
+                CreateTaskError::Duplicate {
+                    title: req.title().clone(),
+                }
+            } else if let Some(id) = req.list_id().filter(|_| is_foreign_key_violation(&e)) {
+                CreateTaskError::ListNotFound { id }
+            } else {
+                anyhow!(e)
+                    .context(format!("failed to save task with title: {:?}", req.title()))
+                    .into()
+            }
+        })?;
+        let clash = self
+            .sync_ancestors(&mut tx, task.parent_id, task.updated_by, task.created_at)
+            .await
+            .with_context(|| format!("failed to reopen the parents of task: {}", task.id))?;
+        if let Some(title) = clash {
+            let title = TaskTitle::new(&title).with_context(|| {
+                format!("invalid title stored for a parent of task {}", task.id)
+            })?;
+            return Err(CreateTaskError::Duplicate { title });
+        }
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(task)
+    }
+
+    #[instrument(skip(self))]
+    async fn get_task(&self, user: &Uuid, id: &Uuid) -> Result<Task, GetTaskError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .context("failed to acquire SQLite connection")?;
+        let row = self
+            .find_visible_task(&mut conn, user, id)
+            .await
+            .with_context(|| format!("failed to fetch task with id: {}", id))?
+            .ok_or(GetTaskError::NotFound { id: *id })?;
+
+        Ok(Task::try_from(row)?)
+    }
+
+    #[instrument(skip(self, req))]
+    async fn list_tasks(
+        &self,
+        user: &Uuid,
+        req: &ListTasksRequest,
+    ) -> Result<TaskPage, ListTasksError> {
+        let rows = self
+            .find_tasks(user, req)
+            .await
+            .context("failed to fetch tasks")?;
+        let tasks = rows
+            .into_iter()
+            .map(Task::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(req.page(tasks))
+    }
+
+    #[instrument(skip(self))]
+    async fn list_subtasks(&self, owner: &Uuid, id: &Uuid) -> Result<Vec<Task>, ListTasksError> {
+        let rows = self
+            .find_subtasks(owner, id)
+            .await
+            .with_context(|| format!("failed to fetch subtasks of task with id: {}", id))?;
+        let subtasks = rows
+            .into_iter()
+            .map(Task::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(subtasks)
+    }
+
+    #[instrument(skip(self, req))]
+    async fn update_task(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &UpdateTaskRequest,
+    ) -> Result<Task, UpdateTaskError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start SQLite transaction")?;
+
+        let row = self
+            .find_task(&mut tx, Some(owner), id)
+            .await
+            .with_context(|| format!("failed to fetch task with id: {}", id))?
+            .ok_or(UpdateTaskError::NotFound { id: *id })?;
+        let mut task = Task::try_from(row)?;
+        if let Some(version) = req.version().filter(|&v| v != task.version) {
+            return Err(UpdateTaskError::Conflict { id: *id, version });
+        }
+        let before = task.clone();
+        let version = task.version;
+        task.version += 1;
+        if let Some(title) = req.title() {
+            task.title = title.clone();
+        }
+        if let Some(completed) = req.completed().filter(|&c| c != task.completed) {
+            task.completed = completed;
+            task.completed_by = req.updated_by().filter(|_| completed);
+        }
+        if let Some(user) = req.updated_by() {
+            task.updated_by = Some(user);
+        }
+        if let Some(priority) = req.priority() {
+            task.priority = priority;
+        }
+        if let Some(notes) = req.notes() {
+            task.notes = notes.cloned();
+        }
+        if let Some(due_at) = req.due_at() {
+            task.due_at = due_at.copied();
+        }
+        if let Some(remind_at) = req.remind_at() {
+            task.remind_at = remind_at.clone();
+        }
+        if let Some(recurrence) = req.recurrence() {
+            task.recurrence = recurrence.cloned();
+        }
+        if let Some(list_id) = req.list_id() {
+            if let Some(list_id) = list_id {
+                self.find_list(&mut tx, owner, &list_id)
+                    .await
+                    .with_context(|| format!("failed to fetch task list with id: {}", list_id))?
+                    .ok_or(UpdateTaskError::ListNotFound { id: list_id })?;
+            }
+            task.list_id = list_id;
+        }
+        if let Some(parent_id) = req.parent_id() {
+            if let Some(parent_id) = parent_id {
+                self.find_task(&mut tx, Some(owner), &parent_id)
+                    .await
+                    .with_context(|| format!("failed to fetch task with id: {}", parent_id))?
+                    .ok_or(UpdateTaskError::ParentNotFound { id: parent_id })?;
+            }
+            if task.parent_id != parent_id {
+                task.parent_id = parent_id;
+                task.position = self
+                    .next_position(&mut tx, owner, parent_id)
+                    .await
+                    .context("failed to position task")?;
+            }
+        }
+        if let Some(position) = req.position() {
+            task.position = position;
+        }
+        if let Some(tags) = req.tags() {
+            task.tags = tags.clone();
+        }
+        if let Some(assignee_id) = req.assignee_id() {
+            task.assignee_id = assignee_id;
+        }
+
+        let now = Utc::now();
+        let modified = self.modify_task(&mut tx, &task, now).await.map_err(|e| {
+            if is_unique_constraint_violation(&e) {
+                UpdateTaskError::Duplicate {
+                    title: task.title.clone(),
+                }
+            } else if let Some(id) = task.list_id.filter(|_| is_foreign_key_violation(&e)) {
+                UpdateTaskError::ListNotFound { id }
+            } else {
+                anyhow!(e)
+                    .context(format!("failed to update task with id: {}", id))
+                    .into()
+            }
+        })?;
+        if !modified {
+            return Err(UpdateTaskError::Conflict { id: *id, version });
+        }
+
+        self.sync_reminders(&mut tx, &task)
+            .await
+            .with_context(|| format!("failed to schedule reminders for task: {}", id))?;
+        if req.tags().is_some() {
+            self.save_tags(&mut tx, &task)
+                .await
+                .with_context(|| format!("failed to save tags of task: {}", id))?;
+        }
+        if !before.completed && task.completed {
+            self.complete_subtasks(&mut tx, id, req.updated_by(), now)
+                .await
+                .with_context(|| format!("failed to complete subtasks of task: {}", id))?;
+            if let Some(mut next) = task.next_occurrence(now) {
+                if let Some(user) = req.updated_by() {
+                    next = next.with_updated_by(user);
+                }
+                self.insert_task(&mut tx, owner, &next)
+                    .await
+                    .map_err(|e| -> UpdateTaskError {
+                        if is_unique_constraint_violation(&e) {
+                            UpdateTaskError::Duplicate {
+                                title: next.title().clone(),
+                            }
+                        } else {
+                            anyhow!(e)
+                                .context(format!("failed to create next occurrence of task {}", id))
+                                .into()
+                        }
+                    })?;
+            }
+        }
+        if req.completed().is_some() || req.parent_id().is_some() {
+            let mut clash = None;
+            if before.parent_id != task.parent_id {
+                clash = self
+                    .sync_ancestors(&mut tx, before.parent_id, req.updated_by(), now)
+                    .await
+                    .with_context(|| format!("failed to update the parents of task: {}", id))?;
+            }
+            if clash.is_none() {
+                clash = self
+                    .sync_ancestors(&mut tx, task.parent_id, req.updated_by(), now)
+                    .await
+                    .with_context(|| format!("failed to update the parents of task: {}", id))?;
+            }
+            if let Some(title) = clash {
+                let title = TaskTitle::new(&title)
+                    .with_context(|| format!("invalid title stored for a parent of task {}", id))?;
+                return Err(UpdateTaskError::Duplicate { title });
+            }
+        }
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(task)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_task(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        version: Option<i64>,
+        updated_by: &Uuid,
+    ) -> Result<(), DeleteTaskError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start SQLite transaction")?;
+
+        let row = self
+            .find_task(&mut tx, Some(owner), id)
+            .await
+            .with_context(|| format!("failed to fetch task with id: {}", id))?
+            .ok_or(DeleteTaskError::NotFound { id: *id })?;
+        let now = Utc::now();
+        let deleted = self
+            .remove_task(&mut tx, owner, id, version, now)
+            .await
+            .with_context(|| format!("failed to delete task with id: {}", id))?;
+
+        if !deleted {
+            // The task exists, so it was kept because it changed since `version`
+            return Err(match version {
+                Some(version) => DeleteTaskError::Conflict { id: *id, version },
+                None => DeleteTaskError::NotFound { id: *id },
+            });
+        }
+        // Deleting a subtask can only complete its parents, which never clashes
+        self.sync_ancestors(&mut tx, row.parent_id, Some(*updated_by), now)
+            .await
+            .with_context(|| format!("failed to update the parents of task: {}", id))?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, req))]
+    async fn create_list(
+        &self,
+        owner: &Uuid,
+        req: &CreateTaskListRequest,
+    ) -> Result<TaskList, CreateTaskListError> {
+        let row = self.save_list(owner, req, Utc::now()).await.map_err(|e| {
+            if is_unique_constraint_violation(&e) {
+                CreateTaskListError::Duplicate {
+                    title: req.title().clone(),
+                }
+            } else {
+                anyhow!(e)
+                    .context(format!(
+                        "failed to save task list with title: {:?}",
+                        req.title()
+                    ))
+                    .into()
+            }
+        })?;
+
+        Ok(TaskList::try_from(row)?)
+    }
+
+    #[instrument(skip(self))]
+    async fn get_list(&self, user: &Uuid, id: &Uuid) -> Result<TaskList, GetTaskListError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .context("failed to acquire SQLite connection")?;
+        let row = self
+            .find_visible_list(&mut conn, user, id)
+            .await
+            .with_context(|| format!("failed to fetch task list with id: {}", id))?
+            .ok_or(GetTaskListError::NotFound { id: *id })?;
+
+        Ok(TaskList::try_from(row)?)
+    }
+
+    #[instrument(skip(self))]
+    async fn list_lists(
+        &self,
+        user: &Uuid,
+        include_archived: bool,
+    ) -> Result<Vec<TaskList>, ListTaskListsError> {
+        let rows = self
+            .find_lists(user, include_archived)
+            .await
+            .context("failed to fetch task lists")?;
+        let lists = rows
+            .into_iter()
+            .map(TaskList::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(lists)
+    }
+
+    #[instrument(skip(self, req))]
+    async fn update_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &UpdateTaskListRequest,
+    ) -> Result<TaskList, UpdateTaskListError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start SQLite transaction")?;
+
+        let row = self
+            .find_list(&mut tx, owner, id)
+            .await
+            .with_context(|| format!("failed to fetch task list with id: {}", id))?
+            .ok_or(UpdateTaskListError::NotFound { id: *id })?;
+        let mut list = TaskList::try_from(row)?;
+        if let Some(title) = req.title() {
+            list.title = title.clone();
+        }
+        if let Some(colour) = req.colour() {
+            list.colour = colour.cloned();
+        }
+        if let Some(position) = req.position() {
+            list.position = position;
+        }
+        if let Some(archived) = req.archived() {
+            list.archived = archived;
+        }
+
+        self.modify_list(&mut tx, &list, Utc::now())
+            .await
+            .map_err(|e| {
+                if is_unique_constraint_violation(&e) {
+                    UpdateTaskListError::Duplicate {
+                        title: list.title.clone(),
+                    }
+                } else {
+                    anyhow!(e)
+                        .context(format!("failed to update task list with id: {}", id))
+                        .into()
+                }
+            })?;
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(list)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        mode: DeleteTaskListMode,
+    ) -> Result<(), DeleteTaskListError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start SQLite transaction")?;
+
+        match self
+            .remove_list(&mut tx, owner, id, mode, Utc::now())
+            .await
+            .with_context(|| format!("failed to delete task list with id: {}", id))?
+        {
+            ListRemoval::Removed => {}
+            ListRemoval::NotFound => return Err(DeleteTaskListError::NotFound { id: *id }),
+            ListRemoval::NotEmpty(tasks) => {
+                return Err(DeleteTaskListError::NotEmpty { id: *id, tasks })
+            }
+        }
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_members(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> Result<Vec<TaskListMember>, ListTaskListMembersError> {
+        let rows = self
+            .find_members(owner, id)
+            .await
+            .with_context(|| format!("failed to fetch members of task list with id: {}", id))?
+            .ok_or(ListTaskListMembersError::NotFound { id: *id })?;
+        let members = rows
+            .into_iter()
+            .map(TaskListMember::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(members)
+    }
+
+    #[instrument(skip(self, req))]
+    async fn share_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        req: &ShareTaskListRequest,
+    ) -> Result<TaskListMember, ShareTaskListError> {
+        match self
+            .save_member(owner, id, req, Utc::now())
+            .await
+            .with_context(|| format!("failed to share task list with id: {}", id))?
+        {
+            MemberSave::Saved(row) => Ok(TaskListMember::try_from(row)?),
+            MemberSave::ListNotFound => Err(ShareTaskListError::NotFound { id: *id }),
+            MemberSave::UserNotFound => Err(ShareTaskListError::UserNotFound {
+                email: req.email().clone(),
+            }),
+            MemberSave::Creator => Err(ShareTaskListError::Creator {
+                id: *id,
+                email: req.email().clone(),
+            }),
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn unshare_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        member: &Uuid,
+    ) -> Result<(), UnshareTaskListError> {
+        match self
+            .remove_member(owner, id, member)
+            .await
+            .with_context(|| format!("failed to unshare task list with id: {}", id))?
+        {
+            (false, _) => Err(UnshareTaskListError::NotFound { id: *id }),
+            (true, false) => Err(UnshareTaskListError::MemberNotFound {
+                id: *id,
+                user_id: *member,
+            }),
+            (true, true) => Ok(()),
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn list_tags(&self, owner: &Uuid) -> Result<Vec<TagUsage>, ListTagsError> {
+        let rows = self
+            .find_tags(owner)
+            .await
+            .context("failed to fetch tags")?;
+        let tags = rows
+            .into_iter()
+            .map(TagUsage::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(tags)
+    }
+
+    #[instrument(skip(self, from, to))]
+    async fn rename_tag(
+        &self,
+        owner: &Uuid,
+        from: &Tag,
+        to: &Tag,
+    ) -> Result<TagUsage, RenameTagError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start SQLite transaction")?;
+
+        let tasks = match self
+            .modify_tag(&mut tx, owner, from, to)
+            .await
+            .with_context(|| format!("failed to rename tag {} to {}", from, to))?
+        {
+            TagRename::Renamed(tasks) => tasks,
+            TagRename::NotFound => return Err(RenameTagError::NotFound { tag: from.clone() }),
+            TagRename::Duplicate => return Err(RenameTagError::Duplicate { tag: to.clone() }),
+        };
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(TagUsage {
+            tag: to.clone(),
+            tasks,
+        })
+    }
+
+    #[instrument(skip(self, tags, into))]
+    async fn merge_tags(
+        &self,
+        owner: &Uuid,
+        tags: &[Tag],
+        into: &Tag,
+    ) -> Result<TagUsage, MergeTagsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start SQLite transaction")?;
+
+        let tasks = match self
+            .combine_tags(&mut tx, owner, tags, into)
+            .await
+            .with_context(|| format!("failed to merge tags into {}", into))?
+        {
+            TagMerge::Merged(tasks) => tasks,
+            TagMerge::NotFound(tag) => return Err(MergeTagsError::NotFound { tag }),
+        };
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(TagUsage {
+            tag: into.clone(),
+            tasks,
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn claim_due_reminders(
+        &self,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<DueReminder>, FireRemindersError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start SQLite transaction")?;
+
+        let rows = self
+            .claim_reminders(&mut tx, now, limit)
+            .await
+            .context("failed to claim due reminders")?;
+        let mut reminders = Vec::with_capacity(rows.len());
+        for row in rows {
+            let task = self
+                .find_task(&mut tx, None, &row.task_id)
+                .await
+                .with_context(|| format!("failed to fetch task with id: {}", row.task_id))?
+                .with_context(|| format!("claimed reminder of missing task: {}", row.task_id))?;
+            reminders.push(DueReminder {
+                task: Task::try_from(task)?,
+                remind_at: row.remind_at,
+                recipient: row.recipient,
+                attempts: u32::try_from(row.attempts).context("invalid reminder attempts")?,
+            });
+        }
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(reminders)
+    }
+
+    #[instrument(skip(self, reminder, error))]
+    async fn record_reminder_failure(
+        &self,
+        reminder: &DueReminder,
+        error: &str,
+        retry: bool,
+    ) -> Result<(), FireRemindersError> {
+        self.release_reminder(reminder, error, retry)
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to record reminder failure for task: {}",
+                    reminder.task().id()
+                )
+            })?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_trash(&self, user: &Uuid) -> Result<Trash, ListTrashError> {
+        let lists = self
+            .find_trashed_lists(user)
+            .await
+            .context("failed to fetch task lists in the trash")?
+            .into_iter()
+            .map(Trashed::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let tasks = self
+            .find_trashed_tasks(user)
+            .await
+            .context("failed to fetch tasks in the trash")?
+            .into_iter()
+            .map(Trashed::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Trash { lists, tasks })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_trashed_task(&self, user: &Uuid, id: &Uuid) -> Result<Task, GetTaskError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .context("failed to acquire SQLite connection")?;
+        let row = self
+            .find_trashed_task(&mut conn, user, id)
+            .await
+            .with_context(|| format!("failed to fetch task in the trash with id: {}", id))?
+            .ok_or(GetTaskError::NotFound { id: *id })?;
+
+        Ok(Task::try_from(row)?)
+    }
+
+    #[instrument(skip(self))]
+    async fn restore_task(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+        updated_by: &Uuid,
+    ) -> Result<Task, RestoreTaskError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start SQLite transaction")?;
+
+        let now = Utc::now();
+        let row = match self
+            .undelete_task(&mut tx, owner, id, now)
+            .await
+            .with_context(|| format!("failed to restore task with id: {}", id))?
+        {
+            TaskRestore::Restored(row) => row,
+            TaskRestore::NotFound => return Err(RestoreTaskError::NotFound { id: *id }),
+            TaskRestore::ParentDeleted(id) => return Err(RestoreTaskError::ParentDeleted { id }),
+            TaskRestore::ListDeleted(id) => return Err(RestoreTaskError::ListDeleted { id }),
+            TaskRestore::Duplicate(title) => {
+                let title = TaskTitle::new(&title).with_context(|| {
+                    format!("invalid title stored for a subtask of task {}", id)
+                })?;
+                return Err(RestoreTaskError::Duplicate { title });
+            }
+        };
+        let clash = self
+            .sync_ancestors(&mut tx, row.parent_id, Some(*updated_by), now)
+            .await
+            .with_context(|| format!("failed to reopen the parents of task: {}", id))?;
+        if let Some(title) = clash {
+            let title = TaskTitle::new(&title)
+                .with_context(|| format!("invalid title stored for a parent of task {}", id))?;
+            return Err(RestoreTaskError::Duplicate { title });
+        }
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(Task::try_from(*row)?)
+    }
+
+    #[instrument(skip(self))]
+    async fn restore_list(
+        &self,
+        owner: &Uuid,
+        id: &Uuid,
+    ) -> Result<TaskList, RestoreTaskListError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start SQLite transaction")?;
+
+        let row = match self
+            .undelete_list(&mut tx, owner, id, Utc::now())
+            .await
+            .with_context(|| format!("failed to restore task list with id: {}", id))?
+        {
+            ListRestore::Restored(row) => row,
+            ListRestore::NotFound => return Err(RestoreTaskListError::NotFound { id: *id }),
+            ListRestore::Duplicate(title) => {
+                let title = TaskListTitle::new(&title)
+                    .with_context(|| format!("invalid title stored for task list {}", id))?;
+                return Err(RestoreTaskListError::Duplicate { title });
+            }
+            ListRestore::TaskDuplicate(title) => {
+                let title = TaskTitle::new(&title)
+                    .with_context(|| format!("invalid title stored for a task of list {}", id))?;
+                return Err(RestoreTaskListError::TaskDuplicate { title });
+            }
+        };
+
+        tx.commit()
+            .await
+            .context("failed to commit SQLite transaction")?;
+
+        Ok(TaskList::try_from(row)?)
+    }
+
+    #[instrument(skip(self))]
+    async fn empty_trash(&self, owner: &Uuid) -> Result<PurgedTrash, PurgeTrashError> {
+        let purged = self
+            .remove_trash(Some(owner), None)
+            .await
+            .context("failed to empty the trash")?;
+
+        Ok(purged)
+    }
+
+    #[instrument(skip(self))]
+    async fn purge_trash(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<PurgedTrash, PurgeTrashError> {
+        let purged = self
+            .remove_trash(None, Some(deleted_before))
+            .await
+            .with_context(|| format!("failed to purge the trash from before {}", deleted_before))?;
+
+        Ok(purged)
+    }
+}
+
+impl UserRepository for Sqlite {
+    #[instrument(skip(self, email, password_hash))]
+    async fn create_user(
+        &self,
+        email: &EmailAddress,
+        password_hash: &PasswordHash,
+    ) -> Result<User, RegisterUserError> {
+        let row = self
+            .save_user(email, password_hash, Utc::now())
+            .await
+            .map_err(|e| {
+                if is_unique_constraint_violation(&e) {
+                    RegisterUserError::Duplicate {
+                        email: email.clone(),
+                    }
+                } else {
+                    anyhow!(e)
+                        .context(format!("failed to save user with email: {}", email))
+                        .into()
+                }
+            })?;
+
+        Ok(UserCredentials::try_from(row)?.user)
+    }
+
+    #[instrument(skip(self, email))]
+    async fn get_credentials(
+        &self,
+        email: &EmailAddress,
+    ) -> Result<Option<UserCredentials>, LogInError> {
+        let row = self
+            .find_user(email)
+            .await
+            .with_context(|| format!("failed to fetch user with email: {}", email))?;
+
+        Ok(row.map(UserCredentials::try_from).transpose()?)
+    }
+
+    #[instrument(skip(self, session))]
+    async fn create_session(&self, session: &Session) -> Result<(), LogInError> {
+        self.save_session(session, Utc::now())
+            .await
+            .with_context(|| format!("failed to save session of user: {}", session.user().id()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, token))]
+    async fn get_session_user(
+        &self,
+        token: &SessionToken,
+        now: DateTime<Utc>,
+    ) -> Result<User, AuthenticateError> {
+        let row = self
+            .find_session_user(token, now)
+            .await
+            .context("failed to fetch session")?
+            .ok_or(AuthenticateError::InvalidToken)?;
+
+        Ok(UserCredentials::try_from(row)?.user)
+    }
+
+    #[instrument(skip(self, token))]
+    async fn delete_session(&self, token: &SessionToken) -> Result<(), LogOutError> {
+        self.remove_session(token)
+            .await
+            .context("failed to delete session")?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, token))]
+    async fn create_api_token(
+        &self,
+        user: &Uuid,
+        token: &NewApiToken,
+    ) -> Result<(), CreateApiTokenError> {
+        let saved = self
+            .save_api_token(user, token)
+            .await
+            .with_context(|| format!("failed to save API token of user: {}", user))?;
+
+        match token.token().scope().list_id() {
+            Some(id) if !saved => Err(CreateApiTokenError::ListNotFound { id }),
+            _ => Ok(()),
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn list_api_tokens(&self, user: &Uuid) -> Result<Vec<ApiToken>, ListApiTokensError> {
+        let rows = self
+            .find_api_tokens(user)
+            .await
+            .with_context(|| format!("failed to fetch API tokens of user: {}", user))?;
+
+        Ok(rows
+            .into_iter()
+            .map(ApiToken::try_from)
+            .collect::<anyhow::Result<_>>()?)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_api_token(&self, user: &Uuid, id: &Uuid) -> Result<(), RevokeApiTokenError> {
+        let deleted = self
+            .remove_api_token(user, id)
+            .await
+            .with_context(|| format!("failed to delete API token with id: {}", id))?;
+
+        if deleted {
+            Ok(())
+        } else {
+            Err(RevokeApiTokenError::NotFound { id: *id })
+        }
+    }
+
+    #[instrument(skip(self, secret))]
+    async fn use_api_token(
+        &self,
+        secret: &ApiTokenSecret,
+        now: DateTime<Utc>,
+    ) -> Result<Principal, AuthenticateError> {
+        let (user, used) = self
+            .touch_api_token(secret, now)
+            .await
+            .context("failed to fetch API token")?
+            .ok_or(AuthenticateError::InvalidApiToken)?;
+
+        Ok(Principal {
+            user: UserCredentials::try_from(user)?.user,
+            scope: token_scope(&used.access, used.list_id)?,
+        })
+    }
+}
+
+impl IdempotencyRepository for Sqlite {
+    #[instrument(skip(self, req))]
+    async fn claim_idempotency_key(
+        &self,
+        req: &IdempotentRequest,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<IdempotencyRecord>, ClaimIdempotencyKeyError> {
+        let existing = self
+            .save_idempotency_key(req, now, expires_at)
+            .await
+            .context("failed to claim idempotency key")?;
+        match existing {
+            None => Ok(None),
+            Some(Some(row)) => Ok(Some(IdempotencyRecord::try_from(row)?)),
+            // The request that claimed the key released it, and has not completed
+            Some(None) => Err(ClaimIdempotencyKeyError::InProgress),
+        }
+    }
+
+    #[instrument(skip(self, req, response))]
+    async fn save_idempotent_response(
+        &self,
+        req: &IdempotentRequest,
+        response: &StoredResponse,
+    ) -> Result<(), CompleteIdempotentRequestError> {
+        self.save_response(req, response)
+            .await
+            .context("failed to save idempotent response")?;
+        Ok(())
+    }
+
+    #[instrument(skip(self, req))]
+    async fn delete_idempotency_key(
+        &self,
+        req: &IdempotentRequest,
+    ) -> Result<(), CompleteIdempotentRequestError> {
+        self.remove_idempotency_key(req)
+            .await
+            .context("failed to delete idempotency key")?;
+        Ok(())
+    }
+}
+
+impl ReadinessRepository for Sqlite {
+    #[instrument(skip(self))]
+    async fn is_ready(&self) -> Result<(), ReadinessError> {
+        self.ready().await.map_err(|e| {
+            anyhow!(e)
+                .context("failed to check if database is ready")
+                .into()
+        })
+    }
+}
+
+/// The rank of the priority of a task, in the order of [Priority], for sorting by priority.
+const PRIORITY_RANK: &str = "CASE priority \
+    WHEN 'low' THEN 1 WHEN 'medium' THEN 2 WHEN 'high' THEN 3 WHEN 'urgent' THEN 4 \
+    ELSE 0 END";
+
+fn is_unique_constraint_violation(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Database(db_err) => db_err.is_unique_violation(),
+        _ => false,
+    }
+}
+
+fn is_foreign_key_violation(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Database(db_err) => db_err.is_foreign_key_violation(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::idempotency::models::key::IdempotencyKey;
+    use crate::domain::reminders::models::query::{PageSize, TaskFilter, TaskSort, TaskTagFilter};
+    use crate::domain::users::models::api_token::CreateApiTokenRequest;
+    use chrono::Duration;
+
+    async fn sqlite() -> Sqlite {
+        Sqlite::new("sqlite::memory:").await.unwrap()
+    }
+
+    fn title(raw: &str) -> TaskTitle {
+        TaskTitle::new(raw).unwrap()
+    }
+
+    async fn user(sqlite: &Sqlite, email: &str) -> Uuid {
+        let email = EmailAddress::new(email).unwrap();
+        let user = sqlite
+            .create_user(&email, &PasswordHash::from_stored("!"))
+            .await
+            .unwrap();
+        user.id()
+    }
+
+    #[tokio::test]
+    async fn test_titles_are_unique_among_incomplete_tasks_ignoring_case() {
+        let sqlite = sqlite().await;
+        let owner = user(&sqlite, "ada@example.com").await;
+        let task = sqlite
+            .create_task(&owner, &CreateTaskRequest::new(title("Water plants")))
+            .await
+            .unwrap();
+
+        let result = sqlite
+            .create_task(&owner, &CreateTaskRequest::new(title("water PLANTS")))
+            .await;
+        assert!(matches!(result, Err(CreateTaskError::Duplicate { .. })));
+
+        sqlite
+            .update_task(
+                &owner,
+                &task.id(),
+                &UpdateTaskRequest::new().with_completed(true),
+            )
+            .await
+            .unwrap();
+        sqlite
+            .create_task(&owner, &CreateTaskRequest::new(title("water PLANTS")))
+            .await
+            .unwrap();
+
+        let result = sqlite
+            .update_task(
+                &owner,
+                &task.id(),
+                &UpdateTaskRequest::new().with_completed(false),
+            )
+            .await;
+        assert!(matches!(result, Err(UpdateTaskError::Duplicate { .. })));
+        let page = sqlite
+            .list_tasks(&owner, &ListTasksRequest::new())
+            .await
+            .unwrap();
+        assert_eq!(page.tasks.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_priority_and_notes_are_saved_and_cleared() {
+        let sqlite = sqlite().await;
+        let owner = user(&sqlite, "ada@example.com").await;
+        let notes = TaskNotes::new("Bring the *blue* folder").unwrap();
+        let req = CreateTaskRequest::new(title("File taxes"))
+            .with_priority(Priority::Urgent)
+            .with_notes(notes.clone());
+        let task = sqlite.create_task(&owner, &req).await.unwrap();
+        let stored = sqlite.get_task(&owner, &task.id()).await.unwrap();
+        assert_eq!(stored.priority(), Priority::Urgent);
+        assert_eq!(stored.notes(), Some(&notes));
+
+        let req = UpdateTaskRequest::new()
+            .with_priority(Priority::Low)
+            .with_notes(None);
+        let updated = sqlite.update_task(&owner, &task.id(), &req).await.unwrap();
+        assert_eq!(updated.priority(), Priority::Low);
+        assert_eq!(updated.notes(), None);
+    }
+
+    #[tokio::test]
+    async fn test_changes_to_an_outdated_version_conflict() {
+        let sqlite = sqlite().await;
+        let owner = user(&sqlite, "ada@example.com").await;
+        let task = sqlite
+            .create_task(&owner, &CreateTaskRequest::new(title("Renew passport")))
+            .await
+            .unwrap();
+        assert_eq!(task.version(), 1);
+
+        let req = UpdateTaskRequest::new()
+            .with_completed(true)
+            .with_version(1);
+        let updated = sqlite.update_task(&owner, &task.id(), &req).await.unwrap();
+        assert_eq!(updated.version(), 2);
+        let result = sqlite.update_task(&owner, &task.id(), &req).await;
+        assert!(matches!(
+            result,
+            Err(UpdateTaskError::Conflict { version: 1, .. })
+        ));
+
+        let result = sqlite
+            .delete_task(&owner, &task.id(), Some(1), &owner)
+            .await;
+        assert!(matches!(
+            result,
+            Err(DeleteTaskError::Conflict { version: 1, .. })
+        ));
+        sqlite
+            .delete_task(&owner, &task.id(), Some(2), &owner)
+            .await
+            .unwrap();
+        let result = sqlite
+            .delete_task(&owner, &task.id(), Some(2), &owner)
+            .await;
+        assert!(matches!(result, Err(DeleteTaskError::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_due_reminders_are_claimed_once_oldest_first() {
+        let sqlite = sqlite().await;
+        let owner = user(&sqlite, "ada@example.com").await;
+        let now = Utc::now();
+        let offsets = RemindAt::new(vec![
+            ReminderOffset::from_minutes(10).unwrap(),
+            ReminderOffset::from_minutes(60).unwrap(),
+        ])
+        .unwrap();
+        let req = CreateTaskRequest::new(title("Call the dentist"))
+            .with_due_at(DueAt::new(now + Duration::minutes(5)))
+            .with_remind_at(offsets);
+        let task = sqlite.create_task(&owner, &req).await.unwrap();
+        assert_eq!(sqlite.get_task(&owner, &task.id()).await.unwrap(), task);
+
+        let claimed = sqlite.claim_due_reminders(now, 10).await.unwrap();
+        let remind_at = claimed.iter().map(|r| *r.remind_at()).collect::<Vec<_>>();
+        assert_eq!(
+            remind_at,
+            vec![now - Duration::minutes(55), now - Duration::minutes(5)]
+        );
+        assert!(sqlite
+            .claim_due_reminders(now, 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        sqlite
+            .record_reminder_failure(&claimed[0], "unreachable", true)
+            .await
+            .unwrap();
+        let retried = sqlite.claim_due_reminders(now, 10).await.unwrap();
+        assert_eq!(retried.len(), 1);
+        assert_eq!(retried[0].attempts(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_delete_list_keeps_moves_or_deletes_its_tasks() {
+        let sqlite = sqlite().await;
+        let owner = user(&sqlite, "ada@example.com").await;
+        let list_title = |raw| TaskListTitle::new(raw).unwrap();
+        let list = sqlite
+            .create_list(&owner, &CreateTaskListRequest::new(list_title("Garden")))
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Water plants")).with_list_id(list.id());
+        let task = sqlite.create_task(&owner, &req).await.unwrap();
+
+        let result = sqlite
+            .delete_list(&owner, &list.id(), DeleteTaskListMode::OnlyIfEmpty)
+            .await;
+        assert!(matches!(
+            result,
+            Err(DeleteTaskListError::NotEmpty { tasks: 1, .. })
+        ));
+        sqlite
+            .delete_list(&owner, &list.id(), DeleteTaskListMode::MoveToInbox)
+            .await
+            .unwrap();
+        assert_eq!(
+            sqlite.get_task(&owner, &task.id()).await.unwrap().list_id(),
+            None
+        );
+
+        let list = sqlite
+            .create_list(&owner, &CreateTaskListRequest::new(list_title("Garden")))
+            .await
+            .unwrap();
+        let req = UpdateTaskRequest::new().with_list_id(Some(list.id()));
+        sqlite.update_task(&owner, &task.id(), &req).await.unwrap();
+        sqlite
+            .delete_list(&owner, &list.id(), DeleteTaskListMode::Cascade)
+            .await
+            .unwrap();
+        let result = sqlite.get_task(&owner, &task.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+
+        let req = CreateTaskRequest::new(title("Mow lawn")).with_list_id(list.id());
+        let result = sqlite.create_task(&owner, &req).await;
+        assert!(matches!(result, Err(CreateTaskError::ListNotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_tasks_sorted_by_priority_page_across_equal_priorities() {
+        let sqlite = sqlite().await;
+        let owner = user(&sqlite, "ada@example.com").await;
+        for (name, priority) in [
+            ("Buy milk", Priority::High),
+            ("Walk dog", Priority::None),
+            ("Pay rent", Priority::High),
+            ("Call mom", Priority::High),
+            ("File taxes", Priority::Urgent),
+        ] {
+            let req = CreateTaskRequest::new(title(name)).with_priority(priority);
+            sqlite.create_task(&owner, &req).await.unwrap();
+        }
+
+        let mut req = ListTasksRequest::new()
+            .with_sort(TaskSort::parse("-priority").unwrap())
+            .with_size(PageSize::new(2).unwrap());
+        let mut titles = vec![];
+        loop {
+            let page = sqlite.list_tasks(&owner, &req).await.unwrap();
+            titles.extend(page.tasks.iter().map(|task| task.title().to_string()));
+            match page.next_cursor {
+                Some(cursor) => req = req.with_cursor(cursor).unwrap(),
+                None => break,
+            }
+        }
+        assert_eq!(
+            titles,
+            vec!["File taxes", "Call mom", "Pay rent", "Buy milk", "Walk dog"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_completion_spreads_to_subtasks_and_parents_or_not_at_all() {
+        let sqlite = sqlite().await;
+        let owner = user(&sqlite, "ada@example.com").await;
+        let trip = sqlite
+            .create_task(&owner, &CreateTaskRequest::new(title("Plan trip")))
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Pack")).with_parent_id(trip.id());
+        let pack = sqlite.create_task(&owner, &req).await.unwrap();
+        let req = CreateTaskRequest::new(title("Socks")).with_parent_id(pack.id());
+        let socks = sqlite.create_task(&owner, &req).await.unwrap();
+
+        let done = UpdateTaskRequest::new()
+            .with_completed(true)
+            .with_updated_by(owner);
+        sqlite.update_task(&owner, &pack.id(), &done).await.unwrap();
+        let socks = sqlite.get_task(&owner, &socks.id()).await.unwrap();
+        assert!(socks.completed());
+        assert_eq!(socks.completed_by(), Some(owner));
+        assert!(sqlite
+            .get_task(&owner, &trip.id())
+            .await
+            .unwrap()
+            .completed());
+
+        // Reopening the subtask would reopen both parents, but the top one clashes
+        sqlite
+            .create_task(&owner, &CreateTaskRequest::new(title("Plan TRIP")))
+            .await
+            .unwrap();
+        let reopen = UpdateTaskRequest::new().with_completed(false);
+        match sqlite.update_task(&owner, &socks.id(), &reopen).await {
+            Err(UpdateTaskError::Duplicate { title }) => assert_eq!(title.to_string(), "Plan trip"),
+            other => panic!("expected a duplicate title error, got {:?}", other),
+        }
+        assert!(sqlite
+            .get_task(&owner, &socks.id())
+            .await
+            .unwrap()
+            .completed());
+        assert!(sqlite
+            .get_task(&owner, &pack.id())
+            .await
+            .unwrap()
+            .completed());
+    }
+
+    #[tokio::test]
+    async fn test_subtasks_are_listed_in_sibling_order_and_deleted_with_parent() {
+        let sqlite = sqlite().await;
+        let owner = user(&sqlite, "ada@example.com").await;
+        let trip = sqlite
+            .create_task(&owner, &CreateTaskRequest::new(title("Prepare trip")))
+            .await
+            .unwrap();
+        let subtask = |raw| CreateTaskRequest::new(title(raw)).with_parent_id(trip.id());
+        let pack = sqlite.create_task(&owner, &subtask("Pack")).await.unwrap();
+        let hotel = sqlite
+            .create_task(&owner, &subtask("Book hotel").with_position(-1))
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Buy sunscreen")).with_parent_id(pack.id());
+        let sunscreen = sqlite.create_task(&owner, &req).await.unwrap();
+        assert_eq!(pack.position(), 0);
+        assert_eq!(sunscreen.position(), 0);
+
+        let subtasks = sqlite.list_subtasks(&owner, &trip.id()).await.unwrap();
+        let ids = subtasks.iter().map(Task::id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![hotel.id(), pack.id(), sunscreen.id()]);
+
+        let missing = Uuid::new_v4();
+        let req = CreateTaskRequest::new(title("Orphan")).with_parent_id(missing);
+        let result = sqlite.create_task(&owner, &req).await;
+        assert!(matches!(result, Err(CreateTaskError::ParentNotFound { id }) if id == missing));
+
+        sqlite
+            .delete_task(&owner, &trip.id(), None, &owner)
+            .await
+            .unwrap();
+        let result = sqlite.get_task(&owner, &sunscreen.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_deleted_tasks_are_trashed_restored_and_purged() {
+        let sqlite = sqlite().await;
+        let owner = user(&sqlite, "ada@example.com").await;
+        let trip = sqlite
+            .create_task(&owner, &CreateTaskRequest::new(title("Prepare trip")))
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Pack")).with_parent_id(trip.id());
+        let pack = sqlite.create_task(&owner, &req).await.unwrap();
+
+        sqlite
+            .delete_task(&owner, &trip.id(), None, &owner)
+            .await
+            .unwrap();
+        let result = sqlite.get_task(&owner, &pack.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+        let trash = sqlite.list_trash(&owner).await.unwrap();
+        assert_eq!(trash.tasks().len(), 2);
+        let result = sqlite.restore_task(&owner, &pack.id(), &owner).await;
+        assert!(matches!(result, Err(RestoreTaskError::ParentDeleted { id }) if id == trip.id()));
+
+        let again = sqlite
+            .create_task(&owner, &CreateTaskRequest::new(title("Prepare trip")))
+            .await
+            .unwrap();
+        let result = sqlite.restore_task(&owner, &trip.id(), &owner).await;
+        assert!(matches!(result, Err(RestoreTaskError::Duplicate { .. })));
+        let req = UpdateTaskRequest::new().with_completed(true);
+        sqlite.update_task(&owner, &again.id(), &req).await.unwrap();
+        sqlite
+            .restore_task(&owner, &trip.id(), &owner)
+            .await
+            .unwrap();
+        sqlite.get_task(&owner, &pack.id()).await.unwrap();
+        assert!(sqlite.list_trash(&owner).await.unwrap().tasks().is_empty());
+
+        let list_title = TaskListTitle::new("Errands").unwrap();
+        let list = sqlite
+            .create_list(&owner, &CreateTaskListRequest::new(list_title))
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Post letter")).with_list_id(list.id());
+        let letter = sqlite.create_task(&owner, &req).await.unwrap();
+        sqlite
+            .delete_list(&owner, &list.id(), DeleteTaskListMode::Cascade)
+            .await
+            .unwrap();
+        let result = sqlite.restore_task(&owner, &letter.id(), &owner).await;
+        assert!(matches!(result, Err(RestoreTaskError::ListDeleted { id }) if id == list.id()));
+        sqlite.restore_list(&owner, &list.id()).await.unwrap();
+        sqlite.get_task(&owner, &letter.id()).await.unwrap();
+
+        sqlite
+            .delete_task(&owner, &letter.id(), None, &owner)
+            .await
+            .unwrap();
+        let purged = sqlite
+            .purge_trash(Utc::now() - Duration::days(1))
+            .await
+            .unwrap();
+        assert_eq!(purged, PurgedTrash::default());
+        let purged = sqlite
+            .purge_trash(Utc::now() + Duration::seconds(1))
+            .await
+            .unwrap();
+        assert_eq!(purged, PurgedTrash { tasks: 1, lists: 0 });
+        let result = sqlite.get_trashed_task(&owner, &letter.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+
+        sqlite
+            .delete_list(&owner, &list.id(), DeleteTaskListMode::OnlyIfEmpty)
+            .await
+            .unwrap();
+        sqlite
+            .delete_task(&owner, &trip.id(), None, &owner)
+            .await
+            .unwrap();
+        let purged = sqlite.empty_trash(&owner).await.unwrap();
+        assert_eq!(purged, PurgedTrash { tasks: 2, lists: 1 });
+        assert_eq!(sqlite.list_trash(&owner).await.unwrap(), Trash::default());
+    }
+
+    #[tokio::test]
+    async fn test_tags_are_renamed_merged_and_filtered() {
+        let sqlite = sqlite().await;
+        let owner = user(&sqlite, "ada@example.com").await;
+        let tag = |raw: &str| Tag::new(raw).unwrap();
+        let tags = |raw: &[&str]| TaskTags::new(raw.iter().map(|raw| tag(raw)).collect()).unwrap();
+        let req = CreateTaskRequest::new(title("Buy stamps")).with_tags(tags(&["errands"]));
+        let stamps = sqlite.create_task(&owner, &req).await.unwrap();
+        let req = CreateTaskRequest::new(title("Paint fence")).with_tags(tags(&["garden", "diy"]));
+        let fence = sqlite.create_task(&owner, &req).await.unwrap();
+        assert_eq!(
+            sqlite.get_task(&owner, &fence.id()).await.unwrap().tags(),
+            &tags(&["diy", "garden"])
+        );
+
+        let req = UpdateTaskRequest::new().with_tags(tags(&["errands", "garden"]));
+        sqlite
+            .update_task(&owner, &stamps.id(), &req)
+            .await
+            .unwrap();
+        let result = sqlite.rename_tag(&owner, &tag("diy"), &tag("garden")).await;
+        assert!(matches!(result, Err(RenameTagError::Duplicate { .. })));
+        let usage = sqlite
+            .rename_tag(&owner, &tag("diy"), &tag("home"))
+            .await
+            .unwrap();
+        assert_eq!(usage.tasks(), 1);
+        let usage = sqlite
+            .merge_tags(&owner, &[tag("garden"), tag("home")], &tag("outside"))
+            .await
+            .unwrap();
+        assert_eq!(usage.tasks(), 2);
+        let result = sqlite
+            .merge_tags(&owner, &[tag("garden")], &tag("home"))
+            .await;
+        assert!(matches!(result, Err(MergeTagsError::NotFound { .. })));
+
+        let usage = sqlite.list_tags(&owner).await.unwrap();
+        let usage = usage
+            .iter()
+            .map(|usage| (usage.tag().as_str(), usage.tasks()))
+            .collect::<Vec<_>>();
+        assert_eq!(usage, vec![("errands", 1), ("outside", 2)]);
+
+        let filter = |mode| {
+            let tags = TaskTagFilter::new(vec![tag("errands"), tag("outside")], mode).unwrap();
+            ListTasksRequest::new().with_filter(TaskFilter::new().with_tags(tags))
+        };
+        let page = sqlite
+            .list_tasks(&owner, &filter(TagMatch::All))
+            .await
+            .unwrap();
+        let ids = page.tasks.iter().map(Task::id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![stamps.id()]);
+        let page = sqlite
+            .list_tasks(&owner, &filter(TagMatch::Any))
+            .await
+            .unwrap();
+        assert_eq!(page.tasks.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_users_only_see_their_own_tasks_and_lists() {
+        let sqlite = sqlite().await;
+        let ada = user(&sqlite, "ada@example.com").await;
+        let bob = user(&sqlite, "bob@example.com").await;
+        let list = sqlite
+            .create_list(
+                &ada,
+                &CreateTaskListRequest::new(TaskListTitle::new("Garden").unwrap()),
+            )
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Water plants")).with_list_id(list.id());
+        let task = sqlite.create_task(&ada, &req).await.unwrap();
+
+        let result = sqlite.get_task(&bob, &task.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+        let result = sqlite.create_task(&bob, &req).await;
+        assert!(matches!(result, Err(CreateTaskError::ListNotFound { .. })));
+        let req = CreateTaskRequest::new(title("Water plants")).with_parent_id(task.id());
+        let result = sqlite.create_task(&bob, &req).await;
+        assert!(matches!(
+            result,
+            Err(CreateTaskError::ParentNotFound { .. })
+        ));
+
+        sqlite
+            .create_task(&bob, &CreateTaskRequest::new(title("Water plants")))
+            .await
+            .unwrap();
+        let page = sqlite
+            .list_tasks(&ada, &ListTasksRequest::new())
+            .await
+            .unwrap();
+        assert_eq!(page.tasks, vec![task]);
+        assert!(sqlite.list_lists(&bob, true).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_shared_lists_are_visible_to_their_members() {
+        let sqlite = sqlite().await;
+        let ada = user(&sqlite, "ada@example.com").await;
+        let bob = user(&sqlite, "bob@example.com").await;
+        let email = |raw| EmailAddress::new(raw).unwrap();
+        let list = sqlite
+            .create_list(
+                &ada,
+                &CreateTaskListRequest::new(TaskListTitle::new("Groceries").unwrap()),
+            )
+            .await
+            .unwrap();
+        let req = CreateTaskRequest::new(title("Buy milk")).with_list_id(list.id());
+        let task = sqlite.create_task(&ada, &req).await.unwrap();
+
+        let req = ShareTaskListRequest::new(email("bob@example.com"), ListRole::Viewer);
+        sqlite.share_list(&ada, &list.id(), &req).await.unwrap();
+        let req = ShareTaskListRequest::new(email("bob@example.com"), ListRole::Editor);
+        let member = sqlite.share_list(&ada, &list.id(), &req).await.unwrap();
+        assert_eq!((member.user_id(), member.role()), (bob, ListRole::Editor));
+        let req = ShareTaskListRequest::new(email("ada@example.com"), ListRole::Editor);
+        let result = sqlite.share_list(&ada, &list.id(), &req).await;
+        assert!(matches!(result, Err(ShareTaskListError::Creator { .. })));
+        let req = ShareTaskListRequest::new(email("cy@example.com"), ListRole::Editor);
+        let result = sqlite.share_list(&ada, &list.id(), &req).await;
+        assert!(matches!(
+            result,
+            Err(ShareTaskListError::UserNotFound { .. })
+        ));
+
+        let members = sqlite.list_members(&ada, &list.id()).await.unwrap();
+        let members = members
+            .iter()
+            .map(|member| (member.user_id(), member.role()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            members,
+            vec![(ada, ListRole::Owner), (bob, ListRole::Editor)]
+        );
+        let shared = sqlite.get_list(&bob, &list.id()).await.unwrap();
+        assert_eq!((shared.owner_id(), shared.role()), (ada, ListRole::Editor));
+        assert_eq!(sqlite.list_lists(&bob, false).await.unwrap(), vec![shared]);
+        assert_eq!(sqlite.get_task(&bob, &task.id()).await.unwrap(), task);
+
+        let req = UpdateTaskRequest::new()
+            .with_completed(true)
+            .with_updated_by(bob);
+        let completed = sqlite.update_task(&ada, &task.id(), &req).await.unwrap();
+        assert_eq!(completed.completed_by(), Some(bob));
+        let req = UpdateTaskRequest::new().with_title(title("Buy oat milk"));
+        let renamed = sqlite.update_task(&ada, &task.id(), &req).await.unwrap();
+        assert_eq!(
+            (renamed.updated_by(), renamed.completed_by()),
+            (Some(bob), Some(bob))
+        );
+        let req = UpdateTaskRequest::new()
+            .with_completed(false)
+            .with_updated_by(ada);
+        let reopened = sqlite.update_task(&ada, &task.id(), &req).await.unwrap();
+        assert_eq!(
+            (reopened.updated_by(), reopened.completed_by()),
+            (Some(ada), None)
+        );
+
+        sqlite.unshare_list(&ada, &list.id(), &bob).await.unwrap();
+        let result = sqlite.unshare_list(&ada, &list.id(), &bob).await;
+        assert!(matches!(
+            result,
+            Err(UnshareTaskListError::MemberNotFound { .. })
+        ));
+        let result = sqlite.get_task(&bob, &task.id()).await;
+        assert!(matches!(result, Err(GetTaskError::NotFound { .. })));
+        assert!(sqlite.list_lists(&bob, true).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_assigned_tasks_are_listed_and_reminded_to_their_assignee() {
+        let sqlite = sqlite().await;
+        let ada = user(&sqlite, "ada@example.com").await;
+        let bob = user(&sqlite, "bob@example.com").await;
+        let list = sqlite
+            .create_list(
+                &ada,
+                &CreateTaskListRequest::new(TaskListTitle::new("Chores").unwrap()),
+            )
+            .await
+            .unwrap();
+        let email = EmailAddress::new("bob@example.com").unwrap();
+        let req = ShareTaskListRequest::new(email, ListRole::Editor);
+        sqlite.share_list(&ada, &list.id(), &req).await.unwrap();
+        let offsets = vec![ReminderOffset::from_minutes(5).unwrap()];
+        let req = CreateTaskRequest::new(title("Take out bins"))
+            .with_list_id(list.id())
+            .with_due_at(DueAt::new(Utc::now()))
+            .with_remind_at(RemindAt::new(offsets).unwrap())
+            .with_assignee_id(bob);
+        let task = sqlite.create_task(&ada, &req).await.unwrap();
+        let req = CreateTaskRequest::new(title("Water plants"));
+        sqlite.create_task(&ada, &req).await.unwrap();
+
+        let ids = |tasks: &[Task]| tasks.iter().map(Task::id).collect::<Vec<_>>();
+        let mine = ListTasksRequest::new().with_filter(TaskFilter::new().with_assignee(bob));
+        let page = sqlite.list_tasks(&bob, &mine).await.unwrap();
+        assert_eq!(ids(&page.tasks), vec![task.id()]);
+        let page = sqlite
+            .list_tasks(&bob, &ListTasksRequest::new())
+            .await
+            .unwrap();
+        assert_eq!(ids(&page.tasks), vec![task.id()]);
+
+        let claimed = sqlite.claim_due_reminders(Utc::now(), 10).await.unwrap();
+        let claimed = claimed
+            .iter()
+            .map(|reminder| (reminder.task().id(), reminder.recipient()))
+            .collect::<Vec<_>>();
+        assert_eq!(claimed, vec![(task.id(), bob)]);
+
+        sqlite.unshare_list(&ada, &list.id(), &bob).await.unwrap();
+        let task = sqlite.get_task(&ada, &task.id()).await.unwrap();
+        assert_eq!(task.assignee_id(), None);
+    }
+
+    #[tokio::test]
+    async fn test_api_tokens_record_use_and_are_revoked_with_their_list() {
+        let sqlite = sqlite().await;
+        let ada = user(&sqlite, "ada@example.com").await;
+        let bob = user(&sqlite, "bob@example.com").await;
+        let list = sqlite
+            .create_list(
+                &ada,
+                &CreateTaskListRequest::new(TaskListTitle::new("Garden").unwrap()),
+            )
+            .await
+            .unwrap();
+        let scope = TokenScope::full()
+            .with_access(TokenAccess::ReadOnly)
+            .with_list_id(list.id());
+        let req = CreateApiTokenRequest::new(ApiTokenName::new("cron").unwrap(), scope);
+        let token = NewApiToken::issue(&req, Utc::now());
+        let result = sqlite.create_api_token(&bob, &token).await;
+        assert!(matches!(
+            result,
+            Err(CreateApiTokenError::ListNotFound { .. })
+        ));
+        sqlite.create_api_token(&ada, &token).await.unwrap();
+
+        let principal = sqlite
+            .use_api_token(token.secret(), Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(principal.user.id(), ada);
+        assert_eq!(principal.scope, scope);
+        let tokens = sqlite.list_api_tokens(&ada).await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert!(tokens[0].last_used_at().is_some());
+        assert!(sqlite.list_api_tokens(&bob).await.unwrap().is_empty());
+
+        sqlite
+            .delete_list(&ada, &list.id(), DeleteTaskListMode::OnlyIfEmpty)
+            .await
+            .unwrap();
+        let result = sqlite.use_api_token(token.secret(), Utc::now()).await;
+        assert!(matches!(result, Err(AuthenticateError::InvalidApiToken)));
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_keys_are_claimed_replayed_and_expire() {
+        let sqlite = sqlite().await;
+        let key = IdempotencyKey::new("retry-1").unwrap();
+        let req = IdempotentRequest::new("owner".to_string(), key.clone(), "a".to_string());
+        let now = Utc::now();
+        let expires_at = now + Duration::hours(1);
+        let claimed = sqlite
+            .claim_idempotency_key(&req, now, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(claimed, None);
+        let pending = sqlite
+            .claim_idempotency_key(&req, now, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(
+            pending,
+            Some(IdempotencyRecord {
+                fingerprint: "a".to_string(),
+                response: None,
+            })
+        );
+        let other = IdempotentRequest::new("other".to_string(), key, "b".to_string());
+        let claimed = sqlite
+            .claim_idempotency_key(&other, now, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(claimed, None);
+
+        let response = StoredResponse::new(201, Some("application/json".to_string()), vec![7])
+            .with_headers(vec![("etag".to_string(), "\"1\"".to_string())]);
+        sqlite
+            .save_idempotent_response(&req, &response)
+            .await
+            .unwrap();
+        let replayed = sqlite
+            .claim_idempotency_key(&req, now, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(replayed.unwrap().response, Some(response));
+
+        let claimed = sqlite
+            .claim_idempotency_key(&req, expires_at, expires_at + Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(claimed, None);
+        sqlite.delete_idempotency_key(&req).await.unwrap();
+        let claimed = sqlite
+            .claim_idempotency_key(&req, now, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(claimed, None);
+    }
+}
@@ -0,0 +1,35 @@
+use crate::domain::reminders::models::reminder::{DueReminder, NotifyReminderError};
+use crate::domain::reminders::ports::ReminderNotifier;
+
+/// A [ReminderNotifier] that writes reminders to standard output, addressed to their recipient.
+#[derive(Debug, Clone, Default)]
+pub struct Stdout;
+
+impl Stdout {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ReminderNotifier for Stdout {
+    async fn notify(&self, reminder: &DueReminder) -> Result<(), NotifyReminderError> {
+        let task = reminder.task();
+        let recipient = reminder.recipient();
+        match task.due_at() {
+            Some(due_at) => println!(
+                "Reminder for user {}: {} ({}) is due at {}",
+                recipient,
+                task.title(),
+                task.id(),
+                due_at
+            ),
+            None => println!(
+                "Reminder for user {}: {} ({})",
+                recipient,
+                task.title(),
+                task.id()
+            ),
+        }
+        Ok(())
+    }
+}